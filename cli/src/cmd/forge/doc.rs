@@ -1,10 +1,36 @@
-use crate::{cmd::Cmd, opts::GH_REPO_PREFIX_REGEX};
+use crate::{
+    cmd::{forge::watch::WatchArgs, Cmd},
+    opts::{ChainValueParser, GH_REPO_PREFIX_REGEX},
+    utils,
+};
 use clap::{Parser, ValueHint};
-use forge_doc::{ContractInheritance, DocBuilder, GitSource, Inheritdoc, Server};
-use foundry_config::{find_project_root_path, load_config_with_root};
-use std::{path::PathBuf, process::Command};
+use forge_doc::{
+    ArchiveFormat, AssemblyIndex, BuildInfo, CompilerSettings, ContractInheritance, ContractIntro,
+    Dependencies, Deployments, Diagnostics, DocBuilder, DocCoverage, DocDiagnostic, DocEvent,
+    DocFormat, DocModel, DocStats, EditLink, EffectiveVisibility, EmbedAbi, EventLayout, EventSink,
+    EvmCompatibility, FileTypes, GasEstimates, GitSince, GitSource, Inheritdoc,
+    InterfaceConformance, JsonLines, LandingPage, ModelDiff, OverloadDocs, PageHeaders,
+    Preconditions, ScriptUsage, SectionLimits, Security, SeeAlso, SelectorCollisions,
+    SelectorRegistry, SelectorSignatures, Server, Severity, StandardBases, StandardJson,
+    StructFields, StructLinks, TestPlan,
+};
+use foundry_config::{
+    find_project_root_path, load_config_with_root, Chain, Config, DocConfig, DocSelectorsConfig,
+    FilenameCase, SolcReq, SummaryOrder,
+};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+use watchexec::config::{InitConfig, RuntimeConfig};
+use yansi::Paint;
 
 #[derive(Debug, Clone, Parser)]
+#[clap(group = clap::ArgGroup::new("json-output")
+    .multiple(true)
+    .args(["diff", "dry_run", "item", "stats", "coverage"]))]
 pub struct DocArgs {
     /// The project's root path.
     ///
@@ -24,10 +50,26 @@ pub struct DocArgs {
     )]
     out: Option<PathBuf>,
 
+    /// The config profile to document the project with, e.g. `docs`.
+    ///
+    /// By default, it is the profile selected with `FOUNDRY_PROFILE`. The compiler settings
+    /// and the artifacts are read from the profile's `out`, so the lighter profile, e.g. without
+    /// the optimizer, keeps the docs from overwriting the artifacts of the build profile, and
+    /// the project must be built with the same profile. The `doc` section of the profile
+    /// overrides the top-level `[doc]`, and the output selection flags override both.
+    #[clap(long, value_name = "PROFILE")]
+    profile: Option<String>,
+
     /// Build the `mdbook` from generated files.
     #[clap(long, short)]
     build: bool,
 
+    /// The URL the built book is published at, e.g. `https://docs.example.com`.
+    ///
+    /// If set, the `sitemap.xml` of the HTML pages is written with `--build`.
+    #[clap(long, value_name = "URL")]
+    site_url: Option<String>,
+
     /// Serve the documentation.
     #[clap(long, short)]
     serve: bool,
@@ -39,18 +81,367 @@ pub struct DocArgs {
     /// Port for serving documentation.
     #[clap(long, short, requires = "serve")]
     port: Option<usize>,
+
+    /// Open the documentation after the generation.
+    ///
+    /// Opens the served url with `--serve`. Otherwise, the book is built
+    /// and its index page is opened, falling back to the markdown homepage.
+    #[clap(long, conflicts_with = "archive_only")]
+    open: bool,
+
+    /// Number of threads to use for parsing and rendering the documentation.
+    ///
+    /// By default, it is the available parallelism.
+    #[clap(long, short, value_name = "JOBS")]
+    jobs: Option<usize>,
+
+    /// Pack the generated documentation into an archive.
+    ///
+    /// The format is inferred from the extension: `.tar`, `.tar.gz`, `.tgz` or `.zip`.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    archive: Option<PathBuf>,
+
+    /// Only write the archive, without keeping the generated documentation directory.
+    #[clap(long, requires = "archive", conflicts_with = "serve")]
+    archive_only: bool,
+
+    /// Document the scripts with their `forge script` usage.
+    #[clap(long)]
+    include_scripts: bool,
+
+    /// Document the tests as a test plan with the used cheatcodes.
+    #[clap(long)]
+    include_tests: bool,
+
+    /// Write the `docs.json` model as compact JSON instead of pretty-printing it.
+    #[clap(long)]
+    minify: bool,
+
+    /// Additional output formats written alongside the book.
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    format: Vec<DocFormat>,
+
+    /// Include the build info page with the compiler settings of the project.
+    #[clap(long)]
+    build_info: bool,
+
+    /// Write the solc standard json input of each contract, as submitted for the verification,
+    /// and link it from the contract page.
+    #[clap(long)]
+    include_standard_json: bool,
+
+    /// Include the appendix listing the functions with inline assembly, with the blocks
+    /// declared `memory-safe`.
+    #[clap(long)]
+    assembly_index: bool,
+
+    /// Document only the externally accessible items, hiding the internal and private
+    /// functions, the modifiers and the non-public state variables.
+    #[clap(long)]
+    api_only: bool,
+
+    /// Escape the characters significant in MDX, so the pages can be compiled by
+    /// the MDX based sites, e.g. Docusaurus.
+    #[clap(long)]
+    mdx: bool,
+
+    /// The casing of the generated page file names and directories:
+    /// `preserve`, `lower`, `kebab` or `snake`.
+    ///
+    /// The summary and the cross-page links follow the cased paths.
+    #[clap(long, value_name = "CASE")]
+    filename_case: Option<FilenameCase>,
+
+    /// The order of the directories and the files within each level of the summary:
+    /// `directories-first`, `files-first` or `mixed`.
+    #[clap(long, value_name = "ORDER")]
+    summary_order: Option<SummaryOrder>,
+
+    /// Write all pages into a single directory instead of the nested source layout.
+    ///
+    /// The pages are named after their full paths, e.g. `tokens_erc20_Token.md`,
+    /// and the names colliding across the directories keep the item kind and the file name.
+    #[clap(long)]
+    flat: bool,
+
+    /// Append the "Protocol at a glance" overview to the homepage.
+    ///
+    /// It lists the contracts not inherited by any other documented contract and the libraries
+    /// with their notices and deployments, unless they are tagged with `@custom:nodoc-overview`.
+    #[clap(long)]
+    title_page: bool,
+
+    /// Include the deployed addresses of the contracts from the broadcast artifacts.
+    #[clap(long)]
+    deployments: bool,
+
+    /// The chains to include the deployments on, by name or id.
+    ///
+    /// Only used with `--deployments`. By default, the deployments on all chains are included.
+    #[clap(long, value_name = "CHAIN", value_parser = ChainValueParser::default())]
+    deployment_chains: Vec<Chain>,
+
+    /// Embed the ABI JSON of each contract at the bottom of its page, collapsed by default.
+    ///
+    /// The ABI is read from the compiled artifacts, so the project must be built first.
+    #[clap(long)]
+    embed_abi: bool,
+
+    /// Show the compiler's gas estimates of the functions next to the `@custom:gas` notes.
+    ///
+    /// The estimates are read from the compiled artifacts, so the project must be built
+    /// with `evm.gasEstimates` in the extra output.
+    #[clap(long)]
+    gas_estimates: bool,
+
+    /// Show the log layout of each event, i.e. the topics of the indexed parameters
+    /// and the byte ranges of the others within the data, for decoding the logs by hand.
+    #[clap(long)]
+    event_layout: bool,
+
+    /// List the preconditions of each function inferred from the `require` and
+    /// `if (...) revert` guards at the top level of its body, marked as inferred.
+    #[clap(long)]
+    infer_requires: bool,
+
+    /// Add the "Edit this page" link to each page, pointing to the editor of its source
+    /// on the repository.
+    #[clap(long)]
+    edit_link: bool,
+
+    /// The compiled artifacts path, relative to the project root.
+    ///
+    /// By default, the artifacts are read from the `out` of the selected profile.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    artifacts: Option<PathBuf>,
+
+    /// Also document the ABI artifacts in the directory without their source, e.g. the foundry
+    /// artifacts or the solc metadata files of the deployed contracts, relative to the project
+    /// root.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    abi_dir: Option<PathBuf>,
+
+    /// The section of the book the pages of the ABI artifacts are grouped under.
+    ///
+    /// By default, the name of the ABI directory.
+    #[clap(long, value_name = "NAME")]
+    abi_section: Option<String>,
+
+    /// Write the Solidity interface stubs with the external and public functions and the
+    /// public state variable getters of the documented contracts, mirroring their source paths.
+    #[clap(long)]
+    emit_interface: bool,
+
+    /// The interface stubs output path, relative to the project root.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    interfaces_out: Option<PathBuf>,
+
+    /// The directory of the hand-written contract introductions, with a `<Contract>.md`
+    /// per contract, relative to the project root.
+    ///
+    /// By default, the `<Source>.sol.md` next to the source file introduces the contract
+    /// named after the file.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    intro_dir: Option<PathBuf>,
+
+    /// Compare the generated documentation with the previous generation at the given path
+    /// and print the added, removed and changed symbols.
+    #[clap(long, value_hint = ValueHint::DirPath, value_name = "PATH", conflicts_with = "serve")]
+    diff: Option<PathBuf>,
+
+    /// Compare the generated documentation with the previous generation in the output
+    /// directory, if any. Set by the watch mode for each regeneration.
+    #[clap(long, hide = true, conflicts_with = "diff")]
+    diff_previous: bool,
+
+    /// Document only the items in the source files changed since the git ref,
+    /// leaving the other pages untouched.
+    ///
+    /// Falls back to the full generation if the project is not in a git repository.
+    #[clap(long, value_name = "REF")]
+    since: Option<String>,
+
+    /// Register the function, error and event signatures of the documented contracts
+    /// in the signature databases configured under `[doc.selectors]`.
+    #[clap(long)]
+    register_selectors: bool,
+
+    /// Print the pages that would be created or overwritten, the stale pages, the created
+    /// directories and the summary changes, without writing to the output directory.
+    ///
+    /// With `--json`, the plan is printed as JSON. With `--register-selectors`,
+    /// the signatures that would be registered are listed without submitting them.
+    #[clap(long, conflicts_with_all = &["serve", "open", "archive", "stats"])]
+    dry_run: bool,
+
+    /// Fail on the warnings, e.g. the `@param` not matching any parameter,
+    /// the missing `@return` or the malformed custom tag, listing all of them as errors.
+    #[clap(long)]
+    deny_warnings: bool,
+
+    /// Count the parameters only documented by their trailing comments as documented
+    /// with `--deny-warnings`, rather than reporting them.
+    ///
+    /// The trailing comments are only read with `doc.param_comment_fallback`.
+    #[clap(long)]
+    strict_accept_inline: bool,
+
+    /// Fail if no contracts are documented, e.g. due to the wrong `src` path
+    /// or the `doc.ignore` globs matching all sources.
+    #[clap(long)]
+    fail_on_empty: bool,
+
+    /// The format of the warnings reported after the generation.
+    ///
+    /// The JSON warnings are printed to stderr one per line, with the file, the line,
+    /// the code, the message and the item identifier, e.g. for the CI annotations.
+    #[clap(long, value_enum, value_name = "FORMAT", default_value = "human")]
+    warnings_format: WarningsFormat,
+
+    /// The format of the generation output.
+    ///
+    /// With `json-lines`, the events of the generation are streamed to stdout one JSON object
+    /// per line, i.e. the rendered pages, the diagnostics, the written summary and the
+    /// finished run with its stats. Each event has the `version` of its shape and its `event`
+    /// kind. The options printing other output to stdout are rejected.
+    #[clap(long, value_enum, value_name = "FORMAT", default_value = "human")]
+    message_format: MessageFormat,
+
+    /// Print the docs of a single item instead of generating the documentation,
+    /// e.g. `Vault`, `Vault.deposit` or `Vault.deposit(uint256,address)`.
+    ///
+    /// All overloads are printed unless the parameter types are given.
+    /// With `--json`, the item name, kind, signature, source and markdown are printed.
+    #[clap(long, value_name = "ITEM", conflicts_with_all = &["serve", "diff", "archive"])]
+    item: Option<String>,
+
+    /// Print the timings, the written pages and the documented items after the generation.
+    #[clap(long)]
+    stats: bool,
+
+    /// Abort on the first source or page failing to generate.
+    ///
+    /// By default, the other pages are written and the failures are reported at the end.
+    #[clap(long)]
+    fail_fast: bool,
+
+    /// Print the natspec coverage of each documented item after the generation.
+    ///
+    /// With `--json`, the coverage report is written to `doc-coverage.json`
+    /// in the output directory instead.
+    #[clap(long)]
+    coverage: bool,
+
+    /// Print the diff, the plan, the item and the stats as JSON, and write the coverage report.
+    #[clap(long, requires = "json-output")]
+    json: bool,
+
+    /// Regenerate the documentation on the source changes and print the changed items
+    /// of each regeneration.
+    #[clap(flatten)]
+    pub watch: WatchArgs,
+}
+
+/// The format of the reported warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WarningsFormat {
+    /// The human readable warnings.
+    Human,
+    /// The warnings as JSON lines.
+    Json,
+}
+
+/// The format of the generation output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MessageFormat {
+    /// The human readable output, with the diagnostics in the `--warnings-format`.
+    Human,
+    /// The generation events as JSON lines.
+    JsonLines,
+}
+
+/// The human readable output of the generation events.
+/// Only the diagnostics are printed, to stderr in the warnings format, and the messages,
+/// to stdout.
+#[derive(Debug)]
+struct HumanEvents {
+    warnings_format: WarningsFormat,
+}
+
+impl EventSink for HumanEvents {
+    fn emit(&self, event: &DocEvent) {
+        let diagnostic = match event {
+            DocEvent::Diagnostic(diagnostic) => diagnostic,
+            DocEvent::Message { message } => {
+                println!("{message}");
+                return
+            }
+            _ => return,
+        };
+        match self.warnings_format {
+            WarningsFormat::Human => {
+                let severity = match diagnostic.severity {
+                    Severity::Warning => Paint::yellow("Warning:").bold(),
+                    Severity::Error => Paint::red("Error:").bold(),
+                };
+                eprintln!("{severity} {diagnostic} [{}]", diagnostic.code);
+            }
+            WarningsFormat::Json => match serde_json::to_string(diagnostic) {
+                Ok(json) => eprintln!("{json}"),
+                Err(err) => eprintln!("failed to serialize the diagnostic: {err}"),
+            },
+        }
+    }
 }
 
 impl Cmd for DocArgs {
     type Output = ();
 
     fn run(self) -> eyre::Result<Self::Output> {
+        // The archive format is inferred from the extension, so it's checked before generating
+        if let Some(ref archive) = self.archive {
+            ArchiveFormat::from_path(archive)?;
+        }
+
         let root = self.root.clone().unwrap_or(find_project_root_path()?);
-        let config = load_config_with_root(Some(root.clone()));
+        let config = self.load_config(&root);
 
-        let mut doc_config = config.doc.clone();
-        if let Some(out) = self.out {
-            doc_config.out = out;
+        let mut doc_config = self.doc_config(&config);
+
+        let events: Arc<dyn EventSink> = match self.message_format {
+            MessageFormat::Human => Arc::new(HumanEvents { warnings_format: self.warnings_format }),
+            MessageFormat::JsonLines => {
+                let printing = [
+                    ("--item", self.item.is_some()),
+                    ("--dry-run", self.dry_run),
+                    ("--diff", self.diff.is_some()),
+                    ("--stats", self.stats),
+                    ("--coverage", self.coverage && !self.json),
+                    ("--register-selectors", self.register_selectors),
+                    ("--serve", self.serve),
+                    ("--open", self.open),
+                ];
+                if let Some((flag, _)) = printing.iter().find(|(_, set)| *set) {
+                    eyre::bail!("`--message-format json-lines` can't be combined with `{flag}`")
+                }
+                Arc::new(JsonLines::default())
+            }
+        };
+
+        // Read the previous model before it may be overwritten by this generation.
+        let previous = match self.diff {
+            Some(ref diff) => Some(DocModel::read(diff)?),
+            // The first generation in watch mode has nothing to compare with
+            None if self.diff_previous => DocModel::read(&root.join(&doc_config.out)).ok(),
+            None => None,
+        };
+
+        // Generate into a temporary directory if only the archive or the plan is requested.
+        let plan_out = self.dry_run.then(|| root.join(&doc_config.out));
+        let tmp_out =
+            if self.archive_only || self.dry_run { Some(tempfile::tempdir()?) } else { None };
+        if let Some(ref tmp_out) = tmp_out {
+            doc_config.out = tmp_out.path().to_path_buf();
         }
         if doc_config.repository.is_none() {
             // Attempt to read repo from git
@@ -79,26 +470,611 @@ impl Cmd for DocArgs {
                 }
             });
 
-        DocBuilder::new(root.clone(), config.project_paths().sources)
-            .with_should_build(self.build)
+        // The artifacts of the selected profile, unless overridden
+        let artifacts = match doc_config.artifacts {
+            Some(ref artifacts) => root.join(artifacts),
+            None => config.project_paths().artifacts,
+        };
+
+        let diagnostics = Arc::new(Diagnostics::default());
+        let mut builder = DocBuilder::new(root.clone(), config.project_paths().sources)
+            .with_should_build(self.build || (self.open && !self.serve))
             .with_config(doc_config.clone())
+            .with_diagnostics(diagnostics.clone())
+            .with_events(events.clone())
+            .with_fail_fast(self.fail_fast)
             .with_fmt(config.fmt)
-            .with_preprocessor(ContractInheritance::default())
+            .with_artifacts(artifacts.clone())
+            .with_remappings(config.get_all_remappings())
+            .with_preprocessor(ContractInheritance { section_limit: doc_config.section_limit })
+            .with_preprocessor(FileTypes::default())
+            .with_preprocessor(SeeAlso { root: root.clone(), diagnostics: diagnostics.clone() })
             .with_preprocessor(Inheritdoc::default())
+            .with_preprocessor(OverloadDocs::default())
+            .with_preprocessor(EffectiveVisibility::default())
             .with_preprocessor(GitSource {
-                root,
-                commit,
+                root: root.clone(),
+                commit: commit.clone(),
                 repository: doc_config.repository.clone(),
             })
-            .build()?;
+            .with_preprocessor(ScriptUsage {
+                root: root.clone(),
+                remappings: config.get_all_remappings(),
+            })
+            .with_preprocessor(TestPlan {
+                root: root.clone(),
+                remappings: config.get_all_remappings(),
+            })
+            .with_preprocessor(StructLinks {
+                out: doc_config.out.clone(),
+                show_aliases: doc_config.show_import_aliases,
+                root: root.clone(),
+                remappings: config.get_all_remappings(),
+                section_limit: doc_config.section_limit,
+            })
+            .with_preprocessor(StructFields {
+                max_depth: doc_config.struct_depth,
+                root: root.clone(),
+                remappings: config.get_all_remappings(),
+            })
+            .with_preprocessor(Security { root: root.clone(), out: doc_config.out.clone() })
+            .with_preprocessor(SelectorCollisions {
+                root: root.clone(),
+                out: doc_config.out.clone(),
+                remappings: config.get_all_remappings(),
+                diagnostics: diagnostics.clone(),
+            })
+            .with_preprocessor(Dependencies {
+                root: root.clone(),
+                out: doc_config.out.clone(),
+                libs: config.libs.clone(),
+                remappings: config.get_all_remappings(),
+                section_limit: doc_config.section_limit,
+            })
+            .with_preprocessor(InterfaceConformance {
+                root: root.clone(),
+                out: doc_config.out.clone(),
+                remappings: config.get_all_remappings(),
+            });
+        let version = Command::new("git")
+            .args(["describe", "--tags", "--always"])
+            .current_dir(&root)
+            .output()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|version| version.trim().to_owned());
+        let variables = vec![
+            ("project".to_owned(), builder.title()),
+            ("version".to_owned(), version.unwrap_or_default()),
+            ("commit".to_owned(), commit.unwrap_or_default()),
+        ];
+        let mut headers = PageHeaders::new(root.clone());
+        headers.variables = variables.clone();
+        builder = builder.with_page_hook(headers).with_preprocessor(ContractIntro {
+            root: root.clone(),
+            dir: doc_config.intro_dir.clone(),
+            variables,
+            out: doc_config.out.clone(),
+            diagnostics: diagnostics.clone(),
+        });
+        if self.include_scripts {
+            builder = builder.with_include(root.join(&config.script));
+        }
+        for format in self.format.iter() {
+            builder = builder.with_format(*format);
+        }
+        if self.include_tests {
+            builder = builder.with_include(root.join(&config.test));
+        }
+        if let Some(ref item) = self.item {
+            let items = builder.lookup(item)?;
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            } else {
+                println!(
+                    "{}",
+                    items
+                        .iter()
+                        .map(|item| item.markdown.trim_end())
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                );
+            }
+            return Ok(())
+        }
+        if doc_config.git_since {
+            builder = builder.with_preprocessor(GitSince {
+                root: root.clone(),
+                cache: config.cache_path.join("doc-since.json"),
+                jobs: doc_config.jobs,
+            });
+        }
+        if doc_config.deployments {
+            builder = builder.with_preprocessor(Deployments {
+                broadcast: config.broadcast.clone(),
+                chains: doc_config.deployment_chains.clone(),
+            });
+        }
+        if doc_config.embed_abi {
+            builder = builder.with_preprocessor(EmbedAbi {
+                root: root.clone(),
+                artifacts: artifacts.clone(),
+                diagnostics: diagnostics.clone(),
+            });
+        }
+        if doc_config.include_standard_json {
+            builder = builder.with_preprocessor(StandardJson {
+                project: config.project()?,
+                out: doc_config.out.clone(),
+                diagnostics: diagnostics.clone(),
+            });
+        }
+        if !doc_config.exclude_external_overrides.is_empty() {
+            builder = builder.with_preprocessor(StandardBases {
+                bases: doc_config.exclude_external_overrides.clone(),
+            });
+        }
+        if doc_config.gas_estimates {
+            builder = builder.with_preprocessor(GasEstimates { artifacts: artifacts.clone() });
+        }
+        if doc_config.event_layout {
+            builder = builder.with_preprocessor(EventLayout {
+                root: root.clone(),
+                remappings: config.get_all_remappings(),
+            });
+        }
+        if doc_config.infer_requires {
+            builder = builder.with_preprocessor(Preconditions::default());
+        }
+        if let Some(repository) = doc_config.repository.as_ref().filter(|_| doc_config.edit_link) {
+            // The pages are edited on the current branch, or the default one if detached
+            let branch = Command::new("git")
+                .args(["rev-parse", "--abbrev-ref", "HEAD"])
+                .current_dir(&root)
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|branch| branch.trim().to_owned())
+                .filter(|branch| !branch.is_empty() && branch != "HEAD")
+                .unwrap_or_else(|| "main".to_owned());
+            builder = builder.with_preprocessor(EditLink {
+                root: root.clone(),
+                repository: repository.clone(),
+                branch,
+                template: doc_config.edit_link_template.clone(),
+            });
+        }
+        let settings = CompilerSettings {
+            solc: config.solc.as_ref().map(|solc| match solc {
+                SolcReq::Version(version) => version.to_string(),
+                SolcReq::Local(path) => path.display().to_string(),
+            }),
+            evm_version: config.evm_version.to_string(),
+            optimizer: config.optimizer,
+            optimizer_runs: config.optimizer_runs,
+            via_ir: config.via_ir,
+        };
+        builder = builder.with_preprocessor(EvmCompatibility {
+            settings: settings.clone(),
+            diagnostics: diagnostics.clone(),
+        });
+        if doc_config.assembly_index {
+            builder = builder.with_preprocessor(AssemblyIndex {
+                root: root.clone(),
+                out: doc_config.out.clone(),
+                section_limit: doc_config.section_limit,
+            });
+        }
+        if doc_config.build_info {
+            builder = builder.with_preprocessor(BuildInfo {
+                root: root.clone(),
+                out: doc_config.out.clone(),
+                settings,
+                artifacts: artifacts.clone(),
+            });
+        }
+        // The continuation pages are split last, with the context of the contract pages
+        if doc_config.section_limit > 0 {
+            builder =
+                builder.with_preprocessor(SectionLimits { max_items: doc_config.section_limit });
+        }
+        if let Some(archive) = self.archive {
+            builder = builder.with_archive(archive);
+        }
+        if let Some(ref since) = self.since {
+            match changed_files(&root, since) {
+                Some(changed) => builder = builder.with_changed(changed),
+                None => diagnostics.push(DocDiagnostic::warning(
+                    "since-fallback",
+                    format!(
+                        "failed to collect the files changed since `{since}`, documenting all files"
+                    ),
+                )),
+            }
+        }
+        let out_dir = builder.out_dir();
+        let generated = match plan_out {
+            Some(ref plan_out) => {
+                builder.plan(plan_out).map(|plan| (DocStats::default(), Some(plan)))
+            }
+            None => builder.build().map(|stats| (stats, None)),
+        };
+        let (stats, plan) = match generated {
+            Ok(generated) => generated,
+            Err(err) => {
+                report_diagnostics(&diagnostics, events.as_ref());
+                events.emit(&DocEvent::Finished {
+                    stats: DocStats::default(),
+                    success: false,
+                    error: Some(err.to_string()),
+                });
+                return Err(err)
+            }
+        };
+
+        if let Some(plan) = plan {
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&plan)?);
+            } else {
+                print!("{plan}");
+            }
+        }
+
+        // The changed items of the watch mode regenerations aren't printed with the events
+        if let Some(previous) = previous.filter(|_| self.message_format == MessageFormat::Human) {
+            let diff = ModelDiff::new(&previous, &DocModel::read(&out_dir)?);
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                print!("{diff}");
+            }
+        }
+
+        if self.stats {
+            if self.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({ "stats": stats }))?
+                );
+            } else {
+                print!("{stats}");
+            }
+        }
+
+        if self.coverage {
+            let coverage = DocCoverage::new(&DocModel::read(&out_dir)?);
+            if self.json {
+                coverage.write(&out_dir)?;
+            } else {
+                print!("{coverage}");
+            }
+        }
+
+        if self.register_selectors {
+            register_selectors(
+                &DocModel::read(&out_dir)?,
+                &doc_config.selectors,
+                self.dry_run,
+                &diagnostics,
+            )?;
+        }
+
+        // Report the warnings, failing on them if denied
+        let warnings = diagnostics.warnings();
+        let denied = doc_config.deny_warnings && warnings > 0;
+        if denied {
+            diagnostics.escalate();
+        }
+        report_diagnostics(&diagnostics, events.as_ref());
+        if denied {
+            let error = format!("{warnings} warning(s) denied");
+            events.emit(&DocEvent::Finished { stats, success: false, error: Some(error.clone()) });
+            eyre::bail!(error)
+        }
+        events.emit(&DocEvent::Finished { stats, success: true, error: None });
 
         if self.serve {
             Server::new(doc_config.out)
                 .with_hostname(self.hostname.unwrap_or("localhost".to_owned()))
                 .with_port(self.port.unwrap_or(3000))
+                .with_open(self.open)
+                .with_events(events)
                 .serve()?;
+        } else if self.open {
+            let page = LandingPage::resolve(&out_dir, None);
+            if !page.open() {
+                events.emit(&DocEvent::Message {
+                    message: format!("Open {page} to view the documentation"),
+                });
+            }
         }
 
         Ok(())
     }
 }
+
+impl DocArgs {
+    /// Returns whether `DocArgs` was configured with `--watch`
+    pub fn is_watch(&self) -> bool {
+        self.watch.watch.is_some()
+    }
+
+    /// Returns the [`watchexec::InitConfig`] and [`watchexec::RuntimeConfig`] necessary to
+    /// bootstrap a new [`watchexec::Watchexec`] loop.
+    pub(crate) fn watchexec_config(&self) -> eyre::Result<(InitConfig, RuntimeConfig)> {
+        let root = self.root.clone().unwrap_or(find_project_root_path()?);
+        let config = self.load_config(&root);
+        let (watch, paths) = self.watched_paths(&root, &config);
+
+        // use the path arguments or if none where provided the documented sources
+        watch.watchexec_config(|| paths)
+    }
+
+    /// Returns the watch args with the `--watch` paths and the paths watched if none were
+    /// provided, i.e. the documented sources. The paths in the output directory are left out.
+    fn watched_paths(&self, root: &Path, config: &Config) -> (WatchArgs, Vec<PathBuf>) {
+        // The regenerated output is never watched, so it can't trigger the regeneration
+        let doc_config = self.doc_config(config);
+        let out = canonical_path(&root.join(&doc_config.out));
+        let outside_out = |path: &PathBuf| !canonical_path(path).starts_with(&out);
+        let mut watch = self.watch.clone();
+        if let Some(ref mut paths) = watch.watch {
+            paths.retain(outside_out);
+        }
+
+        let mut paths = vec![config.src.clone()];
+        if self.include_scripts {
+            paths.push(config.script.clone());
+        }
+        if self.include_tests {
+            paths.push(config.test.clone());
+        }
+        paths.extend(doc_config.intro_dir);
+        paths.extend(doc_config.abi_dir);
+        paths.retain(outside_out);
+        (watch, paths)
+    }
+
+    /// Returns the config of the project, selecting the `--profile` if set.
+    ///
+    /// The profile is selected on the figment of the project, so `FOUNDRY_PROFILE` is left as is
+    /// for the rest of the process.
+    fn load_config(&self, root: &Path) -> Config {
+        match self.profile {
+            Some(ref profile) => {
+                let figment = Config::figment_with_root(root).select(profile.as_str());
+                Config::from_provider(figment).sanitized()
+            }
+            None => load_config_with_root(Some(root.to_path_buf())),
+        }
+    }
+
+    /// Returns the doc config of the selected profile with the CLI overrides applied.
+    fn doc_config(&self, config: &Config) -> DocConfig {
+        let mut doc_config = config.doc.clone();
+        if let Some(ref out) = self.out {
+            doc_config.out = out.clone();
+        }
+        if let Some(jobs) = self.jobs {
+            doc_config.jobs = Some(jobs);
+        }
+        if let Some(ref site_url) = self.site_url {
+            doc_config.site_url = Some(site_url.clone());
+        }
+        if self.build_info {
+            doc_config.build_info = true;
+        }
+        if self.include_standard_json {
+            doc_config.include_standard_json = true;
+        }
+        if self.assembly_index {
+            doc_config.assembly_index = true;
+        }
+        if self.minify {
+            doc_config.minify = true;
+        }
+        if self.api_only {
+            doc_config.api_only = true;
+        }
+        if self.mdx {
+            doc_config.mdx = true;
+        }
+        if let Some(filename_case) = self.filename_case {
+            doc_config.filename_case = filename_case;
+        }
+        if let Some(summary_order) = self.summary_order {
+            doc_config.summary_order = summary_order;
+        }
+        if self.flat {
+            doc_config.flat = true;
+        }
+        if self.title_page {
+            doc_config.title_page = true;
+        }
+        if self.deployments {
+            doc_config.deployments = true;
+        }
+        if !self.deployment_chains.is_empty() {
+            doc_config.deployment_chains = self.deployment_chains.clone();
+        }
+        if self.embed_abi {
+            doc_config.embed_abi = true;
+        }
+        if self.gas_estimates {
+            doc_config.gas_estimates = true;
+        }
+        if self.event_layout {
+            doc_config.event_layout = true;
+        }
+        if self.infer_requires {
+            doc_config.infer_requires = true;
+        }
+        if self.edit_link {
+            doc_config.edit_link = true;
+        }
+        if self.emit_interface {
+            doc_config.emit_interface = true;
+        }
+        if let Some(ref artifacts) = self.artifacts {
+            doc_config.artifacts = Some(artifacts.clone());
+        }
+        if let Some(ref abi_dir) = self.abi_dir {
+            doc_config.abi_dir = Some(abi_dir.clone());
+        }
+        if let Some(ref abi_section) = self.abi_section {
+            doc_config.abi_section = Some(abi_section.clone());
+        }
+        if let Some(ref interfaces_out) = self.interfaces_out {
+            doc_config.interfaces_out = Some(interfaces_out.clone());
+        }
+        if let Some(ref intro_dir) = self.intro_dir {
+            doc_config.intro_dir = Some(intro_dir.clone());
+        }
+        if self.deny_warnings {
+            doc_config.deny_warnings = true;
+        }
+        if self.strict_accept_inline {
+            doc_config.strict_accept_inline = true;
+        }
+        if self.fail_on_empty {
+            doc_config.fail_on_empty = true;
+        }
+        doc_config
+    }
+}
+
+/// Emit the reported diagnostics to the event sink.
+fn report_diagnostics(diagnostics: &Diagnostics, events: &dyn EventSink) {
+    for diagnostic in diagnostics.take() {
+        events.emit(&DocEvent::Diagnostic(diagnostic));
+    }
+}
+
+/// Register the documented selectors in the configured signature databases.
+/// The failures to reach the databases are reported as warnings.
+fn register_selectors(
+    model: &DocModel,
+    config: &DocSelectorsConfig,
+    dry_run: bool,
+    diagnostics: &Diagnostics,
+) -> eyre::Result<()> {
+    let signatures = SelectorSignatures::new(model);
+    for skipped in signatures.skipped.iter() {
+        diagnostics.push(
+            DocDiagnostic::warning(
+                "selector-skipped",
+                format!("skipping `{skipped}` with the user defined types"),
+            )
+            .with_item(skipped),
+        );
+    }
+    if dry_run {
+        let databases = config.databases.iter().map(|database| database.kind.to_string());
+        println!("Would submit to {}:", databases.collect::<Vec<_>>().join(", "));
+        print!("{signatures}");
+        return Ok(())
+    }
+    if signatures.is_empty() {
+        println!("No selectors to register");
+        return Ok(())
+    }
+
+    let registry = SelectorRegistry::new(config.clone())?;
+    for (database, result) in utils::block_on(registry.register(&signatures)) {
+        match result {
+            Ok(upload) => println!("{database}: {upload}"),
+            Err(err) => diagnostics.push(DocDiagnostic::warning(
+                "selector-registration",
+                format!("failed to register the selectors in {database}: {err}"),
+            )),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the files changed since the git ref, including the untracked ones.
+/// Returns [None] if the project is not in a git repository or the ref doesn't exist.
+fn changed_files(root: &Path, since: &str) -> Option<HashSet<PathBuf>> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+    };
+    let changed = git(&["diff", "--name-only", "--relative", since])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+    Some(
+        changed
+            .lines()
+            .chain(untracked.lines())
+            .filter(|line| !line.is_empty())
+            .map(|line| root.join(line))
+            .collect(),
+    )
+}
+
+/// Returns the canonical path, or the path itself if it doesn't exist yet.
+fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_overrides_doc_config() {
+        let config = Config {
+            doc: DocConfig { out: "docs-site".into(), jobs: Some(2), ..Default::default() },
+            ..Default::default()
+        };
+
+        let args = DocArgs::parse_from(["foundry-cli"]);
+        assert_eq!(args.doc_config(&config), config.doc);
+
+        let args = DocArgs::parse_from(["foundry-cli", "--out", "site", "--jobs", "4"]);
+        assert_eq!(
+            args.doc_config(&config),
+            DocConfig { out: "site".into(), jobs: Some(4), ..Default::default() }
+        );
+    }
+
+    #[test]
+    fn watched_paths_leave_out_output() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = canonical_path(tmp.path());
+        for dir in ["src", "test", "docs/src"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let config = Config {
+            src: root.join("src"),
+            test: root.join("test"),
+            script: root.join("script"),
+            doc: DocConfig { out: "docs".into(), ..Default::default() },
+            ..Default::default()
+        };
+
+        let args = DocArgs::parse_from(["foundry-cli", "--include-tests"]);
+        let (watch, paths) = args.watched_paths(&root, &config);
+        assert_eq!(watch.watch, None);
+        assert_eq!(paths, vec![root.join("src"), root.join("test")]);
+
+        // The intro directory in the output isn't watched
+        let config = Config {
+            doc: DocConfig { intro_dir: Some(root.join("docs/src")), ..config.doc },
+            ..config
+        };
+        let args = DocArgs::parse_from([
+            "foundry-cli".into(),
+            "--watch".into(),
+            root.join("src"),
+            root.join("docs/src"),
+        ]);
+        let (watch, paths) = args.watched_paths(&root, &config);
+        assert_eq!(watch.watch, Some(vec![root.join("src")]));
+        assert_eq!(paths, vec![root.join("src")]);
+    }
+}
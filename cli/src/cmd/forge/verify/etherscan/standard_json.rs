@@ -3,6 +3,7 @@ use ethers::{
     etherscan::verify::CodeFormat, prelude::artifacts::StandardJsonCompilerInput, solc::Project,
 };
 use eyre::Context;
+use foundry_common::compile::standard_json_input;
 use semver::Version;
 use std::path::Path;
 use tracing::trace;
@@ -17,19 +18,10 @@ impl EtherscanSourceProvider for EtherscanStandardJsonSource {
         target: &Path,
         version: &Version,
     ) -> eyre::Result<(String, String, CodeFormat)> {
-        let mut input: StandardJsonCompilerInput = project
-            .standard_json_input(target)
+        let mut input: StandardJsonCompilerInput = standard_json_input(project, target)
             .wrap_err("Failed to get standard json input")?
             .normalize_evm_version(version);
 
-        input.settings.libraries.libs = input
-            .settings
-            .libraries
-            .libs
-            .into_iter()
-            .map(|(f, libs)| (f.strip_prefix(project.root()).unwrap_or(&f).to_path_buf(), libs))
-            .collect();
-
         // remove all incompatible settings
         input.settings.sanitize(version);
 
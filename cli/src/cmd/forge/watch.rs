@@ -1,7 +1,7 @@
 //! Watch mode support
 
 use crate::{
-    cmd::forge::{build::BuildArgs, snapshot::SnapshotArgs, test::TestArgs},
+    cmd::forge::{build::BuildArgs, doc::DocArgs, snapshot::SnapshotArgs, test::TestArgs},
     utils::{self, FoundryPathExt},
 };
 use clap::Parser;
@@ -102,6 +102,26 @@ pub async fn watch_build(args: BuildArgs) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Executes a [`Watchexec`] that listens for changes in the project's src dir and reruns `forge
+/// doc`, printing the items changed since the previous regeneration
+pub async fn watch_doc(args: DocArgs) -> eyre::Result<()> {
+    let (init, mut runtime) = args.watchexec_config()?;
+    let mut cmd = cmd_args(args.watch.watch.as_ref().map(|paths| paths.len()).unwrap_or_default());
+    cmd.push("--diff-previous".to_owned());
+
+    trace!("watch doc cmd={:?}", cmd);
+    runtime.command(watch_command(cmd.clone()));
+    let wx = Watchexec::new(init, runtime.clone())?;
+
+    on_action(args.watch.clone(), runtime, Arc::clone(&wx), cmd, (), |_| {});
+
+    // start executing the command immediately
+    wx.send_event(Event::default(), Priority::default()).await?;
+    wx.main().await??;
+
+    Ok(())
+}
+
 /// Executes a [`Watchexec`] that listens for changes in the project's src dir and reruns `forge
 /// snapshot`
 pub async fn watch_snapshot(args: SnapshotArgs) -> eyre::Result<()> {
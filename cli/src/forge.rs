@@ -95,6 +95,12 @@ fn main() -> eyre::Result<()> {
             }
             Ok(())
         }
-        Subcommands::Doc(cmd) => cmd.run(),
+        Subcommands::Doc(cmd) => {
+            if cmd.is_watch() {
+                utils::block_on(watch::watch_doc(cmd))
+            } else {
+                cmd.run()
+            }
+        }
     }
 }
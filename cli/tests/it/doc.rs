@@ -1,4 +1,8 @@
-use foundry_cli_test_utils::util::{setup_forge_remote, RemoteProject};
+use foundry_cli_test_utils::{
+    forgetest,
+    util::{setup_forge_remote, RemoteProject, TestCommand, TestProject},
+};
+use std::fs;
 
 #[test]
 fn can_generate_solmate_docs() {
@@ -9,3 +13,337 @@ fn can_generate_solmate_docs() {
         .ensure_execute_success()
         .expect("`forge doc` failed");
 }
+
+// tests that the artifacts are read from the output directory of the selected profile
+forgetest!(can_read_profile_artifacts, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "foundry.toml",
+        r#"
+[profile.default]
+
+[profile.relocated]
+src = "contracts"
+out = "artifacts"
+cache_path = "build-cache"
+"#,
+    );
+    prj.create_file(
+        "contracts/Counter.sol",
+        "contract Counter { function increment() external { } }",
+    );
+    let abi = r#"{"abi":[{"type":"function","name":"increment","inputs":[],"outputs":[],"stateMutability":"nonpayable"}]}"#;
+    prj.create_file("artifacts/Counter.sol/Counter.json", abi);
+    let page = prj.root().join("docs/src/contracts/Counter.sol/contract.Counter.md");
+
+    cmd.set_env("FOUNDRY_PROFILE", "relocated");
+    cmd.args(["doc", "--embed-abi"]).assert_success();
+    assert!(fs::read_to_string(&page).unwrap().contains(r#""name": "increment""#));
+
+    // The artifacts path can be overridden
+    fs::remove_dir_all(prj.root().join("artifacts")).unwrap();
+    prj.create_file("custom/Counter.sol/Counter.json", &abi.replace("increment", "decrement"));
+    cmd.forge_fuse();
+    cmd.set_env("FOUNDRY_PROFILE", "relocated");
+    cmd.args(["doc", "--embed-abi", "--artifacts", "custom"]).assert_success();
+    assert!(fs::read_to_string(&page).unwrap().contains(r#""name": "decrement""#));
+});
+
+// tests that the doc profile is selected with `--profile` rather than `FOUNDRY_PROFILE`
+forgetest!(can_select_doc_profile, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "foundry.toml",
+        r#"
+[profile.default]
+optimizer = true
+
+[profile.docs]
+optimizer = false
+out = "docs-out"
+
+[profile.docs.doc]
+out = "light-docs"
+"#,
+    );
+    prj.create_file("src/Counter.sol", "contract Counter { function increment() external { } }");
+
+    cmd.args(["doc", "--profile", "docs", "--build-info"]).assert_success();
+    let build_info =
+        fs::read_to_string(prj.root().join("light-docs/src/appendix/build-info.md")).unwrap();
+    assert!(build_info.contains("Optimizer|disabled"));
+    assert!(!prj.root().join("docs").exists());
+
+    // The output selection flags override the profile
+    cmd.forge_fuse().args(["doc", "--profile", "docs", "--out", "custom"]).assert_success();
+    assert!(prj.root().join("custom/src/src/Counter.sol/contract.Counter.md").exists());
+});
+
+// tests that the CLI flags win over the doc profile and the `FORGE_DOC_` variables
+forgetest!(can_override_doc_profile_and_env, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "foundry.toml",
+        r#"
+[profile.docs.doc]
+out = "docs-site"
+"#,
+    );
+    prj.create_file("src/Counter.sol", "contract Counter { function increment() external { } }");
+    let page = "src/src/Counter.sol/contract.Counter.md";
+
+    cmd.set_env("FOUNDRY_PROFILE", "docs");
+    cmd.args(["doc"]).assert_success();
+    assert!(prj.root().join("docs-site").join(page).exists());
+
+    cmd.set_env("FORGE_DOC_OUT", "docs-env");
+    cmd.assert_success();
+    assert!(prj.root().join("docs-env").join(page).exists());
+
+    cmd.forge_fuse();
+    cmd.set_env("FOUNDRY_PROFILE", "docs");
+    cmd.set_env("FORGE_DOC_OUT", "docs-env");
+    cmd.args(["doc", "--out", "site"]).assert_success();
+    assert!(prj.root().join("site").join(page).exists());
+    assert!(!prj.root().join("docs").exists());
+});
+
+// tests that the ABI artifacts are documented without the source under the section
+forgetest!(can_document_abi_dir, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file("src/Counter.sol", "contract Counter { }");
+    prj.create_file(
+        "abis/Vault.metadata.json",
+        r#"{
+    "settings": {"compilationTarget": {"src/Vault.sol": "Vault"}},
+    "output": {
+        "abi": [{"type": "function", "name": "total", "stateMutability": "view",
+                 "inputs": [], "outputs": [{"name": "", "type": "uint256"}]}],
+        "userdoc": {"methods": {"total()": {"notice": "The total deposits."}}}
+    }
+}"#,
+    );
+
+    cmd.args(["doc", "--abi-dir", "abis", "--abi-section", "deployed"]).assert_success();
+    let page = fs::read_to_string(
+        prj.root().join("docs/src/deployed/Vault.metadata.json/contract.Vault.md"),
+    )
+    .unwrap();
+    assert!(page.contains("> Generated from the compiled artifact without the source"));
+    assert!(page.contains("The total deposits."));
+    assert!(prj.root().join("docs/src/src/Counter.sol/contract.Counter.md").exists());
+    let summary = fs::read_to_string(prj.root().join("docs/src/SUMMARY.md")).unwrap();
+    assert!(summary.contains("# deployed"));
+});
+
+// tests that the Vyper contracts are documented from their artifacts, and that the artifact
+// which can't be documented is reported rather than failing the generation
+forgetest!(can_document_vyper_contracts, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file("src/Counter.sol", "contract Counter { function increment() external { } }");
+    prj.create_file("src/Vault.vy", "# @version 0.3.7");
+    prj.create_file("src/Broken.vy", "# @version 0.3.7");
+    prj.create_file(
+        "out/Vault.vy/Vault.json",
+        r#"{
+            "abi": [{"type": "function", "name": "deposit", "stateMutability": "nonpayable",
+                     "inputs": [{"name": "assets", "type": "uint256"}], "outputs": []}],
+            "devdoc": {"title": "The vault"},
+            "userdoc": {"methods": {"deposit(uint256)": "Deposit the assets."}}
+        }"#,
+    );
+    // The function named after the Solidity keyword can't be declared in Solidity
+    prj.create_file(
+        "out/Broken.vy/Broken.json",
+        r#"{"abi": [{"type": "function", "name": "function", "stateMutability": "nonpayable",
+                     "inputs": [], "outputs": []}]}"#,
+    );
+
+    cmd.args(["doc"]);
+    let stderr = cmd.stderr_lossy();
+    assert!(stderr.contains("Broken.vy"));
+    assert!(stderr.contains("generated from the compiled artifact can't be parsed"));
+    let page =
+        fs::read_to_string(prj.root().join("docs/src/src/Vault.vy/contract.Vault.md")).unwrap();
+    assert!(page.contains("Deposit the assets."));
+    assert!(!prj.root().join("docs/src/src/Broken.vy").exists());
+    assert!(prj.root().join("docs/src/src/Counter.sol/contract.Counter.md").exists());
+});
+
+// tests that the flags of the test command are rejected rather than silently ignored
+forgetest!(rejects_test_command_flags, |_prj: TestProject, mut cmd: TestCommand| {
+    for flag in ["--debug", "--allow-failure", "--gas-report"] {
+        cmd.forge_fuse().args(["doc", flag]);
+        cmd.assert_err();
+        assert!(cmd.stderr_lossy().contains(flag));
+    }
+
+    cmd.forge_fuse().args(["doc", "--help"]);
+    let help = cmd.stdout_lossy();
+    assert!(!help.contains("--allow-failure"));
+    assert!(!help.contains("fuzz"));
+});
+
+// tests that the pages are the same whether the contracts are compiled through the IR pipeline
+forgetest!(same_docs_with_via_ir, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "src/Vault.sol",
+        r#"
+pragma solidity ^0.8.13;
+
+/// @title The vault
+/// @notice Holds the deposits.
+contract Vault {
+    /// @notice The total deposits.
+    uint256 public total;
+
+    /// @notice Deposit the assets.
+    /// @param amount The amount of the assets.
+    function deposit(uint256 amount) external {
+        total += amount;
+    }
+}
+"#,
+    );
+    let page = prj.root().join("docs/src/src/Vault.sol/contract.Vault.md");
+    let build_info = prj.root().join("docs/src/appendix/build-info.md");
+
+    let mut pages = vec![];
+    for via_ir in [false, true] {
+        prj.create_file("foundry.toml", &format!("[profile.default]\nvia_ir = {via_ir}\n"));
+        cmd.forge_fuse().args(["build"]).assert_success();
+        cmd.forge_fuse().args(["doc", "--embed-abi", "--build-info"]).assert_success();
+        pages.push(fs::read_to_string(&page).unwrap());
+
+        // The pipeline is noted on the build info page
+        let build_info = fs::read_to_string(&build_info).unwrap();
+        let pipeline = if via_ir { "Via IR|enabled" } else { "Via IR|disabled" };
+        assert!(build_info.contains(pipeline));
+        assert_eq!(build_info.contains("compiled through the IR pipeline"), via_ir);
+    }
+    assert!(pages[0].contains("Deposit the assets."));
+    assert!(pages[0].contains(r#""name": "deposit""#));
+    assert_eq!(pages[0], pages[1]);
+});
+
+// tests that the minified output has the same content as the pretty-printed one
+forgetest!(minified_docs_same_content, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "src/Vault.sol",
+        r#"
+/// @title The vault
+contract Vault {
+    /// @notice Deposit the assets.
+    /// @param amount The amount of the assets.
+    function deposit(uint256 amount) external { }
+}
+"#,
+    );
+    let read = |file: &str| fs::read_to_string(prj.root().join("docs").join(file)).unwrap();
+
+    let mut outputs = vec![];
+    for minify in [false, true] {
+        cmd.forge_fuse().arg("doc");
+        if minify {
+            cmd.arg("--minify");
+        }
+        cmd.assert_success();
+        outputs.push((
+            read("docs.json"),
+            read("summary.json"),
+            read("src/src/Vault.sol/contract.Vault.md"),
+            read("src/SUMMARY.md"),
+        ));
+    }
+    let (pretty, minified) = (&outputs[0], &outputs[1]);
+    assert!(!minified.0.contains('\n'));
+    for (pretty, minified) in [(&pretty.0, &minified.0), (&pretty.1, &minified.1)] {
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(minified).unwrap()
+        );
+    }
+    assert!(minified.2.contains("Deposit the assets."));
+    assert_eq!((&pretty.2, &pretty.3), (&minified.2, &minified.3));
+});
+
+// tests that `--json` requires an output to print as JSON
+forgetest!(json_requires_output, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file("src/Counter.sol", "contract Counter { function increment() external { } }");
+
+    cmd.args(["doc", "--json"]).assert_err();
+    cmd.forge_fuse().args(["doc", "--json", "--stats"]).assert_success();
+});
+
+// tests that `--since` renders only the pages of the files changed since the git ref
+forgetest!(can_document_changed_since, |prj: TestProject, mut cmd: TestCommand| {
+    cmd.git_init();
+    prj.create_file("src/Vault.sol", "/// @notice The vault.\ncontract Vault { }");
+    prj.create_file("src/Token.sol", "/// @notice The token.\ncontract Token { }");
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=foundry", "-c", "user.email=foundry@local"])
+            .args(args)
+            .current_dir(prj.root())
+            .status()
+            .expect("could not run git");
+        assert!(status.success());
+    };
+    git(&["add", "src"]);
+    git(&["commit", "-m", "sources"]);
+    cmd.args(["doc"]).assert_success();
+
+    let vault = prj.root().join("docs/src/src/Vault.sol/contract.Vault.md");
+    let token = prj.root().join("docs/src/src/Token.sol/contract.Token.md");
+    fs::write(&token, "untouched").unwrap();
+    prj.create_file("src/Vault.sol", "/// @notice The updated vault.\ncontract Vault { }");
+    prj.create_file("src/Pool.sol", "/// @notice The pool.\ncontract Pool { }");
+
+    cmd.forge_fuse().args(["doc", "--since", "HEAD"]).assert_success();
+    assert!(fs::read_to_string(&vault).unwrap().contains("The updated vault."));
+    assert_eq!(fs::read_to_string(&token).unwrap(), "untouched");
+    let pool = prj.root().join("docs/src/src/Pool.sol/contract.Pool.md");
+    assert!(fs::read_to_string(pool).unwrap().contains("The pool."));
+});
+
+// tests that the plan only lists the files the generation would write
+forgetest!(can_plan_written_files, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file("src/Counter.sol", "contract Counter { function increment() external { } }");
+    cmd.args(["doc"]).assert_success();
+
+    // The files of the previous output which aren't generated are left out of the plan
+    prj.create_file("docs/src/notes.md", "# Notes");
+    prj.create_file("src/Vault.sol", "contract Vault { function deposit() external { } }");
+    cmd.forge_fuse().args(["doc", "--dry-run"]);
+    let plan = cmd.stdout_lossy();
+    assert!(plan.contains("mkdir      src/src/Vault.sol\n"));
+    assert!(plan.contains("create     src/src/Vault.sol/contract.Vault.md\n"));
+    assert!(plan.contains("overwrite  src/src/Counter.sol/contract.Counter.md (unchanged)\n"));
+    assert!(!plan.contains("notes.md"));
+    assert!(!prj.root().join("docs/src/src/Vault.sol").exists());
+});
+
+// tests that only the events are printed with json-lines, including the failed run
+forgetest!(can_stream_failed_json_lines, |prj: TestProject, mut cmd: TestCommand| {
+    prj.create_file(
+        "src/Vault.sol",
+        r#"
+contract Vault {
+    /// @param owner The owner.
+    function deposit(uint256 amount) external { }
+}
+"#,
+    );
+    cmd.args(["doc", "--message-format", "json-lines", "--deny-warnings"]);
+    let output = cmd.unchecked_output();
+    assert!(!output.status.success());
+    let events = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(events[0]["event"], "diagnostic");
+    let finished = events.last().unwrap();
+    assert_eq!(finished["event"], "finished");
+    assert_eq!(finished["success"], false);
+    assert_eq!(finished["error"], "1 warning(s) denied");
+
+    // The options printing to stdout are rejected
+    for flag in ["--serve", "--open"] {
+        cmd.forge_fuse().args(["doc", "--message-format", "json-lines", flag]).assert_err();
+    }
+});
@@ -3,7 +3,7 @@ use crate::{term, TestFunctionExt};
 use comfy_table::{presets::ASCII_MARKDOWN, *};
 use ethers_etherscan::contract::Metadata;
 use ethers_solc::{
-    artifacts::{BytecodeObject, ContractBytecodeSome},
+    artifacts::{BytecodeObject, ContractBytecodeSome, StandardJsonCompilerInput},
     remappings::Remapping,
     report::NoReporter,
     Artifact, ArtifactId, FileFilter, Graph, Project, ProjectCompileOutput, ProjectPathsConfig,
@@ -427,6 +427,20 @@ pub async fn compile_from_source(
     Ok((artifact_id, bytecode))
 }
 
+/// Returns the standard json input of the target's compilation unit, with the library paths
+/// relative to the project root, as submitted for the verification.
+pub fn standard_json_input(project: &Project, target: &Path) -> Result<StandardJsonCompilerInput> {
+    let mut input = project.standard_json_input(target)?;
+    input.settings.libraries.libs = input
+        .settings
+        .libraries
+        .libs
+        .into_iter()
+        .map(|(f, libs)| (f.strip_prefix(project.root()).unwrap_or(&f).to_path_buf(), libs))
+        .collect();
+    Ok(input)
+}
+
 /// Creates a [Project] from an Etherscan source.
 pub fn etherscan_project(metadata: &Metadata, target_path: impl AsRef<Path>) -> Result<Project> {
     let target_path = dunce::canonicalize(target_path.as_ref())?;
@@ -1,7 +1,8 @@
 //! Configuration specific to the `forge doc` command and the `forge_doc` package
 
+use crate::Chain;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
 
 /// Contains the config for parsing and rendering docs
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,11 +18,188 @@ pub struct DocConfig {
     /// If none is provided, it defaults to `README.md`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub homepage: Option<PathBuf>,
+    /// Whether to append the "Protocol at a glance" overview to the homepage, i.e. the
+    /// contracts not inherited by any other documented contract and the libraries with
+    /// their notices and deployments, and the links to the appendix pages.
+    ///
+    /// The contracts tagged with `@custom:nodoc-overview` are left out.
+    pub title_page: bool,
     /// The repository url.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub repository: Option<String>,
+    /// Whether to add the "Edit this page" link to each page, pointing to the editor of its
+    /// source on the repository.
+    pub edit_link: bool,
+    /// The template of the edit link, with the `{repository}`, `{branch}`, `{path}` and `{page}`
+    /// placeholders, i.e. the source file and the generated page paths relative to the root.
+    ///
+    /// Use `{page}` instead of `{path}` if the generated pages are committed.
+    pub edit_link_template: String,
+    /// The URL the HTML book is published at, e.g. `https://docs.example.com`.
+    ///
+    /// If set, the `sitemap.xml` of the built HTML pages is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_url: Option<String>,
     /// Globs to ignore
     pub ignore: Vec<String>,
+    /// The maximum number of threads used to parse and render the documents.
+    ///
+    /// If none is provided, it defaults to the available parallelism.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    /// Whether to include the build info page with the compiler settings.
+    pub build_info: bool,
+    /// Whether to write the solc standard json input of each contract to
+    /// `verify/<Contract>.input.json`, linked from the contract page.
+    pub include_standard_json: bool,
+    /// Whether to include the appendix listing the functions with inline assembly.
+    pub assembly_index: bool,
+    /// Whether to write the `docs.json` model without pretty-printing.
+    pub minify: bool,
+    /// The maximum size of the `llms-full.txt` export in bytes.
+    ///
+    /// If none is provided, the export is not truncated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llms_max_bytes: Option<usize>,
+    /// The style of the rendered function signatures.
+    pub signature_style: SignatureStyle,
+    /// The maximum width of the rendered function signatures, the parameters of the longer
+    /// ones are wrapped one per line.
+    ///
+    /// If none is provided, the signatures follow the formatter config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_width: Option<usize>,
+    /// Whether to write the Solidity interface stubs of the documented contracts.
+    pub emit_interface: bool,
+    /// The interface stubs output path.
+    ///
+    /// If none is provided, it defaults to `interfaces` within the doc output path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interfaces_out: Option<PathBuf>,
+    /// The directory of the hand-written contract introductions, with a `<Contract>.md`
+    /// per contract, e.g. `docs-src`.
+    ///
+    /// If none is provided, the `<Source>.sol.md` next to the source file introduces
+    /// the contract named after the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intro_dir: Option<PathBuf>,
+    /// The command each rendered page is piped through before it's written.
+    ///
+    /// The command receives the page path as the last argument and the page content on stdin,
+    /// and writes the transformed content to stdout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_process: Option<String>,
+    /// Whether to document only the externally accessible items, i.e. the external and public
+    /// functions, the public state variables, events, errors and the type definitions.
+    pub api_only: bool,
+    /// Whether to escape the characters significant in MDX, e.g. for Docusaurus.
+    pub mdx: bool,
+    /// Whether to include the deployed addresses of the contracts from the broadcast artifacts.
+    pub deployments: bool,
+    /// The chains to include the deployments on.
+    ///
+    /// If empty, the deployments on all chains are included.
+    pub deployment_chains: Vec<Chain>,
+    /// Whether to embed the ABI JSON of each contract from its compiled artifact.
+    pub embed_abi: bool,
+    /// Whether to show the compiler's gas estimates of the functions from the compiled
+    /// artifacts next to the `@custom:gas` notes.
+    pub gas_estimates: bool,
+    /// Whether to show the log layout of each event, i.e. the topics of the indexed parameters
+    /// and the byte ranges of the others within the data.
+    pub event_layout: bool,
+    /// Whether to list the preconditions of each function inferred from the `require` and
+    /// `if (...) revert` guards at the top level of its body.
+    pub infer_requires: bool,
+    /// The compiled artifacts path the Vyper contracts, the ABI and the gas estimates
+    /// are read from.
+    ///
+    /// If none is provided, it defaults to the `out` of the selected profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<PathBuf>,
+    /// The directory of the ABI artifacts to document without the source, either the foundry
+    /// artifacts or the raw solc metadata files, e.g. of the contracts of the other projects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi_dir: Option<PathBuf>,
+    /// The section of the book the pages of the ABI artifacts are grouped under.
+    ///
+    /// If none is provided, it defaults to the name of the ABI directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi_section: Option<String>,
+    /// The import paths of the well-known bases, matched by suffix, e.g.
+    /// `token/ERC20/ERC20.sol`. The contracts inheriting them note the standard interface
+    /// once, and their overrides only forwarding to the base aren't listed.
+    ///
+    /// If empty, all functions are listed.
+    pub exclude_external_overrides: Vec<String>,
+    /// Whether to validate the rendered pages as CommonMark, reporting the table rows with
+    /// the mismatched number of columns, the unclosed code fences and the empty headings.
+    pub validate_markdown: bool,
+    /// Whether to detect the version the items were introduced in from the git history,
+    /// if the item doesn't have the `@custom:since` tag.
+    pub git_since: bool,
+    /// The maximum depth the fields of the nested structs are expanded to.
+    ///
+    /// The nested structs are only linked if zero.
+    pub struct_depth: usize,
+    /// Whether to show the import alias next to the canonical type name, e.g.
+    /// `Foo (as Bar)` for `import {Foo as Bar} from "./Foo.sol"`.
+    pub show_import_aliases: bool,
+    /// Whether to use the trailing line comment of the parameter, e.g.
+    /// `uint256 amount, // The amount to deposit`, as its description if there is no `@param`.
+    ///
+    /// The inferred descriptions are kept as the `@custom:inferred-param` comments in the model.
+    pub param_comment_fallback: bool,
+    /// Whether the parameters only documented by the inferred trailing comments count as
+    /// documented with `deny_warnings`.
+    ///
+    /// Otherwise they're reported as the warnings when the warnings are denied.
+    pub strict_accept_inline: bool,
+    /// The signature databases the selectors are registered in.
+    pub selectors: DocSelectorsConfig,
+    /// The casing of the generated page paths, e.g. `kebab` for `src/erc20-permit.sol/...`.
+    pub filename_case: FilenameCase,
+    /// The order of the directories and the files within each level of the summary.
+    pub summary_order: SummaryOrder,
+    /// Whether to write all pages into a single directory, named after their full paths,
+    /// e.g. `tokens_erc20_Token.md` for the `Token` contract of `src/tokens/erc20/Token.sol`.
+    pub flat: bool,
+    /// The redirects of the moved pages, from the old to the new page path relative to
+    /// the book source directory, e.g.
+    /// `"src/Vault.sol/contract.Vault.md" = "src/core/Vault.sol/contract.Vault.md"`.
+    ///
+    /// The pages moved since the previous generation are redirected as well.
+    pub redirects: BTreeMap<PathBuf, PathBuf>,
+    /// The format of the redirects file for the static site hosts.
+    ///
+    /// If none is provided, only the redirect pages are written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_format: Option<RedirectFormat>,
+    /// Whether to fail the generation on the warnings, e.g. the `@param` not matching
+    /// any parameter, the missing `@return` or the malformed custom tag.
+    pub deny_warnings: bool,
+    /// Whether to fail the generation if no contracts are documented, e.g. due to the wrong
+    /// `src` path or the `ignore` globs matching all sources.
+    pub fail_on_empty: bool,
+    /// The maximum number of the members of each section of the contract page, e.g. the
+    /// functions, the rest are moved to the continuation page of the section.
+    ///
+    /// `0` disables the limit.
+    pub section_limit: usize,
+    /// The page size in bytes above which the largest pages are reported in a warning.
+    ///
+    /// `0` disables the warning.
+    pub page_size_warning: usize,
+    /// The page size in bytes above which each page is reported in a warning, failing
+    /// the generation with `--deny-warnings`.
+    ///
+    /// `0` disables the limit.
+    pub max_page_size: usize,
+    /// The maximum number of the characters of the constant values written on the pages,
+    /// the longer values are cut noting the number of the omitted characters.
+    ///
+    /// `0` disables the limit.
+    pub max_value_chars: usize,
 }
 
 impl Default for DocConfig {
@@ -30,9 +208,221 @@ impl Default for DocConfig {
             out: PathBuf::from("docs"),
             book: PathBuf::from("book.toml"),
             homepage: Some(PathBuf::from("README.md")),
+            title_page: false,
             title: String::default(),
             repository: None,
+            edit_link: false,
+            edit_link_template: "{repository}/edit/{branch}/{path}".to_owned(),
+            site_url: None,
             ignore: Vec::default(),
+            jobs: None,
+            build_info: false,
+            include_standard_json: false,
+            assembly_index: false,
+            minify: false,
+            llms_max_bytes: None,
+            signature_style: SignatureStyle::default(),
+            signature_width: None,
+            emit_interface: false,
+            interfaces_out: None,
+            intro_dir: None,
+            post_process: None,
+            api_only: false,
+            mdx: false,
+            deployments: false,
+            deployment_chains: Vec::default(),
+            embed_abi: false,
+            gas_estimates: false,
+            event_layout: false,
+            infer_requires: false,
+            artifacts: None,
+            abi_dir: None,
+            abi_section: None,
+            exclude_external_overrides: Vec::default(),
+            validate_markdown: false,
+            git_since: false,
+            struct_depth: 0,
+            show_import_aliases: false,
+            param_comment_fallback: false,
+            strict_accept_inline: false,
+            selectors: DocSelectorsConfig::default(),
+            filename_case: FilenameCase::default(),
+            summary_order: SummaryOrder::default(),
+            flat: false,
+            redirects: BTreeMap::default(),
+            redirect_format: None,
+            deny_warnings: false,
+            fail_on_empty: false,
+            section_limit: 250,
+            page_size_warning: 1024 * 1024,
+            max_page_size: 5 * 1024 * 1024,
+            max_value_chars: 256,
         }
     }
 }
+
+/// The format of the redirects file for the static site hosts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedirectFormat {
+    /// The `_redirects` file in the book, e.g. for Netlify or Cloudflare Pages.
+    Redirects,
+    /// The `netlify.toml` fragment with the `[[redirects]]` rules.
+    Netlify,
+}
+
+/// The casing of the generated page paths.
+///
+/// It's applied to the file names of the pages and the directories they're written to,
+/// keeping the extensions, e.g. `src/ERC20Permit.sol/contract.ERC20Permit.md` becomes
+/// `src/erc20-permit.sol/contract.erc20-permit.md` in kebab case.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilenameCase {
+    /// The names of the source files and items as they are.
+    #[default]
+    Preserve,
+    /// The lowercase names, e.g. `erc20permit`.
+    Lower,
+    /// The lowercase words separated by hyphens, e.g. `erc20-permit`.
+    Kebab,
+    /// The lowercase words separated by underscores, e.g. `erc20_permit`.
+    Snake,
+}
+
+impl FromStr for FilenameCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(Self::Preserve),
+            "lower" => Ok(Self::Lower),
+            "kebab" => Ok(Self::Kebab),
+            "snake" => Ok(Self::Snake),
+            _ => Err(format!(
+                "unknown filename case `{s}`, expected one of `preserve`, `lower`, `kebab`, `snake`"
+            )),
+        }
+    }
+}
+
+/// The order of the directories and the files within each level of the summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SummaryOrder {
+    /// The directories before the files, each sorted by name.
+    #[default]
+    DirectoriesFirst,
+    /// The files before the directories, each sorted by name.
+    FilesFirst,
+    /// The directories and the files sorted together by name.
+    Mixed,
+}
+
+impl FromStr for SummaryOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "directories-first" => Ok(Self::DirectoriesFirst),
+            "files-first" => Ok(Self::FilesFirst),
+            "mixed" => Ok(Self::Mixed),
+            _ => Err(format!(
+                "unknown summary order `{s}`, expected one of `directories-first`, \
+                 `files-first`, `mixed`"
+            )),
+        }
+    }
+}
+
+/// The config of the signature databases the selectors are registered in
+/// by `forge doc --register-selectors`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocSelectorsConfig {
+    /// The databases to submit the signatures to.
+    pub databases: Vec<SelectorDatabase>,
+    /// The maximum number of retries of the rate limited or failed requests.
+    pub retries: u32,
+}
+
+impl Default for DocSelectorsConfig {
+    fn default() -> Self {
+        Self {
+            databases: vec![
+                SelectorDatabase::new(SignatureDatabase::Openchain),
+                SelectorDatabase::new(SignatureDatabase::Fourbyte),
+            ],
+            retries: 3,
+        }
+    }
+}
+
+/// The signature database endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectorDatabase {
+    /// The database kind, which determines the API used to submit the signatures.
+    pub kind: SignatureDatabase,
+    /// The API url.
+    ///
+    /// If none is provided, it defaults to the public API of the database.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The API key, sent as the `Authorization` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl SelectorDatabase {
+    /// Create the endpoint of the public API of the database.
+    pub fn new(kind: SignatureDatabase) -> Self {
+        Self { kind, url: None, key: None }
+    }
+
+    /// Returns the configured API url or the public API url of the database.
+    pub fn url(&self) -> &str {
+        self.url.as_deref().unwrap_or_else(|| self.kind.default_url())
+    }
+}
+
+/// The public signature database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureDatabase {
+    /// The openchain.xyz signature database.
+    Openchain,
+    /// The 4byte.directory signature database.
+    #[serde(rename = "4byte")]
+    Fourbyte,
+}
+
+impl SignatureDatabase {
+    /// Returns the url of the public API of the database.
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            Self::Openchain => "https://api.openchain.xyz/signature-database/v1/import",
+            Self::Fourbyte => "https://www.4byte.directory/api/v1",
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Openchain => f.write_str("openchain.xyz"),
+            Self::Fourbyte => f.write_str("4byte.directory"),
+        }
+    }
+}
+
+/// The style of the function signatures rendered in the documentation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStyle {
+    /// The copy-pasteable Solidity declaration with the parameter names,
+    /// internal types and named returns, e.g.
+    /// `function transfer(address to, uint256 amount) external returns (bool success);`
+    #[default]
+    Solidity,
+    /// The canonical ABI types, e.g. `function transfer(address,uint256) external returns (bool)`
+    Abi,
+}
@@ -65,7 +65,10 @@ pub mod error;
 pub use error::SolidityErrorCode;
 
 pub mod doc;
-pub use doc::DocConfig;
+pub use doc::{
+    DocConfig, DocSelectorsConfig, FilenameCase, RedirectFormat, SelectorDatabase,
+    SignatureDatabase, SignatureStyle, SummaryOrder,
+};
 
 mod warning;
 pub use warning::*;
@@ -1029,6 +1032,8 @@ impl Config {
     ///   1. [`Config::default()`] (see [defaults](#defaults))
     ///   2. `foundry.toml` _or_ filename in `FOUNDRY_CONFIG` environment variable
     ///   3. `FOUNDRY_` prefixed environment variables
+    ///   4. `FORGE_DOC_` prefixed environment variables for the `[doc]` section, with
+    ///      `FORGE_DOC_STRICT` for `deny_warnings`
     ///
     /// The profile selected is the value set in the `FOUNDRY_PROFILE`
     /// environment variable. If it is not set, it defaults to `default`.
@@ -1557,6 +1562,15 @@ impl From<Config> for Figment {
                     })
                     .global(),
             )
+            .merge(
+                Env::prefixed("FORGE_DOC_")
+                    .map(|key| match key.as_str() {
+                        // `FORGE_DOC_STRICT` denies the warnings like `--deny-warnings`
+                        key if key.eq_ignore_ascii_case("strict") => "doc.deny_warnings".into(),
+                        key => format!("doc.{key}").into(),
+                    })
+                    .global(),
+            )
             .select(profile.clone());
 
         // we try to merge remappings after we've merged all other providers, this prevents
@@ -3707,6 +3721,59 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_doc_profile_sections() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [doc]
+                out = "docs"
+                title = "Project"
+
+                [profile.docs.doc]
+                out = "docs-site"
+                jobs = 2
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.doc,
+                DocConfig {
+                    out: "docs".into(),
+                    title: "Project".to_string(),
+                    ..Default::default()
+                }
+            );
+
+            jail.set_env("FOUNDRY_PROFILE", "docs");
+            let config = Config::load();
+            assert_eq!(
+                config.doc,
+                DocConfig {
+                    out: "docs-site".into(),
+                    title: "Project".to_string(),
+                    jobs: Some(2),
+                    ..Default::default()
+                }
+            );
+
+            jail.set_env("FOUNDRY_DOC_JOBS", "4");
+            jail.set_env("FORGE_DOC_OUT", "docs-env");
+            let config = Config::load();
+            assert_eq!(config.doc.out, PathBuf::from("docs-env"));
+            assert_eq!(config.doc.jobs, Some(4));
+            assert!(!config.doc.deny_warnings);
+
+            jail.set_env("FORGE_DOC_STRICT", "true");
+            let config = Config::load();
+            assert!(config.doc.deny_warnings);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn can_handle_deviating_dapp_aliases() {
         figment::Jail::expect_with(|jail| {
@@ -0,0 +1,236 @@
+//! The heading anchors of the contract pages.
+
+use crate::{
+    helpers::{immutable_assignments, is_initializer},
+    preprocessor::continuation_path,
+    writer::heading_anchor,
+    ParseItem,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// The registry of the heading anchors of the page.
+///
+/// The anchors are assigned in the page order the way mdbook deduplicates them, i.e. the
+/// heading repeating an earlier one, ignoring the case, is suffixed with the number of the
+/// earlier ones, e.g. `deposit-1` for the `deposit` function after the `Deposit` event.
+#[derive(Debug, Default)]
+pub(crate) struct PageAnchors {
+    /// The number of the headings with the anchor.
+    counts: HashMap<String, usize>,
+    /// The anchors of the members by the section title and the member name, and whether
+    /// the member was moved to the continuation page of the section.
+    members: HashMap<(&'static str, String), Vec<(String, bool)>>,
+}
+
+impl PageAnchors {
+    /// Assign the anchor of the next heading of the page.
+    pub(crate) fn assign(&mut self, heading: &str) -> String {
+        let anchor = heading_anchor(heading);
+        let count = self.counts.entry(anchor.clone()).or_default();
+        let assigned = if *count == 0 { anchor } else { format!("{anchor}-{count}") };
+        *count += 1;
+        assigned
+    }
+
+    /// Assign the anchor of the next member heading of the section.
+    fn assign_member(&mut self, section: &'static str, name: &str) {
+        let anchor = self.assign(name);
+        self.members.entry((section, name.to_owned())).or_default().push((anchor, false));
+    }
+
+    /// Returns the anchor of the first heading of the member of the section,
+    /// e.g. the first overload of the function.
+    pub(crate) fn get(&self, section: &str, name: &str) -> Option<&str> {
+        self.members.get(&(section, name.to_owned()))?.first().map(|(anchor, _)| anchor.as_str())
+    }
+
+    /// Returns the page of the first heading of the member of the section with its anchor,
+    /// i.e. the contract page or the continuation page of the section if the member was moved.
+    pub(crate) fn locate(&self, page: &Path, section: &str, name: &str) -> Option<(PathBuf, &str)> {
+        let (anchor, continued) = self.members.get(&(section, name.to_owned()))?.first()?;
+        let page = if *continued { continuation_path(page, section) } else { page.to_path_buf() };
+        Some((page, anchor))
+    }
+
+    /// Assign the anchors of the contract page in a single pass over its headings.
+    /// See [PageAnchors::contract_pages].
+    pub(crate) fn contract(item: &ParseItem) -> Self {
+        Self::contract_pages(item, 0)
+    }
+
+    /// Assign the anchors of the contract page and of the continuation pages of the sections
+    /// cut at the section limit, `0` for no limit, in a single pass over their headings.
+    ///
+    /// The optional sections before the members, e.g. `Usage` or `Deployments`, have titles
+    /// that never match an identifier, so only the title, the initialization and the member
+    /// sections are registered. The members left out of the page, e.g. the functions of the
+    /// standard bases, are assumed to be written. The members over the section limit are
+    /// registered through the continuation page of the section, see [PageAnchors::locate].
+    pub(crate) fn contract_pages(item: &ParseItem, section_limit: usize) -> Self {
+        let mut anchors = Self::default();
+        let contract = match item.as_contract() {
+            Some(contract) => contract,
+            None => return anchors,
+        };
+        let title = &contract.name.safe_unwrap().name;
+        anchors.assign(title);
+
+        let functions = item.functions().unwrap_or_default();
+        let function_name = |func: &solang_parser::pt::FunctionDefinition| {
+            func.name.as_ref().map_or(func.ty.to_string(), |name| name.name.clone())
+        };
+        let initializers = functions
+            .iter()
+            .filter(|(func, _, _)| {
+                is_initializer(func) || func.name.as_ref().map_or(false, |n| n.name == "initialize")
+            })
+            .collect::<Vec<_>>();
+        if !initializers.is_empty() || !immutable_assignments(item).is_empty() {
+            anchors.assign("Initialization");
+        }
+        for (func, _, _) in initializers {
+            anchors.assign_member("Initialization", &function_name(func));
+        }
+
+        let sections: [(&'static str, Vec<String>); 6] = [
+            (
+                "State Variables",
+                item.variables()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(var, _, _)| var.name.safe_unwrap().name.clone())
+                    .collect(),
+            ),
+            ("Functions", functions.iter().map(|(func, _, _)| function_name(func)).collect()),
+            (
+                "Events",
+                item.events()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(event, _, _)| event.name.safe_unwrap().name.clone())
+                    .collect(),
+            ),
+            (
+                "Errors",
+                item.errors()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(error, _, _)| error.name.safe_unwrap().name.clone())
+                    .collect(),
+            ),
+            (
+                "Structs",
+                item.structs()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(def, _, _)| def.name.safe_unwrap().name.clone())
+                    .collect(),
+            ),
+            (
+                "Enums",
+                item.enums()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(def, _, _)| def.name.safe_unwrap().name.clone())
+                    .collect(),
+            ),
+        ];
+        for (section, names) in sections {
+            if names.is_empty() {
+                continue
+            }
+            anchors.assign(section);
+            let shown = if section_limit == 0 { names.len() } else { section_limit };
+            let (shown, moved) = names.split_at(shown.min(names.len()));
+            for name in shown {
+                anchors.assign_member(section, name);
+            }
+
+            // The continuation page has its own title and the section heading
+            if !moved.is_empty() {
+                let mut continuation = Self::default();
+                continuation.assign(&format!("{title} (continued)"));
+                continuation.assign(section);
+                for name in moved {
+                    let anchor = continuation.assign(name);
+                    anchors
+                        .members
+                        .entry((section, name.clone()))
+                        .or_default()
+                        .push((anchor, true));
+                }
+            }
+        }
+        anchors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use forge_fmt::Visitable;
+
+    #[test]
+    fn case_insensitive_anchors() {
+        let src = r#"
+            contract Vault {
+                event Deposit(uint256 amount);
+                struct Status { uint256 total; }
+                function deposit() external { }
+                function deposit(uint256 amount) external { }
+                function vault() external { }
+                function status() external { }
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+        let mut doc = Parser::new(comments, src.to_owned());
+        source.visit(&mut doc).expect("failed to visit source");
+        let anchors = PageAnchors::contract(&doc.items().remove(0));
+
+        // The functions are written before the events and the structs
+        assert_eq!(anchors.get("Functions", "deposit"), Some("deposit"));
+        assert_eq!(anchors.get("Events", "Deposit"), Some("deposit-2"));
+        assert_eq!(anchors.get("Functions", "vault"), Some("vault-1"));
+        assert_eq!(anchors.get("Functions", "status"), Some("status"));
+        assert_eq!(anchors.get("Structs", "Status"), Some("status-1"));
+        assert_eq!(anchors.get("Events", "Withdraw"), None);
+    }
+
+    #[test]
+    fn continuation_page_anchors() {
+        let src = r#"
+            contract Vault {
+                event Deposit(uint256 amount);
+                function withdraw() external { }
+                function deposit() external { }
+                function vault() external { }
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+        let mut doc = Parser::new(comments, src.to_owned());
+        source.visit(&mut doc).expect("failed to visit source");
+        let anchors = PageAnchors::contract_pages(&doc.items().remove(0), 1);
+        let page = Path::new("src/Vault.sol/contract.Vault.md");
+        let continuation = PathBuf::from("src/Vault.sol/contract.Vault.functions.md");
+
+        // The moved functions don't take the anchors of the contract page
+        assert_eq!(
+            anchors.locate(page, "Functions", "withdraw"),
+            Some((page.to_path_buf(), "withdraw"))
+        );
+        assert_eq!(
+            anchors.locate(page, "Functions", "deposit"),
+            Some((continuation.clone(), "deposit"))
+        );
+        assert_eq!(anchors.locate(page, "Functions", "vault"), Some((continuation, "vault")));
+        assert_eq!(
+            anchors.locate(page, "Events", "Deposit"),
+            Some((page.to_path_buf(), "deposit"))
+        );
+    }
+}
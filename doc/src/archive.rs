@@ -0,0 +1,199 @@
+//! Packing the generated documentation into a reproducible archive.
+
+use crate::{builder::canonicalize, output::OutputFiles};
+use flate2::{write::GzEncoder, Compression};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::FileOptions, CompressionMethod, DateTime, ZipWriter};
+
+/// The archive format, inferred from the archive file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Uncompressed tar archive (`.tar`).
+    Tar,
+    /// Gzip compressed tar archive (`.tar.gz` or `.tgz`).
+    TarGz,
+    /// Zip archive (`.zip`).
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Infer the archive format from the path extension.
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Ok(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else {
+            eyre::bail!(
+                "unsupported archive extension for {}, expected one of `.tar`, `.tar.gz`, `.tgz` or `.zip`",
+                path.display()
+            )
+        }
+    }
+}
+
+/// Pack all files of the directory into the archive.
+///
+/// The entries are sorted by their path relative to the directory, and the timestamps
+/// and permissions are fixed so that the archive hash is reproducible.
+/// The archive written into the directory isn't packed into itself, however its path is spelled.
+pub fn write_archive(dir: &Path, archive: &Path) -> eyre::Result<()> {
+    write_archive_with(&OutputFiles::default(), dir, archive)
+}
+
+/// Pack all files of the directory into the archive written with the output files.
+/// See [write_archive].
+pub(crate) fn write_archive_with(
+    output: &OutputFiles,
+    dir: &Path,
+    archive: &Path,
+) -> eyre::Result<()> {
+    let format = ArchiveFormat::from_path(archive)?;
+
+    let canonical_archive = canonicalize(archive);
+    let mut files = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| canonicalize(path) != canonical_archive)
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+
+    let entries = files
+        .iter()
+        .map(|path| {
+            let name = path
+                .strip_prefix(dir)?
+                .iter()
+                .map(|component| component.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Ok((name, fs::read(path)?))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    if let Some(parent) = archive.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        output.create_dir_all(parent)?;
+    }
+    let file = output.create(archive)?;
+    match format {
+        ArchiveFormat::Tar => write_tar(file, &entries)?,
+        ArchiveFormat::TarGz => {
+            // The default gzip header has the modification time zeroed.
+            let encoder = write_tar(GzEncoder::new(file, Compression::default()), &entries)?;
+            encoder.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(file);
+            let options = FileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .last_modified_time(DateTime::default())
+                .unix_permissions(0o644);
+            for (name, content) in entries.iter() {
+                zip.start_file(name, options)?;
+                zip.write_all(content)?;
+            }
+            zip.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The tar block size.
+const TAR_BLOCK: usize = 512;
+
+/// Write the entries as a ustar archive with zeroed timestamps and ownership.
+fn write_tar<W: Write>(mut writer: W, entries: &[(String, Vec<u8>)]) -> io::Result<W> {
+    for (name, content) in entries {
+        writer.write_all(&tar_header(name, content.len())?)?;
+        writer.write_all(content)?;
+        let padding = (TAR_BLOCK - content.len() % TAR_BLOCK) % TAR_BLOCK;
+        writer.write_all(&vec![0; padding])?;
+    }
+    // The archive ends with two empty blocks.
+    writer.write_all(&[0; TAR_BLOCK * 2])?;
+    Ok(writer)
+}
+
+/// Build the ustar header block for a regular file.
+fn tar_header(name: &str, size: usize) -> io::Result<[u8; TAR_BLOCK]> {
+    // Names longer than 100 bytes are split into the prefix and the name at a path separator.
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name)
+    } else {
+        name.char_indices()
+            .filter(|(idx, ch)| *ch == '/' && *idx <= 155 && name.len() - idx - 1 <= 100)
+            .map(|(idx, _)| (&name[..idx], &name[idx + 1..]))
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("path is too long: {name}"))
+            })?
+    };
+
+    let mut header = [0u8; TAR_BLOCK];
+    let mut set = |offset: usize, value: &[u8]| {
+        header[offset..offset + value.len()].copy_from_slice(value);
+    };
+    set(0, name.as_bytes());
+    set(100, b"0000644\0");
+    set(108, b"0000000\0");
+    set(116, b"0000000\0");
+    set(124, format!("{size:011o}\0").as_bytes());
+    set(136, b"00000000000\0");
+    set(148, b"        ");
+    set(156, b"0");
+    set(257, b"ustar\0");
+    set(263, b"00");
+    set(345, prefix.as_bytes());
+
+    let checksum = header.iter().map(|byte| *byte as u32).sum::<u32>();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn reproducible_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("src/tokens")).unwrap();
+        fs::write(docs.join("book.toml"), "[book]").unwrap();
+        fs::write(docs.join("src/tokens/Token.md"), "# Token").unwrap();
+        fs::write(docs.join("src/README.md"), "# Home").unwrap();
+
+        for name in ["docs.tar", "docs.tar.gz", "docs.zip"] {
+            let first = dir.path().join(format!("first.{name}"));
+            write_archive(&docs, &first).unwrap();
+
+            // The rewritten files have the newer modification times
+            thread::sleep(Duration::from_millis(10));
+            fs::write(docs.join("src/README.md"), "# Home").unwrap();
+            let second = dir.path().join(format!("second.{name}"));
+            write_archive(&docs, &second).unwrap();
+            assert_eq!(fs::read(&first).unwrap(), fs::read(&second).unwrap(), "{name}");
+
+            // The archive within the directory isn't packed into the next one
+            let inner = docs.join("src/..").join(name);
+            write_archive(&docs, &inner).unwrap();
+            write_archive(&docs, &inner).unwrap();
+            assert_eq!(fs::read(&inner).unwrap(), fs::read(&first).unwrap(), "{name}");
+            fs::remove_file(docs.join(name)).unwrap();
+        }
+
+        assert!(ArchiveFormat::from_path(Path::new("docs.rar")).is_err());
+    }
+}
@@ -0,0 +1,370 @@
+//! The contracts documented from the ABI and the natspec of their compiled artifacts,
+//! without the source.
+
+use serde_json::Value;
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// The artifact file extension.
+pub(crate) const ARTIFACT_EXT: &str = "json";
+
+/// Returns true if the path is the artifact file.
+pub(crate) fn is_artifact(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == ARTIFACT_EXT)
+}
+
+/// Returns the artifact files in the directory, skipping the foundry build info.
+pub(crate) fn artifact_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(dir)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != "build-info")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_artifact(entry.path()))
+        .map(|entry| entry.into_path())
+}
+
+/// Returns the compilation target of the artifact file, e.g. `src/Vault.sol:Vault`, so the
+/// artifacts of the same contract, e.g. the foundry artifact and the raw solc metadata, or the
+/// artifacts of the compiler versions, are documented once.
+/// Returns [None] if the file isn't the artifact or has no compilation target.
+pub(crate) fn artifact_target(path: &Path) -> Option<String> {
+    let artifact = read_json(path)?;
+    let (source, name) = compilation_target(&artifact)?;
+    Some(format!("{source}:{name}"))
+}
+
+/// Returns the Solidity declaration of the contract built from the artifact file, either
+/// the foundry artifact, e.g. `out/Vault.sol/Vault.json`, or the raw solc metadata.
+///
+/// The contract is named after the compilation target of the metadata, falling back to
+/// the file name, e.g. `Vault` for `Vault.json` or `Vault.metadata.json`.
+/// Returns [None] if the file isn't the artifact with the ABI.
+pub(crate) fn artifact_declaration(path: &Path) -> Option<String> {
+    let artifact = read_json(path)?;
+    let name = match compilation_target(&artifact) {
+        Some((_, name)) => name.to_owned(),
+        None => path.file_name()?.to_str()?.split('.').next()?.to_owned(),
+    };
+    abi_declaration(&name, &artifact)
+}
+
+/// Returns the JSON content of the artifact file.
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Returns the source path and the contract name of the compilation target of the metadata.
+fn compilation_target(artifact: &Value) -> Option<(&str, &str)> {
+    ["/metadata/settings/compilationTarget", "/settings/compilationTarget"].iter().find_map(
+        |pointer| {
+            let (source, name) = artifact.pointer(pointer)?.as_object()?.iter().next()?;
+            Some((source.as_str(), name.as_str()?))
+        },
+    )
+}
+
+/// Returns the Solidity declaration of the contract built from its ABI and natspec,
+/// so it's parsed and documented like the declared contracts. The functions are declared
+/// `external`, as the ABI only describes the external interface, and the events and the errors
+/// as they're emitted, e.g. the anonymous events `anonymous`.
+///
+/// The ABI is read from the artifact or the `output` of the solc metadata, and the `devdoc`
+/// and `userdoc` from the artifact or its metadata, and may be missing.
+/// Returns [None] if the ABI is missing.
+pub(crate) fn abi_declaration(name: &str, artifact: &Value) -> Option<String> {
+    let natspec = |key: &str| {
+        artifact
+            .get(key)
+            .or_else(|| artifact.pointer(&format!("/metadata/output/{key}")))
+            .or_else(|| artifact.pointer(&format!("/output/{key}")))
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let (devdoc, userdoc) = (natspec("devdoc"), natspec("userdoc"));
+
+    let mut out = String::new();
+    let contract_docs = [
+        ("title", text(&devdoc["title"])),
+        ("author", text(&devdoc["author"])),
+        ("notice", text(&userdoc["notice"])),
+        ("dev", text(&devdoc["details"])),
+    ];
+    write_natspec(&mut out, "", contract_docs.iter().map(|(tag, doc)| (*tag, doc.as_deref())));
+    writeln!(out, "contract {name} {{").ok()?;
+
+    let abi = artifact.get("abi").or_else(|| artifact.pointer("/output/abi"))?.as_array()?;
+    for entry in abi {
+        let params = |key: &str| entry[key].as_array().cloned().unwrap_or_default();
+        let (inputs, outputs) = (params("inputs"), params("outputs"));
+        let mutability = match entry["stateMutability"].as_str() {
+            Some(mutability @ ("view" | "pure" | "payable")) => format!(" {mutability}"),
+            _ => String::new(),
+        };
+        let declaration = match entry["type"].as_str().unwrap_or_default() {
+            "function" => {
+                let fn_name = entry["name"].as_str().unwrap_or_default();
+                let returns = if outputs.is_empty() {
+                    String::new()
+                } else {
+                    format!(" returns ({})", parameters(&outputs, true))
+                };
+                format!(
+                    "function {fn_name}({}) external{mutability}{returns} {{}}",
+                    parameters(&inputs, true)
+                )
+            }
+            "constructor" => {
+                format!("constructor({}){mutability} {{}}", parameters(&inputs, true))
+            }
+            "fallback" => format!("fallback() external{mutability} {{}}"),
+            "receive" => "receive() external payable {}".to_owned(),
+            "event" => {
+                let event_name = entry["name"].as_str().unwrap_or_default();
+                let anonymous = if entry["anonymous"].as_bool().unwrap_or_default() {
+                    " anonymous"
+                } else {
+                    ""
+                };
+                format!("event {event_name}({}){anonymous};", parameters(&inputs, false))
+            }
+            "error" => {
+                let error_name = entry["name"].as_str().unwrap_or_default();
+                format!("error {error_name}({});", parameters(&inputs, false))
+            }
+            _ => continue,
+        };
+
+        // The natspec is keyed by the canonical signature
+        let signature = format!(
+            "{}({})",
+            entry["name"].as_str().unwrap_or_default(),
+            inputs.iter().map(canonical_type).collect::<Vec<_>>().join(",")
+        );
+        let (dev, user) = match entry["type"].as_str() {
+            Some("event") => (&devdoc["events"][&signature], &userdoc["events"][&signature]),
+            // The errors are documented per declaration, as they may be declared more than once
+            Some("error") => (&devdoc["errors"][&signature][0], &userdoc["errors"][&signature][0]),
+            Some("constructor") => {
+                (&devdoc["methods"]["constructor"], &userdoc["methods"]["constructor"])
+            }
+            _ => (&devdoc["methods"][&signature], &userdoc["methods"][&signature]),
+        };
+        let mut docs = vec![
+            ("notice", text(&user["notice"]).or_else(|| text(user))),
+            ("dev", text(&dev["details"])),
+        ];
+        if let Some(params) = dev["params"].as_object() {
+            for (param, doc) in params.iter() {
+                docs.push(("param", text(doc).map(|doc| format!("{param} {doc}"))));
+            }
+        }
+        match dev["returns"] {
+            Value::Object(ref returns) => {
+                docs.extend(returns.values().map(|doc| ("return", text(doc))));
+            }
+            ref returns => docs.push(("return", text(returns))),
+        }
+        write_natspec(&mut out, "    ", docs.iter().map(|(tag, doc)| (*tag, doc.as_deref())));
+        writeln!(out, "    {declaration}").ok()?;
+    }
+
+    out.push_str("}\n");
+    Some(out)
+}
+
+/// Returns the trimmed non-empty text of the natspec value.
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(str::trim).filter(|text| !text.is_empty()).map(str::to_owned)
+}
+
+/// Write the natspec comment lines of the tags, skipping the missing ones.
+fn write_natspec<'a>(
+    out: &mut String,
+    indent: &str,
+    docs: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+) {
+    for (tag, doc) in docs {
+        if let Some(doc) = doc {
+            for (i, line) in doc.lines().enumerate() {
+                match i {
+                    0 => out.push_str(&format!("{indent}/// @{tag} {line}\n")),
+                    _ => out.push_str(&format!("{indent}/// {line}\n")),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the Solidity parameter list of the ABI parameters, with the `memory` location of
+/// the reference types unless the parameters are of the event or the error.
+/// The structs are named after their internal type, e.g. `Position` for `struct Vault.Position`.
+fn parameters(params: &[Value], in_memory: bool) -> String {
+    params
+        .iter()
+        .map(|param| {
+            let ty = param["type"].as_str().unwrap_or_default();
+            let ty = match ty.strip_prefix("tuple") {
+                Some(suffix) => {
+                    let name = param["internalType"]
+                        .as_str()
+                        .and_then(|internal| internal.strip_prefix("struct "))
+                        .map(|name| name.split(['[', ' ']).next().unwrap_or(name));
+                    let name = name.map_or("tuple", |name| name.rsplit('.').next().unwrap_or(name));
+                    format!("{name}{suffix}")
+                }
+                None => ty.to_owned(),
+            };
+            let location = match param["type"].as_str().unwrap_or_default() {
+                _ if !in_memory => "",
+                ty if ty.starts_with("tuple") || ty.ends_with(']') => " memory",
+                "string" | "bytes" => " memory",
+                _ => "",
+            };
+            let indexed =
+                if param["indexed"].as_bool().unwrap_or_default() { " indexed" } else { "" };
+            match param["name"].as_str().filter(|name| !name.is_empty()) {
+                Some(name) => format!("{ty}{indexed}{location} {name}"),
+                None => format!("{ty}{indexed}{location}"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the canonical ABI type of the parameter, expanding the tuples.
+fn canonical_type(param: &Value) -> String {
+    let ty = param["type"].as_str().unwrap_or_default();
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => {
+            let components = param["components"].as_array().cloned().unwrap_or_default();
+            let components = components.iter().map(canonical_type).collect::<Vec<_>>();
+            format!("({}){suffix}", components.join(","))
+        }
+        None => ty.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        fs::create_dir_all(dir.join("Vault.sol")).unwrap();
+        fs::create_dir_all(dir.join("build-info")).unwrap();
+        fs::write(
+            dir.join("Vault.sol/Vault.json"),
+            r#"{
+                "abi": [{"type": "function", "name": "total", "stateMutability": "view",
+                         "inputs": [], "outputs": [{"name": "", "type": "uint256"}]}],
+                "metadata": {
+                    "settings": {"compilationTarget": {"src/Vault.sol": "Vault"}},
+                    "output": {"userdoc": {"notice": "Holds the deposits."}}
+                }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("Token.metadata.json"),
+            r#"{
+                "settings": {"compilationTarget": {"src/Token.sol": "Token"}},
+                "output": {
+                    "abi": [{"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+                        {"name": "to", "type": "address", "indexed": true}
+                    ]}],
+                    "devdoc": {"events": {"Transfer(address)": {"details": "Moved."}}}
+                }
+            }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("build-info/a1b2.json"), "{}").unwrap();
+
+        assert_eq!(
+            artifact_files(dir).collect::<Vec<_>>(),
+            vec![dir.join("Token.metadata.json"), dir.join("Vault.sol/Vault.json")]
+        );
+        assert_eq!(
+            artifact_declaration(&dir.join("Vault.sol/Vault.json")).unwrap(),
+            "/// @notice Holds the deposits.\n\
+             contract Vault {\n    \
+             function total() external view returns (uint256) {}\n\
+             }\n"
+        );
+        assert_eq!(
+            artifact_declaration(&dir.join("Token.metadata.json")).unwrap(),
+            "contract Token {\n    \
+             /// @dev Moved.\n    \
+             event Transfer(address indexed to);\n\
+             }\n"
+        );
+
+        // The files without the ABI aren't documented
+        assert_eq!(artifact_declaration(&dir.join("build-info/a1b2.json")), None);
+    }
+
+    #[test]
+    fn errors_and_anonymous_events() {
+        let artifact = serde_json::json!({
+            "abi": [
+                {"type": "error", "name": "InsufficientBalance", "inputs": [
+                    {"name": "available", "type": "uint256"},
+                    {"name": "required", "type": "uint256"}
+                ]},
+                {"type": "event", "name": "Sync", "anonymous": true, "inputs": [
+                    {"name": "reserve", "type": "uint256", "indexed": false}
+                ]}
+            ],
+            "devdoc": {"errors": {"InsufficientBalance(uint256,uint256)": [
+                {"details": "Raised on the withdrawal.", "params": {"available": "The balance."}}
+            ]}},
+            "userdoc": {"errors": {"InsufficientBalance(uint256,uint256)": [
+                {"notice": "The balance is too low."}
+            ]}}
+        });
+        let declaration = abi_declaration("Pair", &artifact).unwrap();
+        assert_eq!(
+            declaration,
+            "contract Pair {\n    \
+             /// @notice The balance is too low.\n    \
+             /// @dev Raised on the withdrawal.\n    \
+             /// @param available The balance.\n    \
+             error InsufficientBalance(uint256 available, uint256 required);\n    \
+             event Sync(uint256 reserve) anonymous;\n\
+             }\n"
+        );
+        assert!(solang_parser::parse(&declaration, 0).is_ok());
+    }
+
+    #[test]
+    fn artifact_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        fs::write(
+            dir.join("Vault.json"),
+            r#"{"abi": [],
+                "metadata": {"settings": {"compilationTarget": {"src/Vault.sol": "Vault"}}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("Vault.metadata.json"),
+            r#"{"settings": {"compilationTarget": {"src/Vault.sol": "Vault"}},
+                "output": {"abi": []}}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("Token.json"), r#"{"abi": []}"#).unwrap();
+
+        let target = Some("src/Vault.sol:Vault".to_owned());
+        assert_eq!(artifact_target(&dir.join("Vault.json")), target);
+        assert_eq!(artifact_target(&dir.join("Vault.metadata.json")), target);
+        assert_eq!(artifact_target(&dir.join("Token.json")), None);
+    }
+}
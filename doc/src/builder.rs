@@ -1,21 +1,62 @@
 use crate::{
-    document::DocumentContent, helpers::merge_toml_table, AsDoc, BufWriter, Document, ParseItem,
-    ParseSource, Parser, Preprocessor,
+    archive::{write_archive_with, ArchiveFormat},
+    artifact::{artifact_declaration, artifact_files, artifact_target, is_artifact},
+    document::{read_context, DocumentContent},
+    helpers::{as_contract, is_externally_visible, is_public_variable, merge_toml_table},
+    interface::interface_stub,
+    lint::lint_documents,
+    llms::{llms_full, llms_index},
+    lookup::{ItemDoc, ItemQuery},
+    number,
+    output::OutputFiles,
+    plan::copy_output,
+    redirect::{
+        netlify_redirects, page_url, redirect_stub, redirects_file, relative_page_url,
+        resolve_redirects,
+    },
+    resolver::ImportResolver,
+    sitemap::{sitemap, SITEMAP},
+    subgraph::SubgraphHelpers,
+    summary::SummaryTree,
+    title_page::protocol_overview,
+    validate::validate_markdown,
+    vyper::{is_vyper, vyper_declaration},
+    AsDoc, BufWriter, ContractSpec, Diagnostics, DocDiagnostic, DocEvent, DocModel, DocStats,
+    Document, EventSink, MdxEscape, PageHook, ParseItem, ParseSource, Parser, Plan,
+    PostProcessCommand, Preprocessor, PreprocessorOutput, SpecIndex, SpecIndexEntry,
+    STANDARD_JSON_ID,
 };
-use ethers_solc::utils::source_files_iter;
+use ethers_solc::{remappings::Remapping, utils::source_files_iter};
 use forge_fmt::{FormatterConfig, Visitable};
-use foundry_config::DocConfig;
+use foundry_config::{DocConfig, FilenameCase, RedirectFormat, SummaryOrder};
 use foundry_utils::glob::expand_globs;
 use itertools::Itertools;
 use mdbook::MDBook;
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use solang_parser::pt::{ContractTy, FunctionAttribute, FunctionTy, VariableAttribute, Visibility};
 use std::{
-    cmp::Ordering,
-    collections::HashMap,
-    fs,
+    any::Any,
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, fs,
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
 };
 use toml::value;
+use walkdir::WalkDir;
+
+/// The additional output format written alongside the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DocFormat {
+    /// The `llms.txt` index and the `llms-full.txt` plain-text corpus.
+    Llms,
+    /// The per-contract JSON interaction spec with the project index.
+    InteractionSpec,
+    /// The per-contract subgraph entities, `subgraph.yaml` event handlers and handler stubs.
+    SubgraphHelpers,
+}
 
 /// Build Solidity documentation for a project from natspec comments.
 /// The builder parses the source files using [Parser],
@@ -26,32 +67,69 @@ pub struct DocBuilder {
     pub root: PathBuf,
     /// Path to Solidity source files.
     pub sources: PathBuf,
+    /// Additional paths to Solidity source files, e.g. scripts.
+    pub include: Vec<PathBuf>,
+    /// The additional output formats.
+    pub formats: Vec<DocFormat>,
     /// Flag whether to build mdbook.
     pub should_build: bool,
     /// Documentation configuration.
     pub config: DocConfig,
     /// The array of preprocessors to apply.
     pub preprocessors: Vec<Box<dyn Preprocessor>>,
+    /// The array of hooks applied to the rendered pages.
+    pub page_hooks: Vec<Box<dyn PageHook>>,
     /// The formatter config.
     pub fmt: FormatterConfig,
+    /// Path to the archive the generated documentation will be packed into.
+    pub archive: Option<PathBuf>,
+    /// The changed source files. If set, only the pages of the items
+    /// declared in these files are rendered.
+    pub changed: Option<HashSet<PathBuf>>,
+    /// The collector of the diagnostics reported during the generation.
+    pub diagnostics: Arc<Diagnostics>,
+    /// Path to the compiled artifacts the Vyper contracts are documented from.
+    pub artifacts: Option<PathBuf>,
+    /// The import remappings the types of the interface stubs and the subgraph helpers are
+    /// resolved through.
+    pub remappings: Vec<Remapping>,
+    /// The sink of the generation events, if any.
+    pub events: Option<Arc<dyn EventSink>>,
+    /// Flag whether to abort on the first source or page failing to generate, rather than
+    /// writing the other pages and reporting the failures at the end.
+    pub fail_fast: bool,
+    /// The writer of the generated files.
+    output: OutputFiles,
 }
 
 // TODO: consider using `tfio`
 impl DocBuilder {
-    const SRC: &'static str = "src";
+    pub(crate) const SRC: &'static str = "src";
+    const DEFAULT_TITLE: &'static str = "Documentation";
     const SOL_EXT: &'static str = "sol";
-    const README: &'static str = "README.md";
-    const SUMMARY: &'static str = "SUMMARY.md";
+    pub(crate) const README: &'static str = "README.md";
+    pub(crate) const SUMMARY: &'static str = "SUMMARY.md";
 
     /// Create new instance of builder.
     pub fn new(root: PathBuf, sources: PathBuf) -> Self {
         Self {
             root,
             sources,
+            include: Vec::default(),
+            formats: Vec::default(),
             should_build: false,
             config: DocConfig::default(),
             preprocessors: Default::default(),
+            page_hooks: Default::default(),
             fmt: Default::default(),
+            archive: None,
+            changed: None,
+            diagnostics: Arc::default(),
+            artifacts: None,
+            remappings: Vec::default(),
+            events: None,
+            fail_fast: false,
+            output: OutputFiles::default(),
         }
     }
 
@@ -61,177 +139,893 @@ impl DocBuilder {
         self
     }
 
+    /// Include additional source path on the builder.
+    pub fn with_include(mut self, path: PathBuf) -> Self {
+        self.include.push(path);
+        self
+    }
+
+    /// Add the output format on the builder.
+    pub fn with_format(mut self, format: DocFormat) -> Self {
+        self.formats.push(format);
+        self
+    }
+
     /// Set config on the builder.
     pub fn with_config(mut self, config: DocConfig) -> Self {
         self.config = config;
         self
     }
 
+    /// Set the output directory on the builder, relative to the project root.
+    pub fn with_out(mut self, out: PathBuf) -> Self {
+        self.config.out = out;
+        self
+    }
+
+    /// Add the glob of the source files excluded from the documentation on the builder.
+    /// The glob is relative to the project root, e.g. `src/mocks/*.sol`.
+    pub fn with_ignore(mut self, glob: impl Into<String>) -> Self {
+        self.config.ignore.push(glob.into());
+        self
+    }
+
     /// Set formatter config on the builder.
     pub fn with_fmt(mut self, fmt: FormatterConfig) -> Self {
         self.fmt = fmt;
         self
     }
 
+    /// Set the archive path on the builder.
+    /// The format of the archive is inferred from the path extension.
+    pub fn with_archive(mut self, archive: PathBuf) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Set the changed source files on the builder.
+    /// The pages of the items declared in other files are left untouched.
+    pub fn with_changed(mut self, changed: HashSet<PathBuf>) -> Self {
+        self.changed = Some(changed.iter().map(|path| canonicalize(path)).collect());
+        self
+    }
+
+    /// Set the collector of the diagnostics on the builder.
+    pub fn with_diagnostics(mut self, diagnostics: Arc<Diagnostics>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Set the sink of the generation events on the builder.
+    pub fn with_events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Set `fail_fast` flag on the builder.
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Set the compiled artifacts path on the builder.
+    /// The Vyper contracts without the artifacts aren't documented.
+    pub fn with_artifacts(mut self, artifacts: PathBuf) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    /// Set the import remappings on the builder.
+    pub fn with_remappings(mut self, remappings: Vec<Remapping>) -> Self {
+        self.remappings = remappings;
+        self
+    }
+
     /// Set preprocessors on the builder.
     pub fn with_preprocessor<P: Preprocessor + 'static>(mut self, preprocessor: P) -> Self {
         self.preprocessors.push(Box::new(preprocessor) as Box<dyn Preprocessor>);
         self
     }
 
+    /// Add the page hook on the builder.
+    /// The hooks are applied in order, after the MDX escaping and the `post_process` command
+    /// if they are configured.
+    pub fn with_page_hook<H: PageHook + 'static>(mut self, hook: H) -> Self {
+        self.page_hooks.push(Box::new(hook) as Box<dyn PageHook>);
+        self
+    }
+
     /// Get the output directory
     pub fn out_dir(&self) -> PathBuf {
         self.root.join(&self.config.out)
     }
 
-    /// Parse the sources and build the documentation.
-    pub fn build(self) -> eyre::Result<()> {
-        // Expand ignore globs
-        let ignored = expand_globs(&self.root, self.config.ignore.iter())?;
+    /// Get the interface stubs output directory
+    pub fn interfaces_out_dir(&self) -> PathBuf {
+        match self.config.interfaces_out {
+            Some(ref interfaces_out) => self.root.join(interfaces_out),
+            None => self.out_dir().join("interfaces"),
+        }
+    }
 
-        // Collect and parse source files
-        let sources = source_files_iter(&self.sources)
-            .filter(|file| !ignored.contains(file))
-            .collect::<Vec<_>>();
+    /// Get the directory of the ABI artifacts documented without the source, if any.
+    pub fn abi_dir(&self) -> Option<PathBuf> {
+        self.config.abi_dir.as_ref().map(|abi_dir| self.root.join(abi_dir))
+    }
 
-        if sources.is_empty() {
-            println!("No sources detected at {}", self.sources.display());
-            return Ok(())
+    /// Returns the path of the source's pages relative to the book source directory,
+    /// i.e. the path relative to the project root, or within the ABI section for
+    /// the ABI artifacts, e.g. `abis/Vault.sol/Vault.json`.
+    ///
+    /// Fails if the ABI section collides with the source directories, as the pages of the
+    /// artifacts would overwrite the pages of the sources.
+    fn relative_source_path(&self, path: &Path) -> eyre::Result<PathBuf> {
+        if let Some(abi_dir) = self.abi_dir() {
+            if let Ok(relative) = path.strip_prefix(&abi_dir) {
+                let section = match self.config.abi_section {
+                    Some(ref section) => PathBuf::from(section),
+                    None => abi_dir.file_name().map(PathBuf::from).unwrap_or_default(),
+                };
+                let section_dir = self.root.join(&section);
+                let collision =
+                    std::iter::once(&self.sources).chain(self.include.iter()).find(|sources| {
+                        **sources != self.root &&
+                            (sources.starts_with(&section_dir) ||
+                                section_dir.starts_with(sources))
+                    });
+                if let Some(sources) = collision {
+                    eyre::bail!(
+                        "the ABI section `{}` collides with the source directory {}, \
+                         set another `abi_section`",
+                        section.display(),
+                        sources.strip_prefix(&self.root).unwrap_or(sources).display()
+                    )
+                }
+                return Ok(section.join(relative))
+            }
         }
+        Ok(path.strip_prefix(&self.root)?.to_path_buf())
+    }
+
+    /// Get the documentation title.
+    /// Falls back to the `name` of the project `package.json` if the title is not configured,
+    /// and then to the name of the directory containing the sources.
+    pub fn title(&self) -> String {
+        let package_name = fs::read_to_string(self.root.join("package.json"))
+            .ok()
+            .and_then(|package| serde_json::from_str::<serde_json::Value>(&package).ok())
+            .and_then(|package| package.get("name")?.as_str().map(str::to_owned));
+        resolve_title(&self.config.title, package_name, &self.sources)
+    }
 
-        let documents = sources
-            .par_iter()
-            .enumerate()
-            .map(|(i, path)| {
-                // Read and parse source file
-                let source = fs::read_to_string(path)?;
-                let (mut source_unit, comments) =
-                    solang_parser::parse(&source, i).map_err(|diags| {
-                        eyre::eyre!(
+    /// Collect the Solidity and Vyper source files and the ABI artifacts, excluding the ignored
+    /// and the generated ones. Fails if the output directory overlaps the sources.
+    ///
+    /// The files are matched by their canonical paths, so the files reachable through
+    /// the symlinks are ignored and documented once. The documented path is the first one
+    /// the file is found at, i.e. the in-project symlink path rather than its target.
+    /// The artifacts of the same compilation target are documented once, from the first one
+    /// by the file name.
+    fn collect_sources(&self) -> eyre::Result<Vec<PathBuf>> {
+        let out_dir = self.out_dir();
+        for sources in std::iter::once(&self.sources).chain(self.include.iter()) {
+            check_overlap(sources, &out_dir)?;
+        }
+        // The generated interface stubs are never documented, even if written to the sources
+        let generated = [canonicalize(&out_dir), canonicalize(&self.interfaces_out_dir())];
+
+        // Expand ignore globs
+        let ignored = expand_globs(&self.root, self.config.ignore.iter())?
+            .iter()
+            .map(|path| canonicalize(path))
+            .collect::<HashSet<_>>();
+
+        let mut seen = HashSet::new();
+        let mut targets = HashSet::new();
+        Ok(source_files_iter(&self.sources)
+            .chain(self.include.iter().flat_map(source_files_iter))
+            .chain(std::iter::once(&self.sources).chain(self.include.iter()).flat_map(vyper_files))
+            .chain(self.abi_dir().into_iter().flat_map(|abi_dir| artifact_files(&abi_dir)))
+            .filter(|file| {
+                let canonical = canonicalize(file);
+                !ignored.contains(&canonical) &&
+                    !generated.iter().any(|dir| canonical.starts_with(dir)) &&
+                    seen.insert(canonical) &&
+                    (!is_artifact(file) ||
+                        artifact_target(file).map_or(true, |target| targets.insert(target)))
+            })
+            .collect())
+    }
+
+    /// Look up the items matching the query, e.g. `Vault`, `Vault.deposit` or
+    /// `Vault.deposit(uint256,address)`, and render their docs without writing anything.
+    ///
+    /// Only the sources mentioning the item are parsed and the preprocessors aren't run,
+    /// so the rendered docs don't include e.g. the inherited comments.
+    /// The matches from more than one source are ambiguous and fail the lookup.
+    pub fn lookup(&self, query: &str) -> eyre::Result<Vec<ItemDoc>> {
+        let query = ItemQuery::parse(query)?;
+        let sources = self.collect_sources()?;
+        let pool =
+            ThreadPoolBuilder::new().num_threads(self.config.jobs.unwrap_or_default()).build()?;
+
+        let (root, fmt, artifacts) = (&self.root, &self.fmt, self.artifacts.as_deref());
+        let (api_only, signature_style, signature_width) =
+            (self.config.api_only, self.config.signature_style, self.config.signature_width);
+        let param_comment_fallback = self.config.param_comment_fallback;
+        let found = pool.install(|| {
+            sources
+                .par_iter()
+                .enumerate()
+                .map(|(i, path)| {
+                    let source = match read_source(path, artifacts)? {
+                        Some(source) if source.contains(query.top_level()) => source,
+                        _ => return Ok(vec![]),
+                    };
+                    let (mut source_unit, comments) = match solang_parser::parse(&source, i) {
+                        Ok(parsed) => parsed,
+                        // The invalid generated declarations are reported by the generation
+                        Err(_) if is_vyper(path) || is_artifact(path) => return Ok(vec![]),
+                        Err(diags) => eyre::bail!(
                             "Failed to parse Solidity code for {}\nDebug info: {:?}",
                             path.display(),
                             diags
-                        )
-                    })?;
-
-                // Visit the parse tree
-                let mut doc = Parser::new(comments, source).with_fmt(self.fmt.clone());
-                source_unit
-                    .visit(&mut doc)
-                    .map_err(|err| eyre::eyre!("Failed to parse source: {err}"))?;
-
-                // Split the parsed items on top-level constants and rest.
-                let (items, consts): (Vec<ParseItem>, Vec<ParseItem>) = doc
-                    .items()
-                    .into_iter()
-                    .partition(|item| !matches!(item.source, ParseSource::Variable(_)));
-
-                // Attempt to group overloaded top-level functions
-                let mut remaining = Vec::with_capacity(items.len());
-                let mut funcs: HashMap<String, Vec<ParseItem>> = HashMap::default();
-                for item in items {
-                    if matches!(item.source, ParseSource::Function(_)) {
-                        funcs.entry(item.source.ident()).or_default().push(item);
-                    } else {
-                        // Put the item back
-                        remaining.push(item);
-                    }
-                }
-                let (items, overloaded): (
-                    HashMap<String, Vec<ParseItem>>,
-                    HashMap<String, Vec<ParseItem>>,
-                ) = funcs.into_iter().partition(|(_, v)| v.len() == 1);
-                remaining.extend(items.into_iter().flat_map(|(_, v)| v));
-
-                // Each regular item will be written into its own file.
-                let mut files = remaining
-                    .into_iter()
-                    .map(|item| {
-                        let relative_path = path.strip_prefix(&self.root)?.join(item.filename());
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
-                        let ident = item.source.ident();
-                        Ok(Document::new(path.clone(), target_path)
-                            .with_content(DocumentContent::Single(item), ident))
-                    })
-                    .collect::<eyre::Result<Vec<_>>>()?;
-
-                // If top-level constants exist, they will be written to the same file.
-                if !consts.is_empty() {
-                    let filestem = path.file_stem().and_then(|stem| stem.to_str());
-
-                    let filename = {
-                        let mut name = "constants".to_owned();
-                        if let Some(stem) = filestem {
-                            name.push_str(&format!(".{stem}"));
-                        }
-                        name.push_str(".md");
-                        name
+                        ),
                     };
-                    let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                    let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                    let mut doc = Parser::new(comments, source)
+                        .with_fmt(fmt.clone())
+                        .with_signature_style(signature_style)
+                        .with_signature_width(signature_width)
+                        .with_param_comment_fallback(param_comment_fallback);
+                    source_unit
+                        .visit(&mut doc)
+                        .map_err(|err| eyre::eyre!("Failed to parse source: {err}"))?;
 
-                    let identity = match filestem {
-                        Some(stem) if stem.to_lowercase().contains("constants") => stem.to_owned(),
-                        Some(stem) => format!("{stem} constants"),
-                        None => "constants".to_owned(),
-                    };
+                    let items = if api_only { api_items(doc.items()) } else { doc.items() };
+                    query.find(items, path.strip_prefix(root).unwrap_or(path))
+                })
+                .collect::<eyre::Result<Vec<_>>>()
+        })?;
+        let found = found.into_iter().flatten().collect::<Vec<_>>();
 
-                    files.push(
-                        Document::new(path.clone(), target_path)
-                            .with_content(DocumentContent::Constants(consts), identity),
-                    )
-                }
+        if found.is_empty() {
+            eyre::bail!("no item matching `{query}` found")
+        }
+        if found.iter().map(|item| &item.source).unique().count() > 1 {
+            let candidates = found
+                .iter()
+                .map(|item| format!("  {} ({})", item.name, item.source.display()))
+                .unique()
+                .join("\n");
+            eyre::bail!("`{query}` is ambiguous, the candidates are:\n{candidates}")
+        }
+        Ok(found)
+    }
 
-                // If overloaded functions exist, they will be written to the same file
-                if !overloaded.is_empty() {
-                    for (ident, funcs) in overloaded {
-                        let filename = funcs.first().expect("no overloaded functions").filename();
-                        let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
-                        files.push(
-                            Document::new(path.clone(), target_path)
-                                .with_content(DocumentContent::OverloadedFunctions(funcs), ident),
-                        );
-                    }
-                }
+    /// Plan the generation into the output directory without writing to it.
+    ///
+    /// The documentation is generated into the builder's output directory instead, which is
+    /// expected to be a scratch directory, e.g. a temporary one, the preprocessors are
+    /// configured with. The previous output is copied there first, so the generation sees
+    /// the same pages, and the files written by the generation are compared with the output
+    /// directory. The book isn't built nor archived, and the interface stubs written elsewhere
+    /// are skipped.
+    pub fn plan(mut self, out: &Path) -> eyre::Result<Plan> {
+        let scratch = self.out_dir();
+        if canonicalize(&scratch) == canonicalize(out) {
+            eyre::bail!("the plan must be generated outside of the output directory")
+        }
+        copy_output(out, &scratch)?;
+        self.should_build = false;
+        self.archive = None;
+        if self.config.interfaces_out.is_some() {
+            self.config.emit_interface = false;
+        }
+        let output = self.output.clone();
+        let stats = self.build()?;
+        Plan::new(out, &scratch, &output.written(), stats.stale_pages)
+    }
 
-                Ok(files)
-            })
-            .collect::<eyre::Result<Vec<_>>>()?;
+    /// Parse the sources and build the documentation.
+    /// Returns the statistics of the generation.
+    pub fn build(self) -> eyre::Result<DocStats> {
+        let mut stats = DocStats::default();
+        let started = Instant::now();
 
-        // Flatten results and apply preprocessors to files
-        let documents = self
-            .preprocessors
-            .iter()
-            .try_fold(documents.into_iter().flatten().collect_vec(), |docs, p| {
-                p.preprocess(docs)
-            })?;
+        // Fail on the unsupported archive before generating anything
+        if let Some(ref archive) = self.archive {
+            ArchiveFormat::from_path(archive)?;
+        }
+
+        // Collect and parse source files
+        let sources = self.collect_sources()?;
+
+        if sources.is_empty() {
+            if self.config.fail_on_empty {
+                return Err(self.empty_error())
+            }
+            self.emit(DocEvent::Message {
+                message: format!("No sources detected at {}", self.sources.display()),
+            });
+            return Ok(stats)
+        }
+
+        // Limit the number of threads used for parsing and rendering.
+        // Zero lets rayon default to the available parallelism.
+        let pool =
+            ThreadPoolBuilder::new().num_threads(self.config.jobs.unwrap_or_default()).build()?;
+
+        let case = self.config.filename_case;
+        let documents = pool.install(|| {
+            sources
+                .par_iter()
+                .enumerate()
+                .map(|(i, path)| {
+                    let parsed = self.isolated(|| {
+                        // Read and parse source file
+                        let source = match read_source(path, self.artifacts.as_deref())? {
+                            Some(source) => source,
+                            None => {
+                                let diagnostic = if is_artifact(path) {
+                                    DocDiagnostic::warning(
+                                        "abi-artifact-invalid",
+                                        "the file isn't the compiled artifact with the ABI, \
+                                         so it's not documented",
+                                    )
+                                } else {
+                                    DocDiagnostic::warning(
+                                        "vyper-artifact-missing",
+                                        "the Vyper contract has no compiled artifact, \
+                                         build the project to document it",
+                                    )
+                                };
+                                self.diagnostics.push(diagnostic.with_location(
+                                    path.strip_prefix(&self.root).unwrap_or(path).to_path_buf(),
+                                    None,
+                                ));
+                                return Ok(vec![])
+                            }
+                        };
+                        let (mut source_unit, comments) = match solang_parser::parse(&source, i) {
+                            Ok(parsed) => parsed,
+                            // The declaration generated from the artifact may not be valid
+                            // Solidity, e.g. with the Vyper names reserved in Solidity
+                            Err(diags) if is_vyper(path) || is_artifact(path) => {
+                                let code = if is_vyper(path) {
+                                    "vyper-artifact-invalid"
+                                } else {
+                                    "abi-artifact-invalid"
+                                };
+                                let message = format!(
+                                    "the declaration generated from the compiled artifact \
+                                     can't be parsed, so it's not documented: {diags:?}"
+                                );
+                                self.diagnostics.push(
+                                    DocDiagnostic::warning(code, message).with_location(
+                                        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf(),
+                                        None,
+                                    ),
+                                );
+                                return Ok(vec![])
+                            }
+                            Err(diags) => eyre::bail!(
+                                "Failed to parse Solidity code for {}\nDebug info: {:?}",
+                                path.display(),
+                                diags
+                            ),
+                        };
+
+                        // Visit the parse tree
+                        let mut doc = Parser::new(comments, source.clone())
+                            .with_fmt(self.fmt.clone())
+                            .with_signature_style(self.config.signature_style)
+                            .with_signature_width(self.config.signature_width)
+                            .with_param_comment_fallback(self.config.param_comment_fallback);
+                        source_unit
+                            .visit(&mut doc)
+                            .map_err(|err| eyre::eyre!("Failed to parse source: {err}"))?;
+
+                        let items =
+                            if self.config.api_only { api_items(doc.items()) } else { doc.items() };
+
+                        // Split the parsed items on top-level constants and rest.
+                        let (items, consts): (Vec<ParseItem>, Vec<ParseItem>) = items
+                            .into_iter()
+                            .partition(|item| !matches!(item.source, ParseSource::Variable(_)));
+
+                        // Attempt to group overloaded top-level functions
+                        let mut remaining = Vec::with_capacity(items.len());
+                        let mut funcs: HashMap<String, Vec<ParseItem>> = HashMap::default();
+                        for item in items {
+                            if matches!(item.source, ParseSource::Function(_)) {
+                                funcs.entry(item.source.ident()).or_default().push(item);
+                            } else {
+                                // Put the item back
+                                remaining.push(item);
+                            }
+                        }
+                        let (items, overloaded): (
+                            HashMap<String, Vec<ParseItem>>,
+                            HashMap<String, Vec<ParseItem>>,
+                        ) = funcs.into_iter().partition(|(_, v)| v.len() == 1);
+                        remaining.extend(items.into_iter().flat_map(|(_, v)| v));
+
+                        // Each regular item will be written into its own file.
+                        let mut files = remaining
+                            .into_iter()
+                            .map(|item| {
+                                let relative_path =
+                                    self.relative_source_path(path)?.join(item.filename());
+                                let target_path = self
+                                    .config
+                                    .out
+                                    .join(Self::SRC)
+                                    .join(case_path(&relative_path, case));
+                                let ident = item.source.ident();
+                                Ok(Document::new(path.clone(), target_path)
+                                    .with_content(DocumentContent::Single(item), ident))
+                            })
+                            .collect::<eyre::Result<Vec<_>>>()?;
+
+                        // If top-level constants exist, they will be written to the same file.
+                        if !consts.is_empty() {
+                            let filestem = path.file_stem().and_then(|stem| stem.to_str());
+
+                            let filename = {
+                                let mut name = "constants".to_owned();
+                                if let Some(stem) = filestem {
+                                    name.push_str(&format!(".{stem}"));
+                                }
+                                name.push_str(".md");
+                                name
+                            };
+                            let relative_path = self.relative_source_path(path)?.join(filename);
+                            let target_path = self
+                                .config
+                                .out
+                                .join(Self::SRC)
+                                .join(case_path(&relative_path, case));
+
+                            let identity = match filestem {
+                                Some(stem) if stem.to_lowercase().contains("constants") => {
+                                    stem.to_owned()
+                                }
+                                Some(stem) => format!("{stem} constants"),
+                                None => "constants".to_owned(),
+                            };
+
+                            files.push(
+                                Document::new(path.clone(), target_path)
+                                    .with_content(DocumentContent::Constants(consts), identity),
+                            )
+                        }
+
+                        // If overloaded functions exist, they will be written to the same file
+                        if !overloaded.is_empty() {
+                            for (ident, funcs) in overloaded {
+                                let filename =
+                                    funcs.first().expect("no overloaded functions").filename();
+                                let relative_path = self.relative_source_path(path)?.join(filename);
+                                let target_path = self
+                                    .config
+                                    .out
+                                    .join(Self::SRC)
+                                    .join(case_path(&relative_path, case));
+                                files.push(Document::new(path.clone(), target_path).with_content(
+                                    DocumentContent::OverloadedFunctions(funcs),
+                                    ident,
+                                ));
+                            }
+                        }
+
+                        // Keep the source content for the preprocessors.
+                        files.iter_mut().for_each(|file| file.item_content = source.clone());
+
+                        Ok(files)
+                    });
+                    (path, parsed)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // The sources failing to parse are reported at the end, unless `fail_fast` is set
+        let mut failures = vec![];
+        let mut parsed = vec![];
+        for (path, result) in documents {
+            match result {
+                Ok(documents) => parsed.extend(documents),
+                Err(err) if self.fail_fast => return Err(err),
+                Err(err) => failures.push(self.failure(path, None, err)),
+            }
+        }
+
+        stats.parse = started.elapsed();
+        let started = Instant::now();
+
+        let mut documents = parsed;
+        if self.config.flat {
+            let out_src = self.config.out.join(Self::SRC);
+            flatten_paths(&mut documents, &self.root, &self.sources, &out_src, case);
+        }
+        // The pages are suffixed before the preprocessors link them
+        disambiguate_paths(&mut documents);
+        if self.config.fail_on_empty &&
+            !documents.iter().any(|document| as_contract(document).is_some())
+        {
+            return Err(self.empty_error())
+        }
+
+        // Check the natspec comments of the parsed items
+        // The inferred parameter descriptions only count as documented in the strict mode if
+        // accepted
+        let inferred_params = self.config.deny_warnings && !self.config.strict_accept_inline;
+        self.diagnostics.extend(lint_documents(&documents, &self.root, inferred_params));
+
+        // Apply preprocessors to files
+        let mut documents = self.preprocess(documents, &mut failures)?;
+
+        let out_src = self.config.out.join(Self::SRC);
+        for document in documents.iter_mut() {
+            // The appendix pages are named by the preprocessors, only linked from the summary.
+            if document.content.is_appendix() {
+                if let Some(name) = document.target_path.file_name() {
+                    let name = case_filename(&name.to_string_lossy(), case);
+                    document.target_path.set_file_name(name);
+                }
+            }
+            document.book_src = out_src.clone();
+            document.max_value_chars = self.config.max_value_chars;
+        }
 
         // Sort the results
         let documents = documents.into_iter().sorted_by(|doc1, doc2| {
             doc1.item_path.display().to_string().cmp(&doc2.item_path.display().to_string())
         });
 
+        let documents = documents.collect_vec();
+        check_path_collisions(&documents)?;
+
+        // Collect the documentation model
+        let (documents, mut model) = self.collect_model(documents, &out_src, &mut failures)?;
+
+        // Redirect the pages moved since the previous generation, before its model is replaced
+        let previous = DocModel::read(&self.out_dir()).ok();
+        model.redirects =
+            resolve_redirects(previous.as_ref(), &model, &self.config.redirects, &self.diagnostics);
+
+        // Fail on the warnings before anything is written
+        let warnings = self.diagnostics.warnings();
+        if self.config.deny_warnings && warnings > 0 {
+            self.diagnostics.escalate();
+            eyre::bail!("{warnings} warning(s) denied")
+        }
+        let mut stats = stats.with_model(&model);
+
+        // Collect the interface stubs if requested
+        let interfaces = if self.config.emit_interface {
+            let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+                .with_documents(&documents);
+            documents
+                .iter()
+                .map(|document| interface_stub(document, &self.root, &mut resolver))
+                .filter_map(Result::transpose)
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![]
+        };
+
+        // Collect the interaction specs if requested
+        let specs = if self.formats.contains(&DocFormat::InteractionSpec) {
+            ContractSpec::collect(&documents, &self.root)
+        } else {
+            vec![]
+        };
+
+        // Collect the subgraph helpers if requested
+        let subgraph = if self.formats.contains(&DocFormat::SubgraphHelpers) {
+            SubgraphHelpers::collect(&documents, &self.root, &self.remappings)
+        } else {
+            vec![]
+        };
+
+        // Collect the standard json inputs of the contracts, if included
+        let inputs = documents
+            .iter()
+            .filter_map(|document| read_context!(document, STANDARD_JSON_ID, StandardJson))
+            .collect_vec();
+
+        stats.model = started.elapsed();
+
         // Write mdbook related files
-        self.write_mdbook(documents.collect_vec())?;
+        self.write_mdbook(documents, &model.redirects, &pool, &mut stats, &mut failures)?;
+        let started = Instant::now();
+
+        // The pages failing to render are left out of the model like out of the summary
+        let failed =
+            failures.iter().filter_map(|failure| failure.page.as_ref()).collect::<HashSet<_>>();
+        model.items.retain(|item| !failed.contains(&out_src.join(&item.page)));
+
+        // Write the standard json inputs within the book source, so they're copied to the book.
+        // They aren't chapters, so they're left out of the summary and the search index.
+        for input in inputs {
+            if let Some(parent) = input.path.parent() {
+                self.output.create_dir_all(parent)?;
+            }
+            self.output.write(&input.path, input.input)?;
+        }
+        let model_json = if self.config.minify {
+            serde_json::to_string(&model)?
+        } else {
+            serde_json::to_string_pretty(&model)?
+        };
+        self.output.write(self.out_dir().join(DocModel::FILE_NAME), model_json)?;
+
+        // Write the additional formats
+        for format in self.formats.iter() {
+            match format {
+                DocFormat::Llms => {
+                    let title = self.title();
+                    self.output
+                        .write(self.out_dir().join("llms.txt"), llms_index(&model, &title)?)?;
+                    self.output.write(
+                        self.out_dir().join("llms-full.txt"),
+                        llms_full(&model, &title, self.config.llms_max_bytes)?,
+                    )?;
+                }
+                DocFormat::InteractionSpec => self.write_specs(&specs)?,
+                DocFormat::SubgraphHelpers => self.write_subgraph(&subgraph)?,
+            }
+        }
+
+        // Write the interface stubs
+        if !interfaces.is_empty() {
+            let interfaces_out = self.interfaces_out_dir();
+            self.output.create_dir_all(&interfaces_out)?;
+            for (file, stub) in interfaces {
+                let path = interfaces_out.join(file);
+                if let Some(parent) = path.parent() {
+                    self.output.create_dir_all(parent)?;
+                }
+                self.output.write(path, stub)?;
+            }
+        }
+
+        stats.write += started.elapsed();
+
+        // The book and the archive aren't built from the pages left over from the previous
+        // generations, so the failures are reported before
+        if !failures.is_empty() {
+            eyre::bail!(
+                "failed to document {} source(s) or page(s), the other pages were written:\n{}",
+                failures.len(),
+                failures.iter().map(|failure| format!("  {failure}")).join("\n")
+            )
+        }
 
         // Build the book if requested
         if self.should_build {
-            MDBook::load(self.out_dir())
-                .and_then(|book| book.build())
-                .map_err(|err| eyre::eyre!("failed to build book: {err:?}"))?;
+            let book = MDBook::load(self.out_dir())
+                .map_err(|err| eyre::eyre!("failed to load book: {err:?}"))?;
+            book.build().map_err(|err| eyre::eyre!("failed to build book: {err:?}"))?;
+
+            // List the HTML pages for the search engines if the site is published
+            if let Some(ref site_url) = self.config.site_url {
+                let html_dir = book.build_dir_for("html");
+                self.output.write(html_dir.join(SITEMAP), sitemap(&html_dir, site_url)?)?;
+            }
         }
 
-        Ok(())
+        // Pack the output into the archive if requested
+        if let Some(ref archive) = self.archive {
+            write_archive_with(&self.output, &self.out_dir(), archive)?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Emit the generation event to the sink, if any.
+    fn emit(&self, event: DocEvent) {
+        if let Some(ref events) = self.events {
+            events.emit(&event);
+        }
     }
 
-    fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
+    /// Run the generation step of a source or a page, catching its panic as the error unless
+    /// `fail_fast` is set.
+    fn isolated<T>(&self, step: impl FnOnce() -> eyre::Result<T>) -> eyre::Result<T> {
+        if self.fail_fast {
+            return step()
+        }
+        panic::catch_unwind(AssertUnwindSafe(step)).unwrap_or_else(|payload| {
+            Err(eyre::eyre!("panicked: {}", panic_message(payload.as_ref())))
+        })
+    }
+
+    /// Returns the failure of the source, or of its page, reported at the end of the generation.
+    fn failure(
+        &self,
+        source: &Path,
+        page: Option<PathBuf>,
+        err: eyre::Report,
+    ) -> GenerationFailure {
+        GenerationFailure {
+            source: source.strip_prefix(&self.root).unwrap_or(source).to_path_buf(),
+            page,
+            cause: err.to_string(),
+        }
+    }
+
+    /// Apply the preprocessors to the documents.
+    ///
+    /// Unless `fail_fast` is set, the preprocessors failing on the documents are run on the
+    /// documents of each source on their own, and the failing sources are left out and added
+    /// to the failures. The diagnostics of the isolating runs aren't reported.
+    fn preprocess(
+        &self,
+        documents: Vec<Document>,
+        failures: &mut Vec<GenerationFailure>,
+    ) -> eyre::Result<Vec<Document>> {
+        let run = |documents: Vec<Document>| {
+            self.isolated(|| {
+                self.preprocessors.iter().try_fold(documents, |docs, p| p.preprocess(docs))
+            })
+        };
+        if self.fail_fast {
+            return run(documents)
+        }
+
+        let snapshot = documents.clone();
+        let reported = self.diagnostics.take();
+        let err = match run(documents) {
+            Ok(documents) => {
+                self.diagnostics.extend(reported);
+                return Ok(documents)
+            }
+            Err(err) => err,
+        };
+
+        let mut sources: BTreeMap<PathBuf, Vec<Document>> = BTreeMap::new();
+        for document in snapshot {
+            sources.entry(document.item_path.clone()).or_default().push(document);
+        }
+        let mut remaining = vec![];
+        let mut isolated = false;
+        for (source, documents) in sources {
+            match run(documents.clone()) {
+                Ok(_) => remaining.extend(documents),
+                Err(err) => {
+                    failures.push(self.failure(&source, None, err));
+                    isolated = true;
+                }
+            }
+        }
+        self.diagnostics.take();
+        // The preprocessors fail on the documents together, e.g. on the path collision
+        if !isolated {
+            self.diagnostics.extend(reported);
+            return Err(err)
+        }
+
+        let documents = run(remaining);
+        self.diagnostics.extend(reported);
+        documents
+    }
+
+    /// Collect the documentation model of the documents.
+    ///
+    /// Unless `fail_fast` is set, the model of each document is collected on its own if the
+    /// collection panics, and the sources of the panicking documents are left out and added
+    /// to the failures.
+    fn collect_model(
+        &self,
+        documents: Vec<Document>,
+        out_src: &Path,
+        failures: &mut Vec<GenerationFailure>,
+    ) -> eyre::Result<(Vec<Document>, DocModel)> {
+        let collect = |documents: &[Document]| {
+            self.isolated(|| Ok(DocModel::new(documents, &self.root, out_src)))
+        };
+        let err = match collect(&documents) {
+            Ok(model) => return Ok((documents, model)),
+            Err(err) => err,
+        };
+
+        let mut failed = BTreeMap::new();
+        for document in documents.iter() {
+            if let Err(err) = collect(std::slice::from_ref(document)) {
+                failed.entry(document.item_path.clone()).or_insert(err);
+            }
+        }
+        if failed.is_empty() {
+            return Err(err)
+        }
+        let documents = documents
+            .into_iter()
+            .filter(|document| !failed.contains_key(&document.item_path))
+            .collect::<Vec<_>>();
+        for (source, err) in failed {
+            failures.push(self.failure(&source, None, err));
+        }
+        let model = collect(&documents)?;
+        Ok((documents, model))
+    }
+
+    /// Write the book files and the rendered pages.
+    ///
+    /// Adds the failures of the pages failing to render, unless `fail_fast` is set.
+    /// Each page is rendered on its own, so the failure of one, including the panic, doesn't
+    /// stop the others from being written. The failed pages are left as they were, and aren't
+    /// listed in the summary.
+    fn write_mdbook(
+        &self,
+        documents: Vec<Document>,
+        redirects: &BTreeMap<PathBuf, PathBuf>,
+        pool: &ThreadPool,
+        stats: &mut DocStats,
+        failures: &mut Vec<GenerationFailure>,
+    ) -> eyre::Result<()> {
+        let started = Instant::now();
+
+        // Render doc files. The results are collected in the order of the documents,
+        // so the output doesn't depend on the number of jobs.
+        // The pages of the unchanged sources are skipped if the changed files are set.
+        let post_process = self
+            .config
+            .post_process
+            .as_ref()
+            .map(|command| PostProcessCommand { command: command.clone() });
+        let mdx = self.config.mdx.then_some(MdxEscape);
+        let hooks = mdx
+            .iter()
+            .map(|hook| hook as &dyn PageHook)
+            .chain(post_process.iter().map(|hook| hook as &dyn PageHook))
+            .chain(self.page_hooks.iter().map(|hook| hook.as_ref()))
+            .collect::<Vec<_>>();
+        let changed = documents.iter().filter(|document| self.is_changed(document)).collect_vec();
+        let results = pool.install(|| {
+            changed
+                .into_par_iter()
+                .map(|document| {
+                    let content = self.isolated(|| {
+                        hooks.iter().try_fold(document.as_doc()?, |content, hook| {
+                            hook.process(document, content)
+                        })
+                    });
+                    (document, content)
+                })
+                .collect::<Vec<_>>()
+        });
+        let mut rendered = vec![];
+        let mut failed = HashSet::new();
+        for (document, content) in results {
+            match content {
+                Ok(content) => rendered.push((document, content)),
+                Err(err) if self.fail_fast => return Err(err),
+                Err(err) => {
+                    failed.insert(document.target_path.as_path());
+                    failures.push(self.failure(
+                        &document.item_path,
+                        Some(document.target_path.clone()),
+                        err,
+                    ));
+                }
+            }
+        }
+        stats.render = started.elapsed();
+        let started = Instant::now();
+
+        // The pages failing to render aren't listed
+        let listed = documents
+            .iter()
+            .filter(|document| !failed.contains(document.target_path.as_path()))
+            .collect::<Vec<_>>();
+
         let out_dir = self.out_dir();
         let out_dir_src = out_dir.join(Self::SRC);
-        fs::create_dir_all(&out_dir_src)?;
+        self.output.create_dir_all(&out_dir_src)?;
 
         // Write readme content if any
-        let homepage_content = {
+        let mut homepage_content = {
             // Default to the homepage README if it's available.
             // If not, use the src README as a fallback.
             let homepage_or_src_readme = self
@@ -250,59 +1044,277 @@ impl DocBuilder {
             } else if root_readme.exists() {
                 fs::read_to_string(root_readme)?
             } else {
-                String::new()
+                format!("# {}\n", self.title())
             }
         };
+        if self.config.title_page {
+            homepage_content
+                .push_str(&protocol_overview(&listed, &self.config.out.join(Self::SRC))?);
+        }
 
         let readme_path = out_dir_src.join(Self::README);
-        fs::write(&readme_path, homepage_content)?;
+        self.output.write(&readme_path, homepage_content)?;
 
         // Write summary and section readmes
-        let mut summary = BufWriter::default();
-        summary.write_title("Summary")?;
-        summary.write_link_list_item("Home", Self::README, 0)?;
-        self.write_summary_section(&mut summary, &documents.iter().collect::<Vec<_>>(), None, 0)?;
-        fs::write(out_dir_src.join(Self::SUMMARY), summary.finish())?;
+        let mut summary = SummaryTree::default();
+        summary.push_part("Summary");
+        summary.push_entry("Home", Self::README, 0);
+        let (appendix, sources): (Vec<_>, Vec<_>) =
+            listed.iter().copied().partition(|document| document.content.is_appendix());
+        self.write_summary_section(&mut summary, &sources, None, 0)?;
+        if !appendix.is_empty() {
+            summary.push_part("Appendix");
+            for document in appendix {
+                let summary_path =
+                    document.target_path.strip_prefix(self.config.out.join(Self::SRC))?;
+                summary.push_entry(&document.identity, &summary_path.display().to_string(), 0);
+            }
+        }
+        self.output.write(out_dir_src.join(Self::SUMMARY), summary.as_doc()?)?;
+        self.emit(DocEvent::SummaryWritten { path: Path::new(Self::SRC).join(Self::SUMMARY) });
+        let summary_json = if self.config.minify {
+            serde_json::to_string(&summary)?
+        } else {
+            serde_json::to_string_pretty(&summary)?
+        };
+        self.output.write(out_dir.join(SummaryTree::FILE_NAME), summary_json)?;
 
         // Write solidity syntax highlighting
-        fs::write(out_dir.join("solidity.min.js"), include_str!("../static/solidity.min.js"))?;
+        self.output
+            .write(out_dir.join("solidity.min.js"), include_str!("../static/solidity.min.js"))?;
 
         // Write css files
-        fs::write(out_dir.join("book.css"), include_str!("../static/book.css"))?;
+        self.output.write(out_dir.join("book.css"), include_str!("../static/book.css"))?;
 
         // Write book config
-        fs::write(self.out_dir().join("book.toml"), self.book_config()?)?;
+        self.output.write(self.out_dir().join("book.toml"), self.book_config(redirects)?)?;
 
         // Write .gitignore
         let gitignore = "book/";
-        fs::write(self.out_dir().join(".gitignore"), gitignore)?;
+        self.output.write(self.out_dir().join(".gitignore"), gitignore)?;
+
+        stats.write += started.elapsed();
+        let started = Instant::now();
 
-        // Write doc files
-        for document in documents {
-            fs::create_dir_all(
-                document
+        // Report the structural problems of the rendered markdown
+        if self.config.validate_markdown {
+            for (document, content) in rendered.iter() {
+                self.diagnostics.extend(validate_markdown(document, content));
+            }
+        }
+
+        // Report the largest pages and the pages over the limit
+        self.check_page_sizes(&rendered);
+
+        // Write doc files. The unchanged files are not rewritten.
+        for (document, content) in rendered {
+            let written =
+                !fs::read_to_string(&document.target_path).map_or(false, |prev| prev == content);
+            if written {
+                self.output.create_dir_all(
+                    document
+                        .target_path
+                        .parent()
+                        .ok_or(eyre::format_err!("empty target path; noop"))?,
+                )?;
+                self.output.write(&document.target_path, content)?;
+                stats.pages_written += 1;
+            } else {
+                stats.pages_unchanged += 1;
+            }
+            self.emit(DocEvent::PageRendered {
+                path: document
                     .target_path
-                    .parent()
-                    .ok_or(eyre::format_err!("empty target path; noop"))?,
-            )?;
-            fs::write(&document.target_path, document.as_doc()?)?;
+                    .strip_prefix(&self.config.out)
+                    .unwrap_or(&document.target_path)
+                    .to_path_buf(),
+                contracts: document_contracts(document),
+                written,
+            });
+        }
+
+        // Write the redirect pages at the paths of the moved pages
+        for (from, to) in redirects.iter() {
+            let path = out_dir_src.join(from);
+            let content = redirect_stub(to);
+            if fs::read_to_string(&path).map_or(false, |prev| prev == content) {
+                continue
+            }
+            self.output
+                .create_dir_all(path.parent().ok_or(eyre::format_err!("empty redirect path"))?)?;
+            self.output.write(path, content)?;
+        }
+        match self.config.redirect_format {
+            Some(RedirectFormat::Redirects) => {
+                self.output.write(out_dir_src.join("_redirects"), redirects_file(redirects))?
+            }
+            Some(RedirectFormat::Netlify) => {
+                self.output.write(out_dir.join("netlify.toml"), netlify_redirects(redirects))?
+            }
+            None => {}
         }
 
+        // Count the pages left over from the previous generations.
+        let out_src = self.config.out.join(Self::SRC);
+        let redirected = redirects.keys().map(|from| out_src.join(from)).collect_vec();
+        let pages = documents
+            .iter()
+            .map(|document| document.target_path.as_path())
+            .chain(redirected.iter().map(PathBuf::as_path))
+            .collect::<HashSet<_>>();
+        stats.stale_pages = WalkDir::new(&out_src)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let path = entry.path();
+                path.extension().map(|ext| ext == "md").unwrap_or_default() &&
+                    ![Self::README, Self::SUMMARY].iter().any(|name| {
+                        path.file_name().map(|file| file == *name).unwrap_or_default()
+                    }) &&
+                    !pages.contains(path)
+            })
+            .filter_map(|entry| {
+                entry.path().strip_prefix(&self.config.out).ok().map(Path::to_path_buf)
+            })
+            .collect();
+        stats.pages_stale = stats.stale_pages.len();
+        stats.write += started.elapsed();
+
         Ok(())
     }
 
-    fn book_config(&self) -> eyre::Result<String> {
+    /// Warn about the rendered pages over `doc.page_size_warning`, listing the largest ones,
+    /// and about each page over `doc.max_page_size`.
+    fn check_page_sizes(&self, rendered: &[(&Document, String)]) {
+        let out_src = self.config.out.join(Self::SRC);
+        let page = |document: &Document| {
+            document
+                .target_path
+                .strip_prefix(&out_src)
+                .unwrap_or(&document.target_path)
+                .display()
+                .to_string()
+        };
+        let largest = rendered
+            .iter()
+            .sorted_by_key(|(_, content)| Reverse(content.len()))
+            .collect::<Vec<_>>();
+
+        let warning = self.config.page_size_warning;
+        let large = largest.iter().filter(|(_, content)| content.len() > warning).collect_vec();
+        if warning > 0 && !large.is_empty() {
+            self.diagnostics.push(DocDiagnostic::warning(
+                "page-size",
+                format!(
+                    "{} page(s) over {}, the largest: {}; lower `doc.section_limit` \
+                     to move more members to the continuation pages",
+                    large.len(),
+                    number::size(warning),
+                    large
+                        .iter()
+                        .take(5)
+                        .map(|(document, content)| {
+                            format!("{} ({})", page(document), number::size(content.len()))
+                        })
+                        .join(", ")
+                ),
+            ));
+        }
+
+        let max = self.config.max_page_size;
+        for (document, content) in
+            largest.iter().filter(|(_, content)| max > 0 && content.len() > max)
+        {
+            let source = document.item_path.strip_prefix(&self.root).unwrap_or(&document.item_path);
+            let diagnostic = DocDiagnostic::warning(
+                "max-page-size",
+                format!(
+                    "the page {} is {}, over the `doc.max_page_size` of {}; lower \
+                     `doc.section_limit` to move more members to the continuation pages, \
+                     split the contract, or raise `doc.max_page_size`",
+                    page(document),
+                    number::size(content.len()),
+                    number::size(max)
+                ),
+            );
+            self.diagnostics.push(
+                diagnostic.with_location(source.to_path_buf(), None).with_item(&document.identity),
+            );
+        }
+    }
+
+    /// Returns the error of the generation without any documented contracts.
+    fn empty_error(&self) -> eyre::Report {
+        eyre::eyre!(
+            "no contracts documented at {}; check the `src` path of the profile \
+             and the `doc.ignore` globs",
+            self.sources.display()
+        )
+    }
+
+    /// Returns true if the document should be rendered, i.e. its source file has changed.
+    /// The appendix documents are always rendered.
+    fn is_changed(&self, document: &Document) -> bool {
+        match self.changed {
+            Some(ref changed) => {
+                document.content.is_appendix() ||
+                    changed.contains(&canonicalize(&document.item_path))
+            }
+            None => true,
+        }
+    }
+
+    fn write_specs(&self, specs: &[ContractSpec]) -> eyre::Result<()> {
+        let spec_dir = self.out_dir().join(ContractSpec::DIR);
+        let mut index = SpecIndex { version: ContractSpec::VERSION, contracts: vec![] };
+        for spec in specs.iter() {
+            let file = spec.file();
+            let path = spec_dir.join(&file);
+            self.output
+                .create_dir_all(path.parent().ok_or(eyre::format_err!("empty spec path"))?)?;
+            self.output.write(path, serde_json::to_string_pretty(spec)?)?;
+            index.contracts.push(SpecIndexEntry {
+                name: spec.name.clone(),
+                kind: spec.kind.clone(),
+                spec: file,
+            });
+        }
+        self.output.create_dir_all(&spec_dir)?;
+        self.output
+            .write(spec_dir.join(ContractSpec::INDEX), serde_json::to_string_pretty(&index)?)?;
+        Ok(())
+    }
+
+    fn write_subgraph(&self, helpers: &[SubgraphHelpers]) -> eyre::Result<()> {
+        let subgraph_dir = self.out_dir().join(SubgraphHelpers::DIR);
+        for helpers in helpers.iter() {
+            let dir = subgraph_dir.join(helpers.dir());
+            self.output.create_dir_all(&dir)?;
+            self.output.write(dir.join("schema.graphql"), helpers.schema()?)?;
+            self.output.write(dir.join("subgraph.yaml"), helpers.manifest()?)?;
+            self.output.write(dir.join("mappings.ts"), helpers.mappings()?)?;
+        }
+        Ok(())
+    }
+
+    fn book_config(&self, redirects: &BTreeMap<PathBuf, PathBuf>) -> eyre::Result<String> {
         // Read the default book first
         let mut book: value::Table = toml::from_str(include_str!("../static/book.toml"))?;
-        book["book"]
-            .as_table_mut()
-            .unwrap()
-            .insert(String::from("title"), self.config.title.clone().into());
+        book["book"].as_table_mut().unwrap().insert(String::from("title"), self.title().into());
+        let html = book["output"].as_table_mut().unwrap()["html"].as_table_mut().unwrap();
         if let Some(ref repo) = self.config.repository {
-            book["output"].as_table_mut().unwrap()["html"]
-                .as_table_mut()
-                .unwrap()
-                .insert(String::from("git-repository-url"), repo.clone().into());
+            html.insert(String::from("git-repository-url"), repo.clone().into());
+        }
+        // The moved pages are redirected by the html renderer
+        if !redirects.is_empty() {
+            let redirect = redirects
+                .iter()
+                .map(|(from, to)| {
+                    (page_url(from), value::Value::String(relative_page_url(from, to)))
+                })
+                .collect::<value::Table>();
+            html.insert(String::from("redirect"), redirect.into());
         }
 
         // Attempt to find the user provided book path
@@ -329,7 +1341,7 @@ impl DocBuilder {
 
     fn write_summary_section(
         &self,
-        summary: &mut BufWriter,
+        summary: &mut SummaryTree,
         files: &[&Document],
         base_path: Option<&Path>,
         depth: usize,
@@ -338,56 +1350,69 @@ impl DocBuilder {
             return Ok(())
         }
 
+        let case = self.config.filename_case;
+        let cased_base_path = base_path.map(|path| case_path(path, case));
         if let Some(path) = base_path {
             let title = path.iter().last().unwrap().to_string_lossy();
+            // The sections are nested within the part of the top-level directory
             if depth == 1 {
-                summary.write_title(&title)?;
+                summary.push_part(&title);
             } else {
-                let summary_path = path.join(Self::README);
-                summary.write_link_list_item(
+                let summary_path = self.section_readme(path);
+                summary.push_entry(
                     &format!("❱ {title}"),
                     &summary_path.display().to_string(),
-                    depth - 1,
-                )?;
+                    depth - 2,
+                );
             }
         }
 
         // Group entries by path depth
         let mut grouped = HashMap::new();
         for file in files {
-            let path = file.item_path.strip_prefix(&self.root)?;
+            let path = self.relative_source_path(&file.item_path)?;
             let key = path.iter().take(depth + 1).collect::<PathBuf>();
             grouped.entry(key).or_insert_with(Vec::new).push(*file);
         }
-        // Sort entries by path depth
+        // Sort entries by path depth, ordering the directories and the files as configured.
+        // The Vyper sources and the ABI artifacts are files like the Solidity ones
+        let is_file = |path: &Path| {
+            path.extension().map(|ext| ext.eq(Self::SOL_EXT)).unwrap_or_default() ||
+                is_vyper(path) ||
+                is_artifact(path)
+        };
         let grouped = grouped.into_iter().sorted_by(|(lhs, _), (rhs, _)| {
-            let lhs_at_end = lhs.extension().map(|ext| ext.eq(Self::SOL_EXT)).unwrap_or_default();
-            let rhs_at_end = rhs.extension().map(|ext| ext.eq(Self::SOL_EXT)).unwrap_or_default();
-            if lhs_at_end == rhs_at_end {
-                lhs.cmp(rhs)
-            } else if lhs_at_end {
-                Ordering::Greater
-            } else {
-                Ordering::Less
-            }
+            let kind = match self.config.summary_order {
+                SummaryOrder::DirectoriesFirst => is_file(lhs).cmp(&is_file(rhs)),
+                SummaryOrder::FilesFirst => is_file(rhs).cmp(&is_file(lhs)),
+                SummaryOrder::Mixed => Ordering::Equal,
+            };
+            kind.then_with(|| lhs.cmp(rhs))
         });
 
         let mut readme = BufWriter::new("\n\n# Contents\n");
         for (path, files) in grouped {
-            if path.extension().map(|ext| ext.eq(Self::SOL_EXT)).unwrap_or_default() {
+            if is_file(&path) {
                 for file in files {
                     let ident = &file.identity;
 
-                    let summary_path = file
-                        .target_path
-                        .strip_prefix(self.out_dir().strip_prefix(&self.root)?.join(Self::SRC))?;
-                    summary.write_link_list_item(
+                    let summary_path =
+                        file.target_path.strip_prefix(self.config.out.join(Self::SRC))?;
+                    // The continuation pages are nested under the contract page
+                    let continued = matches!(file.content, DocumentContent::Continuation(_));
+                    summary.push_entry(
                         ident,
                         &summary_path.display().to_string(),
-                        depth,
-                    )?;
+                        depth.saturating_sub(1) + usize::from(continued),
+                    );
+                    if continued {
+                        continue
+                    }
 
-                    let readme_path = base_path
+                    // The flat pages are in the same directory as the section readme
+                    let readme_path = cased_base_path
+                        .as_ref()
+                        .filter(|_| !self.config.flat)
                         .map(|path| summary_path.strip_prefix(path))
                         .transpose()?
                         .unwrap_or(summary_path);
@@ -395,18 +1420,856 @@ impl DocBuilder {
                 }
             } else {
                 let name = path.iter().last().unwrap().to_string_lossy();
-                let readme_path = Path::new("/").join(&path).display().to_string();
+                let readme_path = if self.config.flat {
+                    self.section_readme(&path).display().to_string()
+                } else {
+                    Path::new("/").join(case_path(&path, case)).display().to_string()
+                };
                 readme.write_link_list_item(&name, &readme_path, 0)?;
                 self.write_summary_section(summary, &files, Some(&path), depth + 1)?;
             }
         }
         if !readme.is_empty() {
             if let Some(path) = base_path {
-                let path = self.out_dir().join(Self::SRC).join(path);
-                fs::create_dir_all(&path)?;
-                fs::write(path.join(Self::README), readme.finish())?;
+                let path = self.out_dir().join(Self::SRC).join(self.section_readme(path));
+                self.output
+                    .create_dir_all(path.parent().expect("section readme without parent"))?;
+                self.output.write(path, readme.finish())?;
             }
         }
         Ok(())
     }
+
+    /// Returns the readme path of the section relative to the book source directory,
+    /// e.g. `src/tokens/README.md`, or `src_tokens_README.md` if the output is flat.
+    fn section_readme(&self, path: &Path) -> PathBuf {
+        let path = case_path(path, self.config.filename_case);
+        if self.config.flat {
+            let name = path.iter().map(|component| component.to_string_lossy()).join("_");
+            PathBuf::from(format!("{name}_{}", Self::README))
+        } else {
+            path.join(Self::README)
+        }
+    }
+}
+
+/// Move the pages into the book source directory, named after the directories of their
+/// source relative to the sources, e.g. `tokens_erc20_Token.md` for the `Token` contract
+/// of `src/tokens/erc20/Token.sol`. The sources outside the sources directory, e.g. the
+/// scripts, are named relative to the root.
+///
+/// The names colliding across the directories fall back to the full nested path,
+/// e.g. `src_tokens_erc20_Token.sol_contract.Token.md`.
+fn flatten_paths(
+    documents: &mut [Document],
+    root: &Path,
+    sources: &Path,
+    out_src: &Path,
+    case: FilenameCase,
+) {
+    let names = documents
+        .iter()
+        .map(|document| {
+            let source = document
+                .item_path
+                .strip_prefix(sources)
+                .or_else(|_| document.item_path.strip_prefix(root))
+                .unwrap_or(&document.item_path);
+            let page = document
+                .target_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            // The kind prefix is only kept for the top-level constants, e.g. `constants.Token.md`
+            let page = match document.content {
+                DocumentContent::Constants(_) => page.as_str(),
+                _ => page.split_once('.').map_or(page.as_str(), |(_, page)| page),
+            };
+            let dirs = source.parent().map(|dirs| case_path(dirs, case)).unwrap_or_default();
+            dirs.iter()
+                .map(|dir| dir.to_string_lossy())
+                .chain(std::iter::once(page.into()))
+                .join("_")
+        })
+        .collect::<Vec<_>>();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in names.iter() {
+        *counts.entry(name.to_lowercase()).or_default() += 1;
+    }
+    for (document, name) in documents.iter_mut().zip(names) {
+        let name = if counts[&name.to_lowercase()] > 1 {
+            let nested =
+                document.target_path.strip_prefix(out_src).unwrap_or(&document.target_path);
+            nested.iter().map(|component| component.to_string_lossy()).join("_")
+        } else {
+            name
+        };
+        document.target_path = out_src.join(name);
+    }
+}
+
+/// Returns the externally accessible items, i.e. the external and public functions,
+/// the public state variables, events, errors and the type definitions.
+/// The contract members are filtered the same way, except for the libraries:
+/// their internal functions and constants are the library API, so only the private
+/// members are hidden.
+fn api_items(items: Vec<ParseItem>) -> Vec<ParseItem> {
+    let is_api = |item: &ParseItem| match item.source {
+        ParseSource::Function(ref func) => {
+            !matches!(func.ty, FunctionTy::Constructor | FunctionTy::Modifier) &&
+                is_externally_visible(func)
+        }
+        ParseSource::Variable(ref var) => is_public_variable(var),
+        _ => true,
+    };
+    let is_library_api = |item: &ParseItem| match item.source {
+        ParseSource::Function(ref func) => !func
+            .attributes
+            .iter()
+            .any(|attr| matches!(attr, FunctionAttribute::Visibility(Visibility::Private(_)))),
+        ParseSource::Variable(ref var) => !var
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, VariableAttribute::Visibility(Visibility::Private(_)))),
+        _ => true,
+    };
+    items
+        .into_iter()
+        .filter(is_api)
+        .map(|mut item| {
+            let is_library = item
+                .as_contract()
+                .map_or(false, |contract| matches!(contract.ty, ContractTy::Library(_)));
+            if is_library {
+                item.children.retain(is_library_api);
+            } else {
+                item.children.retain(is_api);
+            }
+            item
+        })
+        .collect()
+}
+
+/// Returns the canonical path with the symlinks resolved.
+/// The path which doesn't exist yet, e.g. the output directory, is resolved through
+/// its closest existing ancestor, or returned as is if none can be resolved.
+pub(crate) fn canonicalize(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = fs::canonicalize(ancestor) {
+            let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return if rest.as_os_str().is_empty() { canonical } else { canonical.join(rest) }
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Returns the names of the contracts documented on the page of the document.
+fn document_contracts(document: &Document) -> Vec<String> {
+    match document.content {
+        DocumentContent::Single(ref item) | DocumentContent::Continuation(ref item)
+            if item.as_contract().is_some() =>
+        {
+            vec![item.source.ident()]
+        }
+        _ => vec![],
+    }
+}
+
+/// Returns the Vyper source files in the directory.
+fn vyper_files(dir: &PathBuf) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_vyper(entry.path()))
+        .map(|entry| entry.into_path())
+}
+
+/// Read the source file. The Vyper contract is read as the declaration built from
+/// its compiled artifact, or [None] if there's no artifact, and the ABI artifact
+/// as the declaration built from it, or [None] if it has no ABI.
+fn read_source(path: &Path, artifacts: Option<&Path>) -> eyre::Result<Option<String>> {
+    if is_artifact(path) {
+        return Ok(artifact_declaration(path))
+    }
+    if is_vyper(path) {
+        return Ok(artifacts.and_then(|artifacts| vyper_declaration(path, artifacts)))
+    }
+    Ok(Some(fs::read_to_string(path)?))
+}
+
+/// Ensure the output directory and the source directory don't overlap, i.e. neither is
+/// inside the other, so the generated files are never read back as the sources.
+fn check_overlap(sources: &Path, out: &Path) -> eyre::Result<()> {
+    let (sources, out) = (canonicalize(sources), canonicalize(out));
+    if out.starts_with(&sources) || sources.starts_with(&out) {
+        eyre::bail!(
+            "the documentation output directory {} overlaps the source directory {}, \
+             move the output with `doc.out` or `--out`",
+            out.display(),
+            sources.display()
+        )
+    }
+    Ok(())
+}
+
+/// Convert each component of the page path to the case.
+fn case_path(path: &Path, case: FilenameCase) -> PathBuf {
+    path.iter().map(|component| case_filename(&component.to_string_lossy(), case)).collect()
+}
+
+/// Convert the file name to the case, keeping the extensions,
+/// e.g. `contract.ERC20Permit.md` becomes `contract.erc20-permit.md` in kebab case.
+fn case_filename(name: &str, case: FilenameCase) -> String {
+    let separator = match case {
+        FilenameCase::Preserve => return name.to_owned(),
+        FilenameCase::Lower => return name.to_lowercase(),
+        FilenameCase::Kebab => "-",
+        FilenameCase::Snake => "_",
+    };
+    name.split('.').map(|part| words(part).join(separator)).join(".")
+}
+
+/// Split the name into the lowercase words on the separators and the case changes,
+/// e.g. `ERC20Permit` into `erc20` and `permit`, or `IOUToken` into `iou` and `token`.
+fn words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue
+        }
+        if ch.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.extend(ch.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Suffix the output paths of the documents colliding case-insensitively, e.g.
+/// `vault.sol/contract.vault-1.md` next to `Vault.sol/contract.Vault.md`, since the documents
+/// would overwrite each other on case-insensitive filesystems.
+/// The first path in the case-sensitive order is kept, so the suffixes are deterministic.
+///
+/// The documents written to the exact same path, e.g. `VaultV2` and `Vault_V2` in kebab case,
+/// keep it, so they're reported by [check_path_collisions].
+fn disambiguate_paths(documents: &mut [Document]) {
+    let mut groups: BTreeMap<String, BTreeMap<PathBuf, Vec<usize>>> = BTreeMap::new();
+    for (index, document) in documents.iter().enumerate() {
+        let target = document.target_path.display().to_string().to_lowercase();
+        groups
+            .entry(target)
+            .or_default()
+            .entry(document.target_path.clone())
+            .or_default()
+            .push(index);
+    }
+    for paths in groups.into_values().filter(|paths| paths.len() > 1) {
+        for (suffix, indices) in paths.into_values().enumerate().skip(1) {
+            for index in indices {
+                let path = &mut documents[index].target_path;
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                path.set_file_name(format!("{stem}-{suffix}.md"));
+            }
+        }
+    }
+}
+
+/// Ensure that no two documents are written to the same output path.
+/// The paths are compared case-insensitively, since the documents would overwrite each other
+/// on case-insensitive filesystems.
+fn check_path_collisions(documents: &[Document]) -> eyre::Result<()> {
+    let mut targets: HashMap<String, &Document> = HashMap::new();
+    for document in documents.iter() {
+        let target = document.target_path.display().to_string().to_lowercase();
+        if let Some(other) = targets.insert(target, document) {
+            eyre::bail!(
+                "`{}` from {} and `{}` from {} are written to the same output path {}",
+                other.identity,
+                other.item_path.display(),
+                document.identity,
+                document.item_path.display(),
+                document.target_path.display()
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the documentation title from the configured title, the package name
+/// and the sources path, in that order.
+fn resolve_title(title: &str, package_name: Option<String>, sources: &Path) -> String {
+    if !title.is_empty() {
+        return title.to_owned()
+    }
+    package_name
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            sources
+                .parent()
+                .and_then(|parent| parent.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| DocBuilder::DEFAULT_TITLE.to_owned())
+}
+
+/// The source or the page failing to generate, reported at the end of the generation.
+#[derive(Debug)]
+struct GenerationFailure {
+    /// The source path, relative to the project root.
+    source: PathBuf,
+    /// The page failing to render, if the source was documented.
+    page: Option<PathBuf>,
+    /// The cause of the failure.
+    cause: String,
+}
+
+impl fmt::Display for GenerationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.page {
+            Some(ref page) => {
+                write!(f, "{} ({}): {}", self.source.display(), page.display(), self.cause)
+            }
+            None => write!(f, "{}: {}", self.source.display(), self.cause),
+        }
+    }
+}
+
+/// Returns the message of the panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_output() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+
+        // The output inside the sources, the sources inside the output and the same paths
+        assert!(check_overlap(&root.join("src"), &root.join("src/docs")).is_err());
+        assert!(check_overlap(&root.join("docs/src"), &root.join("docs")).is_err());
+        let err = check_overlap(&root.join("src"), &root.join("src/")).unwrap_err();
+        assert!(err.to_string().contains(&canonicalize(&root.join("src")).display().to_string()));
+
+        assert!(check_overlap(&root.join("src"), &root.join("docs")).is_ok());
+        // The sibling with the common name prefix doesn't overlap
+        assert!(check_overlap(&root.join("src"), &root.join("src-docs")).is_ok());
+    }
+
+    #[test]
+    fn vyper_sources() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("out/Vault.vy")).unwrap();
+        fs::write(root.join("src/Token.sol"), "contract Token { }").unwrap();
+        fs::write(root.join("src/Vault.vy"), "# @version 0.3.7").unwrap();
+        fs::write(root.join("src/Pool.vy"), "# @version 0.3.7").unwrap();
+        fs::write(
+            root.join("out/Vault.vy/Vault.json"),
+            r#"{"abi": [{"type": "function", "name": "total", "stateMutability": "view",
+                "inputs": [], "outputs": [{"name": "", "type": "uint256"}]}]}"#,
+        )
+        .unwrap();
+
+        let builder =
+            DocBuilder::new(root.to_path_buf(), root.join("src")).with_artifacts(root.join("out"));
+        let sources = builder.collect_sources().unwrap().into_iter().collect::<HashSet<_>>();
+        assert_eq!(
+            sources,
+            HashSet::from([
+                root.join("src/Token.sol"),
+                root.join("src/Vault.vy"),
+                root.join("src/Pool.vy")
+            ])
+        );
+
+        let artifacts = Some(root.join("out"));
+        let vault = read_source(&root.join("src/Vault.vy"), artifacts.as_deref()).unwrap();
+        assert!(vault.unwrap().contains("function total() external view returns (uint256) {}"));
+        assert_eq!(read_source(&root.join("src/Pool.vy"), artifacts.as_deref()).unwrap(), None);
+        assert_eq!(read_source(&root.join("src/Vault.vy"), None).unwrap(), None);
+    }
+
+    #[test]
+    fn abi_dir_sources() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("abis/Vault.sol")).unwrap();
+        fs::write(root.join("src/Token.sol"), "contract Token { }").unwrap();
+        fs::create_dir_all(root.join("abis/metadata")).unwrap();
+        fs::write(
+            root.join("abis/Vault.sol/Vault.json"),
+            r#"{"abi": [{"type": "function", "name": "total", "stateMutability": "view",
+                "inputs": [], "outputs": [{"name": "", "type": "uint256"}]}],
+                "metadata": {"settings": {"compilationTarget": {"src/Vault.sol": "Vault"}}}}"#,
+        )
+        .unwrap();
+        // The raw metadata of the same contract isn't documented twice
+        fs::write(
+            root.join("abis/metadata/Vault.json"),
+            r#"{"settings": {"compilationTarget": {"src/Vault.sol": "Vault"}},
+                "output": {"abi": []}}"#,
+        )
+        .unwrap();
+
+        let config = DocConfig { abi_dir: Some(PathBuf::from("abis")), ..Default::default() };
+        let builder = DocBuilder::new(root.to_path_buf(), root.join("src")).with_config(config);
+        let vault = root.join("abis/Vault.sol/Vault.json");
+        assert_eq!(
+            builder.collect_sources().unwrap(),
+            vec![root.join("src/Token.sol"), vault.clone()]
+        );
+        assert_eq!(
+            builder.relative_source_path(&vault).unwrap(),
+            PathBuf::from("abis/Vault.sol/Vault.json")
+        );
+        assert!(read_source(&vault, None).unwrap().unwrap().contains("contract Vault {"));
+
+        // The pages of the artifacts are grouped under the section
+        let config = DocConfig {
+            abi_dir: Some(PathBuf::from("abis")),
+            abi_section: Some("Deployed".to_owned()),
+            ..Default::default()
+        };
+        let builder = builder.with_config(config);
+        assert_eq!(
+            builder.relative_source_path(&vault).unwrap(),
+            PathBuf::from("Deployed/Vault.sol/Vault.json")
+        );
+        assert_eq!(
+            builder.relative_source_path(&root.join("src/Token.sol")).unwrap(),
+            PathBuf::from("src/Token.sol")
+        );
+
+        // The pages of the artifacts can't overwrite the pages of the sources
+        let config = DocConfig {
+            abi_dir: Some(PathBuf::from("abis")),
+            abi_section: Some("src".to_owned()),
+            ..Default::default()
+        };
+        let builder = builder.with_config(config);
+        assert_eq!(
+            builder.relative_source_path(&vault).unwrap_err().to_string(),
+            "the ABI section `src` collides with the source directory src, \
+             set another `abi_section`"
+        );
+    }
+
+    #[test]
+    fn fluent_configuration() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src/mocks")).unwrap();
+        fs::write(root.join("src/Token.sol"), "contract Token { }").unwrap();
+        fs::write(root.join("src/mocks/MockToken.sol"), "contract MockToken { }").unwrap();
+
+        DocBuilder::new(root.to_path_buf(), root.join("src"))
+            .with_out(PathBuf::from("book"))
+            .with_ignore("src/mocks/*.sol")
+            .build()
+            .unwrap();
+        assert!(root.join("book/src/src/Token.sol/contract.Token.md").exists());
+        assert!(!root.join("book/src/src/mocks").exists());
+        assert!(!root.join("docs").exists());
+    }
+
+    #[test]
+    fn summary_order() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src/Tokens")).unwrap();
+        fs::write(root.join("src/Vault.sol"), "contract Vault { }").unwrap();
+        fs::write(root.join("src/Tokens/Token.sol"), "contract Token { }").unwrap();
+        fs::write(root.join("src/Access.sol"), "contract Access { }").unwrap();
+        let summary = |summary_order: SummaryOrder| {
+            let config = DocConfig { summary_order, ..Default::default() };
+            DocBuilder::new(root.to_path_buf(), root.join("src"))
+                .with_config(config)
+                .build()
+                .unwrap();
+            let summary = fs::read_to_string(root.join("docs/src/SUMMARY.md")).unwrap();
+            ["[Access]", "[❱ Tokens]", "[Vault]"]
+                .into_iter()
+                .sorted_by_key(|entry| summary.find(entry).expect("missing summary entry"))
+                .collect_vec()
+        };
+
+        assert_eq!(summary(SummaryOrder::default()), ["[❱ Tokens]", "[Access]", "[Vault]"]);
+        assert_eq!(summary(SummaryOrder::FilesFirst), ["[Access]", "[Vault]", "[❱ Tokens]"]);
+        assert_eq!(summary(SummaryOrder::Mixed), ["[Access]", "[❱ Tokens]", "[Vault]"]);
+    }
+
+    #[test]
+    fn fail_on_empty() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/Constants.sol"), "uint256 constant FEE = 100;").unwrap();
+        let build = |config: DocConfig| {
+            DocBuilder::new(root.to_path_buf(), root.join("src")).with_config(config).build()
+        };
+
+        let config = DocConfig { fail_on_empty: true, ..Default::default() };
+        let err = build(config.clone()).unwrap_err();
+        assert!(err.to_string().starts_with("no contracts documented at"));
+
+        // All sources are ignored
+        fs::write(root.join("src/Vault.sol"), "contract Vault { }").unwrap();
+        let ignore = vec!["src/*.sol".to_owned()];
+        assert!(build(DocConfig { ignore: ignore.clone(), ..config }).is_err());
+        assert!(build(DocConfig { ignore, ..Default::default() }).is_ok());
+    }
+
+    #[test]
+    fn partial_output() {
+        #[derive(Debug)]
+        struct FailingHook {
+            panic: bool,
+        }
+
+        impl PageHook for FailingHook {
+            fn process(&self, document: &Document, content: String) -> eyre::Result<String> {
+                match document.identity.as_str() {
+                    "Vault" => eyre::bail!("unsupported page"),
+                    "Pool" if self.panic => panic!("unexpected item"),
+                    _ => Ok(content),
+                }
+            }
+        }
+
+        #[derive(Debug)]
+        struct FailingPreprocessor;
+
+        impl Preprocessor for FailingPreprocessor {
+            fn id(&self) -> crate::PreprocessorId {
+                STANDARD_JSON_ID
+            }
+
+            fn preprocess(&self, documents: Vec<Document>) -> eyre::Result<Vec<Document>> {
+                if documents.iter().any(|document| document.identity == "Escrow") {
+                    eyre::bail!("unsupported source")
+                }
+                Ok(documents)
+            }
+        }
+
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/Token.sol"), "contract Token { }").unwrap();
+        fs::write(root.join("src/Vault.sol"), "contract Vault { }").unwrap();
+        fs::write(root.join("src/Pool.sol"), "contract Pool { }").unwrap();
+        let build = |fail_fast: bool| {
+            DocBuilder::new(root.to_path_buf(), root.join("src"))
+                .with_page_hook(FailingHook { panic: !fail_fast })
+                .with_preprocessor(FailingPreprocessor)
+                .with_fail_fast(fail_fast)
+                .build()
+        };
+
+        // The panics aren't caught with `fail_fast`, so only the error is raised
+        let err = build(true).unwrap_err().to_string();
+        assert_eq!(err, "unsupported page");
+        assert!(!root.join("docs/src/src/Token.sol/contract.Token.md").exists());
+
+        // The sources failing to parse and to preprocess are left out like the failed pages
+        fs::write(root.join("src/Broken.sol"), "contract Broken {").unwrap();
+        fs::write(root.join("src/Escrow.sol"), "contract Escrow { }").unwrap();
+        let err = build(false).unwrap_err().to_string();
+        assert!(err.starts_with(
+            "failed to document 4 source(s) or page(s), the other pages were written:\n"
+        ));
+        assert!(err.contains("  src/Broken.sol: Failed to parse Solidity code for"));
+        assert!(err.contains("  src/Escrow.sol: unsupported source\n"));
+        assert!(err.contains(
+            "  src/Vault.sol (docs/src/src/Vault.sol/contract.Vault.md): unsupported page"
+        ));
+        assert!(err.contains(
+            "  src/Pool.sol (docs/src/src/Pool.sol/contract.Pool.md): panicked: unexpected item"
+        ));
+        assert!(root.join("docs/src/src/Token.sol/contract.Token.md").exists());
+        assert!(!root.join("docs/src/src/Vault.sol/contract.Vault.md").exists());
+
+        // Only the written pages are listed in the summary and the model
+        let summary = fs::read_to_string(root.join("docs/src/SUMMARY.md")).unwrap();
+        assert!(summary.contains("contract.Token.md"));
+        assert!(!["Vault", "Pool", "Escrow", "Broken"].iter().any(|name| summary.contains(name)));
+        let model = DocModel::read(&root.join("docs")).unwrap();
+        assert_eq!(
+            model.items.iter().map(|item| item.name.as_str()).collect::<Vec<_>>(),
+            ["Token"]
+        );
+    }
+
+    #[test]
+    fn path_collision() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/VaultV2.sol"), "contract Vault { }").unwrap();
+        fs::write(root.join("src/Vault_V2.sol"), "contract Vault { }").unwrap();
+        let build = |config: DocConfig| {
+            DocBuilder::new(root.to_path_buf(), root.join("src")).with_config(config).build()
+        };
+        assert!(build(DocConfig::default()).is_ok());
+
+        // The sources are written to the same directory in kebab case
+        let config = DocConfig { filename_case: FilenameCase::Kebab, ..Default::default() };
+        let err = build(config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "`Vault` from {} and `Vault` from {} are written to the same output path \
+                 docs/src/src/vault-v2.sol/contract.vault.md",
+                root.join("src/VaultV2.sol").display(),
+                root.join("src/Vault_V2.sol").display(),
+            )
+        );
+    }
+
+    #[test]
+    fn exact_path_collision() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/Vault.sol"), "contract VaultV2 { }\ncontract Vault_V2 { }")
+            .unwrap();
+
+        // Both contracts are written to `contract.vault-v2.md`, so neither is suffixed
+        let config = DocConfig { filename_case: FilenameCase::Kebab, ..Default::default() };
+        let err = DocBuilder::new(root.to_path_buf(), root.join("src"))
+            .with_config(config)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().ends_with(
+            "are written to the same output path docs/src/src/vault.sol/contract.vault-v2.md"
+        ));
+        assert!(!root.join("docs/src/src/vault.sol/contract.vault-v2-1.md").exists());
+    }
+
+    #[test]
+    fn case_only_path_suffixes() {
+        let document = |source: &str, ident: &str| {
+            Document::new(
+                PathBuf::from(format!("src/{source}")),
+                PathBuf::from(format!("docs/src/src/{source}/contract.{ident}.md")),
+            )
+            .with_content(DocumentContent::Empty, ident.to_owned())
+        };
+
+        let mut documents = vec![
+            document("vault.sol", "vault"),
+            document("Vault.sol", "Vault"),
+            document("Token.sol", "Token"),
+        ];
+        disambiguate_paths(&mut documents);
+        assert_eq!(
+            documents.iter().map(|document| document.target_path.as_path()).collect::<Vec<_>>(),
+            vec![
+                Path::new("docs/src/src/vault.sol/contract.vault-1.md"),
+                Path::new("docs/src/src/Vault.sol/contract.Vault.md"),
+                Path::new("docs/src/src/Token.sol/contract.Token.md"),
+            ]
+        );
+        assert!(check_path_collisions(&documents).is_ok());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinked_sources() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("shared")).unwrap();
+        fs::write(root.join("src/Vault.sol"), "contract Vault { }").unwrap();
+        fs::write(root.join("shared/Token.sol"), "contract Token { }").unwrap();
+        std::os::unix::fs::symlink(root.join("shared"), root.join("src/shared")).unwrap();
+
+        // The symlinked file is documented once at its in-project path.
+        let builder =
+            DocBuilder::new(root.to_path_buf(), root.join("src")).with_include(root.join("shared"));
+        let sources = builder.collect_sources().unwrap().into_iter().collect::<HashSet<_>>();
+        assert_eq!(
+            sources,
+            HashSet::from([root.join("src/Vault.sol"), root.join("src/shared/Token.sol")])
+        );
+
+        // The ignore globs match the symlink targets.
+        let builder =
+            DocBuilder::new(root.to_path_buf(), root.join("src")).with_config(DocConfig {
+                ignore: vec!["shared/*.sol".to_owned()],
+                ..Default::default()
+            });
+        assert_eq!(builder.collect_sources().unwrap(), vec![root.join("src/Vault.sol")]);
+    }
+
+    #[test]
+    fn api_only_items() {
+        let src = r#"
+            function helper() { }
+
+            contract Vault {
+                uint256 public total;
+                uint256 internal fees;
+
+                event Deposit(uint256 amount);
+                error Paused();
+
+                modifier whenNotPaused() { _; }
+
+                constructor() { }
+
+                function deposit(uint256 amount) external { }
+                function balance() public view returns (uint256) { }
+                function _update() internal { }
+                function _check() private { }
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).unwrap();
+        let mut doc = Parser::new(comments, src.to_owned());
+        source.visit(&mut doc).unwrap();
+
+        let items = api_items(doc.items());
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].children.iter().map(|item| item.source.ident()).collect::<Vec<_>>(),
+            vec!["total", "Deposit", "Paused", "deposit", "balance"]
+        );
+    }
+
+    #[test]
+    fn library_internal_functions() {
+        let src = r#"
+            /// @notice The fixed point math.
+            library FixedPointMath {
+                uint256 internal constant WAD = 1e18;
+                uint256 private constant HALF_WAD = 5e17;
+
+                /// @notice Multiply the fixed point numbers, rounding down.
+                /// @param x The multiplicand.
+                /// @param y The multiplier.
+                function mulWad(uint256 x, uint256 y) internal pure returns (uint256) { }
+
+                function _round(uint256 x) private pure returns (uint256) { }
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).unwrap();
+        let mut doc = Parser::new(comments, src.to_owned());
+        source.visit(&mut doc).unwrap();
+
+        // The internal members are documented even if only the API is
+        let items = api_items(doc.items());
+        assert_eq!(
+            items[0].children.iter().map(|item| item.source.ident()).collect::<Vec<_>>(),
+            vec!["WAD", "mulWad"]
+        );
+
+        let document = Document::new(PathBuf::from("src/FixedPointMath.sol"), PathBuf::new())
+            .with_content(
+                DocumentContent::Single(items.into_iter().next().unwrap()),
+                "FixedPointMath".to_owned(),
+            );
+        let page = document.as_doc().unwrap();
+        assert!(page.contains("### mulWad"));
+        assert!(page.contains("Multiply the fixed point numbers, rounding down."));
+        assert!(!page.contains("_round"));
+    }
+
+    #[test]
+    fn flat_paths() {
+        let root = Path::new("/project");
+        let out_src = Path::new("docs/src");
+        let document = |source: &str, page: &str, ident: &str| {
+            let content = match page {
+                "constants" => DocumentContent::Constants(vec![]),
+                _ => DocumentContent::Empty,
+            };
+            Document::new(
+                root.join(source),
+                out_src.join(source).join(format!("{page}.{ident}.md")),
+            )
+            .with_content(content, ident.to_owned())
+        };
+        let mut documents = vec![
+            document("src/tokens/erc20/Token.sol", "contract", "Token"),
+            document("src/tokens/erc20/Token.sol", "constants", "Token"),
+            document("src/Vault.sol", "contract", "Vault"),
+            document("src/a/b/Pool.sol", "contract", "Pool"),
+            document("src/a_b/Pools.sol", "library", "pool"),
+            document("script/Deploy.s.sol", "contract", "Deploy"),
+        ];
+        flatten_paths(&mut documents, root, &root.join("src"), out_src, FilenameCase::Preserve);
+
+        let paths = documents
+            .iter()
+            .map(|document| document.target_path.display().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "docs/src/tokens_erc20_Token.md",
+                "docs/src/tokens_erc20_constants.Token.md",
+                "docs/src/Vault.md",
+                "docs/src/src_a_b_Pool.sol_contract.Pool.md",
+                "docs/src/src_a_b_Pools.sol_library.pool.md",
+                "docs/src/script_Deploy.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn filename_cases() {
+        let path = Path::new("src/tokens/ERC20Permit.sol/contract.ERC20Permit.md");
+        let cased = |case| case_path(path, case).display().to_string();
+        assert_eq!(cased(FilenameCase::Preserve), path.display().to_string());
+        assert_eq!(
+            cased(FilenameCase::Lower),
+            "src/tokens/erc20permit.sol/contract.erc20permit.md"
+        );
+        assert_eq!(
+            cased(FilenameCase::Kebab),
+            "src/tokens/erc20-permit.sol/contract.erc20-permit.md"
+        );
+        assert_eq!(
+            cased(FilenameCase::Snake),
+            "src/tokens/erc20_permit.sol/contract.erc20_permit.md"
+        );
+
+        assert_eq!(case_filename("IOUToken", FilenameCase::Kebab), "iou-token");
+        assert_eq!(case_filename("vault_v2-core.sol", FilenameCase::Kebab), "vault-v2-core.sol");
+        assert_eq!(case_filename("build-info.md", FilenameCase::Snake), "build_info.md");
+    }
+
+    #[test]
+    fn title_fallbacks() {
+        let sources = Path::new("/projects/vault/src");
+        assert_eq!(resolve_title("Vault", Some("vault-core".to_owned()), sources), "Vault");
+        assert_eq!(resolve_title("", Some("vault-core".to_owned()), sources), "vault-core");
+        assert_eq!(resolve_title("", Some(String::new()), sources), "vault");
+        assert_eq!(resolve_title("", None, Path::new("src")), "Documentation");
+    }
 }
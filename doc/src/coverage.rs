@@ -0,0 +1,223 @@
+//! The natspec coverage report of the documented items.
+
+use crate::{number, output::OutputFiles, DocModel, ModelComment};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The natspec coverage report, written as `doc-coverage.json`.
+///
+/// The report is versioned independently of the [DocModel], so the dashboards
+/// tracking the documentation health aren't affected by the model changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocCoverage {
+    /// The report schema version.
+    pub version: u32,
+    /// The number of the symbols.
+    pub total: usize,
+    /// The number of the symbols with the `@notice` or the `@dev` comment.
+    pub documented: usize,
+    /// The percentage of the documented symbols.
+    pub coverage: f64,
+    /// The coverage of each documented item, e.g. a contract or a top-level function.
+    pub items: Vec<ItemCoverage>,
+}
+
+/// The natspec coverage of the documented item and its members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemCoverage {
+    /// The item kind, e.g. `contract` or `function`.
+    pub kind: String,
+    /// The item name.
+    pub name: String,
+    /// The source file path relative to the project root.
+    pub source: PathBuf,
+    /// The number of the symbols, including the item itself.
+    pub total: usize,
+    /// The number of the documented symbols.
+    pub documented: usize,
+    /// The percentage of the documented symbols.
+    pub coverage: f64,
+    /// The item itself followed by its members.
+    pub symbols: Vec<SymbolCoverage>,
+}
+
+/// The natspec comments of the symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolCoverage {
+    /// The symbol kind, e.g. `function` or `event`.
+    pub kind: String,
+    /// The symbol name.
+    pub name: String,
+    /// The symbol signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// Whether the symbol has the `@notice` comment, including the untagged ones.
+    pub notice: bool,
+    /// Whether the symbol has the `@dev` comment.
+    pub dev: bool,
+}
+
+impl SymbolCoverage {
+    fn new(kind: &str, name: &str, signature: &str, comments: &[ModelComment]) -> Self {
+        let has_tag = |tag: &str| comments.iter().any(|comment| comment.tag == tag);
+        Self {
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            signature: signature.to_owned(),
+            notice: has_tag("notice"),
+            dev: has_tag("dev"),
+        }
+    }
+
+    /// Returns true if the symbol has either the `@notice` or the `@dev` comment.
+    pub fn is_documented(&self) -> bool {
+        self.notice || self.dev
+    }
+}
+
+impl DocCoverage {
+    /// The current report schema version.
+    pub const VERSION: u32 = 1;
+
+    /// The report file name within the documentation output directory.
+    pub const FILE_NAME: &'static str = "doc-coverage.json";
+
+    /// Collect the coverage of the model items and their members.
+    pub fn new(model: &DocModel) -> Self {
+        let items = model
+            .items
+            .iter()
+            .map(|item| {
+                let symbols = std::iter::once(SymbolCoverage::new(
+                    &item.kind,
+                    &item.name,
+                    &item.signature,
+                    &item.comments,
+                ))
+                .chain(item.all_members().map(|member| {
+                    SymbolCoverage::new(
+                        &member.kind,
+                        &member.name,
+                        &member.signature,
+                        &member.comments,
+                    )
+                }))
+                .collect::<Vec<_>>();
+                let documented = symbols.iter().filter(|symbol| symbol.is_documented()).count();
+                ItemCoverage {
+                    kind: item.kind.clone(),
+                    name: item.name.clone(),
+                    source: item.source.clone(),
+                    total: symbols.len(),
+                    documented,
+                    coverage: percentage(documented, symbols.len()),
+                    symbols,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let total = items.iter().map(|item| item.total).sum();
+        let documented = items.iter().map(|item| item.documented).sum();
+        Self {
+            version: Self::VERSION,
+            total,
+            documented,
+            coverage: percentage(documented, total),
+            items,
+        }
+    }
+
+    /// Write the report to the documentation output directory.
+    pub fn write(&self, out_dir: &Path) -> eyre::Result<()> {
+        OutputFiles::default()
+            .write(out_dir.join(Self::FILE_NAME), serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for DocCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<40}{:>12}{:>10}", "Item", "Documented", "Coverage")?;
+        for item in self.items.iter() {
+            let documented = format!("{}/{}", item.documented, item.total);
+            writeln!(
+                f,
+                "{:<40}{documented:>12}{:>10}",
+                item.name,
+                number::percentage(item.coverage)
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Coverage: {} ({}/{})",
+            number::percentage(self.coverage),
+            self.documented,
+            self.total
+        )
+    }
+}
+
+fn percentage(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.
+    } else {
+        documented as f64 * 100. / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModelItem, ModelMember, SpecialFunctions};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn symbols_coverage() {
+        let comment = |tag: &str| ModelComment { tag: tag.to_owned(), value: String::new() };
+        let member = |name: &str, comments: Vec<ModelComment>| ModelMember {
+            kind: "function".to_owned(),
+            name: name.to_owned(),
+            signature: format!("{name}()"),
+            code: format!("function {name}() external;"),
+            comments,
+            topic: None,
+            indexed: vec![],
+            visibility: None,
+            page: None,
+        };
+        let model = DocModel {
+            version: DocModel::VERSION,
+            items: vec![ModelItem {
+                kind: "contract".to_owned(),
+                name: "Vault".to_owned(),
+                signature: "Vault".to_owned(),
+                source: PathBuf::from("src/Vault.sol"),
+                page: PathBuf::from("src/Vault.sol/contract.Vault.md"),
+                code: "contract Vault".to_owned(),
+                comments: vec![comment("notice")],
+                security_contact: None,
+                special: SpecialFunctions::default(),
+                members: vec![
+                    member("deposit", vec![comment("dev")]),
+                    member("withdraw", vec![comment("param")]),
+                    member("pause", vec![]),
+                ],
+            }],
+            build_info: None,
+            redirects: BTreeMap::new(),
+        };
+
+        let coverage = DocCoverage::new(&model);
+        assert_eq!((coverage.documented, coverage.total), (2, 4));
+        assert!((coverage.coverage - 50.).abs() < f64::EPSILON);
+
+        let item = &coverage.items[0];
+        assert_eq!(item.symbols.len(), 4);
+        assert!(item.symbols[0].notice && !item.symbols[0].dev);
+        assert!(!item.symbols[1].notice && item.symbols[1].dev);
+        // The `@param` comment alone doesn't document the symbol
+        assert!(!item.symbols[2].is_documented());
+    }
+}
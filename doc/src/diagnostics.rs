@@ -0,0 +1,151 @@
+//! The diagnostics collected during the documentation generation.
+
+use serde::Serialize;
+use std::{fmt, path::PathBuf, sync::Mutex};
+
+/// The severity of the [DocDiagnostic].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The issue is reported, but the generation succeeds.
+    Warning,
+    /// The issue fails the generation, e.g. the warning denied with `--deny-warnings`.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// The diagnostic reported during the generation, e.g. the `@param` not matching any parameter.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DocDiagnostic {
+    /// The source file path relative to the project root, if the diagnostic has a location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// The 1-based line number, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The diagnostic code, e.g. `natspec-param`.
+    pub code: &'static str,
+    /// The diagnostic message.
+    pub message: String,
+    /// The identifier of the item the diagnostic is reported for, e.g. `Vault.deposit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+    /// The diagnostic severity.
+    pub severity: Severity,
+}
+
+impl DocDiagnostic {
+    /// Create the warning without the location.
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line: None,
+            code,
+            message: message.into(),
+            item: None,
+            severity: Severity::Warning,
+        }
+    }
+
+    /// Set the source location of the diagnostic.
+    #[must_use]
+    pub fn with_location(mut self, file: PathBuf, line: Option<usize>) -> Self {
+        self.file = Some(file);
+        self.line = line;
+        self
+    }
+
+    /// Set the identifier of the item the diagnostic is reported for.
+    #[must_use]
+    pub fn with_item(mut self, item: impl Into<String>) -> Self {
+        self.item = Some(item.into());
+        self
+    }
+}
+
+impl fmt::Display for DocDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: ", file.display())?,
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            _ => {}
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// The collector of the diagnostics reported during the generation.
+///
+/// It's shared by the builder and the command, so that all diagnostics are reported
+/// in the same format once the generation is done.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: Mutex<Vec<DocDiagnostic>>,
+}
+
+impl Diagnostics {
+    /// Report the diagnostic.
+    pub fn push(&self, diagnostic: DocDiagnostic) {
+        self.diagnostics.lock().expect("failed to lock diagnostics").push(diagnostic);
+    }
+
+    /// Report the diagnostics.
+    pub fn extend(&self, diagnostics: impl IntoIterator<Item = DocDiagnostic>) {
+        self.diagnostics.lock().expect("failed to lock diagnostics").extend(diagnostics);
+    }
+
+    /// Returns the number of the reported warnings.
+    pub fn warnings(&self) -> usize {
+        let diagnostics = self.diagnostics.lock().expect("failed to lock diagnostics");
+        diagnostics.iter().filter(|diagnostic| diagnostic.severity == Severity::Warning).count()
+    }
+
+    /// Turn the reported warnings into errors, i.e. for `--deny-warnings`.
+    pub fn escalate(&self) {
+        let mut diagnostics = self.diagnostics.lock().expect("failed to lock diagnostics");
+        diagnostics.iter_mut().for_each(|diagnostic| diagnostic.severity = Severity::Error);
+    }
+
+    /// Take the reported diagnostics ordered by the location, leaving the collector empty.
+    pub fn take(&self) -> Vec<DocDiagnostic> {
+        let mut diagnostics =
+            std::mem::take(&mut *self.diagnostics.lock().expect("failed to lock diagnostics"));
+        diagnostics.sort();
+        diagnostics.dedup();
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escalate_warnings() {
+        let diagnostics = Diagnostics::default();
+        diagnostics.push(DocDiagnostic::warning("redirect-ignored", "ignoring the redirect"));
+        diagnostics.push(
+            DocDiagnostic::warning("natspec-tag", "malformed natspec tag `@custom:Security`")
+                .with_location(PathBuf::from("src/Vault.sol"), Some(16)),
+        );
+        assert_eq!(diagnostics.warnings(), 2);
+
+        diagnostics.escalate();
+        assert_eq!(diagnostics.warnings(), 0);
+
+        let diagnostics = diagnostics.take();
+        assert_eq!(diagnostics[0].to_string(), "ignoring the redirect");
+        assert_eq!(
+            serde_json::to_string(&diagnostics[1]).unwrap(),
+            r#"{"file":"src/Vault.sol","line":16,"code":"natspec-tag","message":"malformed natspec tag `@custom:Security`","severity":"error"}"#
+        );
+    }
+}
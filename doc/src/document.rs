@@ -1,6 +1,9 @@
 use std::{collections::HashMap, path::PathBuf, sync::Mutex};
 
-use crate::{ParseItem, PreprocessorId, PreprocessorOutput};
+use crate::{
+    AssemblyFunction, CompilerSettings, ConformanceMatrix, DependencyPackage, EvmRequirements,
+    ParseItem, PreprocessorId, PreprocessorOutput, SecurityNotes, SelectorCollision,
+};
 
 /// The wrapper around the [ParseItem] containing additional
 /// information the original item and extra context for outputting it.
@@ -16,17 +19,46 @@ pub struct Document {
     pub target_path: PathBuf,
     /// The document display identity.
     pub identity: String,
+    /// The source directory of the book the document is written to, i.e. `<out>/src`.
+    /// The links to the other pages are absolute within it.
+    pub book_src: PathBuf,
+    /// The maximum number of the characters of the constant values, `0` for no limit.
+    pub max_value_chars: usize,
     /// The preprocessors results.
     context: Mutex<HashMap<PreprocessorId, PreprocessorOutput>>,
 }
 
 /// The content of the document.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DocumentContent {
     Empty,
     Single(ParseItem),
     Constants(Vec<ParseItem>),
     OverloadedFunctions(Vec<ParseItem>),
+    /// The contract members cut from the contract page by the section limits.
+    Continuation(ParseItem),
+    InterfaceConformance(ConformanceMatrix),
+    BuildInfo(CompilerSettings, Vec<EvmRequirements>),
+    Security(Vec<SecurityNotes>),
+    Dependencies(Vec<DependencyPackage>),
+    SelectorCollisions(Vec<SelectorCollision>),
+    AssemblyIndex(Vec<AssemblyFunction>),
+}
+
+impl DocumentContent {
+    /// Returns true if the content is a project-wide appendix page
+    /// rather than an item of a source file.
+    pub fn is_appendix(&self) -> bool {
+        matches!(
+            self,
+            Self::InterfaceConformance(_) |
+                Self::BuildInfo(..) |
+                Self::Security(_) |
+                Self::Dependencies(_) |
+                Self::SelectorCollisions(_) |
+                Self::AssemblyIndex(_)
+        )
+    }
 }
 
 impl Document {
@@ -37,6 +69,8 @@ impl Document {
             target_path,
             item_content: String::default(),
             identity: String::default(),
+            book_src: PathBuf::default(),
+            max_value_chars: 0,
             content: DocumentContent::Empty,
             context: Mutex::new(HashMap::default()),
         }
@@ -63,6 +97,21 @@ impl Document {
     }
 }
 
+impl Clone for Document {
+    fn clone(&self) -> Self {
+        Self {
+            content: self.content.clone(),
+            item_path: self.item_path.clone(),
+            item_content: self.item_content.clone(),
+            target_path: self.target_path.clone(),
+            identity: self.identity.clone(),
+            book_src: self.book_src.clone(),
+            max_value_chars: self.max_value_chars,
+            context: Mutex::new(self.context.lock().expect("failed to lock context").clone()),
+        }
+    }
+}
+
 /// Read the preprocessor output variant from document context.
 /// Returns [None] if there is no output.
 macro_rules! read_context {
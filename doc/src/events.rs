@@ -0,0 +1,124 @@
+//! The events of the generation, streamed to the integrations, e.g. the editor extensions.
+
+use crate::{DocDiagnostic, DocStats};
+use serde::Serialize;
+use std::{fmt::Debug, path::PathBuf};
+
+/// The event of the generation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum DocEvent {
+    /// The page was rendered.
+    PageRendered {
+        /// The page path, relative to the output directory.
+        path: PathBuf,
+        /// The names of the contracts documented on the page.
+        contracts: Vec<String>,
+        /// Whether the page was written, i.e. its content changed.
+        written: bool,
+    },
+    /// The diagnostic was reported, e.g. the `@param` not matching any parameter.
+    Diagnostic(DocDiagnostic),
+    /// The summary was written.
+    SummaryWritten {
+        /// The summary path, relative to the output directory.
+        path: PathBuf,
+    },
+    /// The informational message, e.g. the address the book is served on.
+    Message {
+        /// The message text.
+        message: String,
+    },
+    /// The generation finished.
+    Finished {
+        /// The statistics of the generation, empty if it failed.
+        stats: DocStats,
+        /// Whether the generation succeeded, i.e. didn't fail nor deny the warnings.
+        success: bool,
+        /// The error of the failed generation.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// The event with the version of its shape.
+#[derive(Serialize)]
+struct VersionedEvent<'a> {
+    version: u32,
+    #[serde(flatten)]
+    event: &'a DocEvent,
+}
+
+impl DocEvent {
+    /// The version of the event shapes, bumped on the breaking changes.
+    pub const VERSION: u32 = 1;
+
+    /// Returns the event as a single line of JSON, with the `version` and the `event` kind,
+    /// e.g. `{"version":1,"event":"summary-written","path":"src/SUMMARY.md"}`.
+    pub fn to_json_line(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&VersionedEvent { version: Self::VERSION, event: self })
+    }
+}
+
+/// Trait for receiving the events of the generation as they happen.
+///
+/// The events are emitted from the rendering threads, so the sink must be shareable.
+pub trait EventSink: Debug + Send + Sync {
+    /// Receive the event.
+    fn emit(&self, event: &DocEvent);
+}
+
+/// The event sink writing each event to stdout as a line of JSON,
+/// i.e. the `json-lines` message format.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct JsonLines;
+
+impl EventSink for JsonLines {
+    fn emit(&self, event: &DocEvent) {
+        match event.to_json_line() {
+            Ok(line) => println!("{line}"),
+            Err(err) => tracing::warn!(target: "forge::doc", ?err, "failed to serialize the event"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versioned_json_lines() {
+        let event = DocEvent::PageRendered {
+            path: PathBuf::from("src/src/Vault.sol/contract.Vault.md"),
+            contracts: vec!["Vault".to_owned()],
+            written: true,
+        };
+        let line = event.to_json_line().unwrap();
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["version"], DocEvent::VERSION);
+        assert_eq!(value["event"], "page-rendered");
+        assert_eq!(value["contracts"][0], "Vault");
+
+        let diagnostic = DocDiagnostic::warning("natspec-param", "unknown parameter `to`")
+            .with_location(PathBuf::from("src/Vault.sol"), Some(4));
+        let value: serde_json::Value =
+            serde_json::from_str(&DocEvent::Diagnostic(diagnostic).to_json_line().unwrap())
+                .unwrap();
+        assert_eq!(value["event"], "diagnostic");
+        assert_eq!(value["code"], "natspec-param");
+        assert_eq!(value["line"], 4);
+
+        let finished = DocEvent::Finished {
+            stats: DocStats::default(),
+            success: false,
+            error: Some("1 warning(s) denied".to_owned()),
+        };
+        let value: serde_json::Value =
+            serde_json::from_str(&finished.to_json_line().unwrap()).unwrap();
+        assert_eq!(value["event"], "finished");
+        assert_eq!(value["success"], false);
+        assert_eq!(value["error"], "1 warning(s) denied");
+    }
+}
@@ -1,5 +1,34 @@
+use crate::{
+    document::DocumentContent, resolver::ImportResolver, Deployment, Document, Markdown, ParseItem,
+};
+use ethers_core::utils::keccak256;
+use forge_fmt::solang_ext::SafeUnwrap;
+use itertools::Itertools;
+use solang_parser::pt::{
+    Base, CatchClause, ContractDefinition, ContractTy, ErrorDefinition, EventDefinition,
+    EventParameter, Expression, FunctionAttribute, FunctionDefinition, FunctionTy, Loc, Parameter,
+    Statement, Type, VariableAttribute, VariableDefinition, Visibility,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
 use toml::{value::Table, Value};
 
+/// Returns the deployed address linked to the block explorer of the known chains,
+/// or the plain address otherwise.
+pub(crate) fn deployment_address(deployment: &Deployment) -> String {
+    match deployment.chain.etherscan_urls() {
+        Some((_, explorer)) => Markdown::Link(
+            &deployment.address,
+            &format!("{}/address/{}", explorer.trim_end_matches('/'), deployment.address),
+        )
+        .to_string(),
+        None => Markdown::Code(&deployment.address).to_string(),
+    }
+}
+
 /// Merge original toml table with the override.
 pub(crate) fn merge_toml_table(table: &mut Table, override_table: Table) {
     for (key, override_value) in override_table {
@@ -26,3 +55,640 @@ pub(crate) fn merge_toml_table(table: &mut Table, override_table: Table) {
         };
     }
 }
+
+/// Read the compiled artifact of the contract, i.e. `<artifacts>/<source file name>/<name>.json`.
+/// The artifacts compiled with multiple compiler versions are suffixed with the version,
+/// e.g. `Vault.0.8.19.json`, the first one is used then.
+pub(crate) fn read_artifact(
+    artifacts: &Path,
+    source: &Path,
+    name: &str,
+) -> Option<serde_json::Value> {
+    let dir = artifacts.join(source.file_name()?);
+    let path =
+        Some(dir.join(format!("{name}.json"))).filter(|path| path.is_file()).or_else(|| {
+            let prefix = format!("{name}.");
+            let mut versioned = fs::read_dir(&dir)
+                .ok()?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name().map_or(false, |file| {
+                        let file = file.to_string_lossy();
+                        file.starts_with(&prefix) && file.ends_with(".json")
+                    })
+                })
+                .collect::<Vec<_>>();
+            versioned.sort();
+            versioned.into_iter().next()
+        })?;
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Format the type expression in its canonical ABI form, e.g. `uint` becomes `uint256`.
+/// User defined types are formatted as they were declared.
+pub(crate) fn canonical_type(ty: &Expression) -> String {
+    match ty {
+        Expression::Type(_, inner) => match inner {
+            Type::Address | Type::AddressPayable | Type::Payable => "address".to_owned(),
+            Type::Bool => "bool".to_owned(),
+            Type::String => "string".to_owned(),
+            Type::DynamicBytes => "bytes".to_owned(),
+            Type::Int(size) => format!("int{size}"),
+            Type::Uint(size) => format!("uint{size}"),
+            Type::Bytes(size) => format!("bytes{size}"),
+            Type::Function { .. } => "function".to_owned(),
+            _ => ty.to_string(),
+        },
+        Expression::ArraySubscript(_, ty, size) => format!(
+            "{}[{}]",
+            canonical_type(ty),
+            size.as_ref().map(|size| size.to_string()).unwrap_or_default()
+        ),
+        ty => ty.to_string(),
+    }
+}
+
+/// Returns the name of the user defined type, e.g. `Vault.Position` for `Vault.Position[]`.
+/// Returns [None] for elementary types.
+pub(crate) fn user_type_name(ty: &Expression) -> Option<String> {
+    match ty {
+        Expression::Variable(ident) => Some(ident.name.clone()),
+        Expression::MemberAccess(_, expr, member) => {
+            user_type_name(expr).map(|parent| format!("{parent}.{}", member.name))
+        }
+        Expression::ArraySubscript(_, ty, _) => user_type_name(ty),
+        _ => None,
+    }
+}
+
+/// Returns the function signature, e.g. `transfer(address,uint256)`.
+/// Returns [None] for functions without name, i.e. constructor, fallback or receive.
+pub(crate) fn function_signature(func: &FunctionDefinition) -> Option<String> {
+    let name = &func.name.as_ref()?.name;
+    let params = func
+        .params
+        .iter()
+        .filter_map(|(_, param)| param.as_ref().map(|param| canonical_type(&param.ty)))
+        .join(",");
+    Some(format!("{name}({params})"))
+}
+
+/// Returns the function declaration with the canonical ABI types, e.g.
+/// `function transfer(address,uint256) external returns (bool)`.
+/// The visibility and mutability are rendered as declared, so `nonpayable` is never written.
+pub(crate) fn abi_declaration(func: &FunctionDefinition) -> String {
+    let types = |params: &[(Loc, Option<Parameter>)]| {
+        params
+            .iter()
+            .filter_map(|(_, param)| param.as_ref().map(|param| canonical_type(&param.ty)))
+            .join(",")
+    };
+
+    let mut declaration = match func.name {
+        Some(ref name) => format!("{} {}({})", func.ty, name.name, types(&func.params)),
+        None => format!("{}({})", func.ty, types(&func.params)),
+    };
+    for attr in func.attributes.iter() {
+        match attr {
+            FunctionAttribute::Visibility(visibility) => {
+                declaration.push_str(&format!(" {visibility}"))
+            }
+            FunctionAttribute::Mutability(mutability) => {
+                declaration.push_str(&format!(" {mutability}"))
+            }
+            _ => {}
+        }
+    }
+    if !func.returns.is_empty() {
+        declaration.push_str(&format!(" returns ({})", types(&func.returns)));
+    }
+    declaration
+}
+
+/// Returns the declaration with the parameters wrapped one per line if it's longer than
+/// the width, in the style of the formatter, e.g.
+/// `function deposit(\n    uint256,\n    address\n) external returns (uint256)`.
+pub(crate) fn wrap_declaration(declaration: &str, width: usize) -> String {
+    let open = match declaration.find('(') {
+        Some(open) if declaration.len() > width => open,
+        _ => return declaration.to_owned(),
+    };
+    let mut params = vec![];
+    let mut depth = 0;
+    let mut start = open + 1;
+    for (i, ch) in declaration.char_indices().skip_while(|(i, _)| *i < open) {
+        match ch {
+            '(' => depth += 1,
+            ',' if depth == 1 => {
+                params.push(&declaration[start..i]);
+                start = i + 1;
+            }
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    params.push(&declaration[start..i]);
+                    if params.iter().all(|param| param.trim().is_empty()) {
+                        break
+                    }
+                    return format!(
+                        "{}\n{}\n){}",
+                        &declaration[..=open],
+                        params.iter().map(|param| format!("    {}", param.trim())).join(",\n"),
+                        &declaration[i + 1..]
+                    )
+                }
+            }
+            _ => {}
+        }
+    }
+    declaration.to_owned()
+}
+
+/// Returns the event signature, e.g. `Transfer(address,address,uint256)`.
+pub(crate) fn event_signature(event: &EventDefinition) -> Option<String> {
+    let name = &event.name.as_ref()?.name;
+    let params = event.fields.iter().map(|param| canonical_type(&param.ty)).join(",");
+    Some(format!("{name}({params})"))
+}
+
+/// Returns the error signature, e.g. `InsufficientBalance(uint256,uint256)`.
+pub(crate) fn error_signature(error: &ErrorDefinition) -> Option<String> {
+    let name = &error.name.as_ref()?.name;
+    let params = error.fields.iter().map(|param| canonical_type(&param.ty)).join(",");
+    Some(format!("{name}({params})"))
+}
+
+/// Returns the signature of the getter generated for a public state variable.
+/// Mapping keys and array indices become getter parameters.
+pub(crate) fn getter_signature(var: &VariableDefinition) -> Option<String> {
+    let name = &var.name.as_ref()?.name;
+    let mut params = vec![];
+    let mut ty = &var.ty;
+    loop {
+        match ty {
+            Expression::Type(_, Type::Mapping { key, value, .. }) => {
+                params.push(canonical_type(key));
+                ty = &**value;
+            }
+            Expression::ArraySubscript(_, inner, _) => {
+                params.push("uint256".to_owned());
+                ty = &**inner;
+            }
+            _ => break,
+        }
+    }
+    Some(format!("{name}({})", params.join(",")))
+}
+
+/// Returns the function selector of the signature, e.g. `0xa9059cbb` for
+/// `transfer(address,uint256)`.
+pub(crate) fn selector(signature: &str) -> String {
+    to_hex(&keccak256(signature.as_bytes())[..4])
+}
+
+/// Returns the topic 0 of the event, i.e. the hash of its signature.
+/// Returns [None] for anonymous events, which don't have the signature topic.
+pub(crate) fn event_topic(event: &EventDefinition) -> Option<String> {
+    if event.anonymous {
+        return None
+    }
+    event_signature(event).map(|signature| to_hex(&keccak256(signature.as_bytes())))
+}
+
+/// Returns the names of the event parameters split into the indexed (topics) and data ones,
+/// the indexed ones with the position of their topic, starting from `1` after the signature
+/// hash, or from `0` for the anonymous events.
+/// The unnamed parameters are identified by their type.
+pub(crate) fn event_params(event: &EventDefinition) -> (Vec<(String, usize)>, Vec<String>) {
+    let (indexed, data): (Vec<_>, Vec<_>) = event.fields.iter().partition(|param| param.indexed);
+    let names = |params: Vec<&EventParameter>| {
+        params
+            .into_iter()
+            .map(|param| {
+                param
+                    .name
+                    .as_ref()
+                    .map(|name| name.name.clone())
+                    .unwrap_or_else(|| param.ty.to_string())
+            })
+            .collect::<Vec<_>>()
+    };
+    let first_topic = usize::from(!event.anonymous);
+    let topics = names(indexed)
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| (name, first_topic + index))
+        .collect();
+    (topics, names(data))
+}
+
+/// Format the bytes as a `0x` prefixed hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|byte| format!("{byte:02x}")).join(""))
+}
+
+/// Returns true if the function of the abstract contract is declared without the body,
+/// i.e. the derived contracts must implement it.
+pub(crate) fn is_unimplemented(contract: &ContractDefinition, func: &FunctionDefinition) -> bool {
+    matches!(contract.ty, ContractTy::Abstract(_)) && func.body.is_none()
+}
+
+/// Returns true if the function is visible externally, i.e. it's either `external` or `public`.
+pub(crate) fn is_externally_visible(func: &FunctionDefinition) -> bool {
+    func.attributes.iter().any(|attr| {
+        matches!(
+            attr,
+            FunctionAttribute::Visibility(Visibility::External(_) | Visibility::Public(_))
+        )
+    })
+}
+
+/// Returns true if the state variable is declared `public`.
+pub(crate) fn is_public_variable(var: &VariableDefinition) -> bool {
+    var.attrs
+        .iter()
+        .any(|attr| matches!(attr, VariableAttribute::Visibility(Visibility::Public(_))))
+}
+
+/// The kind of the state variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VariableKind {
+    /// The value is known at compile time and inlined into the bytecode.
+    Constant,
+    /// The value is assigned once at deployment, e.g. in the constructor.
+    Immutable,
+    /// The value is kept in storage.
+    Mutable,
+}
+
+/// Returns the kind of the state variable.
+pub(crate) fn variable_kind(var: &VariableDefinition) -> VariableKind {
+    if var.attrs.iter().any(|attr| matches!(attr, VariableAttribute::Constant(_))) {
+        VariableKind::Constant
+    } else if var.attrs.iter().any(|attr| matches!(attr, VariableAttribute::Immutable(_))) {
+        VariableKind::Immutable
+    } else {
+        VariableKind::Mutable
+    }
+}
+
+/// Returns the document with its contract item, if the document contains a single contract.
+pub(crate) fn as_contract(
+    document: &Document,
+) -> Option<(&Document, &ParseItem, &ContractDefinition)> {
+    match document.content {
+        DocumentContent::Single(ref item) => {
+            item.as_contract().map(|contract| (document, item, contract))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the names of the documented contracts inheriting from the forge-std base contract,
+/// e.g. `Script` or `Test`, either directly or through other documented contracts.
+///
+/// The base is matched by the remapped path of its import, e.g. `lib/forge-std/src/Script.sol`,
+/// so the project contracts with the same name aren't mistaken for the forge-std ones.
+pub(crate) fn forge_std_descendants(
+    documents: &[Document],
+    base: &str,
+    resolver: &mut ImportResolver,
+) -> HashSet<String> {
+    let contracts = documents.iter().filter_map(as_contract).collect::<Vec<_>>();
+    let mut forge_std = |document: &Document, ident: &str| {
+        let source = resolver
+            .resolve(&document.item_path, ident)
+            .or_else(|| resolver.import_source(&document.item_path, ident));
+        source.map_or(false, |(name, path)| {
+            name == base && path.components().any(|component| component.as_os_str() == "forge-std")
+        })
+    };
+    let mut descendants = HashSet::new();
+    loop {
+        let count = descendants.len();
+        for (document, _, contract) in contracts.iter() {
+            let inherits = contract.base.iter().any(|parent| {
+                let ident = &parent.name.identifiers.last().unwrap().name;
+                descendants.contains(ident) || forge_std(document, ident)
+            });
+            if inherits {
+                descendants.insert(contract.name.safe_unwrap().name.clone());
+            }
+        }
+        if descendants.len() == count {
+            break
+        }
+    }
+    descendants
+}
+
+/// Calls the closure with the statement and every statement nested in it, in the source order.
+pub(crate) fn walk_statements<'a>(statement: &'a Statement, f: &mut dyn FnMut(&'a Statement)) {
+    f(statement);
+    match statement {
+        Statement::Block { statements, .. } => {
+            for statement in statements {
+                walk_statements(statement, f);
+            }
+        }
+        Statement::If(_, _, then, otherwise) => {
+            walk_statements(then, f);
+            if let Some(otherwise) = otherwise {
+                walk_statements(otherwise, f);
+            }
+        }
+        Statement::While(_, _, body) | Statement::DoWhile(_, body, _) => walk_statements(body, f),
+        Statement::For(_, init, _, update, body) => {
+            for statement in [init, update, body].into_iter().flatten() {
+                walk_statements(statement, f);
+            }
+        }
+        Statement::Try(_, _, returns, clauses) => {
+            if let Some((_, body)) = returns {
+                walk_statements(body, f);
+            }
+            for clause in clauses {
+                match clause {
+                    CatchClause::Simple(_, _, body) | CatchClause::Named(_, _, _, body) => {
+                        walk_statements(body, f)
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Calls the closure with every expression within the statement, including the nested
+/// statements and expressions.
+pub(crate) fn walk_expressions<'a>(statement: &'a Statement, f: &mut dyn FnMut(&'a Expression)) {
+    walk_statements(statement, &mut |statement| {
+        let exprs: Vec<&Expression> = match statement {
+            Statement::Expression(_, expr) |
+            Statement::Emit(_, expr) |
+            Statement::If(_, expr, _, _) |
+            Statement::While(_, expr, _) |
+            Statement::DoWhile(_, _, expr) |
+            Statement::Try(_, expr, _, _) => vec![expr],
+            Statement::VariableDefinition(_, _, expr) | Statement::Return(_, expr) => {
+                expr.iter().collect()
+            }
+            Statement::For(_, _, cond, _, _) => cond.iter().map(|cond| &**cond).collect(),
+            Statement::Revert(_, _, args) => args.iter().collect(),
+            Statement::RevertNamedArgs(_, _, args) | Statement::Args(_, args) => {
+                args.iter().map(|arg| &arg.expr).collect()
+            }
+            _ => vec![],
+        };
+        for expr in exprs {
+            walk_expression(expr, &mut *f);
+        }
+    });
+}
+
+/// Calls the closure with the expression and every expression nested in it, in the source order.
+pub(crate) fn walk_expression<'a>(expr: &'a Expression, f: &mut dyn FnMut(&'a Expression)) {
+    f(expr);
+    let nested: Vec<&Expression> = match expr {
+        Expression::PostIncrement(_, expr) |
+        Expression::PostDecrement(_, expr) |
+        Expression::New(_, expr) |
+        Expression::MemberAccess(_, expr, _) |
+        Expression::Not(_, expr) |
+        Expression::BitwiseNot(_, expr) |
+        Expression::Delete(_, expr) |
+        Expression::PreIncrement(_, expr) |
+        Expression::PreDecrement(_, expr) |
+        Expression::UnaryPlus(_, expr) |
+        Expression::Negate(_, expr) |
+        Expression::Parenthesis(_, expr) => vec![&**expr],
+        Expression::ArraySubscript(_, expr, index) => {
+            std::iter::once(&**expr).chain(index.as_deref()).collect()
+        }
+        Expression::ArraySlice(_, expr, start, end) => {
+            std::iter::once(&**expr).chain(start.as_deref()).chain(end.as_deref()).collect()
+        }
+        Expression::FunctionCall(_, callee, args) => {
+            std::iter::once(&**callee).chain(args).collect()
+        }
+        Expression::NamedFunctionCall(_, callee, args) => {
+            std::iter::once(&**callee).chain(args.iter().map(|arg| &arg.expr)).collect()
+        }
+        Expression::FunctionCallBlock(_, callee, block) => {
+            walk_expression(callee, f);
+            walk_expressions(block, f);
+            vec![]
+        }
+        Expression::Power(_, left, right) |
+        Expression::Multiply(_, left, right) |
+        Expression::Divide(_, left, right) |
+        Expression::Modulo(_, left, right) |
+        Expression::Add(_, left, right) |
+        Expression::Subtract(_, left, right) |
+        Expression::ShiftLeft(_, left, right) |
+        Expression::ShiftRight(_, left, right) |
+        Expression::BitwiseAnd(_, left, right) |
+        Expression::BitwiseXor(_, left, right) |
+        Expression::BitwiseOr(_, left, right) |
+        Expression::Less(_, left, right) |
+        Expression::More(_, left, right) |
+        Expression::LessEqual(_, left, right) |
+        Expression::MoreEqual(_, left, right) |
+        Expression::Equal(_, left, right) |
+        Expression::NotEqual(_, left, right) |
+        Expression::And(_, left, right) |
+        Expression::Or(_, left, right) |
+        Expression::Assign(_, left, right) |
+        Expression::AssignOr(_, left, right) |
+        Expression::AssignAnd(_, left, right) |
+        Expression::AssignXor(_, left, right) |
+        Expression::AssignShiftLeft(_, left, right) |
+        Expression::AssignShiftRight(_, left, right) |
+        Expression::AssignAdd(_, left, right) |
+        Expression::AssignSubtract(_, left, right) |
+        Expression::AssignMultiply(_, left, right) |
+        Expression::AssignDivide(_, left, right) |
+        Expression::AssignModulo(_, left, right) => vec![&**left, &**right],
+        Expression::ConditionalOperator(_, cond, left, right) => vec![&**cond, &**left, &**right],
+        Expression::ArrayLiteral(_, exprs) => exprs.iter().collect(),
+        _ => vec![],
+    };
+    for expr in nested {
+        walk_expression(expr, f);
+    }
+}
+
+/// Returns the names of the modifiers applied to the function.
+pub(crate) fn function_modifiers(func: &FunctionDefinition) -> impl Iterator<Item = &str> {
+    func.attributes.iter().filter_map(|attr| match attr {
+        FunctionAttribute::BaseOrModifier(_, base) => {
+            base.name.identifiers.last().map(|ident| ident.name.as_str())
+        }
+        _ => None,
+    })
+}
+
+/// Returns the base contracts receiving the constructor arguments, either in the inheritance
+/// list, e.g. `contract Vault is ERC20("Vault", "V")`, or in the constructor modifiers, e.g.
+/// `constructor(address owner) Ownable(owner)`.
+pub(crate) fn base_constructor_calls<'a>(
+    contract: &'a ContractDefinition,
+    constructor: &'a FunctionDefinition,
+) -> Vec<&'a Base> {
+    let is_base = |base: &Base| {
+        contract.base.iter().any(|inherited| {
+            inherited.name.identifiers.last().map(|ident| &ident.name) ==
+                base.name.identifiers.last().map(|ident| &ident.name)
+        })
+    };
+    let modifiers = constructor.attributes.iter().filter_map(|attr| match attr {
+        FunctionAttribute::BaseOrModifier(_, base) if is_base(base) => Some(base),
+        _ => None,
+    });
+    contract.base.iter().chain(modifiers).filter(|base| base.args.is_some()).collect()
+}
+
+/// Returns true if the function is protected by the `initializer` or `reinitializer` modifier.
+pub(crate) fn is_initializer(func: &FunctionDefinition) -> bool {
+    function_modifiers(func)
+        .any(|modifier| modifier == "initializer" || modifier == "reinitializer")
+}
+
+/// The immutable state variable with the value it is assigned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImmutableAssignment {
+    /// The immutable variable name.
+    pub(crate) name: String,
+    /// The assigned expression.
+    pub(crate) value: String,
+    /// Whether the value is the constructor parameter.
+    pub(crate) from_param: bool,
+}
+
+/// Returns the immutables of the contract with the values they are assigned,
+/// either at the declaration or in the constructor body.
+pub(crate) fn immutable_assignments(item: &ParseItem) -> Vec<ImmutableAssignment> {
+    let immutables = item
+        .variables()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(var, _, _)| variable_kind(var) == VariableKind::Immutable)
+        .map(|(var, _, _)| var)
+        .collect::<Vec<_>>();
+    if immutables.is_empty() {
+        return vec![]
+    }
+
+    let constructor = item
+        .functions()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(func, _, _)| func)
+        .find(|func| matches!(func.ty, FunctionTy::Constructor));
+    let params = constructor
+        .map(|func| {
+            func.params
+                .iter()
+                .filter_map(|(_, param)| param.as_ref()?.name.as_ref())
+                .map(|name| name.name.as_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    // Collect the assignments from the constructor body, including the nested blocks.
+    let mut assignments = HashMap::new();
+    let mut statements = constructor.and_then(|func| func.body.as_ref()).into_iter().collect_vec();
+    while let Some(statement) = statements.pop() {
+        match statement {
+            Statement::Block { statements: inner, .. } => statements.extend(inner.iter().rev()),
+            Statement::Expression(_, Expression::Assign(_, lhs, rhs)) => {
+                if let Expression::Variable(ident) = lhs.as_ref() {
+                    assignments.entry(ident.name.as_str()).or_insert(rhs.as_ref());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    immutables
+        .into_iter()
+        .filter_map(|var| {
+            let name = &var.name.safe_unwrap().name;
+            let value = var.initializer.as_ref().or(assignments.get(name.as_str()).copied())?;
+            let from_param = matches!(
+                value, Expression::Variable(ident) if params.contains(&ident.name.as_str())
+            );
+            Some(ImmutableAssignment { name: name.clone(), value: value.to_string(), from_param })
+        })
+        .collect()
+}
+
+/// The inline assembly blocks of the function body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct InlineAssembly {
+    /// The number of the assembly blocks.
+    pub(crate) blocks: usize,
+    /// The number of the blocks declared memory-safe, either by the `assembly ("memory-safe")`
+    /// flag or the `/// @solidity memory-safe-assembly` comment preceding the block.
+    pub(crate) memory_safe: usize,
+}
+
+/// Returns the inline assembly blocks of the function, including the ones in the nested blocks.
+/// The source is the content of the function's file, searched for the memory-safe comments.
+pub(crate) fn inline_assembly(func: &FunctionDefinition, source: &str) -> InlineAssembly {
+    let mut assembly = InlineAssembly::default();
+    let mut statements = func.body.iter().collect_vec();
+    while let Some(statement) = statements.pop() {
+        match statement {
+            Statement::Block { statements: inner, .. } => statements.extend(inner.iter()),
+            Statement::If(_, _, then, otherwise) => {
+                statements.push(then.as_ref());
+                statements.extend(otherwise.as_deref());
+            }
+            Statement::While(_, _, body) | Statement::DoWhile(_, body, _) => {
+                statements.push(body.as_ref())
+            }
+            Statement::For(_, init, _, _, body) => {
+                statements.extend(init.as_deref());
+                statements.extend(body.as_deref());
+            }
+            Statement::Assembly { loc, flags, .. } => {
+                assembly.blocks += 1;
+                let flagged = flags.iter().flatten().any(|flag| flag.string == "memory-safe");
+                if flagged || memory_safe_comment(source, loc.start()) {
+                    assembly.memory_safe += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    assembly
+}
+
+/// Returns true if the comments right before the offset, i.e. the assembly block,
+/// include the `@solidity memory-safe-assembly` tag.
+fn memory_safe_comment(source: &str, offset: usize) -> bool {
+    source
+        .get(..offset)
+        .unwrap_or_default()
+        .lines()
+        .rev()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| {
+            line.starts_with("//") || line.starts_with("/*") || line.starts_with('*')
+        })
+        .any(|line| line.contains("@solidity memory-safe-assembly"))
+}
+
+/// Returns the displayed version of the `@custom:since` value, prefixing the plain version
+/// numbers with `v`, e.g. `v1.2.0` for `1.2.0`. Tags and commit hashes are kept as is.
+pub(crate) fn since_version(since: &str) -> String {
+    if since.starts_with(|ch: char| ch.is_ascii_digit()) &&
+        since.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+    {
+        format!("v{since}")
+    } else {
+        since.to_owned()
+    }
+}
@@ -0,0 +1,283 @@
+//! Module containing the hooks post-processing the rendered pages.
+
+use crate::Document;
+use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Mutex,
+    thread,
+};
+
+/// Trait for transforming the rendered pages before writing them to disk,
+/// e.g. to inject analytics snippets or append legal footers.
+///
+/// The hooks are applied to the markdown pages only, the `docs.json` model
+/// and the additional output formats are written as is.
+pub trait PageHook: Debug + Send + Sync {
+    /// Transform the rendered content of the document page.
+    fn process(&self, document: &Document, content: String) -> Result<String, eyre::Error>;
+}
+
+/// The page hook piping each rendered page through the external command.
+/// The command receives the page path as the last argument and the page content on stdin,
+/// and writes the transformed content to stdout.
+#[derive(Debug)]
+pub struct PostProcessCommand {
+    /// The command, e.g. `./scripts/footer.sh --year 2023`.
+    /// The arguments are separated by whitespace.
+    pub command: String,
+}
+
+impl PageHook for PostProcessCommand {
+    fn process(&self, document: &Document, content: String) -> Result<String, eyre::Error> {
+        let mut args = self.command.split_whitespace();
+        let program = args.next().ok_or_else(|| eyre::eyre!("empty post-process command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .arg(&document.target_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| eyre::eyre!("failed to run post-process command `{program}`: {err}"))?;
+
+        // Write the content from a separate thread, so the command doesn't block on the full
+        // stdout pipe. The stdin is closed once written, so the command sees the end of input.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = thread::spawn(move || stdin.write_all(content.as_bytes()));
+        let output = child.wait_with_output()?;
+        let written = writer.join().map_err(|_| eyre::eyre!("failed to write the page content"))?;
+        if !output.status.success() {
+            eyre::bail!(
+                "post-process command failed for {}: {}",
+                document.target_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+        }
+        written?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+/// The page hook escaping the characters significant in MDX, i.e. `<`, `{` and `}`,
+/// so that the pages can be compiled by the MDX based sites, e.g. Docusaurus.
+/// The code blocks, the inline code and the HTML of the generated collapsed blocks, e.g. the ABI
+/// embedded with `--embed-abi`, are left as is.
+#[derive(Debug, Default)]
+pub struct MdxEscape;
+
+impl MdxEscape {
+    /// Returns true if the line is the HTML of the generated collapsed block, i.e. the
+    /// `<details>` tags or the `<summary>` line, which are valid MDX.
+    fn is_generated_html(line: &str) -> bool {
+        let line = line.trim();
+        line == "<details>" ||
+            line == "</details>" ||
+            (line.starts_with("<summary>") && line.ends_with("</summary>"))
+    }
+}
+
+impl PageHook for MdxEscape {
+    fn process(&self, _document: &Document, content: String) -> Result<String, eyre::Error> {
+        let mut escaped = String::with_capacity(content.len());
+        let mut in_code_block = false;
+        for line in content.split_inclusive('\n') {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block ||
+                line.trim_start().starts_with("```") ||
+                Self::is_generated_html(line)
+            {
+                escaped.push_str(line);
+                continue
+            }
+
+            // The odd segments are the inline code.
+            for (idx, segment) in line.split('`').enumerate() {
+                if idx > 0 {
+                    escaped.push('`');
+                }
+                if idx % 2 == 1 {
+                    escaped.push_str(segment);
+                } else {
+                    escaped.push_str(
+                        &segment.replace('<', "&lt;").replace('{', "\\{").replace('}', "\\}"),
+                    );
+                }
+            }
+        }
+        Ok(escaped)
+    }
+}
+
+/// The page hook prepending the `doc-header.md` files to the pages, e.g. the disclaimer
+/// banners of the legacy contracts.
+///
+/// The header of the project root applies to all pages, and the headers of the source
+/// directories to the pages of the items declared in them. The nested headers are stacked
+/// from the project root down, unless the nearer one starts with the
+/// `<!-- forge-doc:replace -->` marker, replacing the outer ones.
+///
+/// The `{{name}}` placeholders are substituted with the template variables,
+/// e.g. `{{project}}`, `{{version}}` and `{{commit}}`.
+#[derive(Debug)]
+pub struct PageHeaders {
+    /// The project root.
+    pub root: PathBuf,
+    /// The template variables by the name.
+    pub variables: Vec<(String, String)>,
+    /// The headers read by the directory.
+    headers: Mutex<HashMap<PathBuf, Option<String>>>,
+}
+
+impl PageHeaders {
+    /// The name of the header files.
+    pub const FILE_NAME: &'static str = "doc-header.md";
+
+    /// The marker of the headers replacing the outer ones.
+    const REPLACE_MARKER: &'static str = "<!-- forge-doc:replace -->";
+
+    /// Create new instance of [PageHeaders].
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, variables: vec![], headers: Mutex::new(HashMap::new()) }
+    }
+
+    /// Set the template variable substituted for the `{{name}}` placeholder.
+    pub fn with_variable(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.variables.push((name.to_owned(), value.into()));
+        self
+    }
+
+    /// Returns the header of the directory with the variables substituted.
+    fn header(&self, dir: &Path) -> Option<String> {
+        let mut headers = self.headers.lock().expect("failed to lock headers");
+        headers
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let header = fs::read_to_string(dir.join(Self::FILE_NAME)).ok()?;
+                Some(self.variables.iter().fold(header, |header, (name, value)| {
+                    header.replace(&format!("{{{{{name}}}}}"), value)
+                }))
+            })
+            .clone()
+    }
+}
+
+impl PageHook for PageHeaders {
+    fn process(&self, document: &Document, content: String) -> Result<String, eyre::Error> {
+        // The appendix pages are not declared in the sources, their item path is the root
+        let dir = if document.item_path.is_dir() {
+            document.item_path.as_path()
+        } else {
+            document.item_path.parent().unwrap_or(&document.item_path)
+        };
+        let mut dirs = vec![self.root.clone()];
+        if let Ok(relative) = dir.strip_prefix(&self.root) {
+            for component in relative.components() {
+                dirs.push(dirs.last().expect("root dir").join(component));
+            }
+        }
+
+        let mut headers = vec![];
+        for dir in dirs.iter() {
+            if let Some(header) = self.header(dir) {
+                match header.trim_start().strip_prefix(Self::REPLACE_MARKER) {
+                    Some(header) => {
+                        headers.clear();
+                        headers.push(header.trim().to_owned());
+                    }
+                    None => headers.push(header.trim().to_owned()),
+                }
+            }
+        }
+        headers.retain(|header| !header.is_empty());
+        if headers.is_empty() {
+            return Ok(content)
+        }
+        Ok(format!("{}\n\n{content}", headers.iter().join("\n\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn mdx_escape() {
+        let document = Document::new(PathBuf::from("src/Vault.sol"), PathBuf::from("Vault.md"));
+        let content = "Returns <uint256> for {id}, see `<none>`.\n\
+            ```solidity\n\
+            struct Position { uint256 amount; }\n\
+            ```\n";
+        assert_eq!(
+            MdxEscape.process(&document, content.to_owned()).unwrap(),
+            "Returns &lt;uint256> for \\{id\\}, see `<none>`.\n\
+            ```solidity\n\
+            struct Position { uint256 amount; }\n\
+            ```\n"
+        );
+
+        // The collapsed ABI block is kept, but not the HTML of the comments
+        let content = "<details>\n<summary>ABI JSON</summary>\n\n<b>{bold}</b>\n</details>\n";
+        assert_eq!(
+            MdxEscape.process(&document, content.to_owned()).unwrap(),
+            "<details>\n<summary>ABI JSON</summary>\n\n&lt;b>\\{bold\\}&lt;/b>\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn page_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/legacy")).unwrap();
+        fs::create_dir_all(root.join("src/periphery")).unwrap();
+        fs::write(root.join(PageHeaders::FILE_NAME), "> {{project}} at {{commit}}\n").unwrap();
+        fs::write(root.join("src/legacy").join(PageHeaders::FILE_NAME), "> Deprecated.\n").unwrap();
+        fs::write(
+            root.join("src/periphery").join(PageHeaders::FILE_NAME),
+            "<!-- forge-doc:replace -->\n> For integrators.\n",
+        )
+        .unwrap();
+
+        let hook = PageHeaders::new(root.to_path_buf())
+            .with_variable("project", "Vault")
+            .with_variable("commit", "a1b2c3d");
+        let page = |source: &str| {
+            let document = Document::new(root.join(source), PathBuf::from("page.md"));
+            hook.process(&document, "# Page\n".to_owned()).unwrap()
+        };
+        assert_eq!(page("src/Vault.sol"), "> Vault at a1b2c3d\n\n# Page\n");
+        assert_eq!(page("src/legacy/Old.sol"), "> Vault at a1b2c3d\n\n> Deprecated.\n\n# Page\n");
+        assert_eq!(page("src/periphery/Router.sol"), "> For integrators.\n\n# Page\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn post_process_command() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("footer.sh");
+        fs::write(&script, "#!/bin/sh\ncat\necho \"$1 by $2\"\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let document = Document::new(PathBuf::from("src/Vault.sol"), PathBuf::from("Vault.md"));
+
+        let hook = PostProcessCommand { command: format!("{} Docs", script.display()) };
+        assert_eq!(
+            hook.process(&document, "# Vault\n".to_owned()).unwrap(),
+            "# Vault\nDocs by Vault.md\n"
+        );
+
+        fs::write(&script, "#!/bin/sh\ncat > /dev/null\necho \"invalid page\" >&2\nexit 1\n")
+            .unwrap();
+        let err = hook.process(&document, "# Vault\n".to_owned()).unwrap_err();
+        assert_eq!(err.to_string(), "post-process command failed for Vault.md: invalid page");
+    }
+}
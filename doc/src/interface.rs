@@ -0,0 +1,389 @@
+//! The Solidity interface stubs of the documented contracts.
+
+use crate::{
+    document::DocumentContent,
+    helpers::{is_externally_visible, is_public_variable},
+    resolver::ImportResolver,
+    CommentTag, Comments, Document,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{
+    ContractDefinition, ContractPart, ContractTy, Expression, FunctionAttribute,
+    FunctionDefinition, FunctionTy, Loc, Parameter, StructDefinition, Type, VariableDefinition,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The license identifier used if the source doesn't declare one.
+const DEFAULT_LICENSE: &str = "UNLICENSED";
+
+/// Render the interface stub with the external and public functions and the public state
+/// variable getters of the documented contract.
+/// Returns the interface file path relative to the interfaces output directory, mirroring
+/// the source path, e.g. `src/IVault.sol` for `src/Vault.sol`, and the interface source.
+///
+/// The user-defined types are qualified by their declaring contract, e.g. `Vault.Position`,
+/// and imported from their source through the imports of the documented source.
+///
+/// Returns [None] if the document is not a contract, or the contract doesn't have any
+/// external or public functions.
+pub(crate) fn interface_stub(
+    document: &Document,
+    root: &Path,
+    resolver: &mut ImportResolver,
+) -> Result<Option<(PathBuf, String)>, fmt::Error> {
+    let item = match document.content {
+        DocumentContent::Single(ref item) => item,
+        _ => return Ok(None),
+    };
+    let contract = match item.as_contract() {
+        Some(contract)
+            if matches!(contract.ty, ContractTy::Contract(_) | ContractTy::Abstract(_)) =>
+        {
+            contract
+        }
+        _ => return Ok(None),
+    };
+    let getters = item
+        .variables()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(var, _, _)| is_public_variable(var))
+        .collect::<Vec<_>>();
+    let functions = item
+        .functions()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(func, _, _)| {
+            matches!(func.ty, FunctionTy::Function) &&
+                func.name.is_some() &&
+                is_externally_visible(func)
+        })
+        .collect::<Vec<_>>();
+    if functions.is_empty() && getters.is_empty() {
+        return Ok(None)
+    }
+
+    let mut types = StubTypes::new(document, contract, resolver);
+    let mut declarations = vec![];
+    for (var, comments, _) in getters {
+        if let Some(getter) = types.getter(var) {
+            declarations.push((comments, getter));
+        }
+    }
+    for (func, comments, _) in functions {
+        declarations.push((comments, types.function(func)));
+    }
+
+    let name = format!("I{}", contract.name.safe_unwrap().name);
+    let mut out = String::new();
+    let license = source_line(&document.item_content, "// SPDX-License-Identifier:")
+        .unwrap_or_else(|| format!("// SPDX-License-Identifier: {DEFAULT_LICENSE}"));
+    writeln!(out, "{license}")?;
+    if let Some(pragma) = source_line(&document.item_content, "pragma solidity") {
+        writeln!(out, "{pragma}")?;
+    }
+    writeln!(out)?;
+    if !types.imports.is_empty() {
+        for (source, names) in types.imports.iter() {
+            let source = source.strip_prefix(root).unwrap_or(source);
+            let names = names.iter().cloned().collect::<Vec<_>>().join(", ");
+            writeln!(out, "import {{{names}}} from \"{}\";", source.display())?;
+        }
+        writeln!(out)?;
+    }
+
+    write_natspec(&mut out, &item.comments, "")?;
+    writeln!(out, "interface {name} {{")?;
+    for (idx, (comments, declaration)) in declarations.into_iter().enumerate() {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        write_natspec(&mut out, comments, "    ")?;
+        writeln!(out, "    {declaration}")?;
+    }
+    writeln!(out, "}}")?;
+
+    let path = document.item_path.strip_prefix(root).unwrap_or(&document.item_path);
+    let file = path.parent().unwrap_or(Path::new("")).join(format!("{name}.sol"));
+    Ok(Some((file, out)))
+}
+
+/// The names of the types used by the interface stub, with the imports they need.
+struct StubTypes<'a> {
+    document: &'a Document,
+    contract: &'a str,
+    /// The structs, the enums and the user-defined value types declared in the contract,
+    /// with the struct definitions.
+    members: HashMap<&'a str, Option<&'a StructDefinition>>,
+    resolver: &'a mut ImportResolver,
+    /// The imported names, by their source.
+    imports: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+impl<'a> StubTypes<'a> {
+    fn new(
+        document: &'a Document,
+        contract: &'a ContractDefinition,
+        resolver: &'a mut ImportResolver,
+    ) -> Self {
+        let members = contract
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContractPart::StructDefinition(def) => {
+                    Some((def.name.as_ref()?.name.as_str(), Some(&**def)))
+                }
+                ContractPart::EnumDefinition(def) => Some((def.name.as_ref()?.name.as_str(), None)),
+                ContractPart::TypeDefinition(def) => Some((def.name.name.as_str(), None)),
+                _ => None,
+            })
+            .collect();
+        Self {
+            document,
+            contract: &contract.name.safe_unwrap().name,
+            members,
+            resolver,
+            imports: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the name of the type valid in the interface, e.g. `Vault.Position` for
+    /// the `Position` struct declared in the `Vault` contract, importing the declaring
+    /// contract or the imported type.
+    fn name(&mut self, ty: &Expression) -> String {
+        match ty {
+            Expression::Variable(ident) if self.members.contains_key(ident.name.as_str()) => {
+                let (contract, source) =
+                    (self.contract.to_owned(), self.document.item_path.clone());
+                self.imports.entry(source).or_default().insert(contract.clone());
+                format!("{contract}.{}", ident.name)
+            }
+            Expression::Variable(_) | Expression::MemberAccess(..) => {
+                let name = ty.to_string();
+                match self.resolver.resolve(&self.document.item_path, &name) {
+                    Some((name, source)) => {
+                        let head = name.split('.').next().unwrap_or(&name).to_owned();
+                        self.imports.entry(source).or_default().insert(head);
+                        name
+                    }
+                    None => name,
+                }
+            }
+            Expression::ArraySubscript(_, inner, size) => {
+                let size = size.as_ref().map(|size| size.to_string()).unwrap_or_default();
+                format!("{}[{size}]", self.name(inner))
+            }
+            ty => ty.to_string(),
+        }
+    }
+
+    /// Returns the name of the parameter type with the `memory` data location, if it's
+    /// the reference type.
+    fn param(&mut self, ty: &Expression) -> String {
+        let is_reference = match ty {
+            Expression::Type(_, Type::String | Type::DynamicBytes) |
+            Expression::ArraySubscript(..) => true,
+            Expression::Variable(ident) => {
+                matches!(self.members.get(ident.name.as_str()), Some(Some(_)))
+            }
+            _ => false,
+        };
+        let name = self.name(ty);
+        if is_reference {
+            format!("{name} memory")
+        } else {
+            name
+        }
+    }
+
+    /// Returns the external declaration of the function, e.g.
+    /// `function deposit(uint256 amount) external returns (uint256 shares);`.
+    /// The modifiers, `virtual` and `override` attributes are dropped.
+    fn function(&mut self, func: &FunctionDefinition) -> String {
+        let name = func.name.as_ref().map(|ident| ident.name.as_str()).unwrap_or_default();
+        let mut declaration = format!("function {name}({}) external", self.params(&func.params));
+        for attr in func.attributes.iter() {
+            if let FunctionAttribute::Mutability(mutability) = attr {
+                declaration.push_str(&format!(" {mutability}"));
+            }
+        }
+        if !func.returns.is_empty() {
+            declaration.push_str(&format!(" returns ({})", self.params(&func.returns)));
+        }
+        declaration.push(';');
+        declaration
+    }
+
+    /// Returns the external declaration of the getter of the public state variable, e.g.
+    /// `function balances(address) external view returns (uint256);`.
+    ///
+    /// The getters of the structs return the members, except for the arrays and the mappings.
+    /// Returns [None] if the getter doesn't return anything.
+    fn getter(&mut self, var: &VariableDefinition) -> Option<String> {
+        let name = &var.name.as_ref()?.name;
+        let mut params = vec![];
+        let mut ty = &var.ty;
+        loop {
+            match ty {
+                Expression::Type(_, Type::Mapping { key, value, .. }) => {
+                    params.push(self.param(key));
+                    ty = &**value;
+                }
+                Expression::ArraySubscript(_, inner, _) => {
+                    params.push("uint256".to_owned());
+                    ty = &**inner;
+                }
+                _ => break,
+            }
+        }
+
+        let fields = match ty {
+            Expression::Variable(ident) => self.members.get(ident.name.as_str()).copied().flatten(),
+            _ => None,
+        };
+        let returns = match fields {
+            Some(def) => def
+                .fields
+                .iter()
+                .filter(|field| {
+                    !matches!(
+                        field.ty,
+                        Expression::Type(_, Type::Mapping { .. }) | Expression::ArraySubscript(..)
+                    )
+                })
+                .map(|field| self.param(&field.ty))
+                .collect(),
+            None => vec![self.param(ty)],
+        };
+        if returns.is_empty() {
+            return None
+        }
+        Some(format!(
+            "function {name}({}) external view returns ({});",
+            params.join(", "),
+            returns.join(", ")
+        ))
+    }
+
+    /// Returns the parameter list, e.g. `uint256 amount, address`.
+    fn params(&mut self, params: &[(Loc, Option<Parameter>)]) -> String {
+        params
+            .iter()
+            .filter_map(|(_, param)| param.as_ref())
+            .map(|param| {
+                let mut param_str = self.name(&param.ty);
+                if let Some(ref storage) = param.storage {
+                    param_str.push_str(&format!(" {storage}"));
+                }
+                if let Some(ref name) = param.name {
+                    param_str.push_str(&format!(" {}", name.name));
+                }
+                param_str
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Write the comments as the natspec comment lines.
+/// The `@inheritdoc` tags are skipped, since the base contracts are not known to the interface.
+fn write_natspec(out: &mut String, comments: &Comments, indent: &str) -> fmt::Result {
+    for comment in comments.iter().filter(|comment| comment.tag != CommentTag::Inheritdoc) {
+        let mut lines = comment.value.trim().lines();
+        writeln!(out, "{indent}/// @{} {}", comment.tag, lines.next().unwrap_or_default().trim())?;
+        for line in lines {
+            writeln!(out, "{indent}/// {}", line.trim())?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the first source line starting with the prefix.
+fn source_line(source: &str, prefix: &str) -> Option<String> {
+    source.lines().map(str::trim).find(|line| line.starts_with(prefix)).map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    #[test]
+    fn vault_interface() {
+        let src = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import {IERC20 as Token} from "./IERC20.sol";
+
+/// @title The vault
+contract Vault {
+    struct Position {
+        uint256 amount;
+        bool locked;
+        uint256[] history;
+    }
+
+    /// @notice The total deposits.
+    uint256 public total;
+
+    mapping(address => Position) public positions;
+
+    uint256 internal fees;
+
+    /// @notice Deposits the tokens.
+    /// @param amount The amount to deposit.
+    function deposit(uint256 amount) public virtual returns (uint256 shares) { }
+
+    function balanceOf(address owner) external view returns (uint256) { }
+
+    function position(address owner) external view returns (Position memory) { }
+
+    function asset() external view returns (Token) { }
+
+    function _burn(uint256 amount) internal { }
+}
+"#;
+        let root = Path::new("/root");
+        let mut documents = test_documents("src/Vault.sol", src);
+        documents.extend(test_documents("src/IERC20.sol", "interface IERC20 {}"));
+        for document in documents.iter_mut() {
+            document.item_path = root.join(&document.item_path);
+        }
+
+        let mut resolver =
+            ImportResolver::new(root.to_path_buf(), vec![]).with_documents(&documents);
+        let (file, stub) = interface_stub(&documents[0], root, &mut resolver).unwrap().unwrap();
+        assert_eq!(file, PathBuf::from("src/IVault.sol"));
+        assert_eq!(
+            stub,
+            r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import {IERC20} from "src/IERC20.sol";
+import {Vault} from "src/Vault.sol";
+
+/// @title The vault
+interface IVault {
+    /// @notice The total deposits.
+    function total() external view returns (uint256);
+
+    function positions(address) external view returns (uint256, bool);
+
+    /// @notice Deposits the tokens.
+    /// @param amount The amount to deposit.
+    function deposit(uint256 amount) external returns (uint256 shares);
+
+    function balanceOf(address owner) external view returns (uint256);
+
+    function position(address owner) external view returns (Vault.Position memory);
+
+    function asset() external view returns (IERC20);
+}
+"#
+        );
+    }
+}
@@ -9,28 +9,101 @@
 //!
 //! See [DocBuilder]
 
+mod anchors;
+mod archive;
+mod artifact;
 mod builder;
+mod coverage;
+mod diagnostics;
 mod document;
+mod events;
 mod helpers;
+mod hook;
+mod interface;
+mod lint;
+mod llms;
+mod lookup;
+mod model;
+mod number;
+mod open;
+mod output;
 mod parser;
+mod plan;
 mod preprocessor;
+mod redirect;
+mod resolver;
+mod selectors;
 mod server;
+mod sitemap;
+mod spec;
+mod stats;
+mod subgraph;
+mod summary;
+mod title_page;
+mod validate;
+mod vyper;
 mod writer;
 
 /// The documentation builder.
-pub use builder::DocBuilder;
+pub use builder::{DocBuilder, DocFormat};
+
+/// The documentation archive.
+pub use archive::{write_archive, ArchiveFormat};
+
+/// The documentation model.
+pub use model::{
+    Change, DocModel, ModelComment, ModelDiff, ModelItem, ModelMember, SpecialFunctions,
+    SymbolChange,
+};
+
+/// The interaction spec.
+pub use spec::{ContractSpec, FunctionSpec, ParamSpec, SpecIndex, SpecIndexEntry};
+
+/// The planned filesystem operations.
+pub use plan::{FileOperation, Plan, PlannedFile};
+
+/// The documentation statistics.
+pub use stats::DocStats;
+
+/// The events of the generation.
+pub use events::{DocEvent, EventSink, JsonLines};
+
+/// The diagnostics of the generation.
+pub use diagnostics::{Diagnostics, DocDiagnostic, Severity};
+
+/// The docs of a single item.
+pub use lookup::ItemDoc;
+
+/// The natspec coverage report.
+pub use coverage::{DocCoverage, ItemCoverage, SymbolCoverage};
+
+/// The selectors registration in the signature databases.
+pub use selectors::{SelectorRegistry, SelectorSignatures, SelectorUpload};
 
 /// The documentation server.
 pub use server::Server;
 
+/// The landing page of the documentation.
+pub use open::LandingPage;
+
+/// The navigation tree of the book.
+pub use summary::{SummaryEntry, SummaryPart, SummaryTree};
+
+/// The protocol overview of the title page.
+pub use title_page::NODOC_OVERVIEW_TAG;
+
 /// The document output.
 pub use document::Document;
 
 /// Solidity parser and related output items.
 pub use parser::{
     error, Comment, CommentTag, Comments, CommentsRef, ParseItem, ParseSource, Parser,
+    CONSTRAINT_TAG, DOCS_TAG, INFERRED_PARAM_TAG,
 };
 
+/// Page hooks.
+pub use hook::{MdxEscape, PageHeaders, PageHook, PostProcessCommand};
+
 /// Preprocessors.
 pub use preprocessor::*;
 
@@ -0,0 +1,389 @@
+//! The natspec lints of the documented items.
+
+use crate::{
+    artifact::is_artifact, document::DocumentContent, vyper::is_vyper, CommentTag, DocDiagnostic,
+    Document, ParseItem, ParseSource, INFERRED_PARAM_TAG, SEE_OVERLOAD_TAG,
+};
+use itertools::Itertools;
+use std::{collections::HashSet, path::Path};
+
+/// The natspec tags recognized by the compiler, see
+/// https://docs.soliditylang.org/en/v0.8.17/natspec-format.html#tags
+const NATSPEC_TAGS: &[&str] =
+    &["title", "author", "notice", "dev", "param", "return", "inheritdoc", "solidity"];
+
+/// Lint the natspec comments of the documents, i.e. the `@param` tags not matching
+/// the parameters, the missing or excess `@return` tags and the malformed tags.
+///
+/// If `inferred_params` is set, the parameters only documented by the descriptions inferred
+/// from their trailing comments are reported too, as they aren't natspec.
+///
+/// The diagnostics are ordered by the source location.
+pub(crate) fn lint_documents(
+    documents: &[Document],
+    root: &Path,
+    inferred_params: bool,
+) -> Vec<DocDiagnostic> {
+    let mut diagnostics = vec![];
+    let mut sources = HashSet::new();
+    // The Vyper contracts and the ABI artifacts are documented from the generated declarations,
+    // so the source lines don't match
+    let generated = |path: &Path| is_vyper(path) || is_artifact(path);
+    for document in documents.iter().filter(|document| !generated(&document.item_path)) {
+        let path = document.item_path.strip_prefix(root).unwrap_or(&document.item_path);
+        let diagnostic = |(offset, code, message): (usize, &'static str, String)| {
+            let line = line_number(&document.item_content, offset);
+            DocDiagnostic::warning(code, message).with_location(path.to_path_buf(), Some(line))
+        };
+
+        let items = match document.content {
+            DocumentContent::Single(ref item) => vec![item],
+            DocumentContent::Constants(ref items) |
+            DocumentContent::OverloadedFunctions(ref items) => items.iter().collect(),
+            _ => vec![],
+        };
+        for item in items.iter() {
+            let ident = item.source.ident();
+            for lint in lint_item(item, inferred_params) {
+                diagnostics.push(diagnostic(lint).with_item(&ident));
+            }
+            for child in item.children.iter() {
+                let child_ident = format!("{ident}.{}", child.source.ident());
+                for lint in lint_item(child, inferred_params) {
+                    diagnostics.push(diagnostic(lint).with_item(&child_ident));
+                }
+            }
+        }
+
+        let (parent, overloads) = match document.content {
+            DocumentContent::Single(ref item) => {
+                (Some(item.source.ident()), item.children.iter().collect())
+            }
+            DocumentContent::OverloadedFunctions(ref items) => (None, items.iter().collect()),
+            _ => (None, vec![]),
+        };
+        for (lint, ident) in lint_overloads(&overloads) {
+            let ident = parent.as_ref().map_or(ident.clone(), |parent| format!("{parent}.{ident}"));
+            diagnostics.push(diagnostic(lint).with_item(ident));
+        }
+
+        // The source is shared by the documents of its items
+        if sources.insert(&document.item_path) {
+            for (offset, tag) in malformed_tags(&document.item_content) {
+                let message = match tag.strip_prefix("custom:param:") {
+                    // solc rejects the named parameter constraints, so suggest its spelling
+                    Some(name) => format!(
+                        "malformed natspec tag `@{tag}`, use `@custom:constraint {name}` instead"
+                    ),
+                    None => format!("malformed natspec tag `@{tag}`"),
+                };
+                diagnostics.push(diagnostic((offset, "natspec-tag", message)));
+            }
+        }
+    }
+    diagnostics.sort();
+    diagnostics.dedup();
+    diagnostics
+}
+
+/// Returns the messages of the item parameters and return values at the item offset.
+fn lint_item(item: &ParseItem, inferred_params: bool) -> Vec<(usize, &'static str, String)> {
+    let (offset, params, returns): (_, Vec<_>, _) = match item.source {
+        ParseSource::Function(ref func) => {
+            let names = func
+                .params
+                .iter()
+                .map(|(_, param)| param.as_ref().and_then(|param| param.name.as_ref()))
+                .collect();
+            (func.loc.start(), names, Some(func.returns.len()))
+        }
+        ParseSource::Event(ref event) => {
+            let names = event.fields.iter().map(|field| field.name.as_ref()).collect();
+            (event.loc.start(), names, None)
+        }
+        ParseSource::Error(ref error) => {
+            let names = error.fields.iter().map(|field| field.name.as_ref()).collect();
+            (error.loc.start(), names, None)
+        }
+        _ => return vec![],
+    };
+    let ident = item.source.ident();
+    let mut messages = vec![];
+
+    // The unnamed parameters are documented by the position rather than the name
+    if params.iter().all(Option::is_some) {
+        let names = params.iter().flatten().map(|name| name.name.as_str()).collect::<Vec<_>>();
+        for comment in item.comments.include_tag(CommentTag::Param).iter() {
+            let name = comment.value.split_whitespace().next().unwrap_or_default();
+            if !names.contains(&name) {
+                messages.push((
+                    offset,
+                    "natspec-param",
+                    format!("`@param {name}` doesn't match a parameter of `{ident}`"),
+                ));
+            }
+        }
+    }
+
+    if inferred_params {
+        let inferred = CommentTag::Custom(INFERRED_PARAM_TAG.to_owned());
+        for comment in item.comments.include_tag(inferred).iter() {
+            let name = comment.value.split_whitespace().next().unwrap_or_default();
+            messages.push((
+                offset,
+                "natspec-inferred-param",
+                format!(
+                    "`{name}` of `{ident}` is only documented by its trailing comment, \
+                     add `@param {name}`"
+                ),
+            ));
+        }
+    }
+
+    // The inherited comments document the return values in the base, and the overloads
+    // deferring to another one with `@custom:see-overload` in the referenced overload
+    let see_overload = CommentTag::Custom(SEE_OVERLOAD_TAG.to_owned());
+    let returns = returns.filter(|_| {
+        !item.comments.is_empty() &&
+            item.comments.include_tag(CommentTag::Inheritdoc).is_empty() &&
+            item.comments.include_tag(see_overload).is_empty()
+    });
+    if let Some(returns) = returns {
+        let documented = item.comments.include_tag(CommentTag::Return).len();
+        if documented < returns {
+            messages.push((
+                offset,
+                "natspec-return",
+                format!(
+                    "missing `@return` for {} return value(s) of `{ident}`",
+                    returns - documented
+                ),
+            ));
+        } else if documented > returns {
+            messages.push((
+                offset,
+                "natspec-return",
+                format!("`@return` without a return value of `{ident}`"),
+            ));
+        }
+    }
+    messages
+}
+
+/// Returns the messages and the names of the overloads with the `@notice` or `@dev` comments nearly
+/// identical to the ones of the first overload, i.e. the copies likely left behind when one was
+/// edited. The overloads deferring to another one with `@custom:see-overload` are skipped.
+fn lint_overloads(items: &[&ParseItem]) -> Vec<((usize, &'static str, String), String)> {
+    let see_overload = CommentTag::Custom(SEE_OVERLOAD_TAG.to_owned());
+    let mut groups: Vec<(String, Vec<&ParseItem>)> = vec![];
+    for item in items.iter().filter(|item| {
+        matches!(item.source, ParseSource::Function(_)) &&
+            item.comments.include_tag(see_overload.clone()).is_empty()
+    }) {
+        let ident = item.source.ident();
+        match groups.iter_mut().find(|(name, _)| *name == ident) {
+            Some((_, overloads)) => overloads.push(*item),
+            None => groups.push((ident, vec![*item])),
+        }
+    }
+
+    let mut messages = vec![];
+    for (ident, overloads) in groups.iter() {
+        let first = overloads[0];
+        for overload in overloads.iter().skip(1) {
+            for (tag, name) in [(CommentTag::Notice, "notice"), (CommentTag::Dev, "dev")] {
+                let text = |item: &ParseItem| {
+                    item.comments.include_tag(tag.clone()).iter().map(|c| c.value.trim()).join("\n")
+                };
+                if is_near_identical(&text(first), &text(overload)) {
+                    let lint = (
+                        overload.source.loc().start(),
+                        "natspec-overload",
+                        format!(
+                            "`@{name}` of the `{ident}` overload is nearly identical to the first \
+                             overload, one of them is likely out of date"
+                        ),
+                    );
+                    messages.push((lint, ident.clone()));
+                }
+            }
+        }
+    }
+    messages
+}
+
+/// Returns true if the texts differ by at most a tenth of the longer text, e.g. a word.
+fn is_near_identical(lhs: &str, rhs: &str) -> bool {
+    if lhs == rhs || lhs.is_empty() || rhs.is_empty() {
+        return false
+    }
+    let lhs = lhs.chars().collect::<Vec<_>>();
+    let rhs = rhs.chars().collect::<Vec<_>>();
+    // The edit distance, keeping the previous row only
+    let mut row = (0..=rhs.len()).collect::<Vec<_>>();
+    for (i, lch) in lhs.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, rch) in rhs.iter().enumerate() {
+            let substitution = diagonal + usize::from(lch != rch);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[rhs.len()] * 10 <= lhs.len().max(rhs.len())
+}
+
+/// Returns the unknown natspec tags and the custom tags not matching `custom:[a-z][a-z-]*`,
+/// with their offsets in the source.
+fn malformed_tags(source: &str) -> Vec<(usize, String)> {
+    let mut tags = vec![];
+    let mut offset = 0;
+    let mut in_block = false;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let doc = if in_block {
+            true
+        } else if trimmed.starts_with("/**") && !trimmed.starts_with("/**/") {
+            in_block = true;
+            true
+        } else {
+            trimmed.starts_with("///") && !trimmed.starts_with("////")
+        };
+        if in_block && trimmed.contains("*/") {
+            in_block = false;
+        }
+
+        // The tags start the comment lines, e.g. `/// @notice` or ` * @dev`
+        let content = trimmed.trim_start_matches(['/', '*']).trim_start();
+        if let Some(tag) = content.strip_prefix('@').filter(|_| doc) {
+            let tag = tag.split_whitespace().next().unwrap_or_default();
+            if !is_valid_tag(tag) {
+                tags.push((offset + line.len() - content.len(), tag.to_owned()));
+            }
+        }
+        offset += line.len();
+    }
+    tags
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    match tag.strip_prefix("custom:") {
+        Some(custom) => {
+            custom.starts_with(|ch: char| ch.is_ascii_lowercase()) &&
+                custom.chars().all(|ch| ch.is_ascii_lowercase() || ch == '-')
+        }
+        None => NATSPEC_TAGS.contains(&tag),
+    }
+}
+
+/// Returns the 1-based line number of the byte offset.
+fn line_number(source: &str, offset: usize) -> usize {
+    source.get(..offset).unwrap_or(source).matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, Parser};
+    use forge_fmt::Visitable;
+
+    #[test]
+    fn natspec_warnings() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposit the amount.
+                /// @param amount The amount.
+                /// @param owner The owner.
+                function deposit(uint256 amount) external returns (uint256 shares) { }
+
+                /// @notice Withdraw the amount.
+                /// @return The withdrawn amount.
+                /// @return The remaining balance.
+                function withdraw() external returns (uint256) { }
+
+                /// @inheritdoc IVault
+                function redeem(uint256 shares) external returns (uint256) { }
+
+                /// @custom:Security Reentrancy guarded.
+                /// @param who The account.
+                event Paused(address who);
+
+                /// @notice Mint the shares to the caller.
+                function mint(uint256 shares) external { }
+
+                /// @notice Mint the shares to the callers.
+                function mint(uint256 shares, address to) external { }
+
+                /// @notice The balance of the account.
+                /// @return The balance.
+                function balanceOf(address account) external returns (uint256) { }
+
+                /// @custom:see-overload balanceOf(address)
+                function balanceOf() external returns (uint256) { }
+
+                /// @security Not a natspec tag.
+                function pause() external { }
+
+                /// @custom:param:amount Must be nonzero.
+                /// @custom:constraint amount Must be at most the balance.
+                function burn(uint256 amount) external { }
+            }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+        let diagnostics = lint_documents(&documents, Path::new(""), false);
+        assert_eq!(
+            diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "src/Vault.sol:6: `@param owner` doesn't match a parameter of `deposit`",
+                "src/Vault.sol:6: missing `@return` for 1 return value(s) of `deposit`",
+                "src/Vault.sol:11: `@return` without a return value of `withdraw`",
+                "src/Vault.sol:16: malformed natspec tag `@custom:Security`",
+                "src/Vault.sol:24: `@notice` of the `mint` overload is nearly identical to the \
+                 first overload, one of them is likely out of date",
+                "src/Vault.sol:33: malformed natspec tag `@security`",
+                "src/Vault.sol:36: malformed natspec tag `@custom:param:amount`, \
+                 use `@custom:constraint amount` instead",
+            ]
+        );
+    }
+
+    #[test]
+    fn inferred_params() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposits the assets.
+                /// @param assets The amount of the assets.
+                function deposit(
+                    uint256 assets,
+                    address receiver, // The **receiver** of the `shares`
+                    bytes calldata data // The hook data, e.g. `a | b`
+                ) external { }
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+        let mut parser = Parser::new(comments, src.to_owned()).with_param_comment_fallback(true);
+        source.visit(&mut parser).expect("failed to visit source");
+        let item = parser.items().remove(0);
+        let ident = item.source.ident();
+        let mut document = Document::new(PathBuf::from("/root/src/Vault.sol"), PathBuf::new())
+            .with_content(DocumentContent::Single(item), ident);
+        document.item_content = src.to_owned();
+        let documents = [document];
+
+        // The inferred descriptions are accepted unless the warnings are denied without
+        // `strict_accept_inline`
+        assert!(lint_documents(&documents, Path::new("/root"), false).is_empty());
+        assert_eq!(
+            lint_documents(&documents, Path::new("/root"), true)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "src/Vault.sol:5: `data` of `deposit` is only documented by its trailing \
+                 comment, add `@param data`",
+                "src/Vault.sol:5: `receiver` of `deposit` is only documented by its trailing \
+                 comment, add `@param receiver`",
+            ]
+        );
+    }
+}
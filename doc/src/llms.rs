@@ -0,0 +1,195 @@
+//! The plain-text corpus export following the `llms.txt` convention.
+
+use crate::{helpers::selector, DocBuilder, DocModel, ModelComment, ModelItem};
+use std::fmt::{self, Write};
+
+/// The item kinds listed in the `llms.txt` index.
+const INDEX_KINDS: &[&str] = &["contract", "abstract", "interface", "library"];
+
+/// The member kinds with the function selector.
+const SELECTOR_KINDS: &[&str] = &["function", "error"];
+
+/// Render the concise `llms.txt` index of the documented contracts
+/// with one-line summaries and links to their markdown pages.
+pub(crate) fn llms_index(model: &DocModel, title: &str) -> Result<String, fmt::Error> {
+    let mut out = format!("# {title}\n\n");
+    let mut sections: Vec<(&str, Vec<&ModelItem>)> = vec![];
+    for item in model.items.iter().filter(|item| INDEX_KINDS.contains(&item.kind.as_str())) {
+        let section = match item.kind.as_str() {
+            "interface" => "Interfaces",
+            "library" => "Libraries",
+            _ => "Contracts",
+        };
+        match sections.iter_mut().find(|(name, _)| *name == section) {
+            Some((_, items)) => items.push(item),
+            None => sections.push((section, vec![item])),
+        }
+    }
+    sections.sort_by_key(|(name, _)| *name);
+
+    for (section, items) in sections {
+        writeln!(out, "## {section}\n")?;
+        for item in items {
+            let page = item.page.display().to_string().replace('\\', "/");
+            write!(out, "- [{}]({}/{page})", item.name, DocBuilder::SRC)?;
+            if let Some(summary) = summary(&item.comments) {
+                write!(out, ": {summary}")?;
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Render the full plain-text dump of the documentation.
+///
+/// If the output exceeds the byte budget, the details (`@dev` comments and parameter
+/// descriptions) are dropped first, and then the output is truncated.
+pub(crate) fn llms_full(
+    model: &DocModel,
+    title: &str,
+    max_bytes: Option<usize>,
+) -> Result<String, fmt::Error> {
+    let full = render_full(model, title, true)?;
+    let max_bytes = match max_bytes {
+        Some(max_bytes) if full.len() > max_bytes => max_bytes,
+        _ => return Ok(full),
+    };
+
+    let concise = render_full(model, title, false)?;
+    if concise.len() <= max_bytes {
+        return Ok(concise)
+    }
+
+    const TRUNCATED: &str = "\n[truncated]\n";
+    let mut end = max_bytes.saturating_sub(TRUNCATED.len());
+    while !concise.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = concise[..end].rfind('\n').map(|idx| idx + 1).unwrap_or(end);
+    Ok(format!("{}{TRUNCATED}", &concise[..end]))
+}
+
+fn render_full(model: &DocModel, title: &str, details: bool) -> Result<String, fmt::Error> {
+    let mut out = format!("# {title}\n");
+    for item in model.items.iter() {
+        writeln!(out, "\n## {} {}\n", item.kind, item.name)?;
+        writeln!(out, "Source: {}", item.source.display())?;
+        write_comments(&mut out, &item.comments, details)?;
+        writeln!(out, "\n{}", item.code)?;
+        if let Some(selector) = item_selector(&item.kind, &item.signature) {
+            writeln!(out, "Selector: {selector}")?;
+        }
+
+        for member in item.all_members() {
+            writeln!(out, "\n### {} {}\n", member.kind, member.signature)?;
+            writeln!(out, "{}", member.code)?;
+            if let Some(selector) = item_selector(&member.kind, &member.signature) {
+                writeln!(out, "Selector: {selector}")?;
+            }
+            write_comments(&mut out, &member.comments, details)?;
+        }
+    }
+    Ok(out)
+}
+
+/// Write the comments as prose. The parameter and return tables are written as sentences.
+fn write_comments(out: &mut String, comments: &[ModelComment], details: bool) -> fmt::Result {
+    for comment in comments.iter() {
+        let value = comment.value.trim();
+        let line = match comment.tag.as_str() {
+            "notice" => value.to_owned(),
+            "dev" if details => value.to_owned(),
+            "param" if details => match value.split_once(char::is_whitespace) {
+                Some((name, desc)) => format!("Parameter `{name}`: {}", desc.trim()),
+                None => format!("Parameter `{value}`."),
+            },
+            "return" if details => format!("Returns: {value}"),
+            _ => continue,
+        };
+        writeln!(out, "{}", line.replace('\n', " "))?;
+    }
+    Ok(())
+}
+
+/// Returns the first line of the notice comment.
+fn summary(comments: &[ModelComment]) -> Option<&str> {
+    comments
+        .iter()
+        .find(|comment| comment.tag == "notice")
+        .and_then(|comment| comment.value.lines().map(str::trim).find(|line| !line.is_empty()))
+}
+
+fn item_selector(kind: &str, signature: &str) -> Option<String> {
+    (SELECTOR_KINDS.contains(&kind) && signature.contains('(')).then(|| selector(signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModelMember, SpecialFunctions};
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    fn comment(tag: &str, value: &str) -> ModelComment {
+        ModelComment { tag: tag.to_owned(), value: value.to_owned() }
+    }
+
+    fn model() -> DocModel {
+        DocModel {
+            version: DocModel::VERSION,
+            items: vec![ModelItem {
+                kind: "contract".to_owned(),
+                name: "Vault".to_owned(),
+                signature: "Vault".to_owned(),
+                source: PathBuf::from("src/Vault.sol"),
+                page: PathBuf::from("src/Vault.sol/contract.Vault.md"),
+                code: "contract Vault".to_owned(),
+                comments: vec![comment("notice", "Holds the deposits.\nSecond line.")],
+                security_contact: None,
+                special: SpecialFunctions::default(),
+                members: vec![ModelMember {
+                    kind: "function".to_owned(),
+                    name: "transfer".to_owned(),
+                    signature: "transfer(address,uint256)".to_owned(),
+                    code: "function transfer(address to, uint256 amount) external".to_owned(),
+                    comments: vec![
+                        comment("notice", "Transfers the tokens."),
+                        comment("dev", "Reverts if paused."),
+                        comment("param", "to The recipient."),
+                    ],
+                    topic: None,
+                    indexed: vec![],
+                    visibility: None,
+                    page: None,
+                }],
+            }],
+            build_info: None,
+            redirects: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn llms_index_links() {
+        assert_eq!(
+            llms_index(&model(), "Project").unwrap(),
+            "# Project\n\n## Contracts\n\n- [Vault](src/src/Vault.sol/contract.Vault.md): Holds the deposits.\n\n"
+        );
+    }
+
+    #[test]
+    fn llms_full_budget() {
+        let model = model();
+        let full = llms_full(&model, "Project", None).unwrap();
+        assert!(full.contains("Selector: 0xa9059cbb\n"));
+        assert!(full.contains("Reverts if paused.\nParameter `to`: The recipient.\n"));
+
+        let concise = llms_full(&model, "Project", Some(full.len() - 1)).unwrap();
+        assert!(concise.contains("Transfers the tokens.\n"));
+        assert!(!concise.contains("Reverts if paused."));
+
+        let truncated = llms_full(&model, "Project", Some(40)).unwrap();
+        assert!(truncated.len() <= 40);
+        assert!(truncated.ends_with("[truncated]\n"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Looking up the docs of a single item, e.g. for the editor hovers.
+
+use crate::{
+    document::DocumentContent,
+    model::{kind, signature},
+    AsDoc, Document, ParseItem,
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The rendered docs of the looked up item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemDoc {
+    /// The qualified item name, e.g. `Vault.deposit`.
+    pub name: String,
+    /// The item kind, e.g. `contract` or `function`.
+    pub kind: String,
+    /// The item signature, e.g. `deposit(uint256,address)`.
+    pub signature: String,
+    /// The source file path relative to the project root.
+    pub source: PathBuf,
+    /// The rendered markdown.
+    pub markdown: String,
+}
+
+/// The item query, e.g. `Vault`, `Vault.deposit` or `Vault.deposit(uint256,address)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ItemQuery {
+    /// The name of the top-level item the member is declared in.
+    parent: Option<String>,
+    /// The item name.
+    name: String,
+    /// The item signature without whitespaces, if the parameters are given.
+    signature: Option<String>,
+}
+
+impl ItemQuery {
+    /// Parse the query, the parameter types are optional.
+    pub(crate) fn parse(query: &str) -> eyre::Result<Self> {
+        let query = query.split_whitespace().collect::<String>();
+        let path = query.split('(').next().unwrap_or_default();
+        let (parent, name) = match path.split_once('.') {
+            Some((parent, name)) => (Some(parent.to_owned()), name.to_owned()),
+            None => (None, path.to_owned()),
+        };
+        if name.is_empty() || parent.as_deref() == Some("") || name.contains('.') {
+            eyre::bail!("invalid item `{query}`, expected `<Contract>` or `<Contract>.<member>`")
+        }
+        let signature = query.contains('(').then(|| format!("{name}{}", &query[path.len()..]));
+        Ok(Self { parent, name, signature })
+    }
+
+    /// Returns the name of the top-level item, which the matching sources must mention.
+    pub(crate) fn top_level(&self) -> &str {
+        self.parent.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Returns the rendered docs of the matching items of the source file,
+    /// e.g. all overloads of the function unless the signature is given.
+    pub(crate) fn find(&self, items: Vec<ParseItem>, source: &Path) -> eyre::Result<Vec<ItemDoc>> {
+        let mut found = vec![];
+        for item in items.into_iter().filter(|item| item.source.ident() == self.top_level()) {
+            match self.parent {
+                Some(ref parent) => {
+                    for child in item.children.into_iter().filter(|child| self.matches(child)) {
+                        found.push(item_doc(format!("{parent}.{}", self.name), child, source)?);
+                    }
+                }
+                None if self.matches(&item) => {
+                    found.push(item_doc(self.name.clone(), item, source)?)
+                }
+                None => {}
+            }
+        }
+        Ok(found)
+    }
+
+    fn matches(&self, item: &ParseItem) -> bool {
+        item.source.ident() == self.name &&
+            self.signature.as_ref().map_or(true, |expected| *expected == signature(&item.source))
+    }
+}
+
+impl fmt::Display for ItemQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref parent) = self.parent {
+            write!(f, "{parent}.")?;
+        }
+        f.write_str(self.signature.as_ref().unwrap_or(&self.name))
+    }
+}
+
+fn item_doc(name: String, item: ParseItem, source: &Path) -> eyre::Result<ItemDoc> {
+    let (kind, signature) = (kind(&item.source), signature(&item.source));
+    let ident = item.source.ident();
+    let document = Document::new(source.to_path_buf(), PathBuf::new())
+        .with_content(DocumentContent::Single(item), ident);
+    Ok(ItemDoc {
+        name,
+        kind,
+        signature,
+        source: source.to_path_buf(),
+        markdown: document.as_doc()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use forge_fmt::Visitable;
+
+    #[test]
+    fn find_items() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposit the assets.
+                function deposit(uint256 assets) external { }
+                function deposit(uint256 assets, address receiver) external { }
+            }
+        "#;
+        let items = || {
+            let (mut source, comments) = solang_parser::parse(src, 0).unwrap();
+            let mut parser = Parser::new(comments, src.to_owned());
+            source.visit(&mut parser).unwrap();
+            parser.items()
+        };
+        let find = |query: &str| {
+            ItemQuery::parse(query).unwrap().find(items(), Path::new("src/Vault.sol")).unwrap()
+        };
+
+        assert_eq!(find("Vault")[0].kind, "contract");
+        // All overloads are found unless the signature is given
+        assert_eq!(find("Vault.deposit").len(), 2);
+        let found = find("Vault.deposit(uint256, address)");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Vault.deposit");
+        assert_eq!(found[0].signature, "deposit(uint256,address)");
+        assert!(find("Vault.deposit(uint256)")[0].markdown.contains("Deposit the assets."));
+        assert!(find("Vault.withdraw").is_empty());
+
+        assert!(ItemQuery::parse("Vault.").is_err());
+    }
+}
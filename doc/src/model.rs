@@ -0,0 +1,597 @@
+//! The machine-readable documentation model.
+
+use crate::{
+    document::{read_context, DocumentContent},
+    helpers::{event_params, event_topic},
+    preprocessor::member_pages,
+    CommentTag, Comments, CompilerSettings, Document, ParseItem, ParseSource, PreprocessorOutput,
+    SectionOverflow, SECTION_LIMITS_ID, SECURITY_CONTACT_TAG,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use solang_parser::pt::{ContractTy, Expression, FunctionAttribute};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// The documentation model written alongside the generated documentation.
+/// It describes the documented items independently of the output format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocModel {
+    /// The model schema version.
+    pub version: u32,
+    /// The documented items.
+    pub items: Vec<ModelItem>,
+    /// The compiler settings, if the build info is included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_info: Option<CompilerSettings>,
+    /// The redirects of the moved pages, from the old to the current page path.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub redirects: BTreeMap<PathBuf, PathBuf>,
+}
+
+/// The documented item, e.g. a contract or a top-level function.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelItem {
+    /// The item kind, e.g. `contract`, `interface` or `function`.
+    pub kind: String,
+    /// The item name.
+    pub name: String,
+    /// The item signature, e.g. `transfer(address,uint256)` for top-level functions.
+    pub signature: String,
+    /// The source file path relative to the project root.
+    pub source: PathBuf,
+    /// The generated page path relative to the book source directory.
+    pub page: PathBuf,
+    /// The formatted item declaration.
+    pub code: String,
+    /// The natspec comments.
+    pub comments: Vec<ModelComment>,
+    /// The `@custom:security-contact` value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_contact: Option<String>,
+    /// The special functions of the contract, i.e. the constructor, `receive` and `fallback`.
+    #[serde(default, skip_serializing_if = "SpecialFunctions::is_empty")]
+    pub special: SpecialFunctions,
+    /// The child items, e.g. contract functions and events, except the special functions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<ModelMember>,
+}
+
+/// The special functions of the [ModelItem], keyed by their kind.
+/// They have no selector, so they're kept apart from the other members.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpecialFunctions {
+    /// The constructor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constructor: Option<ModelMember>,
+    /// The `receive` function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receive: Option<ModelMember>,
+    /// The `fallback` function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<ModelMember>,
+}
+
+impl SpecialFunctions {
+    /// Returns true if the item has none of the special functions.
+    pub fn is_empty(&self) -> bool {
+        self.constructor.is_none() && self.receive.is_none() && self.fallback.is_none()
+    }
+
+    /// Returns the special functions in the canonical order,
+    /// i.e. the constructor, `receive` and `fallback`.
+    pub fn iter(&self) -> impl Iterator<Item = &ModelMember> {
+        self.constructor.iter().chain(self.receive.iter()).chain(self.fallback.iter())
+    }
+
+    /// Set the special function of the member kind,
+    /// returning the member back if it isn't one or the function is already set.
+    fn insert(&mut self, member: ModelMember) -> Option<ModelMember> {
+        let slot = match member.kind.as_str() {
+            "constructor" => Some(&mut self.constructor),
+            "receive" => Some(&mut self.receive),
+            "fallback" => Some(&mut self.fallback),
+            _ => None,
+        };
+        match slot {
+            Some(slot) if slot.is_none() => {
+                *slot = Some(member);
+                None
+            }
+            _ => Some(member),
+        }
+    }
+}
+
+impl ModelItem {
+    /// Returns all the members of the item, the special functions first.
+    pub fn all_members(&self) -> impl Iterator<Item = &ModelMember> {
+        self.special.iter().chain(self.members.iter())
+    }
+}
+
+/// The child item of the [ModelItem].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelMember {
+    /// The member kind, e.g. `function`, `event` or `error`.
+    pub kind: String,
+    /// The member name.
+    pub name: String,
+    /// The member signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// The formatted member declaration.
+    pub code: String,
+    /// The natspec comments.
+    pub comments: Vec<ModelComment>,
+    /// The event topic 0, i.e. the hash of the event signature.
+    /// [None] for anonymous events and other kinds of members.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// The names of the indexed event parameters, i.e. the ones stored as topics.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexed: Vec<String>,
+    /// The declared visibility of the function, e.g. `external`.
+    /// [None] for other kinds of members.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// The continuation page of the member relative to the book source directory,
+    /// if the member is cut from the item page by the section limits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<PathBuf>,
+}
+
+/// The natspec comment of the model item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelComment {
+    /// The comment tag, e.g. `notice` or `custom:security`.
+    pub tag: String,
+    /// The comment value.
+    pub value: String,
+}
+
+impl DocModel {
+    /// The current model schema version.
+    pub const VERSION: u32 = 2;
+
+    /// The model file name within the documentation output directory.
+    pub const FILE_NAME: &'static str = "docs.json";
+
+    /// Create the model from the documents.
+    /// The paths are made relative to the project root and the book source directory.
+    /// The continuation pages aren't items, their members are listed with the contract.
+    pub fn new(documents: &[Document], root: &Path, out_src: &Path) -> Self {
+        let items = documents
+            .iter()
+            .flat_map(|document| {
+                let items = match document.content {
+                    DocumentContent::Single(ref item) => vec![item],
+                    DocumentContent::Constants(ref items) |
+                    DocumentContent::OverloadedFunctions(ref items) => items.iter().collect(),
+                    _ => vec![],
+                };
+                let source = document.item_path.strip_prefix(root).unwrap_or(&document.item_path);
+                let page =
+                    document.target_path.strip_prefix(out_src).unwrap_or(&document.target_path);
+                let truncated = match read_context!(document, SECTION_LIMITS_ID, SectionLimits) {
+                    Some(SectionOverflow::Truncated(truncated)) => truncated,
+                    _ => vec![],
+                };
+                items
+                    .into_iter()
+                    .map(|item| {
+                        let mut special = SpecialFunctions::default();
+                        let members = item
+                            .children
+                            .iter()
+                            .zip(member_pages(item, &truncated))
+                            .map(|(child, page)| ModelMember {
+                                page: page
+                                    .map(|page| page.strip_prefix(out_src).unwrap_or(page).into()),
+                                ..ModelMember::new(child)
+                            })
+                            .filter_map(|member| special.insert(member))
+                            .collect();
+                        ModelItem {
+                            kind: kind(&item.source),
+                            name: item.source.ident(),
+                            signature: signature(&item.source),
+                            source: source.to_path_buf(),
+                            page: page.to_path_buf(),
+                            code: item.code.clone(),
+                            comments: model_comments(&item.comments),
+                            security_contact: item
+                                .comments
+                                .include_tag(CommentTag::Custom(SECURITY_CONTACT_TAG.to_owned()))
+                                .first()
+                                .map(|comment| comment.value.trim().to_owned()),
+                            special,
+                            members,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let build_info = documents.iter().find_map(|document| match document.content {
+            DocumentContent::BuildInfo(ref settings, _) => Some(settings.clone()),
+            _ => None,
+        });
+        Self { version: Self::VERSION, items, build_info, redirects: BTreeMap::new() }
+    }
+
+    /// Read the model from the documentation output directory.
+    pub fn read(out_dir: &Path) -> eyre::Result<Self> {
+        let path = out_dir.join(Self::FILE_NAME);
+        let content = fs::read_to_string(&path).map_err(|err| {
+            eyre::eyre!("failed to read the documentation model at {}: {err}", path.display())
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+impl ModelMember {
+    fn new(item: &ParseItem) -> Self {
+        let (topic, indexed) = match item.source {
+            ParseSource::Event(ref event) => (
+                event_topic(event),
+                event_params(event).0.into_iter().map(|(name, _)| name).collect(),
+            ),
+            _ => (None, vec![]),
+        };
+        let visibility = match item.source {
+            ParseSource::Function(ref func) => func.attributes.iter().find_map(|attr| match attr {
+                FunctionAttribute::Visibility(visibility) => Some(visibility.to_string()),
+                _ => None,
+            }),
+            _ => None,
+        };
+        Self {
+            kind: kind(&item.source),
+            name: item.source.ident(),
+            signature: signature(&item.source),
+            code: item.code.clone(),
+            comments: model_comments(&item.comments),
+            topic,
+            indexed,
+            visibility,
+            page: None,
+        }
+    }
+}
+
+/// Returns the kind of the parse source.
+pub(crate) fn kind(source: &ParseSource) -> String {
+    match source {
+        ParseSource::Contract(contract) => match contract.ty {
+            ContractTy::Contract(_) => "contract",
+            ContractTy::Abstract(_) => "abstract",
+            ContractTy::Interface(_) => "interface",
+            ContractTy::Library(_) => "library",
+        }
+        .to_owned(),
+        ParseSource::Function(func) => func.ty.to_string(),
+        ParseSource::Variable(_) => "variable".to_owned(),
+        ParseSource::Event(_) => "event".to_owned(),
+        ParseSource::Error(_) => "error".to_owned(),
+        ParseSource::Struct(_) => "struct".to_owned(),
+        ParseSource::Enum(_) => "enum".to_owned(),
+        ParseSource::Type(_) => "type".to_owned(),
+    }
+}
+
+/// Returns the signature of the parse source. Items without parameters are identified by name.
+pub(crate) fn signature(source: &ParseSource) -> String {
+    let params: Vec<&Expression> = match source {
+        ParseSource::Function(func) => {
+            func.params.iter().filter_map(|(_, param)| param.as_ref()).map(|p| &p.ty).collect()
+        }
+        ParseSource::Event(event) => event.fields.iter().map(|param| &param.ty).collect(),
+        ParseSource::Error(error) => error.fields.iter().map(|param| &param.ty).collect(),
+        _ => return source.ident(),
+    };
+    format!(
+        "{}({})",
+        source.ident(),
+        params.into_iter().map(crate::helpers::canonical_type).join(",")
+    )
+}
+
+fn model_comments(comments: &Comments) -> Vec<ModelComment> {
+    comments
+        .iter()
+        .map(|comment| ModelComment { tag: comment.tag.to_string(), value: comment.value.clone() })
+        .collect()
+}
+
+/// The member kinds compared by the [ModelDiff].
+const DIFF_KINDS: &[&str] = &["constructor", "fallback", "receive", "function", "event", "error"];
+
+/// The difference between two [DocModel]s.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDiff {
+    /// The changed symbols ordered by the parent item, kind and signature.
+    pub changes: Vec<SymbolChange>,
+}
+
+/// The change of the documented symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolChange {
+    /// The name of the parent item, e.g. contract. [None] for top-level items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// The symbol kind, e.g. `function`, `event` or `error`.
+    pub kind: String,
+    /// The symbol signature.
+    pub signature: String,
+    /// The kind of the change.
+    pub change: Change,
+}
+
+/// The kind of the [SymbolChange].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// The symbol was added.
+    Added,
+    /// The symbol was removed.
+    Removed,
+    /// The symbol signature changed. Contains the previous signature.
+    Signature(String),
+    /// The symbol declaration changed, e.g. its mutability or return values.
+    Declaration,
+    /// The symbol natspec comments changed.
+    Docs,
+}
+
+/// The symbol data compared by the [ModelDiff].
+struct Symbol<'a> {
+    signature: &'a str,
+    code: &'a str,
+    comments: &'a [ModelComment],
+}
+
+impl ModelDiff {
+    /// Compare the previous model with the current one.
+    pub fn new(previous: &DocModel, current: &DocModel) -> Self {
+        let previous = Self::symbols(previous);
+        let mut current = Self::symbols(current);
+
+        let mut changes = vec![];
+        let mut push = |(parent, kind, _): &(Option<String>, String, String),
+                        signature: &str,
+                        change: Change| {
+            changes.push(SymbolChange {
+                parent: parent.clone(),
+                kind: kind.clone(),
+                signature: signature.to_owned(),
+                change,
+            })
+        };
+
+        for (key, old) in previous.iter() {
+            let new = current.remove(key).unwrap_or_default();
+            let removed = old
+                .iter()
+                .filter(|o| !new.iter().any(|n| n.signature == o.signature))
+                .collect::<Vec<_>>();
+            let added = new
+                .iter()
+                .filter(|n| !old.iter().any(|o| o.signature == n.signature))
+                .collect::<Vec<_>>();
+
+            // A single symbol with the same name was replaced, i.e. its signature changed.
+            if let ([removed], [added]) = (removed.as_slice(), added.as_slice()) {
+                push(key, added.signature, Change::Signature(removed.signature.to_owned()));
+            } else {
+                removed.iter().for_each(|o| push(key, o.signature, Change::Removed));
+                added.iter().for_each(|n| push(key, n.signature, Change::Added));
+            }
+
+            for o in old.iter() {
+                if let Some(n) = new.iter().find(|n| n.signature == o.signature) {
+                    if o.code != n.code {
+                        push(key, n.signature, Change::Declaration);
+                    }
+                    if o.comments != n.comments {
+                        push(key, n.signature, Change::Docs);
+                    }
+                }
+            }
+        }
+        for (key, new) in current.iter() {
+            new.iter().for_each(|n| push(key, n.signature, Change::Added));
+        }
+
+        changes.sort_by(|lhs, rhs| {
+            (&lhs.parent, &lhs.kind, &lhs.signature).cmp(&(&rhs.parent, &rhs.kind, &rhs.signature))
+        });
+        Self { changes }
+    }
+
+    /// Returns true if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Collect the comparable symbols keyed by the parent name, kind and symbol name.
+    #[allow(clippy::type_complexity)]
+    fn symbols(model: &DocModel) -> BTreeMap<(Option<String>, String, String), Vec<Symbol<'_>>> {
+        let mut symbols: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for item in model.items.iter() {
+            if DIFF_KINDS.contains(&item.kind.as_str()) {
+                let key = (None, item.kind.clone(), item.name.clone());
+                symbols.entry(key).or_default().push(Symbol {
+                    signature: &item.signature,
+                    code: &item.code,
+                    comments: &item.comments,
+                });
+            }
+            for member in item.all_members().filter(|m| DIFF_KINDS.contains(&m.kind.as_str())) {
+                let key = (Some(item.name.clone()), member.kind.clone(), member.name.clone());
+                symbols.entry(key).or_default().push(Symbol {
+                    signature: &member.signature,
+                    code: &member.code,
+                    comments: &member.comments,
+                });
+            }
+        }
+        symbols
+    }
+}
+
+impl fmt::Display for ModelDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes detected.")
+        }
+        for (parent, changes) in &self.changes.iter().group_by(|change| change.parent.clone()) {
+            writeln!(f, "{}", parent.as_deref().unwrap_or("(top-level)"))?;
+            for change in changes {
+                let SymbolChange { kind, signature, .. } = change;
+                match &change.change {
+                    Change::Added => writeln!(f, "  + {kind} {signature}")?,
+                    Change::Removed => writeln!(f, "  - {kind} {signature}")?,
+                    Change::Signature(previous) => {
+                        writeln!(f, "  ~ {kind} {signature} (was {previous})")?
+                    }
+                    Change::Declaration => writeln!(f, "  ~ {kind} {signature} (declaration)")?,
+                    Change::Docs => writeln!(f, "  ~ {kind} {signature} (docs)")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    fn member(kind: &str, signature: &str, code: &str) -> ModelMember {
+        ModelMember {
+            kind: kind.to_owned(),
+            name: signature.split('(').next().unwrap().to_owned(),
+            signature: signature.to_owned(),
+            code: code.to_owned(),
+            comments: vec![],
+            topic: None,
+            indexed: vec![],
+            visibility: None,
+            page: None,
+        }
+    }
+
+    fn model(members: Vec<ModelMember>) -> DocModel {
+        DocModel {
+            version: DocModel::VERSION,
+            items: vec![ModelItem {
+                kind: "contract".to_owned(),
+                name: "Vault".to_owned(),
+                signature: "Vault".to_owned(),
+                source: PathBuf::from("src/Vault.sol"),
+                page: PathBuf::from("src/Vault.sol/contract.Vault.md"),
+                code: "contract Vault".to_owned(),
+                comments: vec![],
+                security_contact: None,
+                special: SpecialFunctions::default(),
+                members,
+            }],
+            build_info: None,
+            redirects: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn diff_models() {
+        let previous = model(vec![
+            member("function", "deposit(uint256)", "function deposit(uint256) external"),
+            member("function", "withdraw(uint256)", "function withdraw(uint256) external"),
+            member("event", "Deposit(uint256)", "event Deposit(uint256)"),
+            member("error", "Paused()", "error Paused()"),
+        ]);
+        let mut current = model(vec![
+            member("function", "deposit(uint256)", "function deposit(uint256) external payable"),
+            member("function", "withdraw(uint256,address)", "function withdraw(uint256, address)"),
+            member("event", "Deposit(uint256)", "event Deposit(uint256)"),
+            member("event", "Withdraw(uint256)", "event Withdraw(uint256)"),
+        ]);
+        current.items[0].members[2].comments =
+            vec![ModelComment { tag: "notice".to_owned(), value: "Emitted".to_owned() }];
+
+        let change = |kind: &str, signature: &str, change: Change| SymbolChange {
+            parent: Some("Vault".to_owned()),
+            kind: kind.to_owned(),
+            signature: signature.to_owned(),
+            change,
+        };
+        assert_eq!(
+            ModelDiff::new(&previous, &current).changes,
+            vec![
+                change("error", "Paused()", Change::Removed),
+                change("event", "Deposit(uint256)", Change::Docs),
+                change("event", "Withdraw(uint256)", Change::Added),
+                change("function", "deposit(uint256)", Change::Declaration),
+                change(
+                    "function",
+                    "withdraw(uint256,address)",
+                    Change::Signature("withdraw(uint256)".to_owned())
+                ),
+            ]
+        );
+        assert!(ModelDiff::new(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn special_functions_schema() {
+        let src = r#"
+            contract All {
+                function deposit() external payable { }
+                fallback() external { }
+                receive() external payable { }
+                constructor(address owner) { }
+            }
+
+            contract OnlyConstructor {
+                constructor() { }
+            }
+
+            contract None {
+                function deposit() external { }
+            }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+        let model = DocModel::new(&documents, Path::new(""), Path::new(""));
+        let json = serde_json::to_value(&model).unwrap();
+        let keys = |value: &serde_json::Value| {
+            value.as_object().map(|object| object.keys().cloned().sorted().collect_vec())
+        };
+        let member_kinds = |index: usize| {
+            json["items"][index]["members"]
+                .as_array()
+                .map(|members| {
+                    members.iter().map(|member| member["kind"].as_str().unwrap()).collect_vec()
+                })
+                .unwrap_or_else(Vec::new)
+        };
+
+        assert_eq!(
+            keys(&json["items"][0]["special"]),
+            Some(vec!["constructor".to_owned(), "fallback".to_owned(), "receive".to_owned()])
+        );
+        assert_eq!(json["items"][0]["special"]["constructor"]["signature"], "constructor(address)");
+        assert_eq!(member_kinds(0), vec!["function"]);
+        assert_eq!(keys(&json["items"][1]["special"]), Some(vec!["constructor".to_owned()]));
+        assert!(member_kinds(1).is_empty());
+        assert!(json["items"][2].get("special").is_none());
+        assert_eq!(member_kinds(2), vec!["function"]);
+
+        // The special functions are compared by the diff along with the other members
+        let kinds = model.items[0].all_members().map(|member| member.kind.as_str()).collect_vec();
+        assert_eq!(kinds, vec!["constructor", "receive", "fallback", "function"]);
+        let read: DocModel = serde_json::from_value(json).unwrap();
+        assert_eq!(read, model);
+    }
+}
@@ -0,0 +1,51 @@
+//! The formatting of the numbers written to the pages and the reports.
+//!
+//! The numbers are written in one style regardless of the environment, i.e. the integers without
+//! the thousands separators and the fractions with a dot and a single decimal, so the regenerated
+//! output only differs when the values do.
+
+/// Returns the integer without the digit separators, e.g. `1000000` for `1_000_000`.
+/// The other values, e.g. the `infinite` gas estimates or `1e18`, are returned as is.
+pub(crate) fn integer(value: &str) -> String {
+    let value = value.trim();
+    let digits = value.chars().filter(char::is_ascii_digit).collect::<String>();
+    if !digits.is_empty() && value.chars().all(|ch| ch.is_ascii_digit() || ch == '_' || ch == ',') {
+        digits
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Returns the percentage with a single decimal, e.g. `87.5%`.
+pub(crate) fn percentage(value: f64) -> String {
+    format!("{value:.1}%")
+}
+
+/// Returns the size in the binary units with a single decimal, e.g. `1.5 MiB` or `512 B`.
+pub(crate) fn size(bytes: usize) -> String {
+    match bytes {
+        bytes if bytes >= 1 << 20 => format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64),
+        bytes if bytes >= 1 << 10 => format!("{:.1} KiB", bytes as f64 / (1 << 10) as f64),
+        bytes => format!("{bytes} B"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_numbers() {
+        assert_eq!(integer("1_000_000"), "1000000");
+        assert_eq!(integer("24,512"), "24512");
+        assert_eq!(integer(" 24512 "), "24512");
+        assert_eq!(integer("infinite"), "infinite");
+        assert_eq!(integer("1e18"), "1e18");
+        assert_eq!(integer("-1"), "-1");
+        assert_eq!(percentage(87.54), "87.5%");
+        assert_eq!(percentage(100.), "100.0%");
+        assert_eq!(size(512), "512 B");
+        assert_eq!(size(1536), "1.5 KiB");
+        assert_eq!(size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
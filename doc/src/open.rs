@@ -0,0 +1,100 @@
+//! Opening the generated documentation with the OS handler.
+
+use crate::DocBuilder;
+use std::{
+    ffi::OsStr,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The landing page of the generated documentation opened by `forge doc --open`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LandingPage {
+    /// The url of the served book.
+    Url(String),
+    /// The built book index or the markdown homepage.
+    File(PathBuf),
+}
+
+impl LandingPage {
+    /// The name of the book build directory within the doc output directory.
+    const BOOK: &'static str = "book";
+
+    /// Resolve the landing page of the documentation in the output directory.
+    ///
+    /// The served url is preferred. Otherwise, the `index.html` of the built book is used
+    /// if it exists, falling back to the markdown homepage.
+    pub fn resolve(out: &Path, served: Option<String>) -> Self {
+        if let Some(url) = served {
+            return Self::Url(url)
+        }
+        let index = out.join(Self::BOOK).join("index.html");
+        if index.is_file() {
+            Self::File(index)
+        } else {
+            Self::File(out.join(DocBuilder::SRC).join(DocBuilder::README))
+        }
+    }
+
+    /// Open the landing page with the OS handler.
+    ///
+    /// The failures are not propagated, they're printed to stderr and `false` is returned,
+    /// so that the caller can point to the landing page to open it manually.
+    pub fn open(&self) -> bool {
+        self.open_with(opener::open)
+    }
+
+    fn open_with<E: fmt::Display>(&self, opener: impl FnOnce(&OsStr) -> Result<(), E>) -> bool {
+        let target = match self {
+            Self::Url(url) => OsStr::new(url),
+            Self::File(path) => path.as_os_str(),
+        };
+        match opener(target) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!("Failed to open the documentation: {err}");
+                false
+            }
+        }
+    }
+}
+
+impl fmt::Display for LandingPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url) => f.write_str(url),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn landing_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let readme = dir.path().join("src/README.md");
+        assert_eq!(LandingPage::resolve(dir.path(), None), LandingPage::File(readme.clone()));
+
+        let index = dir.path().join("book/index.html");
+        fs::create_dir_all(index.parent().unwrap()).unwrap();
+        fs::write(&index, "").unwrap();
+        assert_eq!(LandingPage::resolve(dir.path(), None), LandingPage::File(index.clone()));
+
+        let served = LandingPage::resolve(dir.path(), Some("http://localhost:3000".to_owned()));
+        assert_eq!(served, LandingPage::Url("http://localhost:3000".to_owned()));
+
+        let mut opened = None;
+        assert!(served.open_with(|target| {
+            opened = Some(target.to_owned());
+            Ok::<_, String>(())
+        }));
+        assert_eq!(opened.as_deref(), Some(OsStr::new("http://localhost:3000")));
+
+        // The failure to open the page doesn't fail
+        assert!(!LandingPage::File(readme).open_with(|_| Err("no handler")));
+    }
+}
@@ -0,0 +1,106 @@
+//! The files written to the output directory.
+
+use itertools::Itertools;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// The maximum number of the written files listed in the error of the failed write.
+const MAX_LISTED: usize = 10;
+
+/// The writer of the generated files.
+///
+/// The errors name the file and the OS error, e.g. of the read-only or the full filesystem.
+/// The written files are tracked, so the error of the write failing midway also lists
+/// the files written before the generation aborted.
+///
+/// The clones share the written files, so they can be listed after the builder is consumed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OutputFiles {
+    written: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl OutputFiles {
+    /// Write the file.
+    pub(crate) fn write(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> eyre::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, contents).map_err(|err| self.failure("write", path, err))?;
+        self.written.lock().expect("failed to lock written files").push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Create the file to be written as a stream, e.g. the archive.
+    pub(crate) fn create(&self, path: impl AsRef<Path>) -> eyre::Result<fs::File> {
+        let path = path.as_ref();
+        let file = fs::File::create(path).map_err(|err| self.failure("write", path, err))?;
+        self.written.lock().expect("failed to lock written files").push(path.to_path_buf());
+        Ok(file)
+    }
+
+    /// Create the directory and its missing parents.
+    pub(crate) fn create_dir_all(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+        fs::create_dir_all(path).map_err(|err| self.failure("create", path, err))
+    }
+
+    /// Returns the written files, in the write order.
+    pub(crate) fn written(&self) -> Vec<PathBuf> {
+        self.written.lock().expect("failed to lock written files").clone()
+    }
+
+    /// Returns the error of the failed operation on the path.
+    fn failure(&self, operation: &str, path: &Path, err: io::Error) -> eyre::Report {
+        let written = self.written.lock().expect("failed to lock written files");
+        let mut message = format!("failed to {operation} {}: {err}", path.display());
+        if !written.is_empty() {
+            message.push_str(&format!(
+                "; {} file(s) were written before the failure: {}",
+                written.len(),
+                written.iter().take(MAX_LISTED).map(|path| path.display()).join(", ")
+            ));
+            if written.len() > MAX_LISTED {
+                message.push_str(&format!(" and {} more", written.len() - MAX_LISTED));
+            }
+        }
+        eyre::eyre!(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_write() {
+        let out = tempfile::tempdir().unwrap();
+        let out = out.path();
+        let output = OutputFiles::default();
+        output.create_dir_all(out.join("src")).unwrap();
+        output.write(out.join("book.toml"), "[book]").unwrap();
+
+        // The directory at the page path fails the write like the read-only filesystem
+        let page = out.join("src/README.md");
+        fs::create_dir_all(&page).unwrap();
+        let err = output.write(&page, "# Home").unwrap_err().to_string();
+        assert!(err.starts_with(&format!("failed to write {}: ", page.display())));
+        assert!(err.ends_with(&format!(
+            "; 1 file(s) were written before the failure: {}",
+            out.join("book.toml").display()
+        )));
+
+        // The archive stream is created like the written file
+        let err = output.create(&page).unwrap_err().to_string();
+        assert!(err.starts_with(&format!("failed to write {}: ", page.display())));
+        output.create(out.join("docs.tar")).unwrap();
+        assert_eq!(output.written(), vec![out.join("book.toml"), out.join("docs.tar")]);
+
+        let err = output.create_dir_all(out.join("book.toml/src")).unwrap_err().to_string();
+        assert!(err.starts_with("failed to create "));
+    }
+}
@@ -2,6 +2,21 @@ use derive_more::{Deref, DerefMut};
 use solang_parser::doccomment::DocCommentTag;
 use std::collections::HashMap;
 
+/// The custom tag of the free-form markdown section of the item, i.e. `@custom:docs`.
+pub const DOCS_TAG: &str = "docs";
+
+/// The custom tag of the parameter constraint, i.e. `@custom:constraint <name> <text>`.
+/// It's the spelling accepted by solc of the `@custom:param:<name> <text>` constraint.
+pub const CONSTRAINT_TAG: &str = "constraint";
+
+/// The custom tag of the parameter description inferred from the trailing line comment
+/// of the parameter, i.e. `@custom:inferred-param <name> <text>`.
+pub const INFERRED_PARAM_TAG: &str = "inferred-param";
+
+/// The prefix of the custom tag of the named parameter constraint,
+/// i.e. `@custom:param:<name> <text>`.
+const PARAM_CONSTRAINT_PREFIX: &str = "param:";
+
 /// The natspec comment tag explaining the purpose of the comment.
 /// See: https://docs.soliditylang.org/en/v0.8.17/natspec-format.html#tags.
 #[derive(PartialEq, Clone, Debug)]
@@ -44,15 +59,28 @@ impl CommentTag {
                     _ => CommentTag::Custom(custom_tag.to_owned()),
                 }
             }
-            _ => {
-                tracing::warn!(target: "forge::doc", tag = trimmed, "unknown comment tag. custom tags must be preceded by `custom:`");
-                return None
-            }
+            // The unknown tags are reported by the natspec lint with their source location
+            _ => return None,
         };
         Some(tag)
     }
 }
 
+impl std::fmt::Display for CommentTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommentTag::Title => f.write_str("title"),
+            CommentTag::Author => f.write_str("author"),
+            CommentTag::Notice => f.write_str("notice"),
+            CommentTag::Dev => f.write_str("dev"),
+            CommentTag::Param => f.write_str("param"),
+            CommentTag::Return => f.write_str("return"),
+            CommentTag::Inheritdoc => f.write_str("inheritdoc"),
+            CommentTag::Custom(tag) => write!(f, "custom:{tag}"),
+        }
+    }
+}
+
 /// The natspec documentation comment.
 /// https://docs.soliditylang.org/en/v0.8.17/natspec-format.html
 #[derive(PartialEq, Clone, Debug)]
@@ -81,6 +109,21 @@ impl Comment {
         self.value.trim_start().split_once(' ')
     }
 
+    /// Match the parameter constraint of the comment, i.e. `@custom:param:<name> <text>` or
+    /// `@custom:constraint <name> <text>`, with the parameter name.
+    /// Returns the constraint text, or [None] if the comment isn't the parameter constraint.
+    pub fn match_constraint(&self, param: &str) -> Option<&str> {
+        let tag = match self.tag {
+            CommentTag::Custom(ref tag) => tag,
+            _ => return None,
+        };
+        match tag.strip_prefix(PARAM_CONSTRAINT_PREFIX) {
+            Some(name) => (name == param).then(|| self.value.trim()),
+            None if tag == CONSTRAINT_TAG => self.match_first_word(param).map(str::trim),
+            None => None,
+        }
+    }
+
     /// Match the first word of the comment with the expected.
     /// Returns [None] if the word doesn't match.
     /// Useful for [CommentTag::Param] and [CommentTag::Return] comments.
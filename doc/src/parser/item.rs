@@ -5,11 +5,11 @@ use forge_fmt::{
 };
 use solang_parser::pt::{
     ContractDefinition, ContractTy, EnumDefinition, ErrorDefinition, EventDefinition,
-    FunctionDefinition, StructDefinition, TypeDefinition, VariableDefinition,
+    FunctionDefinition, Loc, StructDefinition, TypeDefinition, VariableDefinition,
 };
 
 /// The parsed item.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Clone, Debug)]
 pub struct ParseItem {
     /// The parse tree source.
     pub source: ParseSource,
@@ -183,4 +183,18 @@ impl ParseSource {
             ParseSource::Type(ty) => ty.name.name.to_owned(),
         }
     }
+
+    /// Get the location of the source
+    pub fn loc(&self) -> Loc {
+        match self {
+            ParseSource::Contract(contract) => contract.loc,
+            ParseSource::Variable(var) => var.loc,
+            ParseSource::Event(event) => event.loc,
+            ParseSource::Error(error) => error.loc,
+            ParseSource::Struct(structure) => structure.loc,
+            ParseSource::Enum(enumerable) => enumerable.loc,
+            ParseSource::Function(func) => func.loc,
+            ParseSource::Type(ty) => ty.loc,
+        }
+    }
 }
@@ -1,13 +1,15 @@
 //! The parser module.
 
+use crate::helpers::{abi_declaration, wrap_declaration};
 use forge_fmt::{FormatterConfig, Visitable, Visitor};
+use foundry_config::{fmt::MultilineFuncHeaderStyle, SignatureStyle};
 use itertools::Itertools;
 use solang_parser::{
     doccomment::{parse_doccomments, DocComment},
     pt::{
         Comment as SolangComment, EnumDefinition, ErrorDefinition, EventDefinition,
-        FunctionDefinition, Identifier, Loc, SourceUnit, SourceUnitPart, StructDefinition,
-        TypeDefinition, VariableDefinition,
+        FunctionDefinition, FunctionTy, Identifier, Loc, SourceUnit, SourceUnitPart,
+        StructDefinition, TypeDefinition, VariableDefinition,
     },
 };
 
@@ -21,7 +23,9 @@ pub use item::{ParseItem, ParseSource};
 
 /// Doc comment.
 mod comment;
-pub use comment::{Comment, CommentTag, Comments, CommentsRef};
+pub use comment::{
+    Comment, CommentTag, Comments, CommentsRef, CONSTRAINT_TAG, DOCS_TAG, INFERRED_PARAM_TAG,
+};
 
 /// The documentation parser. This type implements a [Visitor] trait. While walking the parse tree,
 /// [Parser] will collect relevant source items and corresponding doc comments. The resulting
@@ -38,6 +42,12 @@ pub struct Parser {
     source: String,
     /// The formatter config.
     fmt: FormatterConfig,
+    /// The style of the function signatures.
+    signature_style: SignatureStyle,
+    /// The maximum width of the signatures, or [None] to follow the formatter config.
+    signature_width: Option<usize>,
+    /// Whether to infer the undocumented parameter descriptions from their trailing comments.
+    param_comment_fallback: bool,
 }
 
 /// [Parser] context.
@@ -61,6 +71,28 @@ impl Parser {
         self
     }
 
+    /// Set the function signature style on the [Parser]
+    pub fn with_signature_style(mut self, signature_style: SignatureStyle) -> Self {
+        self.signature_style = signature_style;
+        self
+    }
+
+    /// Set the maximum width of the function signatures on the [Parser].
+    /// The parameters of the longer signatures are wrapped one per line.
+    /// [None] keeps the signatures formatted with the formatter config.
+    pub fn with_signature_width(mut self, signature_width: Option<usize>) -> Self {
+        self.signature_width = signature_width;
+        self
+    }
+
+    /// Set the parameter comment fallback on the [Parser].
+    /// The trailing line comments of the parameters without `@param` are added
+    /// as the [INFERRED_PARAM_TAG] comments.
+    pub fn with_param_comment_fallback(mut self, param_comment_fallback: bool) -> Self {
+        self.param_comment_fallback = param_comment_fallback;
+        self
+    }
+
     /// Return the parsed items. Consumes the parser.
     pub fn items(self) -> Vec<ParseItem> {
         self.items
@@ -98,9 +130,86 @@ impl Parser {
     }
 
     /// Create new [ParseItem] with comments and formatted code.
+    /// The functions are rendered with canonical types if the [SignatureStyle::Abi] is set.
+    /// The function signatures longer than the signature width have the parameters wrapped,
+    /// the other code follows the formatter config.
     fn new_item(&mut self, source: ParseSource, loc_start: usize) -> ParserResult<ParseItem> {
         let docs = self.parse_docs(loc_start)?;
-        ParseItem::new(source).with_comments(docs).with_code(&self.source, self.fmt.clone())
+        let mut fmt = self.fmt.clone();
+        if let (Some(width), ParseSource::Function(_)) = (self.signature_width, &source) {
+            fmt.line_length = width;
+            fmt.multiline_func_header = MultilineFuncHeaderStyle::ParamsFirst;
+        }
+        let mut item = ParseItem::new(source).with_comments(docs).with_code(&self.source, fmt)?;
+        if self.param_comment_fallback {
+            let inferred = self.inferred_param_docs(&item);
+            item.comments.extend(inferred);
+        }
+        if let (SignatureStyle::Abi, ParseSource::Function(func)) =
+            (self.signature_style, &item.source)
+        {
+            if !matches!(func.ty, FunctionTy::Modifier) {
+                let declaration = abi_declaration(func);
+                item.code = match self.signature_width {
+                    Some(width) => wrap_declaration(&declaration, width),
+                    None => declaration,
+                };
+            }
+        }
+        Ok(item)
+    }
+
+    /// Returns the descriptions of the parameters without `@param` inferred from their trailing
+    /// line comments, e.g. `uint256 amount, // The amount to deposit`.
+    fn inferred_param_docs(&self, item: &ParseItem) -> Vec<Comment> {
+        let params = match item.source {
+            ParseSource::Function(ref func) => func
+                .params
+                .iter()
+                .filter_map(|(loc, param)| Some((*loc, param.as_ref()?.name.as_ref()?)))
+                .collect::<Vec<_>>(),
+            ParseSource::Event(ref event) => event
+                .fields
+                .iter()
+                .filter_map(|field| Some((field.loc, field.name.as_ref()?)))
+                .collect(),
+            ParseSource::Error(ref error) => error
+                .fields
+                .iter()
+                .filter_map(|field| Some((field.loc, field.name.as_ref()?)))
+                .collect(),
+            _ => return vec![],
+        };
+        let documented = item.comments.include_tag(CommentTag::Param);
+        params
+            .into_iter()
+            .filter(|(_, name)| {
+                documented.iter().all(|comment| comment.match_first_word(&name.name).is_none())
+            })
+            .filter_map(|(loc, name)| {
+                let text = self.trailing_comment(loc.end())?;
+                Some(Comment::new(
+                    CommentTag::Custom(INFERRED_PARAM_TAG.to_owned()),
+                    format!("{} {text}", name.name),
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the text of the line comment trailing the parameter on the same line,
+    /// i.e. separated from the parameter end by the whitespace and the comma only.
+    fn trailing_comment(&self, end: usize) -> Option<&str> {
+        self.comments.iter().find_map(|comment| match comment {
+            SolangComment::Line(loc, text) if loc.start() >= end => {
+                let between = self.source.get(end..loc.start())?;
+                between
+                    .chars()
+                    .all(|ch| ch == ',' || (ch.is_whitespace() && ch != '\n'))
+                    .then(|| text.trim_start_matches("//").trim())
+                    .filter(|text| !text.is_empty())
+            }
+            _ => None,
+        })
     }
 
     /// Parse the doc comments from the current start location.
@@ -128,6 +237,20 @@ impl Parser {
     }
 }
 
+/// Returns the position of the contract member, so the special functions come first,
+/// i.e. the constructor, `receive` and `fallback`, and the others keep the source order.
+fn member_order(source: &ParseSource) -> u8 {
+    match source {
+        ParseSource::Function(func) => match func.ty {
+            FunctionTy::Constructor => 0,
+            FunctionTy::Receive => 1,
+            FunctionTy::Fallback => 2,
+            _ => 3,
+        },
+        _ => 3,
+    }
+}
+
 impl Visitor for Parser {
     type Error = ParserError;
 
@@ -143,7 +266,7 @@ impl Visitor for Parser {
                     self.context.doc_start_loc = def.loc.start();
 
                     // Parse child elements with current contract as parent
-                    let contract = self.with_parent(contract, |doc| {
+                    let mut contract = self.with_parent(contract, |doc| {
                         def.parts
                             .iter_mut()
                             .map(|d| d.visit(doc))
@@ -151,6 +274,9 @@ impl Visitor for Parser {
                         Ok(())
                     })?;
 
+                    // The special functions precede the others, in the canonical order
+                    contract.children.sort_by_key(|child| member_order(&child.source));
+
                     // Move the doc pointer to the contract location end.
                     self.context.doc_start_loc = def.loc.end();
 
@@ -248,6 +374,101 @@ mod tests {
         };
     }
 
+    #[test]
+    fn signature_styles() {
+        let src = r#"
+            contract Contract {
+                function deposit(uint amount) external { }
+                function pay() public payable returns (bool success) { }
+                function balance(address owner) external view returns (uint256) { }
+                function add(uint8 a, uint8 b) internal pure returns (uint8 sum) { }
+            }
+        "#;
+        let codes = |style| {
+            let (mut source, comments) = parse(src, 0).expect("failed to parse source");
+            let mut doc = Parser::new(comments, src.to_owned()).with_signature_style(style);
+            source.visit(&mut doc).expect("failed to visit source");
+            doc.items().remove(0).children.into_iter().map(|child| child.code).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            codes(SignatureStyle::Solidity),
+            vec![
+                "function deposit(uint256 amount) external;",
+                "function pay() public payable returns (bool success);",
+                "function balance(address owner) external view returns (uint256);",
+                "function add(uint8 a, uint8 b) internal pure returns (uint8 sum);",
+            ]
+        );
+        assert_eq!(
+            codes(SignatureStyle::Abi),
+            vec![
+                "function deposit(uint256) external",
+                "function pay() public payable returns (bool)",
+                "function balance(address) external view returns (uint256)",
+                "function add(uint8,uint8) internal pure returns (uint8)",
+            ]
+        );
+    }
+
+    #[test]
+    fn signature_widths() {
+        let src = r#"
+            contract Contract {
+                function deposit(uint256 assets, address receiver, bytes32 referral) external returns (uint256 shares) { }
+                event Deposited(address indexed owner, uint256 assets, uint256 shares);
+            }
+        "#;
+        let codes = |width| {
+            let (mut source, comments) = parse(src, 0).expect("failed to parse source");
+            let mut doc = Parser::new(comments, src.to_owned()).with_signature_width(width);
+            source.visit(&mut doc).expect("failed to visit source");
+            doc.items().remove(0).children.into_iter().map(|child| child.code).collect::<Vec<_>>()
+        };
+        let unwrapped = vec![
+            "function deposit(uint256 assets, address receiver, bytes32 referral) external returns (uint256 shares);",
+            "event Deposited(address indexed owner, uint256 assets, uint256 shares);",
+        ];
+        // The formatter config is followed if the width isn't set,
+        // and the width only applies to the function signatures
+        assert_eq!(codes(None), unwrapped);
+        assert_eq!(codes(Some(60))[1], unwrapped[1]);
+
+        let code = |style, width| {
+            let (mut source, comments) = parse(src, 0).expect("failed to parse source");
+            let mut doc = Parser::new(comments, src.to_owned())
+                .with_signature_style(style)
+                .with_signature_width(Some(width));
+            source.visit(&mut doc).expect("failed to visit source");
+            doc.items().remove(0).children.remove(0).code
+        };
+
+        assert_eq!(
+            code(SignatureStyle::Solidity, 120),
+            "function deposit(uint256 assets, address receiver, bytes32 referral) external returns (uint256 shares);"
+        );
+        assert_eq!(
+            code(SignatureStyle::Solidity, 60),
+            "function deposit(
+    uint256 assets,
+    address receiver,
+    bytes32 referral
+) external returns (uint256 shares);"
+        );
+        assert_eq!(
+            code(SignatureStyle::Abi, 120),
+            "function deposit(uint256,address,bytes32) external returns (uint256)"
+        );
+        assert_eq!(
+            code(SignatureStyle::Abi, 60),
+            "function deposit(
+    uint256,
+    address,
+    bytes32
+) external returns (uint256)"
+        );
+    }
+
     #[test]
     fn empty_source() {
         assert_eq!(parse_source(""), vec![]);
@@ -347,5 +568,82 @@ mod tests {
         assert!(matches!(fallback.source, ParseSource::Function(_)));
     }
 
+    #[test]
+    fn special_functions_first() {
+        let items = parse_source(
+            r#"
+            contract Contract {
+                uint256 public total;
+                function deposit() external {}
+                fallback() external payable {}
+                receive() external payable {}
+                constructor() {}
+            }
+        "#,
+        );
+
+        let children = items[0].children.iter().map(|ch| ch.source.ident()).collect::<Vec<_>>();
+        assert_eq!(children, vec!["constructor", "receive", "fallback", "total", "deposit"]);
+    }
+
+    #[test]
+    fn param_comment_fallback() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposits the assets.
+                /// @param assets The amount of the assets.
+                function deposit(
+                    uint256 assets, // Ignored, `@param` takes precedence
+                    address receiver, // The **receiver** of the `shares`, e.g. `a | b`
+                    bytes calldata data // The hook data
+
+                ) external { }
+
+                event Deposit(
+                    address indexed owner, // The depositor
+                    uint256 shares
+                    // Not trailing the parameter
+                );
+
+                function withdraw(uint256 shares) external { } // Not a parameter comment
+            }
+        "#;
+        let (mut source, comments) = parse(src, 0).expect("failed to parse source");
+        let mut doc = Parser::new(comments, src.to_owned()).with_param_comment_fallback(true);
+        source.visit(&mut doc).expect("failed to visit source");
+        let contract = doc.items().remove(0);
+
+        let inferred = |item: &ParseItem| {
+            item.comments
+                .iter()
+                .filter(|comment| comment.tag == CommentTag::Custom(INFERRED_PARAM_TAG.to_owned()))
+                .map(|comment| comment.value.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            inferred(&contract.children[0]),
+            vec!["receiver The **receiver** of the `shares`, e.g. `a | b`", "data The hook data"]
+        );
+        assert_eq!(inferred(&contract.children[1]), vec!["owner The depositor"]);
+        assert!(inferred(&contract.children[2]).is_empty());
+
+        let mut writer = crate::BufWriter::default();
+        let func = contract.children[0].as_function().unwrap();
+        let params = func.params.iter().filter_map(|(_, param)| param.as_ref()).collect::<Vec<_>>();
+        writer
+            .try_write_param_table(
+                CommentTag::Param,
+                &params,
+                &contract.children[0].comments,
+                &Default::default(),
+            )
+            .unwrap();
+        let table = writer.finish();
+        assert!(table.contains("|`assets`|`uint256`|The amount of the assets.|"));
+        assert!(table
+            .contains("|`receiver`|`address`|The **receiver** of the `shares`, e.g. `a \\| b`|"));
+        assert!(table.contains("|`data`|`bytes`|The hook data|"));
+    }
+
     // TODO: test regular doc comments & natspec
 }
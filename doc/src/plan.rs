@@ -0,0 +1,241 @@
+//! The planned filesystem operations of the generation.
+
+use crate::DocBuilder;
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// The operation on the output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileOperation {
+    /// The file doesn't exist yet.
+    Create,
+    /// The file exists with a different content.
+    Overwrite,
+    /// The file exists with the same content.
+    Unchanged,
+}
+
+/// The file the generation would write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedFile {
+    /// The file path, relative to the output directory.
+    pub path: PathBuf,
+    /// The operation on the file.
+    pub operation: FileOperation,
+}
+
+/// The filesystem operations the generation would perform in the output directory.
+///
+/// The paths are relative to the output directory. The stale pages are only listed,
+/// since the generation leaves them in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Plan {
+    /// The files written, sorted by the path.
+    pub files: Vec<PlannedFile>,
+    /// The directories created.
+    pub directories: Vec<PathBuf>,
+    /// The pages left over from the previous generations.
+    pub stale: Vec<PathBuf>,
+    /// The changed lines of the summary, prefixed with `-` or `+`.
+    pub summary: Vec<String>,
+}
+
+impl Plan {
+    /// Compare the files written by the generation into the scratch directory with the output
+    /// directory. The previous output copied to the scratch directory isn't compared, so only
+    /// the files the generation would write are listed.
+    pub(crate) fn new(
+        out: &Path,
+        scratch: &Path,
+        written: &[PathBuf],
+        stale: Vec<PathBuf>,
+    ) -> eyre::Result<Self> {
+        let mut plan = Self { stale, ..Default::default() };
+        let written = written
+            .iter()
+            .filter_map(|path| path.strip_prefix(scratch).ok())
+            .filter(|path| !plan.stale.iter().any(|stale| stale == path))
+            .collect::<BTreeSet<_>>();
+        let mut directories = BTreeSet::new();
+        for path in written {
+            directories.extend(
+                path.ancestors()
+                    .skip(1)
+                    .filter(|dir| !dir.as_os_str().is_empty() && !out.join(dir).is_dir()),
+            );
+            let current = out.join(path);
+            let operation = if !current.is_file() {
+                FileOperation::Create
+            } else if fs::read(&current)? == fs::read(scratch.join(path))? {
+                FileOperation::Unchanged
+            } else {
+                FileOperation::Overwrite
+            };
+            plan.files.push(PlannedFile { path: path.to_path_buf(), operation });
+        }
+        plan.directories = directories.into_iter().map(Path::to_path_buf).collect();
+
+        let summary = Path::new(DocBuilder::SRC).join(DocBuilder::SUMMARY);
+        let previous = fs::read_to_string(out.join(&summary)).unwrap_or_default();
+        let planned = fs::read_to_string(scratch.join(&summary)).unwrap_or_default();
+        plan.summary = line_diff(&previous, &planned);
+        Ok(plan)
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for dir in self.directories.iter() {
+            writeln!(f, "mkdir      {}", dir.display())?;
+        }
+        for file in self.files.iter() {
+            match file.operation {
+                FileOperation::Create => writeln!(f, "create     {}", file.path.display())?,
+                FileOperation::Overwrite => {
+                    writeln!(f, "overwrite  {} (changed)", file.path.display())?
+                }
+                FileOperation::Unchanged => {
+                    writeln!(f, "overwrite  {} (unchanged)", file.path.display())?
+                }
+            }
+        }
+        for page in self.stale.iter() {
+            writeln!(f, "stale      {}", page.display())?;
+        }
+        if !self.summary.is_empty() {
+            writeln!(f, "\n{}:", DocBuilder::SUMMARY)?;
+            for line in self.summary.iter() {
+                writeln!(f, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy the previous output into the scratch directory, except for the built book.
+pub(crate) fn copy_output(out: &Path, scratch: &Path) -> io::Result<()> {
+    if !out.is_dir() {
+        return Ok(())
+    }
+    let book = out.join("book");
+    for entry in WalkDir::new(out).min_depth(1).into_iter().filter_entry(|e| e.path() != book) {
+        let entry = entry?;
+        let target = scratch.join(entry.path().strip_prefix(out).expect("entry within output"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the removed and the added lines, prefixed with `-` and `+`,
+/// in the order of the longest common subsequence of the lines.
+fn line_diff(previous: &str, planned: &str) -> Vec<String> {
+    let previous = previous.lines().collect::<Vec<_>>();
+    let planned = planned.lines().collect::<Vec<_>>();
+
+    // The common prefix and suffix are skipped, so the table only covers the changed region
+    let prefix = previous.iter().zip(planned.iter()).take_while(|(lhs, rhs)| lhs == rhs).count();
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(planned[prefix..].iter().rev())
+        .take_while(|(lhs, rhs)| lhs == rhs)
+        .count();
+    let previous = &previous[prefix..previous.len() - suffix];
+    let planned = &planned[prefix..planned.len() - suffix];
+
+    // The lengths of the common subsequences of the line suffixes
+    let mut lengths = vec![vec![0usize; planned.len() + 1]; previous.len() + 1];
+    for i in (0..previous.len()).rev() {
+        for j in (0..planned.len()).rev() {
+            lengths[i][j] = if previous[i] == planned[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = vec![];
+    while i < previous.len() || j < planned.len() {
+        if i < previous.len() && j < planned.len() && previous[i] == planned[j] {
+            i += 1;
+            j += 1;
+        } else if j == planned.len() ||
+            (i < previous.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            diff.push(format!("- {}", previous[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", planned[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::OutputFiles;
+
+    #[test]
+    fn planned_operations() {
+        let out = tempfile::tempdir().unwrap();
+        let scratch = tempfile::tempdir().unwrap();
+        let (out, scratch) = (out.path(), scratch.path());
+        fs::create_dir_all(out.join("src/src/Vault.sol")).unwrap();
+        fs::create_dir_all(out.join("book")).unwrap();
+        fs::write(out.join("src/README.md"), "# Vault").unwrap();
+        fs::write(out.join("src/SUMMARY.md"), "# Summary\n- [Home](README.md)\n- [Vault]()\n")
+            .unwrap();
+        fs::write(out.join("src/src/Vault.sol/contract.Vault.md"), "# Vault").unwrap();
+        fs::write(out.join("src/src/Vault.sol/contract.Old.md"), "# Old").unwrap();
+        fs::write(out.join("book/index.html"), "").unwrap();
+
+        copy_output(out, scratch).unwrap();
+        assert!(!scratch.join("book").exists());
+        let output = OutputFiles::default();
+        output.create_dir_all(scratch.join("src/src/Token.sol")).unwrap();
+        output.write(scratch.join("src/README.md"), "# Vault\n\nThe vault.").unwrap();
+        output
+            .write(
+                scratch.join("src/SUMMARY.md"),
+                "# Summary\n- [Home](README.md)\n- [Token]()\n- [Vault]()\n",
+            )
+            .unwrap();
+        output.write(scratch.join("src/src/Token.sol/contract.Token.md"), "# Token").unwrap();
+        output.write(scratch.join("src/src/Vault.sol/contract.Vault.md"), "# Vault").unwrap();
+        // The copied files which aren't written again aren't listed
+        fs::write(scratch.join("src/src/Vault.sol/contract.Removed.md"), "# Removed").unwrap();
+
+        let stale = vec![PathBuf::from("src/src/Vault.sol/contract.Old.md")];
+        let plan = Plan::new(out, scratch, &output.written(), stale.clone()).unwrap();
+        assert_eq!(plan.directories, vec![PathBuf::from("src/src/Token.sol")]);
+        assert_eq!(plan.stale, stale);
+        assert_eq!(
+            plan.files
+                .iter()
+                .map(|file| (file.path.display().to_string(), file.operation))
+                .collect::<Vec<_>>(),
+            vec![
+                ("src/README.md".to_owned(), FileOperation::Overwrite),
+                ("src/SUMMARY.md".to_owned(), FileOperation::Overwrite),
+                ("src/src/Token.sol/contract.Token.md".to_owned(), FileOperation::Create),
+                ("src/src/Vault.sol/contract.Vault.md".to_owned(), FileOperation::Unchanged),
+            ]
+        );
+        assert_eq!(plan.summary, vec!["+ - [Token]()"]);
+        assert!(plan.to_string().contains("overwrite  src/README.md (changed)\n"));
+    }
+}
@@ -0,0 +1,197 @@
+use super::{function_key, Preprocessor, PreprocessorId};
+use crate::{
+    anchors::PageAnchors,
+    document::DocumentContent,
+    helpers::{as_contract, inline_assembly},
+    DocBuilder, Document,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::path::{Path, PathBuf};
+
+/// [AssemblyIndex] preprocessor id.
+pub const ASSEMBLY_INDEX_ID: PreprocessorId = PreprocessorId("assembly_index");
+
+/// The function of the documented contract with inline assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssemblyFunction {
+    /// The contract name.
+    pub contract: String,
+    /// The function signature, or the kind of the unnamed function, e.g. `fallback`.
+    pub function: String,
+    /// The link to the function documentation.
+    pub link: String,
+    /// The number of the assembly blocks, including the ones in the nested blocks.
+    pub blocks: usize,
+    /// The number of the blocks declared `memory-safe`.
+    pub memory_safe: usize,
+}
+
+/// The assembly index preprocessor.
+/// It collects the functions of the documented contracts with inline assembly into the
+/// project-wide appendix, with the number of the blocks declared `memory-safe`, so the
+/// auditors can scope the review of the low-level code.
+///
+/// The blocks are declared `memory-safe` with the assembly flag or the
+/// `/// @solidity memory-safe-assembly` comment, like the marker of the function.
+///
+/// The functions link to the section they're written in, i.e. the initializers to the
+/// initialization section, and the functions over the section limit to the continuation page.
+///
+/// This preprocessor adds the appendix document.
+#[derive(Debug)]
+pub struct AssemblyIndex {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The maximum number of the members written per contract page section, `0` for no limit.
+    pub section_limit: usize,
+}
+
+impl Preprocessor for AssemblyIndex {
+    fn id(&self) -> PreprocessorId {
+        ASSEMBLY_INDEX_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        let mut functions = vec![];
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let link = |page: &Path| {
+                Path::new("/")
+                    .join(page.strip_prefix(&out_src).unwrap_or(page))
+                    .display()
+                    .to_string()
+            };
+            let anchors = PageAnchors::contract_pages(item, self.section_limit);
+            for (func, _, _) in item.functions().unwrap_or_default() {
+                let assembly = inline_assembly(func, &document.item_content);
+                if assembly.blocks == 0 {
+                    continue
+                }
+                let name = func.name.as_ref().map_or(func.ty.to_string(), |name| name.name.clone());
+                // The initializers are written in the initialization section first
+                let located = ["Initialization", "Functions"]
+                    .into_iter()
+                    .find_map(|section| anchors.locate(&document.target_path, section, &name));
+                functions.push(AssemblyFunction {
+                    contract: contract.name.safe_unwrap().name.clone(),
+                    function: function_key(func),
+                    link: match located {
+                        Some((page, anchor)) => format!("{}#{anchor}", link(&page)),
+                        None => link(&document.target_path),
+                    },
+                    blocks: assembly.blocks,
+                    memory_safe: assembly.memory_safe,
+                });
+            }
+        }
+
+        if !functions.is_empty() {
+            functions.sort_by(|a, b| a.contract.cmp(&b.contract));
+            let target_path = out_src.join("appendix/assembly-index.md");
+            documents.push(Document::new(self.root.clone(), target_path).with_content(
+                DocumentContent::AssemblyIndex(functions),
+                "Assembly Index".to_owned(),
+            ));
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    #[test]
+    fn assembly_functions() {
+        let src = r#"
+            contract Vault {
+                function deposit(uint256 amount) external {
+                    assembly ("memory-safe") { mstore(0, amount) }
+                    if (amount > 0) {
+                        assembly { sstore(0, amount) }
+                    }
+                }
+
+                function total() external view returns (uint256) { }
+            }
+
+            contract Lock {
+                fallback() external {
+                    /// @solidity memory-safe-assembly
+                    assembly { mstore(0, 1) }
+                }
+            }
+
+            contract Token { }
+        "#;
+        let documents =
+            AssemblyIndex { root: PathBuf::new(), out: PathBuf::from("docs"), section_limit: 0 }
+                .preprocess(test_documents("src/Vault.sol", src))
+                .unwrap();
+        assert_eq!(documents.len(), 4);
+        let index = documents.last().unwrap();
+        assert_eq!(index.target_path, PathBuf::from("docs/src/appendix/assembly-index.md"));
+        match index.content {
+            DocumentContent::AssemblyIndex(ref functions) => assert_eq!(
+                functions,
+                &vec![
+                    AssemblyFunction {
+                        contract: "Lock".to_owned(),
+                        function: "fallback".to_owned(),
+                        link: "/src/Vault.sol/contract.Lock.md#fallback".to_owned(),
+                        blocks: 1,
+                        memory_safe: 1,
+                    },
+                    AssemblyFunction {
+                        contract: "Vault".to_owned(),
+                        function: "deposit(uint256)".to_owned(),
+                        link: "/src/Vault.sol/contract.Vault.md#deposit".to_owned(),
+                        blocks: 2,
+                        memory_safe: 1,
+                    },
+                ]
+            ),
+            _ => panic!("expected the assembly index"),
+        }
+
+        let page = index.as_doc().unwrap();
+        assert!(page.contains(
+            "|[`deposit(uint256)`](/src/Vault.sol/contract.Vault.md#deposit)|Vault|2|1|\n"
+        ));
+    }
+
+    #[test]
+    fn assembly_function_sections() {
+        let src = r#"
+            contract Proxy {
+                function initialize(address owner) external initializer {
+                    assembly { sstore(0, owner) }
+                }
+
+                function owner() external view returns (address) { }
+
+                function upgrade(address implementation) external {
+                    assembly { sstore(1, implementation) }
+                }
+            }
+        "#;
+        let documents =
+            AssemblyIndex { root: PathBuf::new(), out: PathBuf::from("docs"), section_limit: 2 }
+                .preprocess(test_documents("src/Proxy.sol", src))
+                .unwrap();
+        match documents[1].content {
+            DocumentContent::AssemblyIndex(ref functions) => assert_eq!(
+                functions.iter().map(|function| function.link.as_str()).collect::<Vec<_>>(),
+                vec![
+                    "/src/Proxy.sol/contract.Proxy.md#initialize",
+                    "/src/Proxy.sol/contract.Proxy.functions.md#upgrade",
+                ]
+            ),
+            _ => panic!("expected the assembly index"),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    artifact::artifact_files, document::DocumentContent, helpers::as_contract, DocBuilder, Document,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// [BuildInfo] preprocessor id.
+pub const BUILD_INFO_ID: PreprocessorId = PreprocessorId("build_info");
+
+/// The hardforks in order, named as the EVM versions of the compiler settings.
+pub(crate) const HARDFORKS: &[&str] = &[
+    "homestead",
+    "tangerineWhistle",
+    "spuriousDragon",
+    "byzantium",
+    "constantinople",
+    "petersburg",
+    "istanbul",
+    "berlin",
+    "london",
+    "paris",
+    "shanghai",
+    "cancun",
+];
+
+/// The opcodes and the features detected in the sources, with the hardfork introducing them.
+const FEATURES: &[(&str, &str)] = &[
+    ("staticcall", "byzantium"),
+    ("returndatasize", "byzantium"),
+    ("returndatacopy", "byzantium"),
+    ("create2", "constantinople"),
+    ("extcodehash", "constantinople"),
+    ("codehash", "constantinople"),
+    ("chainid", "istanbul"),
+    ("selfbalance", "istanbul"),
+    ("basefee", "london"),
+    ("prevrandao", "paris"),
+    ("tload", "cancun"),
+    ("tstore", "cancun"),
+    ("transient", "cancun"),
+    ("mcopy", "cancun"),
+    ("blobhash", "cancun"),
+    ("blobbasefee", "cancun"),
+];
+
+/// The build info preprocessor.
+/// It appends the appendix document recording the [CompilerSettings]
+/// the documented project is built with, along with the [EvmRequirements]
+/// of the contracts using the opcodes or the features introduced by the recent hardforks.
+///
+/// The solc version auto-detected from the source pragmas is resolved from the metadata of
+/// the compiled artifacts of the documented sources, so the page records the versions
+/// the contracts were actually built with.
+#[derive(Debug)]
+pub struct BuildInfo {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The compiler settings.
+    pub settings: CompilerSettings,
+    /// The compiled artifacts path.
+    pub artifacts: PathBuf,
+}
+
+/// The compiler settings of the documented project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerSettings {
+    /// The solc version or the path to the local solc binary, or the versions the documented
+    /// sources were compiled with, e.g. `0.8.19, 0.8.20`.
+    /// [None] if the version is auto-detected and the sources aren't compiled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solc: Option<String>,
+    /// The EVM version.
+    pub evm_version: String,
+    /// Whether the optimizer is enabled.
+    pub optimizer: bool,
+    /// The number of optimizer runs.
+    pub optimizer_runs: usize,
+    /// Whether the code is compiled through the IR pipeline.
+    pub via_ir: bool,
+}
+
+/// The EVM features of the contract requiring a hardfork, detected on the best-effort basis
+/// from the words of the contract source, e.g. `block.prevrandao` or `tstore` in assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmRequirements {
+    /// The contract name.
+    pub contract: String,
+    /// The link to the contract documentation.
+    pub link: String,
+    /// The detected features with the hardfork introducing them, e.g. `("tstore", "cancun")`.
+    pub features: Vec<(String, String)>,
+}
+
+impl EvmRequirements {
+    /// Returns the latest hardfork required by the features.
+    pub fn hardfork(&self) -> Option<&str> {
+        self.features
+            .iter()
+            .map(|(_, hardfork)| hardfork.as_str())
+            .max_by_key(|hardfork| hardfork_index(hardfork))
+    }
+}
+
+/// Returns the position of the hardfork, i.e. the EVM version, in the order of activation.
+pub(crate) fn hardfork_index(hardfork: &str) -> Option<usize> {
+    HARDFORKS.iter().position(|known| known.eq_ignore_ascii_case(hardfork))
+}
+
+impl Preprocessor for BuildInfo {
+    fn id(&self) -> PreprocessorId {
+        BUILD_INFO_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        let requirements = documents
+            .iter()
+            .filter_map(as_contract)
+            .filter_map(|(document, _, contract)| {
+                let source = document.item_content.get(contract.loc.start()..contract.loc.end())?;
+                let page =
+                    document.target_path.strip_prefix(&out_src).unwrap_or(&document.target_path);
+                let requirements = EvmRequirements {
+                    contract: contract.name.safe_unwrap().name.clone(),
+                    link: Path::new("/").join(page).display().to_string(),
+                    features: evm_features(source),
+                };
+                (!requirements.features.is_empty()).then_some(requirements)
+            })
+            .collect();
+
+        let mut settings = self.settings.clone();
+        if settings.solc.is_none() {
+            let sources =
+                documents.iter().map(|document| document.item_path.clone()).collect::<HashSet<_>>();
+            let versions = compiled_versions(&self.artifacts, &self.root, &sources);
+            settings.solc = (!versions.is_empty()).then(|| versions.join(", "));
+        }
+
+        let target_path = out_src.join("appendix/build-info.md");
+        documents.push(Document::new(self.root.clone(), target_path).with_content(
+            DocumentContent::BuildInfo(settings, requirements),
+            "Build Info".to_owned(),
+        ));
+        Ok(documents)
+    }
+}
+
+/// Returns the solc versions the sources were compiled with, without the commit, e.g. `0.8.19`,
+/// read from the metadata of their artifacts.
+fn compiled_versions(artifacts: &Path, root: &Path, sources: &HashSet<PathBuf>) -> Vec<String> {
+    let mut versions = BTreeSet::new();
+    for path in artifact_files(artifacts) {
+        let artifact = match fs::read_to_string(&path)
+            .ok()
+            .and_then(|artifact| serde_json::from_str::<serde_json::Value>(&artifact).ok())
+        {
+            Some(artifact) => artifact,
+            None => continue,
+        };
+        // The foundry artifacts nest the solc metadata
+        let metadata = artifact.get("metadata").unwrap_or(&artifact);
+        let compiled =
+            metadata["settings"]["compilationTarget"].as_object().map_or(false, |targets| {
+                targets.keys().any(|source| sources.contains(&root.join(source)))
+            });
+        if let Some(version) = metadata["compiler"]["version"].as_str().filter(|_| compiled) {
+            versions.insert(version.split('+').next().unwrap_or(version).to_owned());
+        }
+    }
+    versions.into_iter().collect()
+}
+
+/// Returns the features of the source requiring a hardfork, in the order of the hardforks.
+/// The comments and the string literals are skipped, so only the code is matched.
+pub(crate) fn evm_features(source: &str) -> Vec<(String, String)> {
+    let mut code = String::with_capacity(source.len());
+    let mut rest = source;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+        } else if let Some(quote) = rest.chars().next().filter(|ch| matches!(ch, '"' | '\'')) {
+            // The escaped quotes don't end the literal, e.g. `"say \"tstore\""`
+            let mut escaped = false;
+            let end = rest[1..].find(|ch: char| {
+                let end = ch == quote && !escaped;
+                escaped = ch == '\\' && !escaped;
+                end
+            });
+            rest = end.map_or("", |end| &rest[end + 2..]);
+            code.push(' ');
+        } else {
+            let ch = rest.chars().next().expect("non-empty");
+            code.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+
+    let words = code.split(|ch: char| !(ch.is_alphanumeric() || ch == '_')).collect::<HashSet<_>>();
+    FEATURES
+        .iter()
+        .filter(|(feature, _)| words.contains(feature))
+        .map(|(feature, hardfork)| (feature.to_string(), hardfork.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardfork_features() {
+        let source = r#"
+            contract Lock {
+                // The basefee isn't used
+                /* nor the chainid */
+                string constant NOTE = "mcopy isn't used // nor \"blobhash\"";
+                function lock() external {
+                    uint256 seed = block.prevrandao;
+                    assembly { tstore(0, 1) }
+                }
+            }
+        "#;
+        assert_eq!(
+            evm_features(source),
+            vec![
+                ("prevrandao".to_owned(), "paris".to_owned()),
+                ("tstore".to_owned(), "cancun".to_owned()),
+            ]
+        );
+        assert!(evm_features("contract Vault { uint256 total; }").is_empty());
+
+        let requirements = EvmRequirements {
+            contract: "Lock".to_owned(),
+            link: String::new(),
+            features: evm_features(source),
+        };
+        assert_eq!(requirements.hardfork(), Some("cancun"));
+        assert!(hardfork_index("Shanghai") > hardfork_index("paris"));
+    }
+
+    #[test]
+    fn compiled_solc_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifacts = dir.path().join("out");
+        let artifact = |target: &str, version: &str| {
+            serde_json::json!({
+                "abi": [],
+                "metadata": {
+                    "compiler": { "version": version },
+                    "settings": { "compilationTarget": { target: "Vault" } }
+                }
+            })
+            .to_string()
+        };
+        fs::create_dir_all(artifacts.join("Vault.sol")).unwrap();
+        fs::write(
+            artifacts.join("Vault.sol/Vault.json"),
+            artifact("src/Vault.sol", "0.8.19+commit.7dd6d404"),
+        )
+        .unwrap();
+        fs::create_dir_all(artifacts.join("Mock.sol")).unwrap();
+        fs::write(
+            artifacts.join("Mock.sol/Mock.json"),
+            artifact("test/Mock.sol", "0.7.6+commit.7338295f"),
+        )
+        .unwrap();
+
+        let sources = HashSet::from([dir.path().join("src/Vault.sol")]);
+        assert_eq!(compiled_versions(&artifacts, dir.path(), &sources), vec!["0.8.19"]);
+        assert!(compiled_versions(&dir.path().join("missing"), dir.path(), &sources).is_empty());
+    }
+}
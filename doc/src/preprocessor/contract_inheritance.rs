@@ -1,7 +1,9 @@
 use forge_fmt::solang_ext::SafeUnwrap;
 
 use super::{Preprocessor, PreprocessorId};
-use crate::{document::DocumentContent, Document, ParseSource, PreprocessorOutput};
+use crate::{
+    anchors::PageAnchors, document::DocumentContent, Document, ParseSource, PreprocessorOutput,
+};
 use std::{collections::HashMap, path::PathBuf};
 
 /// [ContractInheritance] preprocessor id.
@@ -14,8 +16,21 @@ pub const CONTRACT_INHERITANCE_ID: PreprocessorId = PreprocessorId("contract_inh
 ///
 /// This preprocessor writes to [Document]'s context.
 #[derive(Default, Debug)]
-#[non_exhaustive]
-pub struct ContractInheritance;
+pub struct ContractInheritance {
+    /// The maximum number of the members of each section of the contract page, `0` for no limit,
+    /// so the base constructors are linked on the page they're written on.
+    pub section_limit: usize,
+}
+
+/// The linked base contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseLink {
+    /// The target path of the base contract page.
+    pub path: PathBuf,
+    /// The target path of the page the base constructor is written on and its anchor,
+    /// if the base declares the constructor.
+    pub constructor: Option<(PathBuf, String)>,
+}
 
 impl Preprocessor for ContractInheritance {
     fn id(&self) -> PreprocessorId {
@@ -50,12 +65,20 @@ impl Preprocessor for ContractInheritance {
 }
 
 impl ContractInheritance {
-    fn try_link_base(&self, base: &str, documents: &Vec<Document>) -> Option<PathBuf> {
+    fn try_link_base(&self, base: &str, documents: &Vec<Document>) -> Option<BaseLink> {
         for candidate in documents {
             if let DocumentContent::Single(ref item) = candidate.content {
                 if let ParseSource::Contract(ref contract) = item.source {
                     if base == contract.name.safe_unwrap().name {
-                        return Some(candidate.target_path.clone())
+                        // The constructors with the `initializer` modifier are written in
+                        // the initialization section first
+                        let anchors = PageAnchors::contract_pages(item, self.section_limit);
+                        let page = &candidate.target_path;
+                        let constructor = ["Initialization", "Functions"]
+                            .into_iter()
+                            .find_map(|section| anchors.locate(page, section, "constructor"))
+                            .map(|(page, anchor)| (page, anchor.to_owned()));
+                        return Some(BaseLink { path: page.clone(), constructor })
                     }
                 }
             }
@@ -63,3 +86,46 @@ impl ContractInheritance {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        document::read_context,
+        preprocessor::{continuation_path, test_documents},
+    };
+
+    #[test]
+    fn base_constructor_pages() {
+        let src = r#"
+            contract Token {
+                function transfer() external { }
+                constructor() { }
+            }
+
+            contract Ownable {
+                function owner() external { }
+            }
+
+            contract Vault is Token, Ownable { }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+
+        // The constructor of the base is moved to the continuation page of the functions
+        let documents = ContractInheritance { section_limit: 1 }.preprocess(documents).unwrap();
+        let links =
+            read_context!(documents[2], CONTRACT_INHERITANCE_ID, ContractInheritance).unwrap();
+        let token = PathBuf::from("docs/src/src/Vault.sol/contract.Token.md");
+        assert_eq!(
+            links["Token"],
+            BaseLink {
+                path: token.clone(),
+                constructor: Some((
+                    continuation_path(&token, "Functions"),
+                    "constructor".to_owned()
+                )),
+            }
+        );
+        assert_eq!(links["Ownable"].constructor, None);
+    }
+}
@@ -0,0 +1,277 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::as_contract, Diagnostics, DocBuilder, DocDiagnostic, Document, PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use itertools::Itertools;
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+/// [ContractIntro] preprocessor id.
+pub const CONTRACT_INTRO_ID: PreprocessorId = PreprocessorId("contract_intro");
+
+/// The contract intro preprocessor.
+/// It reads the hand-written introduction of the contract, written at the top of the contract
+/// page below the heading, above the generated reference.
+///
+/// The introduction is the `<Contract>.md` of the intro directory if set, otherwise the
+/// `<Source>.sol.md` next to the source file introduces the contract named after the file,
+/// e.g. `src/Vault.sol.md` the `Vault`.
+///
+/// The `{{name}}` placeholders are substituted with the template variables, e.g. `{{project}}`,
+/// and `{{contract}}` with the contract name. The relative links are rewritten to be relative
+/// to the generated page in the book. The links to the files outside the book, e.g. the
+/// images of the project, are reported, since the served book doesn't include them, and are
+/// rewritten through the project layout if the output directory is in the project, otherwise
+/// left as written.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct ContractIntro {
+    /// The project root.
+    pub root: PathBuf,
+    /// The directory of the introductions, relative to the project root.
+    pub dir: Option<PathBuf>,
+    /// The template variables by the name.
+    pub variables: Vec<(String, String)>,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The collector of the links leaving the book.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+impl Preprocessor for ContractIntro {
+    fn id(&self) -> PreprocessorId {
+        CONTRACT_INTRO_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        // The book in the project layout, unless the output directory is outside the project
+        let book = if out_src.is_relative() {
+            Some(normalized(&out_src))
+        } else {
+            out_src.strip_prefix(&self.root).ok().map(normalized)
+        };
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            let path = match self.dir {
+                Some(ref dir) => self.root.join(dir).join(format!("{name}.md")),
+                None if document
+                    .item_path
+                    .file_stem()
+                    .map_or(false, |stem| stem == name.as_str()) =>
+                {
+                    let mut path = document.item_path.clone().into_os_string();
+                    path.push(".md");
+                    PathBuf::from(path)
+                }
+                None => continue,
+            };
+            let intro = match fs::read_to_string(&path) {
+                Ok(intro) => intro,
+                Err(_) => continue,
+            };
+
+            let contract = ("contract".to_owned(), name.clone());
+            let intro =
+                self.variables.iter().chain([&contract]).fold(intro, |intro, (variable, value)| {
+                    intro.replace(&format!("{{{{{variable}}}}}"), value)
+                });
+            let intro_dir = path.parent().unwrap_or(&self.root);
+            let intro_dir = intro_dir.strip_prefix(&self.root).unwrap_or(intro_dir);
+            let page_dir = document.target_path.parent().unwrap_or(Path::new(""));
+            let page_dir = page_dir.strip_prefix(&out_src).unwrap_or(page_dir);
+            let intro = rewrite_links(&intro, |link| {
+                let target = normalized(&intro_dir.join(link));
+                if let Some(target) = book.as_ref().and_then(|book| target.strip_prefix(book).ok())
+                {
+                    return relative_link(page_dir, target)
+                }
+                self.diagnostics.push(
+                    DocDiagnostic::warning(
+                        "intro-link-outside-book",
+                        format!(
+                            "the link `{link}` of the `{name}` introduction leaves the book, \
+                            so it's broken in the served book"
+                        ),
+                    )
+                    .with_location(path.clone(), None),
+                );
+                match book {
+                    Some(ref book) => relative_link(&book.join(page_dir), &target),
+                    None => link.to_owned(),
+                }
+            });
+            if !intro.trim().is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::ContractIntro(intro));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the content with the relative destinations of the inline links and images rewritten,
+/// skipping the absolute ones, the urls and the anchors.
+fn rewrite_links(content: &str, rewrite: impl Fn(&str) -> String) -> String {
+    let mut rewritten = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("](") {
+        let (head, tail) = rest.split_at(start + 2);
+        rewritten.push_str(head);
+        let end = tail.find(|ch: char| ch == ')' || ch.is_whitespace()).unwrap_or(tail.len());
+        let link = &tail[..end];
+        let relative = !link.is_empty() &&
+            !link.starts_with(&['/', '#'][..]) &&
+            !link.contains("://") &&
+            !link.starts_with("mailto:");
+        if relative {
+            rewritten.push_str(&rewrite(link));
+        } else {
+            rewritten.push_str(link);
+        }
+        rest = &tail[end..];
+    }
+    rewritten.push_str(rest);
+    rewritten
+}
+
+/// Returns the relative path with the `.` and `..` components resolved, e.g. `assets/flow.png`
+/// for `src/../assets/flow.png`.
+fn normalized(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(name) => normalized.push(name),
+            _ => {}
+        }
+    }
+    normalized
+}
+
+/// Returns the path of the target relative to the directory, both relative to the same root,
+/// e.g. `../../../../assets/flow.png`, keeping the anchor of the target.
+fn relative_link(from_dir: &Path, to: &Path) -> String {
+    let target = normalized(to)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let from_dir = from_dir
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let common = from_dir.iter().zip(target.iter()).take_while(|(lhs, rhs)| lhs == rhs).count();
+    std::iter::repeat("..".to_owned())
+        .take(from_dir.len() - common)
+        .chain(target[common..].iter().cloned())
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    fn documents(out: &Path, root: &Path) -> Vec<Document> {
+        let src = r#"
+            /// @notice Holds the deposits.
+            contract Vault { }
+
+            contract Escrow { }
+        "#;
+        test_documents("src/Vault.sol", src)
+            .into_iter()
+            .map(|mut document| {
+                document.item_path = root.join(&document.item_path);
+                document.target_path = out.join(document.target_path.strip_prefix("docs").unwrap());
+                document
+            })
+            .collect()
+    }
+
+    fn intro(documents: &[Document]) -> &str {
+        match documents[0].get_from_context(CONTRACT_INTRO_ID) {
+            Some(PreprocessorOutput::ContractIntro(intro)) => intro,
+            _ => panic!("expected the intro"),
+        }
+    }
+
+    #[test]
+    fn sibling_intro() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/Vault.sol.md"),
+            "The {{contract}} of {{project}} holds the deposits, see the \
+            [flow](../assets/flow.png), the [token](./Token.sol#L4), the \
+            [guide](../docs/src/guide.md) and the [site](https://x.org).",
+        )
+        .unwrap();
+
+        let diagnostics = Arc::new(Diagnostics::default());
+        let preprocessor = ContractIntro {
+            root: root.to_path_buf(),
+            dir: None,
+            variables: vec![("project".to_owned(), "Vault Core".to_owned())],
+            out: PathBuf::from("docs"),
+            diagnostics: diagnostics.clone(),
+        };
+        let documents = preprocessor.preprocess(documents(Path::new("docs"), root)).unwrap();
+        let intro = intro(&documents);
+        assert_eq!(
+            intro,
+            "The Vault of Vault Core holds the deposits, see the \
+            [flow](../../../../assets/flow.png), the [token](../../../../src/Token.sol#L4), \
+            the [guide](../../guide.md) and the [site](https://x.org)."
+        );
+        assert!(documents[1].get_from_context(CONTRACT_INTRO_ID).is_none());
+
+        // The links to the project files are reported, the link in the book isn't
+        let reported = diagnostics.take();
+        assert_eq!(reported.len(), 2);
+        assert!(reported.iter().all(|diagnostic| diagnostic.code == "intro-link-outside-book"));
+        assert_eq!(
+            reported[0].message,
+            "the link `../assets/flow.png` of the `Vault` introduction leaves the book, \
+            so it's broken in the served book"
+        );
+
+        // The intro is written above the generated reference
+        let page = documents[0].as_doc().unwrap();
+        assert!(page.contains(&format!("{intro}\n\nHolds the deposits.")));
+    }
+
+    #[test]
+    fn intro_outside_project() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let out = tempfile::tempdir().unwrap();
+        let out = out.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/Vault.sol.md"), "See the [flow](../assets/flow.png).").unwrap();
+
+        // The output directory isn't in the project, e.g. with `--dry-run`
+        let diagnostics = Arc::new(Diagnostics::default());
+        let preprocessor = ContractIntro {
+            root: root.to_path_buf(),
+            dir: None,
+            variables: vec![],
+            out: out.to_path_buf(),
+            diagnostics: diagnostics.clone(),
+        };
+        let documents = preprocessor.preprocess(documents(out, root)).unwrap();
+        assert_eq!(intro(&documents), "See the [flow](../assets/flow.png).");
+        assert_eq!(diagnostics.warnings(), 1);
+    }
+}
@@ -0,0 +1,319 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    anchors::PageAnchors,
+    document::DocumentContent,
+    helpers::{as_contract, is_externally_visible, user_type_name},
+    resolver::ImportResolver,
+    DocBuilder, Document, ParseSource,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// [Dependencies] preprocessor id.
+pub const DEPENDENCIES_ID: PreprocessorId = PreprocessorId("dependencies");
+
+/// The dependencies preprocessor.
+/// It collects the types declared in the dependencies, e.g. `IERC20` of the OpenZeppelin
+/// contracts, that are taken or returned by the external and public functions of the documented
+/// contracts, and appends the appendix listing them by the dependency package
+/// with the functions using them.
+///
+/// The types are resolved through the imports of the source files, following the remappings.
+/// The package version is read from its `package.json`, falling back to the git revision
+/// of the submodule.
+#[derive(Debug)]
+pub struct Dependencies {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The dependency directories, e.g. `lib` and `node_modules`.
+    pub libs: Vec<PathBuf>,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+    /// The maximum number of the members of each section of the contract page, see
+    /// [SectionLimits](crate::SectionLimits). `0` for no limit.
+    pub section_limit: usize,
+}
+
+/// The dependency package with its types used by the documented contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyPackage {
+    /// The package name, e.g. `@openzeppelin/contracts`.
+    pub name: String,
+    /// The package version or the git revision.
+    pub version: Option<String>,
+    /// The package path relative to the project root.
+    pub path: PathBuf,
+    /// The used types ordered by the name.
+    pub types: Vec<DependencyType>,
+}
+
+/// The dependency type used by the documented contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyType {
+    /// The type name as declared in the dependency.
+    pub name: String,
+    /// The file declaring the type relative to the project root.
+    pub source: PathBuf,
+    /// The functions taking or returning the type.
+    pub usages: Vec<TypeUsage>,
+}
+
+/// The function taking or returning the dependency type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeUsage {
+    /// The function name qualified by the contract, e.g. `Vault.deposit`.
+    pub function: String,
+    /// The link to the function documentation.
+    pub link: String,
+}
+
+impl Preprocessor for Dependencies {
+    fn id(&self) -> PreprocessorId {
+        DEPENDENCIES_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        // The types declared in the project are not dependencies
+        let mut documented = HashSet::new();
+        for document in documents.iter() {
+            if let DocumentContent::Single(ref item) = document.content {
+                documented.insert(item.source.ident());
+                for child in item.children.iter() {
+                    if matches!(
+                        child.source,
+                        ParseSource::Struct(_) | ParseSource::Enum(_) | ParseSource::Type(_)
+                    ) {
+                        documented.insert(child.source.ident());
+                    }
+                }
+            }
+        }
+
+        let out_src = self.out.join(DocBuilder::SRC);
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let mut types: BTreeMap<(PathBuf, String), DependencyType> = BTreeMap::new();
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let contract = &contract.name.safe_unwrap().name;
+            let link = |page: &Path| {
+                Path::new("/")
+                    .join(page.strip_prefix(&out_src).unwrap_or(page))
+                    .display()
+                    .to_string()
+            };
+
+            let anchors = PageAnchors::contract_pages(item, self.section_limit);
+            for (func, _, _) in item.functions().unwrap_or_default() {
+                if !is_externally_visible(func) {
+                    continue
+                }
+                let name = func.name.as_ref().map_or(func.ty.to_string(), |name| name.name.clone());
+                let usage = TypeUsage {
+                    function: format!("{contract}.{name}"),
+                    // The functions over the section limit are on the continuation page
+                    link: match anchors.locate(&document.target_path, "Functions", &name) {
+                        Some((page, anchor)) => format!("{}#{anchor}", link(&page)),
+                        None => link(&document.target_path),
+                    },
+                };
+                let used = func
+                    .params
+                    .iter()
+                    .chain(func.returns.iter())
+                    .filter_map(|(_, param)| param.as_ref())
+                    .filter_map(|param| user_type_name(&param.ty));
+                for ty in used {
+                    let head = ty.split('.').next().unwrap_or_default();
+                    if documented.contains(head) {
+                        continue
+                    }
+                    // The types are listed by the declared name, without the member
+                    let resolved =
+                        resolver.resolve(&document.item_path, &ty).map(|(name, source)| {
+                            (name.split('.').next().unwrap_or_default().to_owned(), source)
+                        });
+                    if let Some((name, source)) = resolved {
+                        if self.package_dir(&source).is_none() {
+                            continue
+                        }
+                        let used = types
+                            .entry((source.clone(), name.clone()))
+                            .or_insert_with(|| DependencyType { name, source, usages: vec![] });
+                        if !used.usages.contains(&usage) {
+                            used.usages.push(usage.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut packages: BTreeMap<PathBuf, DependencyPackage> = BTreeMap::new();
+        for (_, mut ty) in types {
+            let dir = match self.package_dir(&ty.source) {
+                Some(dir) => dir,
+                None => continue,
+            };
+            ty.source = ty.source.strip_prefix(&self.root).unwrap_or(&ty.source).to_path_buf();
+            packages.entry(dir.clone()).or_insert_with(|| self.package(&dir)).types.push(ty);
+        }
+
+        if !packages.is_empty() {
+            let mut packages = packages.into_values().collect::<Vec<_>>();
+            packages.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+            for package in packages.iter_mut() {
+                package.types.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+            }
+            let target_path = out_src.join("appendix/dependencies.md");
+            documents.push(
+                Document::new(self.root.clone(), target_path).with_content(
+                    DocumentContent::Dependencies(packages),
+                    "Dependencies".to_owned(),
+                ),
+            );
+        }
+
+        Ok(documents)
+    }
+}
+
+impl Dependencies {
+    /// Returns the directory of the dependency package containing the file,
+    /// i.e. the closest one with the `package.json` or the top directory within the libs.
+    /// Returns [None] for the files outside of the dependency directories.
+    fn package_dir(&self, file: &Path) -> Option<PathBuf> {
+        let lib = self
+            .libs
+            .iter()
+            .map(|lib| if lib.is_relative() { self.root.join(lib) } else { lib.clone() })
+            .find(|lib| file.starts_with(lib))?;
+        let manifest = file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| *dir != lib)
+            .find(|dir| dir.join("package.json").is_file());
+        if let Some(dir) = manifest {
+            return Some(dir.to_path_buf())
+        }
+        // The scoped packages are nested, e.g. `node_modules/@openzeppelin/contracts`
+        let mut components = file.strip_prefix(&lib).ok()?.components();
+        let mut dir = lib.join(components.next()?);
+        if dir.file_name()?.to_string_lossy().starts_with('@') {
+            dir.push(components.next()?);
+        }
+        Some(dir)
+    }
+
+    /// Returns the package name and version from its `package.json`,
+    /// falling back to the directory name and the git revision.
+    fn package(&self, dir: &Path) -> DependencyPackage {
+        let manifest = fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok());
+        let field = |key: &str| {
+            manifest.as_ref().and_then(|manifest| manifest[key].as_str()).map(str::to_owned)
+        };
+        let name = field("name").unwrap_or_else(|| {
+            dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+        });
+        let version = field("version").or_else(|| {
+            Command::new("git")
+                .args(["describe", "--tags", "--always"])
+                .current_dir(dir)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|revision| revision.trim().to_owned())
+                .filter(|revision| !revision.is_empty())
+        });
+        DependencyPackage {
+            name,
+            version,
+            path: dir.strip_prefix(&self.root).unwrap_or(dir).to_path_buf(),
+            types: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    #[test]
+    fn dependency_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let package = root.join("lib/oz");
+        fs::create_dir_all(package.join("token")).unwrap();
+        fs::write(package.join("package.json"), r#"{"name":"@oz/contracts","version":"4.9.0"}"#)
+            .unwrap();
+        fs::write(package.join("token/IERC20.sol"), "interface IERC20 { }").unwrap();
+        fs::write(package.join("token/ERC20.sol"), "import \"./IERC20.sol\"; contract ERC20 { }")
+            .unwrap();
+
+        let src = r#"
+            import {IERC20 as Token} from "@oz/token/IERC20.sol";
+            import "@oz/token/ERC20.sol";
+
+            contract Vault {
+                struct Position { uint256 amount; }
+
+                function deposit(Token token, uint256 amount) external { }
+                function balanceOf(ERC20 token) public view returns (uint256) { }
+                function open(Position memory position) external returns (IERC20) { }
+                function _sweep(ERC20 token) internal { }
+            }
+        "#;
+        let mut documents = test_documents("src/Vault.sol", src);
+        for document in documents.iter_mut() {
+            document.item_path = root.join(&document.item_path);
+            document.target_path = root.join(&document.target_path);
+        }
+
+        let documents = Dependencies {
+            root: root.to_path_buf(),
+            out: root.join("docs"),
+            libs: vec![PathBuf::from("lib")],
+            remappings: vec!["@oz/=lib/oz/".parse().unwrap()],
+            section_limit: 0,
+        }
+        .preprocess(documents)
+        .unwrap();
+
+        let usage = |function: &str| TypeUsage {
+            function: format!("Vault.{function}"),
+            link: format!("/src/Vault.sol/contract.Vault.md#{}", function.to_lowercase()),
+        };
+        let expected = vec![DependencyPackage {
+            name: "@oz/contracts".to_owned(),
+            version: Some("4.9.0".to_owned()),
+            path: PathBuf::from("lib/oz"),
+            types: vec![
+                DependencyType {
+                    name: "ERC20".to_owned(),
+                    source: PathBuf::from("lib/oz/token/ERC20.sol"),
+                    usages: vec![usage("balanceOf")],
+                },
+                DependencyType {
+                    name: "IERC20".to_owned(),
+                    source: PathBuf::from("lib/oz/token/IERC20.sol"),
+                    usages: vec![usage("deposit"), usage("open")],
+                },
+            ],
+        }];
+        assert_eq!(documents.len(), 2);
+        assert!(matches!(
+            documents[1].content,
+            DocumentContent::Dependencies(ref packages) if packages == &expected
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{helpers::as_contract, Document, PreprocessorOutput};
+use forge_fmt::solang_ext::SafeUnwrap;
+use foundry_config::Chain;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::PathBuf};
+use walkdir::WalkDir;
+
+/// [Deployments] preprocessor id.
+pub const DEPLOYMENTS_ID: PreprocessorId = PreprocessorId("deployments");
+
+/// The name of the broadcast artifact with the latest run of the script.
+const RUN_LATEST: &str = "run-latest.json";
+
+/// The deployments preprocessor.
+/// It collects the addresses of the contracts deployed by the broadcasted scripts,
+/// i.e. `broadcast/<script>/<chain id>/run-latest.json`, matching them by the contract name.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct Deployments {
+    /// The broadcast artifacts directory.
+    pub broadcast: PathBuf,
+    /// The chains to include the deployments on.
+    /// If empty, the deployments on all chains are included.
+    pub chains: Vec<Chain>,
+}
+
+/// The deployed address of the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    /// The chain the contract is deployed on.
+    pub chain: Chain,
+    /// The deployed address.
+    pub address: String,
+}
+
+/// The broadcast artifact of the script run.
+#[derive(Debug, Deserialize)]
+struct BroadcastRun {
+    #[serde(default)]
+    transactions: Vec<BroadcastTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastTransaction {
+    #[serde(default)]
+    transaction_type: String,
+    #[serde(default)]
+    contract_name: Option<String>,
+    #[serde(default)]
+    contract_address: Option<String>,
+}
+
+impl Preprocessor for Deployments {
+    fn id(&self) -> PreprocessorId {
+        DEPLOYMENTS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let deployments = self.collect()?;
+        if deployments.is_empty() {
+            return Ok(documents)
+        }
+
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            if let Some(deployed) = deployments.get(&contract.name.safe_unwrap().name) {
+                document.add_context(self.id(), PreprocessorOutput::Deployments(deployed.clone()));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+impl Deployments {
+    /// Collect the deployments from the broadcast artifacts by the contract name.
+    /// The deployments are sorted by the chain id.
+    fn collect(&self) -> Result<HashMap<String, Vec<Deployment>>, eyre::Error> {
+        let mut deployments: HashMap<String, Vec<Deployment>> = HashMap::new();
+        if !self.broadcast.is_dir() {
+            return Ok(deployments)
+        }
+
+        let runs = WalkDir::new(&self.broadcast)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry.file_name() == RUN_LATEST &&
+                    !entry
+                        .path()
+                        .components()
+                        .any(|component| component.as_os_str() == "dry-run")
+            });
+        for entry in runs {
+            // The artifacts are written to the directory named by the chain id.
+            let chain = match entry
+                .path()
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .and_then(|dir| dir.to_str())
+                .and_then(|dir| dir.parse::<u64>().ok())
+            {
+                Some(chain) => Chain::Id(chain),
+                None => continue,
+            };
+            if !self.chains.is_empty() && !self.chains.iter().any(|known| known.id() == chain.id())
+            {
+                continue
+            }
+
+            let run: BroadcastRun = serde_json::from_str(&fs::read_to_string(entry.path())?)
+                .map_err(|err| eyre::eyre!("failed to parse {}: {err}", entry.path().display()))?;
+            for tx in run.transactions {
+                if !tx.transaction_type.starts_with("CREATE") {
+                    continue
+                }
+                let (name, address) = match (tx.contract_name, tx.contract_address) {
+                    (Some(name), Some(address)) => (name, address),
+                    _ => continue,
+                };
+                let deployed = deployments.entry(name).or_default();
+                let deployment = Deployment { chain, address };
+                if !deployed.contains(&deployment) {
+                    deployed.push(deployment);
+                }
+            }
+        }
+
+        for deployed in deployments.values_mut() {
+            deployed.sort_by_key(|deployment| deployment.chain.id());
+        }
+        Ok(deployments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn broadcast_deployments() {
+        let dir = tempfile::tempdir().unwrap();
+        let run = |chain: &str, address: &str| {
+            format!(
+                r#"{{"transactions":[
+                    {{"transactionType":"CREATE","contractName":"Vault","contractAddress":"{address}"}},
+                    {{"transactionType":"CALL","contractName":"Vault","contractAddress":"0x02"}}
+                ],"chain":{chain}}}"#
+            )
+        };
+        for (chain, address) in [("10", "0x10"), ("1", "0x01")] {
+            let chain_dir = dir.path().join("Deploy.s.sol").join(chain);
+            fs::create_dir_all(chain_dir.join("dry-run")).unwrap();
+            fs::write(chain_dir.join(RUN_LATEST), run(chain, address)).unwrap();
+            fs::write(chain_dir.join("dry-run").join(RUN_LATEST), run(chain, "0xdead")).unwrap();
+        }
+
+        let vault = || test_documents("src/Vault.sol", "contract Vault { }");
+
+        let documents = Deployments { broadcast: dir.path().to_path_buf(), chains: vec![] }
+            .preprocess(vault())
+            .unwrap();
+        assert_eq!(
+            read_context!(documents[0], DEPLOYMENTS_ID, Deployments).unwrap(),
+            vec![
+                Deployment { chain: Chain::Id(1), address: "0x01".to_owned() },
+                Deployment { chain: Chain::Id(10), address: "0x10".to_owned() },
+            ]
+        );
+
+        let documents =
+            Deployments { broadcast: dir.path().to_path_buf(), chains: vec![Chain::Id(10)] }
+                .preprocess(vault())
+                .unwrap();
+        assert_eq!(
+            read_context!(documents[0], DEPLOYMENTS_ID, Deployments).unwrap(),
+            vec![Deployment { chain: Chain::Id(10), address: "0x10".to_owned() }]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{Document, PreprocessorOutput};
+use std::path::PathBuf;
+
+/// [EditLink] preprocessor id.
+pub const EDIT_LINK_ID: PreprocessorId = PreprocessorId("edit_link");
+
+/// The edit link preprocessor.
+/// It links each page to the editor of its source on the repository, rendered from the template
+/// with the `{repository}`, `{branch}`, `{path}` and `{page}` placeholders, e.g.
+/// `{repository}/edit/{branch}/{path}` for the source file, or
+/// `{repository}/edit/{branch}/{page}` for the committed generated page.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct EditLink {
+    /// The project root.
+    pub root: PathBuf,
+    /// The repository url.
+    pub repository: String,
+    /// The branch the pages are edited on.
+    pub branch: String,
+    /// The template of the edit link.
+    pub template: String,
+}
+
+impl Preprocessor for EditLink {
+    fn id(&self) -> PreprocessorId {
+        EDIT_LINK_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let repository = self.repository.trim_end_matches('/');
+        for document in documents.iter().filter(|document| !document.content.is_appendix()) {
+            let path = document.item_path.strip_prefix(&self.root).unwrap_or(&document.item_path);
+            let link = self
+                .template
+                .replace("{repository}", repository)
+                .replace("{branch}", &self.branch)
+                .replace("{path}", &path.display().to_string())
+                .replace("{page}", &document.target_path.display().to_string());
+            document.add_context(self.id(), PreprocessorOutput::EditLink(link));
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DocumentContent;
+
+    #[test]
+    fn templated_links() {
+        let document = || {
+            Document::new(
+                PathBuf::from("/root/src/Vault.sol"),
+                PathBuf::from("docs/src/src/Vault.sol/contract.Vault.md"),
+            )
+            .with_content(DocumentContent::Empty, "Vault".to_owned())
+        };
+        let edit_link = |template: &str| {
+            let preprocessor = EditLink {
+                root: PathBuf::from("/root"),
+                repository: "https://github.com/org/vault/".to_owned(),
+                branch: "main".to_owned(),
+                template: template.to_owned(),
+            };
+            let documents = preprocessor.preprocess(vec![document()]).unwrap();
+            match documents[0].get_from_context(EDIT_LINK_ID) {
+                Some(PreprocessorOutput::EditLink(link)) => link,
+                _ => panic!("expected the edit link"),
+            }
+        };
+
+        assert_eq!(
+            edit_link("{repository}/edit/{branch}/{path}"),
+            "https://github.com/org/vault/edit/main/src/Vault.sol"
+        );
+        assert_eq!(
+            edit_link("{repository}/edit/{branch}/{page}"),
+            "https://github.com/org/vault/edit/main/docs/src/src/Vault.sol/contract.Vault.md"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use super::{function_key, Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{as_contract, function_signature},
+    Document, ParseItem, PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{ContractDefinition, FunctionAttribute, FunctionDefinition};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// [EffectiveVisibility] preprocessor id.
+pub const EFFECTIVE_VISIBILITY_ID: PreprocessorId = PreprocessorId("effective_visibility");
+
+/// The effective visibility preprocessor.
+/// It matches the overriding functions of the contracts with the overridden declarations of
+/// the documented bases, and notes the functions whose visibility differs from the base,
+/// e.g. the `public` override of the `external` interface function, which is also callable
+/// internally, so the derived page shows the visibility the contract actually exposes.
+///
+/// The bases are searched in the inheritance order, the most derived first.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct EffectiveVisibility;
+
+/// The visibility of the overriding function differing from the overridden declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityChange {
+    /// The name of the base declaring the overridden function.
+    pub base: String,
+    /// The visibility of the overridden declaration, e.g. `external`.
+    pub declared: String,
+    /// The visibility of the overriding function, e.g. `public`.
+    pub effective: String,
+}
+
+impl Preprocessor for EffectiveVisibility {
+    fn id(&self) -> PreprocessorId {
+        EFFECTIVE_VISIBILITY_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let contracts = documents
+            .iter()
+            .filter_map(as_contract)
+            .map(|(_, item, contract)| (contract.name.safe_unwrap().name.clone(), item))
+            .collect::<HashMap<_, _>>();
+
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let changes = item
+                .functions()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(func, _, _)| {
+                    func.attributes
+                        .iter()
+                        .any(|attr| matches!(attr, FunctionAttribute::Override(..)))
+                })
+                .filter_map(|(func, _, _)| {
+                    let effective = function_visibility(func)?;
+                    let (base, overridden) = overridden_function(contract, func, &contracts)?;
+                    let declared = function_visibility(overridden)?;
+                    (declared != effective).then(|| {
+                        (function_key(func), VisibilityChange { base, declared, effective })
+                    })
+                })
+                .collect::<HashMap<_, _>>();
+            if !changes.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::EffectiveVisibility(changes));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the declared visibility of the function, if any.
+fn function_visibility(func: &FunctionDefinition) -> Option<String> {
+    func.attributes.iter().find_map(|attr| match attr {
+        FunctionAttribute::Visibility(visibility) => Some(visibility.to_string()),
+        _ => None,
+    })
+}
+
+/// Returns the closest documented base declaring the function with the same signature,
+/// with the declaration.
+fn overridden_function<'a>(
+    contract: &ContractDefinition,
+    func: &FunctionDefinition,
+    contracts: &HashMap<String, &'a ParseItem>,
+) -> Option<(String, &'a FunctionDefinition)> {
+    let signature = function_signature(func)?;
+    let base_names = |contract: &ContractDefinition| {
+        contract
+            .base
+            .iter()
+            .rev()
+            .filter_map(|base| base.name.identifiers.last().map(|ident| ident.name.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from(base_names(contract));
+    while let Some(name) = queue.pop_front() {
+        if !visited.insert(name.clone()) {
+            continue
+        }
+        let item: &'a ParseItem = match contracts.get(&name) {
+            Some(item) => *item,
+            None => continue,
+        };
+        let overridden =
+            item.functions().unwrap_or_default().into_iter().find(|(base_func, _, _)| {
+                function_signature(base_func).as_ref() == Some(&signature)
+            });
+        if let Some((overridden, _, _)) = overridden {
+            return Some((name, overridden))
+        }
+        if let Some(base) = item.as_contract() {
+            queue.extend(base_names(base));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    #[test]
+    fn widened_overrides() {
+        let src = r#"
+            interface IVault {
+                function deposit(uint256 amount) external;
+                function total() external view returns (uint256);
+            }
+
+            abstract contract BaseVault is IVault {
+                function deposit(uint256 amount) public virtual override { }
+            }
+
+            contract Vault is BaseVault {
+                function deposit(uint256 amount) public override { }
+                function total() public view override returns (uint256) { }
+            }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+        let documents = EffectiveVisibility::default().preprocess(documents).unwrap();
+        let changes = |identity: &str| {
+            let document = documents.iter().find(|document| document.identity == identity)?;
+            match document.get_from_context(EFFECTIVE_VISIBILITY_ID) {
+                Some(PreprocessorOutput::EffectiveVisibility(changes)) => Some(changes),
+                _ => None,
+            }
+        };
+
+        // The closest base declares `deposit` public already
+        let vault = changes("Vault").unwrap();
+        assert_eq!(vault.len(), 1);
+        assert_eq!(
+            vault["total()"],
+            VisibilityChange {
+                base: "IVault".to_owned(),
+                declared: "external".to_owned(),
+                effective: "public".to_owned(),
+            }
+        );
+        assert_eq!(changes("BaseVault").unwrap()["deposit(uint256)"].base, "IVault");
+        assert!(changes("IVault").is_none());
+
+        let page = documents[2].as_doc().unwrap();
+        assert!(page.contains("**Visibility:** `public` (declared `external` in `IVault`)"));
+    }
+}
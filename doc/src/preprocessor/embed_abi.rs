@@ -0,0 +1,123 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{as_contract, read_artifact},
+    Diagnostics, DocDiagnostic, Document, PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// [EmbedAbi] preprocessor id.
+pub const EMBED_ABI_ID: PreprocessorId = PreprocessorId("embed_abi");
+
+/// The embed ABI preprocessor.
+/// It reads the ABI of each contract from its compiled artifact, i.e.
+/// `<artifacts>/<source file name>/<contract>.json`, so it can be embedded in the contract page.
+///
+/// The contracts without the artifact are reported, since the project isn't compiled
+/// by the documentation generator.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct EmbedAbi {
+    /// The project root.
+    pub root: PathBuf,
+    /// The compiled artifacts directory.
+    pub artifacts: PathBuf,
+    /// The collector of the missing artifacts diagnostics.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+impl Preprocessor for EmbedAbi {
+    fn id(&self) -> PreprocessorId {
+        EMBED_ABI_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        if !self.artifacts.is_dir() {
+            self.diagnostics.push(DocDiagnostic::warning(
+                "abi-missing",
+                format!(
+                    "no compiled artifacts at {}, build the project to embed the ABI",
+                    self.artifacts.display()
+                ),
+            ));
+            return Ok(documents)
+        }
+
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            match self.read_abi(&document.item_path, name) {
+                Some(abi) => {
+                    document.add_context(self.id(), PreprocessorOutput::EmbedAbi(abi));
+                }
+                None => {
+                    let source =
+                        document.item_path.strip_prefix(&self.root).unwrap_or(&document.item_path);
+                    self.diagnostics.push(
+                        DocDiagnostic::warning(
+                            "abi-missing",
+                            format!("no compiled artifact with the ABI of `{name}`"),
+                        )
+                        .with_location(source.to_path_buf(), None)
+                        .with_item(name),
+                    );
+                }
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+impl EmbedAbi {
+    /// Returns the pretty-printed ABI of the contract.
+    fn read_abi(&self, source: &Path, name: &str) -> Option<String> {
+        let artifact = read_artifact(&self.artifacts, source, name)?;
+        serde_json::to_string_pretty(artifact.get("abi")?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+    use std::fs;
+
+    #[test]
+    fn artifact_abi() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let artifacts = root.join("out");
+        fs::create_dir_all(artifacts.join("Vault.sol")).unwrap();
+        fs::write(
+            artifacts.join("Vault.sol/Vault.json"),
+            r#"{"abi":[{"type":"function","name":"deposit","inputs":[],"outputs":[],"stateMutability":"nonpayable"}],"bytecode":{}}"#,
+        )
+        .unwrap();
+
+        let src = "contract Vault { function deposit() external { } } contract Token { }";
+        let mut documents = test_documents("src/Vault.sol", src);
+        for document in documents.iter_mut() {
+            document.item_path = root.join(&document.item_path);
+        }
+
+        let diagnostics = Arc::new(Diagnostics::default());
+        let documents =
+            EmbedAbi { root: root.to_path_buf(), artifacts, diagnostics: diagnostics.clone() }
+                .preprocess(documents)
+                .unwrap();
+
+        let abi = read_context!(documents[0], EMBED_ABI_ID, EmbedAbi).unwrap();
+        assert!(abi.starts_with("[\n  {\n"));
+        assert!(abi.contains(r#""name": "deposit""#));
+
+        // The contract without the artifact is reported
+        assert_eq!(read_context!(documents[1], EMBED_ABI_ID, EmbedAbi), None);
+        let diagnostics = diagnostics.take();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].item.as_deref(), Some("Token"));
+    }
+}
@@ -0,0 +1,329 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent,
+    helpers::event_signature,
+    resolver::{AbiTypes, ImportResolver},
+    Document, PreprocessorOutput,
+};
+use ethers_core::abi::{HumanReadableParser, ParamType};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{EventDefinition, Expression};
+use std::{collections::HashMap, ops::Range, path::PathBuf};
+
+/// [EventLayout] preprocessor id.
+pub const EVENT_LAYOUT_ID: PreprocessorId = PreprocessorId("event_layout");
+
+/// The event layout preprocessor.
+/// It computes the log layout of the contract events, i.e. the topic of each indexed parameter
+/// and the byte range of each non-indexed parameter within the ABI-encoded data, so the logs
+/// can be decoded by hand or in the indexer mappings.
+///
+/// The contracts, enums and user-defined value types are encoded as their ABI type, i.e.
+/// `address`, `uint8` and the underlying type. The structs and the types which aren't
+/// documented can't be sized from the declaration, so their range and the ranges of
+/// the following parameters are unknown.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+pub struct EventLayout {
+    /// The project root.
+    pub root: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+}
+
+/// The location of the event parameter within the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogLocation {
+    /// The topic at the index, starting from `1` after the signature hash,
+    /// or from `0` for the anonymous events.
+    Topic(usize),
+    /// The bytes of the data head, [None] if the size of the preceding parameters is unknown.
+    Data(Option<Range<usize>>),
+}
+
+/// The encoding of the event parameter at its location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogEncoding {
+    /// The value padded to 32 bytes, or the in-place encoding of the static data parameter.
+    Value,
+    /// The keccak256 hash of the value, for the indexed strings, bytes, arrays and structs.
+    Hash,
+    /// The offset of the value within the data, for the dynamic data parameter.
+    TailOffset,
+    /// The encoding of the user-defined type, which depends on its definition.
+    Unknown,
+}
+
+/// The event parameter with its location in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSlot {
+    /// The parameter name, or the type of the unnamed parameter.
+    pub name: String,
+    /// The ABI type of the parameter, or the declared type of the unknown user-defined type.
+    pub ty: String,
+    /// The location of the parameter.
+    pub location: LogLocation,
+    /// The encoding of the parameter at the location.
+    pub encoding: LogEncoding,
+}
+
+impl Preprocessor for EventLayout {
+    fn id(&self) -> PreprocessorId {
+        EVENT_LAYOUT_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let abi_types = AbiTypes::new(&documents);
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        for document in documents.iter() {
+            let (item, contract) = match document.content {
+                DocumentContent::Single(ref item) => match item.as_contract() {
+                    Some(contract) => (item, &contract.name.safe_unwrap().name),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let mut abi_type = |ty: &Expression| {
+                abi_types.resolve(&mut resolver, &document.item_path, Some(contract.as_str()), ty)
+            };
+            let layouts = item
+                .events()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(event, _, _)| {
+                    Some((event_signature(event)?, log_layout(event, &mut abi_type)))
+                })
+                .collect::<HashMap<_, _>>();
+            if !layouts.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::EventLayout(layouts));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the location of each event parameter, the indexed ones first.
+/// The parameter types are resolved to their ABI type with the given function.
+fn log_layout(
+    event: &EventDefinition,
+    abi_type: &mut impl FnMut(&Expression) -> String,
+) -> Vec<LogSlot> {
+    let params = event
+        .fields
+        .iter()
+        .map(|param| {
+            let ty = abi_type(&param.ty);
+            let name = param.name.as_ref().map_or_else(|| ty.clone(), |name| name.name.clone());
+            let param_type = HumanReadableParser::parse_type(&ty).ok();
+            (param.indexed, name, ty, param_type)
+        })
+        .collect::<Vec<_>>();
+
+    // The anonymous events have no signature topic, so the indexed ones start from topic 0
+    let first_topic = usize::from(!event.anonymous);
+    let topics = params.iter().filter(|(indexed, ..)| *indexed).enumerate().map(
+        |(index, (_, name, ty, param_type))| {
+            let encoding = match param_type {
+                Some(param_type) if is_reference(param_type) => LogEncoding::Hash,
+                Some(_) => LogEncoding::Value,
+                None => LogEncoding::Unknown,
+            };
+            LogSlot {
+                name: name.clone(),
+                ty: ty.clone(),
+                location: LogLocation::Topic(first_topic + index),
+                encoding,
+            }
+        },
+    );
+
+    let mut offset = Some(0);
+    let data = params.iter().filter(|(indexed, ..)| !*indexed).map(|(_, name, ty, param_type)| {
+        let size = param_type.as_ref().map(head_size);
+        let range = offset.zip(size).map(|(start, size)| start..start + size);
+        offset = range.as_ref().map(|range| range.end);
+        let encoding = match param_type {
+            Some(param_type) if is_dynamic(param_type) => LogEncoding::TailOffset,
+            Some(_) => LogEncoding::Value,
+            None => LogEncoding::Unknown,
+        };
+        LogSlot { name: name.clone(), ty: ty.clone(), location: LogLocation::Data(range), encoding }
+    });
+
+    topics.chain(data).collect()
+}
+
+/// Returns true if the type is dynamically encoded, i.e. its data head only holds the offset
+/// of the value encoded in the tail.
+fn is_dynamic(ty: &ParamType) -> bool {
+    match ty {
+        ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+        ParamType::FixedArray(inner, _) => is_dynamic(inner),
+        ParamType::Tuple(items) => items.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+/// Returns the size of the type in the data head in bytes,
+/// i.e. 32 bytes for the offset of the dynamic types, or the in-place encoding of the static ones.
+fn head_size(ty: &ParamType) -> usize {
+    if is_dynamic(ty) {
+        return 32
+    }
+    match ty {
+        ParamType::FixedArray(inner, size) => size * head_size(inner),
+        ParamType::Tuple(items) => items.iter().map(head_size).sum(),
+        _ => 32,
+    }
+}
+
+/// Returns true if the indexed parameter of the type is stored as the hash of its value,
+/// i.e. the strings, bytes, arrays and structs.
+pub(crate) fn is_reference(ty: &ParamType) -> bool {
+    matches!(
+        ty,
+        ParamType::Bytes |
+            ParamType::String |
+            ParamType::Array(_) |
+            ParamType::FixedArray(..) |
+            ParamType::Tuple(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, helpers::canonical_type, preprocessor::test_documents};
+    use ethers_core::{
+        abi::{encode, Token},
+        types::U256,
+    };
+
+    fn parse_event(src: &str) -> EventDefinition {
+        let (source, _) = solang_parser::parse(src, 0).expect("failed to parse source");
+        source
+            .0
+            .into_iter()
+            .find_map(|part| match part {
+                solang_parser::pt::SourceUnitPart::EventDefinition(event) => Some(*event),
+                _ => None,
+            })
+            .expect("no event")
+    }
+
+    #[test]
+    fn head_sizes() {
+        let ty = |ty: &str| HumanReadableParser::parse_type(ty).unwrap();
+        assert_eq!(head_size(&ty("uint8")), 32);
+        assert_eq!(head_size(&ty("uint256[3]")), 96);
+        assert_eq!(head_size(&ty("string[2]")), 32);
+        let pair = ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2);
+        assert_eq!(head_size(&ParamType::Tuple(vec![ParamType::Address, pair])), 96);
+        assert_eq!(head_size(&ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bytes])), 32);
+        assert!(is_dynamic(&ty("uint256[]")));
+        assert!(!is_dynamic(&ty("bytes32[4]")));
+    }
+
+    #[test]
+    fn data_offsets_match_encoding() {
+        let event = parse_event(
+            "event Transfer(address indexed from, string indexed memo, uint256 amount, \
+             bytes note, uint256[2] pair, bool flag);",
+        );
+        let layout = log_layout(&event, &mut canonical_type);
+        let locations = layout
+            .iter()
+            .map(|slot| (slot.name.as_str(), slot.location.clone(), slot.encoding))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locations,
+            vec![
+                ("from", LogLocation::Topic(1), LogEncoding::Value),
+                ("memo", LogLocation::Topic(2), LogEncoding::Hash),
+                ("amount", LogLocation::Data(Some(0..32)), LogEncoding::Value),
+                ("note", LogLocation::Data(Some(32..64)), LogEncoding::TailOffset),
+                ("pair", LogLocation::Data(Some(64..128)), LogEncoding::Value),
+                ("flag", LogLocation::Data(Some(128..160)), LogEncoding::Value),
+            ]
+        );
+
+        // The data of the non-indexed parameters, as encoded by the ABI encoder
+        let data = encode(&[
+            Token::Uint(U256::from(7)),
+            Token::Bytes(vec![0xab; 3]),
+            Token::FixedArray(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+            Token::Bool(true),
+        ]);
+        assert_eq!(U256::from_big_endian(&data[0..32]), U256::from(7));
+        // The head of the bytes holds the offset of its tail, after the 160 bytes of the heads
+        assert_eq!(U256::from_big_endian(&data[32..64]), U256::from(160));
+        assert_eq!(U256::from_big_endian(&data[64..96]), U256::from(1));
+        assert_eq!(U256::from_big_endian(&data[96..128]), U256::from(2));
+        assert_eq!(U256::from_big_endian(&data[128..160]), U256::one());
+        assert_eq!(U256::from_big_endian(&data[160..192]), U256::from(3));
+        assert_eq!(&data[192..195], &[0xab; 3]);
+    }
+
+    #[test]
+    fn anonymous_and_user_types() {
+        let event = parse_event(
+            "event Moved(Position position, uint256 indexed id, uint256 amount) anonymous;",
+        );
+        let layout = log_layout(&event, &mut canonical_type);
+        assert_eq!(layout[0].location, LogLocation::Topic(0));
+        assert_eq!(layout[1].location, LogLocation::Data(None));
+        assert_eq!(layout[1].encoding, LogEncoding::Unknown);
+        assert_eq!(layout[2].location, LogLocation::Data(None));
+    }
+
+    #[test]
+    fn resolved_user_types() {
+        let src = r#"
+            type Price is uint128;
+
+            enum Status { Open, Closed }
+
+            interface IERC20 {}
+
+            contract Vault {
+                enum Side { Buy, Sell }
+
+                struct Order { uint256 amount; }
+
+                event Traded(
+                    IERC20 indexed token,
+                    Side side,
+                    Price price,
+                    Status[2] statuses,
+                    Order order,
+                    uint256 amount
+                );
+            }
+        "#;
+        let documents =
+            EventLayout::default().preprocess(test_documents("src/Vault.sol", src)).unwrap();
+        let layouts = read_context!(documents[3], EVENT_LAYOUT_ID, EventLayout).unwrap();
+        let slots = layouts
+            .values()
+            .next()
+            .unwrap()
+            .iter()
+            .map(|slot| (slot.ty.as_str(), slot.location.clone(), slot.encoding))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            slots,
+            vec![
+                ("address", LogLocation::Topic(1), LogEncoding::Value),
+                ("uint8", LogLocation::Data(Some(0..32)), LogEncoding::Value),
+                ("uint128", LogLocation::Data(Some(32..64)), LogEncoding::Value),
+                ("uint8[2]", LogLocation::Data(Some(64..128)), LogEncoding::Value),
+                ("Order", LogLocation::Data(None), LogEncoding::Unknown),
+                ("uint256", LogLocation::Data(None), LogEncoding::Value),
+            ]
+        );
+    }
+}
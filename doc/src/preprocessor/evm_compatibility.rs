@@ -0,0 +1,201 @@
+use super::{build_info::evm_features, hardfork_index, Preprocessor, PreprocessorId};
+use crate::{
+    helpers::as_contract, CompilerSettings, Diagnostics, DocDiagnostic, Document,
+    PreprocessorOutput,
+};
+use std::sync::Arc;
+
+/// [EvmCompatibility] preprocessor id.
+pub const EVM_COMPATIBILITY_ID: PreprocessorId = PreprocessorId("evm_compatibility");
+
+/// The features of the transient storage (EIP-1153).
+const TRANSIENT_STORAGE: [&str; 3] = ["tstore", "tload", "transient"];
+
+/// The first solc version emitting `PUSH0` when targeting Shanghai.
+const PUSH0_SOLC: (u64, u64, u64) = (0, 8, 20);
+
+/// The EVM compatibility preprocessor.
+/// It notes the compatibility considerations of each contract in its compilation section,
+/// i.e. the transient storage used by the contract and the `PUSH0` opcode emitted for the
+/// Shanghai or later EVM versions, so the integrators see whether the contract can be
+/// deployed on the chain without the hardfork, e.g. some L2s.
+///
+/// The transient storage is detected on the best-effort basis from the words of the contract
+/// source, like the [EvmRequirements](super::EvmRequirements) of the build info.
+/// The unknown EVM version is reported, since whether it emits `PUSH0` can't be told.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct EvmCompatibility {
+    /// The compiler settings.
+    pub settings: CompilerSettings,
+    /// The collector of the unknown EVM version diagnostic.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+/// The EVM compatibility consideration of the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatibilityNote {
+    /// The contract uses the transient storage, with the detected features, e.g. `tstore`.
+    TransientStorage(Vec<String>),
+    /// The bytecode may contain `PUSH0`, since it's compiled for the EVM version.
+    Push0(String),
+}
+
+impl Preprocessor for EvmCompatibility {
+    fn id(&self) -> PreprocessorId {
+        EVM_COMPATIBILITY_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        if hardfork_index(&self.settings.evm_version).is_none() {
+            self.diagnostics.push(DocDiagnostic::warning(
+                "unknown-evm-version",
+                format!(
+                    "unknown EVM version `{}`, so the `PUSH0` compatibility isn't noted",
+                    self.settings.evm_version
+                ),
+            ));
+        }
+        let push0 = self.emits_push0();
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let mut notes = vec![];
+            let source = document.item_content.get(contract.loc.start()..contract.loc.end());
+            let transient = evm_features(source.unwrap_or_default())
+                .into_iter()
+                .map(|(feature, _)| feature)
+                .filter(|feature| TRANSIENT_STORAGE.contains(&feature.as_str()))
+                .collect::<Vec<_>>();
+            if !transient.is_empty() {
+                notes.push(CompatibilityNote::TransientStorage(transient));
+            }
+            if push0 {
+                notes.push(CompatibilityNote::Push0(self.settings.evm_version.clone()));
+            }
+            if !notes.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::EvmCompatibility(notes));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+impl EvmCompatibility {
+    /// Returns true if the bytecode may contain `PUSH0`, i.e. the EVM version is Shanghai
+    /// or later, unless the configured solc version predates `PUSH0`.
+    fn emits_push0(&self) -> bool {
+        let targets_shanghai =
+            hardfork_index(&self.settings.evm_version) >= hardfork_index("shanghai");
+        let predates = self
+            .settings
+            .solc
+            .as_deref()
+            .and_then(solc_version)
+            .map_or(false, |version| version < PUSH0_SOLC);
+        targets_shanghai && !predates
+    }
+}
+
+/// Returns the version of the configured solc, [None] for the path to the local binary.
+fn solc_version(solc: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = solc.trim_start_matches('v').split('.').map(|part| part.parse().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    fn settings(solc: Option<&str>, evm_version: &str) -> CompilerSettings {
+        CompilerSettings {
+            solc: solc.map(str::to_owned),
+            evm_version: evm_version.to_owned(),
+            optimizer: false,
+            optimizer_runs: 200,
+            via_ir: false,
+        }
+    }
+
+    fn notes(settings: CompilerSettings) -> Vec<(String, Option<Vec<CompatibilityNote>>)> {
+        let src = r#"
+            contract Lock {
+                function lock() external {
+                    assembly { tstore(0, 1) }
+                }
+            }
+
+            contract Vault {
+                // tstore isn't used
+                uint256 total;
+            }
+        "#;
+        EvmCompatibility { settings, diagnostics: Arc::new(Diagnostics::default()) }
+            .preprocess(test_documents("src/Lock.sol", src))
+            .unwrap()
+            .iter()
+            .map(|document| {
+                let notes = match document.get_from_context(EVM_COMPATIBILITY_ID) {
+                    Some(PreprocessorOutput::EvmCompatibility(notes)) => Some(notes),
+                    _ => None,
+                };
+                (document.as_doc().unwrap(), notes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compatibility_notes() {
+        let transient = CompatibilityNote::TransientStorage(vec!["tstore".to_owned()]);
+        let push0 = CompatibilityNote::Push0("cancun".to_owned());
+
+        let pages = notes(settings(Some("0.8.24"), "cancun"));
+        assert_eq!(pages[0].1, Some(vec![transient.clone(), push0.clone()]));
+        assert_eq!(pages[1].1, Some(vec![push0]));
+        assert!(pages[0].0.contains(
+            "## Compilation\n\
+            > **Transient storage:** uses `tstore` (EIP-1153), so the contract can only be \
+            deployed on the chains with the Cancun upgrade.\n"
+        ));
+
+        // The solc before 0.8.20 doesn't emit `PUSH0`
+        let pages = notes(settings(Some("0.8.19"), "shanghai"));
+        assert_eq!(pages[0].1, Some(vec![transient]));
+        assert_eq!(pages[1].1, None);
+        assert!(!pages[1].0.contains("## Compilation"));
+
+        let pages = notes(settings(None, "Shanghai"));
+        assert!(pages[1].0.contains("> **PUSH0:** compiled for `Shanghai`"));
+        assert_eq!(notes(settings(Some("0.8.24"), "paris"))[1].1, None);
+    }
+
+    #[test]
+    fn unknown_evm_version() {
+        let diagnostics = Arc::new(Diagnostics::default());
+        let compatibility = |evm_version: &str| EvmCompatibility {
+            settings: settings(Some("0.8.24"), evm_version),
+            diagnostics: diagnostics.clone(),
+        };
+        compatibility("cancun").preprocess(vec![]).unwrap();
+        assert_eq!(diagnostics.warnings(), 0);
+
+        compatibility("cancunn").preprocess(vec![]).unwrap();
+        let reported = diagnostics.take();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].code, "unknown-evm-version");
+        assert_eq!(
+            reported[0].message,
+            "unknown EVM version `cancunn`, so the `PUSH0` compatibility isn't noted"
+        );
+    }
+
+    #[test]
+    fn transient_keyword() {
+        // The transient state variables aren't supported by the parser, so the source is matched
+        let features = evm_features("contract Lock { bool transient locked; }");
+        assert_eq!(features, vec![("transient".to_owned(), "cancun".to_owned())]);
+        assert_eq!(solc_version("0.8.20"), Some(PUSH0_SOLC));
+        assert_eq!(solc_version("/usr/bin/solc"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{document::DocumentContent, CommentTag, Document, ParseSource, PreprocessorOutput};
+use std::path::PathBuf;
+
+/// [FileTypes] preprocessor id.
+pub const FILE_TYPES_ID: PreprocessorId = PreprocessorId("file_types");
+
+/// The file types preprocessor.
+/// It matches the contract documents with the structs, enums and user-defined value types
+/// declared at the file scope of the same source, e.g. the shared types of the library file,
+/// and links them from the contract page. The file-level types are written to their own pages.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Default, Debug)]
+#[non_exhaustive]
+pub struct FileTypes;
+
+/// The type declared at the file scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileType {
+    /// The type name.
+    pub name: String,
+    /// The type kind, i.e. `struct`, `enum` or `type`.
+    pub kind: String,
+    /// The first line of the `@notice` comment, if any.
+    pub notice: Option<String>,
+    /// The target path of the type page.
+    pub page: PathBuf,
+}
+
+impl Preprocessor for FileTypes {
+    fn id(&self) -> PreprocessorId {
+        FILE_TYPES_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let types = documents
+            .iter()
+            .filter_map(|document| {
+                let item = match document.content {
+                    DocumentContent::Single(ref item) => item,
+                    _ => return None,
+                };
+                let kind = match item.source {
+                    ParseSource::Struct(_) => "struct",
+                    ParseSource::Enum(_) => "enum",
+                    ParseSource::Type(_) => "type",
+                    _ => return None,
+                };
+                let notice = item
+                    .comments
+                    .include_tag(CommentTag::Notice)
+                    .first()
+                    .and_then(|notice| notice.value.lines().next())
+                    .map(|notice| notice.trim().to_owned())
+                    .filter(|notice| !notice.is_empty());
+                let file_type = FileType {
+                    name: item.source.ident(),
+                    kind: kind.to_owned(),
+                    notice,
+                    page: document.target_path.clone(),
+                };
+                Some((&document.item_path, file_type))
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() {
+            return Ok(documents)
+        }
+
+        for document in documents.iter() {
+            let is_contract = match document.content {
+                DocumentContent::Single(ref item) => item.as_contract().is_some(),
+                _ => false,
+            };
+            if !is_contract {
+                continue
+            }
+            let file_types = types
+                .iter()
+                .filter(|(path, _)| **path == document.item_path)
+                .map(|(_, file_type)| file_type.clone())
+                .collect::<Vec<_>>();
+            if !file_types.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::FileTypes(file_types));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    #[test]
+    fn file_level_types() {
+        let src = r#"
+            /// @notice The position of the account.
+            struct Position { uint256 shares; }
+
+            type Price is uint128;
+
+            contract Vault { }
+        "#;
+        let mut documents = test_documents("src/Vault.sol", src);
+        for document in documents.iter_mut() {
+            // The links are absolute within the book source of the output directory
+            document.book_src = PathBuf::from("docs/src");
+        }
+
+        let documents = FileTypes::default().preprocess(documents).unwrap();
+        let vault = documents.iter().find(|document| document.identity == "Vault").unwrap();
+        match vault.get_from_context(FILE_TYPES_ID) {
+            Some(PreprocessorOutput::FileTypes(types)) => assert_eq!(types.len(), 2),
+            _ => panic!("expected the file types"),
+        }
+        assert!(vault.as_doc().unwrap().contains(
+            "## Types\n\
+            - [Position](/src/Vault.sol/struct.Position.md) (struct): \
+            The position of the account.\n\
+            - [Price](/src/Vault.sol/type.Price.md) (type)\n"
+        ));
+        assert!(documents.iter().all(|document| document.identity == "Vault" ||
+            document.get_from_context(FILE_TYPES_ID).is_none()));
+    }
+}
@@ -0,0 +1,108 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{as_contract, read_artifact},
+    number, Document, PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::{collections::HashMap, path::PathBuf};
+
+/// [GasEstimates] preprocessor id.
+pub const GAS_ESTIMATES_ID: PreprocessorId = PreprocessorId("gas_estimates");
+
+/// The custom tag of the gas the author expects the function to use, i.e. `@custom:gas`.
+pub const GAS_TAG: &str = "gas";
+
+/// The gas estimates preprocessor.
+/// It reads the compiler's gas estimates of the external functions of each contract
+/// from its compiled artifact, i.e. the `gasEstimates` of
+/// `<artifacts>/<source file name>/<contract>.json`,
+/// so they can be shown next to the author's `@custom:gas` notes.
+///
+/// The estimates are only written to the artifacts with `evm.gasEstimates` in the extra output,
+/// the contracts without them are skipped. The estimates missing from the artifacts compiled
+/// through the IR pipeline, i.e. `null`, are skipped as well.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct GasEstimates {
+    /// The compiled artifacts directory.
+    pub artifacts: PathBuf,
+}
+
+impl Preprocessor for GasEstimates {
+    fn id(&self) -> PreprocessorId {
+        GAS_ESTIMATES_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            let estimates = read_artifact(&self.artifacts, &document.item_path, name)
+                .map(|artifact| gas_estimates(&artifact))
+                .unwrap_or_default();
+            if !estimates.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::GasEstimates(estimates));
+            }
+        }
+        Ok(documents)
+    }
+}
+
+/// Returns the gas estimates of the external functions keyed by their signature,
+/// and of the deployment keyed by `constructor`. The unbounded estimates are `infinite`.
+fn gas_estimates(artifact: &serde_json::Value) -> HashMap<String, String> {
+    let estimates = match artifact.get("gasEstimates") {
+        Some(estimates) => estimates,
+        None => return HashMap::new(),
+    };
+    let value = |value: &serde_json::Value| match value {
+        serde_json::Value::String(value) => Some(number::integer(value)),
+        serde_json::Value::Number(value) => Some(number::integer(&value.to_string())),
+        _ => None,
+    };
+    let mut functions = estimates["external"]
+        .as_object()
+        .map(|external| {
+            external
+                .iter()
+                .filter_map(|(signature, estimate)| Some((signature.clone(), value(estimate)?)))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    if let Some(total) = estimates["creation"].get("totalCost").and_then(value) {
+        functions.insert("constructor".to_owned(), total);
+    }
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact_gas_estimates() {
+        let artifact = serde_json::json!({
+            "gasEstimates": {
+                "creation": {
+                    "codeDepositCost": "412000",
+                    "executionCost": "infinite",
+                    "totalCost": "infinite"
+                },
+                "external": {"deposit(uint256)": "24512", "name()": "infinite"}
+            }
+        });
+        assert_eq!(
+            gas_estimates(&artifact),
+            HashMap::from([
+                ("constructor".to_owned(), "infinite".to_owned()),
+                ("deposit(uint256)".to_owned(), "24512".to_owned()),
+                ("name()".to_owned(), "infinite".to_owned()),
+            ])
+        );
+        assert!(gas_estimates(&serde_json::json!({"abi": []})).is_empty());
+        let missing = serde_json::json!({
+            "gasEstimates": {"creation": {"totalCost": null}, "external": {"name()": null}}
+        });
+        assert!(gas_estimates(&missing).is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    artifact::is_artifact, document::DocumentContent, vyper::is_vyper, Comment, CommentTag,
+    Document, ParseItem,
+};
+use rayon::{prelude::*, ThreadPoolBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// [GitSince] preprocessor id.
+pub const GIT_SINCE_ID: PreprocessorId = PreprocessorId("git_since");
+
+/// The custom tag of the version the item was introduced in, i.e. `@custom:since`.
+pub const SINCE_TAG: &str = "since";
+
+/// The git since preprocessor.
+/// It detects the first tag, or the first commit if it's not tagged yet, that introduced
+/// the declaration of each item without the explicit `@custom:since` tag.
+///
+/// This preprocessor adds the `@custom:since` comments to the items.
+/// The lookups are cached by the blob hash of the source file, so the git history
+/// is only searched again for the changed files.
+#[derive(Debug)]
+pub struct GitSince {
+    /// The project root.
+    pub root: PathBuf,
+    /// The lookups cache file.
+    pub cache: PathBuf,
+    /// The maximum number of the concurrent git lookups.
+    ///
+    /// If none is provided, it defaults to the available parallelism.
+    pub jobs: Option<usize>,
+}
+
+/// The cached lookups of the source file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CachedFile {
+    /// The blob hash of the source file the lookups were made for.
+    blob: String,
+    /// The looked up versions by the item declaration.
+    items: BTreeMap<String, Option<String>>,
+}
+
+impl Preprocessor for GitSince {
+    fn id(&self) -> PreprocessorId {
+        GIT_SINCE_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        if git(&self.root, &["rev-parse", "--is-inside-work-tree"]).is_none() {
+            return Ok(documents)
+        }
+
+        // Collect the declarations to look up by the source file
+        let mut lookups: HashMap<PathBuf, Vec<String>> = HashMap::new();
+        // The declarations of the Vyper contracts and the ABI artifacts are generated,
+        // so never found in the history
+        let generated = |path: &Path| is_vyper(path) || is_artifact(path);
+        for document in documents.iter().filter(|document| !generated(&document.item_path)) {
+            let declarations = lookups.entry(document.item_path.clone()).or_default();
+            for item in content_items(&document.content) {
+                collect_declarations(item, &document.item_content, declarations);
+            }
+        }
+        lookups.retain(|_, declarations| !declarations.is_empty());
+        if lookups.is_empty() {
+            return Ok(documents)
+        }
+
+        let mut cache: BTreeMap<PathBuf, CachedFile> = fs::read_to_string(&self.cache)
+            .ok()
+            .and_then(|cache| serde_json::from_str(&cache).ok())
+            .unwrap_or_default();
+        let pool = ThreadPoolBuilder::new().num_threads(self.jobs.unwrap_or_default()).build()?;
+        let root = &self.root;
+        let cached = &cache;
+        let looked_up = pool.install(|| {
+            lookups
+                .into_par_iter()
+                .filter_map(|(path, declarations)| {
+                    let file = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                    let blob = git(root, &["hash-object", &file.display().to_string()])?;
+                    let mut items = match cached.get(&file) {
+                        Some(cached) if cached.blob == blob => cached.items.clone(),
+                        _ => BTreeMap::new(),
+                    };
+                    for declaration in declarations {
+                        items
+                            .entry(declaration.clone())
+                            .or_insert_with(|| introduced_in(root, &file, &declaration));
+                    }
+                    Some((file, CachedFile { blob, items }))
+                })
+                .collect::<Vec<_>>()
+        });
+        cache.extend(looked_up);
+
+        for document in documents.iter_mut() {
+            let file = document.item_path.strip_prefix(root).unwrap_or(&document.item_path);
+            if let Some(cached) = cache.get(file) {
+                let source = &document.item_content;
+                for item in content_items_mut(&mut document.content) {
+                    add_since(item, source, &cached.items);
+                }
+            }
+        }
+
+        if let Some(parent) = self.cache.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.cache, serde_json::to_string(&cache)?)?;
+
+        Ok(documents)
+    }
+}
+
+fn content_items(content: &DocumentContent) -> Vec<&ParseItem> {
+    match content {
+        DocumentContent::Single(item) => vec![item],
+        DocumentContent::Constants(items) | DocumentContent::OverloadedFunctions(items) => {
+            items.iter().collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn content_items_mut(content: &mut DocumentContent) -> Vec<&mut ParseItem> {
+    match content {
+        DocumentContent::Single(item) => vec![item],
+        DocumentContent::Constants(items) | DocumentContent::OverloadedFunctions(items) => {
+            items.iter_mut().collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Returns true if the item has the explicit `@custom:since` tag.
+fn has_since(item: &ParseItem) -> bool {
+    item.comments.iter().any(|comment| comment.tag == CommentTag::Custom(SINCE_TAG.to_owned()))
+}
+
+fn collect_declarations(item: &ParseItem, source: &str, declarations: &mut Vec<String>) {
+    if !has_since(item) {
+        if let Some(declaration) = declaration(item, source) {
+            if !declarations.contains(&declaration) {
+                declarations.push(declaration);
+            }
+        }
+    }
+    for child in item.children.iter() {
+        collect_declarations(child, source, declarations);
+    }
+}
+
+fn add_since(item: &mut ParseItem, source: &str, looked_up: &BTreeMap<String, Option<String>>) {
+    if !has_since(item) {
+        let since = declaration(item, source)
+            .and_then(|declaration| looked_up.get(&declaration).cloned().flatten());
+        if let Some(since) = since {
+            item.comments.push(Comment::new(CommentTag::Custom(SINCE_TAG.to_owned()), since));
+        }
+    }
+    for child in item.children.iter_mut() {
+        add_since(child, source, looked_up);
+    }
+}
+
+/// Returns the beginning of the item declaration up to the item name, e.g. `function deposit(`
+/// or `contract Vault`. The declaration is searched for in the git history.
+fn declaration(item: &ParseItem, source: &str) -> Option<String> {
+    let loc = item.source.loc();
+    let line = source.get(loc.start()..loc.end())?.lines().next()?.trim();
+    let ident = item.source.ident();
+    // Find the name as a whole word, e.g. not within the `function` keyword
+    let is_ident_char = |ch: char| ch.is_alphanumeric() || ch == '_' || ch == '$';
+    let (start, _) = line.match_indices(&ident).find(|(idx, _)| {
+        !line[..*idx].ends_with(is_ident_char) &&
+            !line[idx + ident.len()..].starts_with(is_ident_char)
+    })?;
+    let end = start + ident.len();
+    let declaration = if line[end..].starts_with('(') { &line[..=end] } else { &line[..end] };
+    Some(declaration.to_owned())
+}
+
+/// Returns the first tag containing the commit that introduced the declaration to the file,
+/// or the short hash of the commit if it's not tagged yet.
+fn introduced_in(root: &Path, file: &Path, declaration: &str) -> Option<String> {
+    let commits = git(
+        root,
+        &[
+            "log",
+            "--follow",
+            "--reverse",
+            "--format=%h",
+            &format!("-S{declaration}"),
+            "--",
+            &file.display().to_string(),
+        ],
+    )?;
+    let commit = commits.lines().next()?.to_owned();
+    // The tag is described relative to the commit, e.g. `v1.2.0~3`
+    let tag = git(root, &["describe", "--tags", "--contains", &commit])
+        .and_then(|tag| tag.split(['~', '^']).next().map(str::to_owned));
+    Some(tag.unwrap_or(commit))
+}
+
+/// Runs the git command, returning the trimmed stdout if it succeeds.
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|stdout| stdout.trim().to_owned())
+        .filter(|stdout| !stdout.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use forge_fmt::Visitable;
+
+    #[test]
+    fn item_declarations() {
+        let src = r#"
+            /// @custom:since 1.0.0
+            contract Vault is Base {
+                function deposit (uint256 amount) external { }
+                function withdraw(uint256 amount) external { }
+                event Deposit(address indexed owner);
+            }
+        "#;
+        let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+        let mut parser = Parser::new(comments, src.to_owned());
+        source.visit(&mut parser).expect("failed to visit source");
+        let mut item = parser.items().pop().unwrap();
+
+        let mut declarations = vec![];
+        collect_declarations(&item, src, &mut declarations);
+        assert_eq!(declarations, vec!["function deposit", "function withdraw(", "event Deposit("]);
+
+        let looked_up = BTreeMap::from([
+            ("function deposit".to_owned(), Some("v1.1.0".to_owned())),
+            ("function withdraw(".to_owned(), None),
+        ]);
+        add_since(&mut item, src, &looked_up);
+        let since = |item: &ParseItem| {
+            item.comments
+                .include_tag(CommentTag::Custom(SINCE_TAG.to_owned()))
+                .iter()
+                .map(|comment| comment.value.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(since(&item), vec!["1.0.0"]);
+        assert_eq!(since(&item.children[0]), vec!["v1.1.0"]);
+        assert!(since(&item.children[1]).is_empty());
+    }
+}
@@ -28,10 +28,12 @@ impl Preprocessor for GitSource {
             let repo = repo.trim_end_matches('/');
             let commit = self.commit.clone().unwrap_or("master".to_owned());
             for document in documents.iter() {
-                let git_url = format!(
-                    "{repo}/blob/{commit}/{}",
-                    document.item_path.strip_prefix(&self.root)?.display()
-                );
+                // The files outside the project, e.g. the ABI artifacts, aren't in the repository
+                let path = match document.item_path.strip_prefix(&self.root) {
+                    Ok(path) => path,
+                    Err(_) => continue,
+                };
+                let git_url = format!("{repo}/blob/{commit}/{}", path.display());
                 document.add_context(self.id(), PreprocessorOutput::GitSource(git_url));
             }
         }
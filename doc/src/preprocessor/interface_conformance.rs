@@ -0,0 +1,313 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent,
+    helpers::{
+        as_contract, function_signature, getter_signature, is_externally_visible,
+        is_public_variable, selector,
+    },
+    resolver::ImportResolver,
+    selectors::is_canonical,
+    DocBuilder, Document, ParseItem,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{ContractTy, FunctionTy};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// [InterfaceConformance] preprocessor id.
+pub const INTERFACE_CONFORMANCE_ID: PreprocessorId = PreprocessorId("interface_conformance");
+
+/// The interface conformance preprocessor.
+/// It compares the externally visible functions of every concrete contract
+/// (including the inherited ones) with the functions of every project interface
+/// and appends the [ConformanceMatrix] appendix document.
+///
+/// The functions are compared by the selectors of their canonical signatures, the same
+/// way the contract dispatches them. The signatures with the user defined types are skipped.
+///
+/// The contracts are keyed by their source, so the contracts with the same name in
+/// the different files are told apart, and the bases are resolved through the imports.
+/// The names declared more than once are qualified by the source in the matrix,
+/// e.g. `Vault (src/v2/Vault.sol)`.
+///
+/// The appendix is skipped if the project has fewer than two interfaces.
+#[derive(Debug)]
+pub struct InterfaceConformance {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+}
+
+/// The conformance of a contract to an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conformance {
+    /// The contract implements all interface functions.
+    Full,
+    /// The contract implements some of the interface functions.
+    /// Contains the signatures of the missing functions.
+    Partial(Vec<String>),
+    /// The contract implements none of the interface functions.
+    Unimplemented,
+}
+
+/// The matrix of project interfaces (columns) and concrete contracts (rows).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceMatrix {
+    /// The interface names.
+    pub interfaces: Vec<String>,
+    /// The contract names with their conformance to each of the interfaces.
+    pub rows: Vec<(String, Vec<Conformance>)>,
+}
+
+impl Preprocessor for InterfaceConformance {
+    fn id(&self) -> PreprocessorId {
+        INTERFACE_CONFORMANCE_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let mut contracts: HashMap<String, Vec<(PathBuf, &ParseItem)>> = HashMap::new();
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            contracts
+                .entry(contract.name.safe_unwrap().name.clone())
+                .or_default()
+                .push((document.item_path.clone(), item));
+        }
+
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let mut with_ty = |matcher: fn(&ContractTy) -> bool| {
+            let mut matching = vec![];
+            for (name, declarations) in contracts.iter() {
+                for (path, item) in declarations.iter() {
+                    if item.as_contract().map(|c| matcher(&c.ty)).unwrap_or_default() {
+                        let label = self.label(name, path, declarations.len());
+                        let inheritance = inheritance(path, item, &contracts, &mut resolver);
+                        let selectors = signatures(&inheritance)
+                            .into_iter()
+                            .map(|signature| (selector(&signature), signature))
+                            .collect::<BTreeMap<_, _>>();
+                        matching.push((label, selectors));
+                    }
+                }
+            }
+            matching.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            matching
+        };
+        let interfaces = with_ty(|ty| matches!(ty, ContractTy::Interface(_)));
+        let concrete = with_ty(|ty| matches!(ty, ContractTy::Contract(_)));
+
+        if interfaces.len() < 2 || concrete.is_empty() {
+            return Ok(documents)
+        }
+
+        let rows = concrete
+            .into_iter()
+            .map(|(name, implemented)| {
+                let conformance = interfaces
+                    .iter()
+                    .map(|(_, required)| {
+                        let missing = required
+                            .iter()
+                            .filter(|(selector, _)| !implemented.contains_key(*selector))
+                            .map(|(_, signature)| signature.clone())
+                            .collect::<Vec<_>>();
+                        if missing.is_empty() {
+                            Conformance::Full
+                        } else if missing.len() == required.len() {
+                            Conformance::Unimplemented
+                        } else {
+                            Conformance::Partial(missing)
+                        }
+                    })
+                    .collect();
+                (name, conformance)
+            })
+            .collect();
+
+        let matrix = ConformanceMatrix {
+            interfaces: interfaces.into_iter().map(|(name, _)| name).collect(),
+            rows,
+        };
+        let target_path = self.out.join(DocBuilder::SRC).join("appendix/interface-conformance.md");
+        documents.push(Document::new(self.root.clone(), target_path).with_content(
+            DocumentContent::InterfaceConformance(matrix),
+            "Interface Conformance".to_owned(),
+        ));
+
+        Ok(documents)
+    }
+}
+
+impl InterfaceConformance {
+    /// Returns the name of the contract in the matrix, qualified by its source relative to
+    /// the project root if the name is declared more than once.
+    fn label(&self, name: &str, path: &Path, declared: usize) -> String {
+        if declared > 1 {
+            format!("{name} ({})", path.strip_prefix(&self.root).unwrap_or(path).display())
+        } else {
+            name.to_owned()
+        }
+    }
+}
+
+/// Returns the contract followed by its bases declared in the project, i.e. its linearized
+/// project ancestors, with the bases resolved through the imports.
+pub(super) fn inheritance<'a>(
+    path: &Path,
+    item: &'a ParseItem,
+    contracts: &HashMap<String, Vec<(PathBuf, &'a ParseItem)>>,
+    resolver: &mut ImportResolver,
+) -> Vec<(PathBuf, &'a ParseItem)> {
+    let mut inheritance = vec![];
+    let mut visited = HashSet::new();
+    let mut queue = vec![(path.to_path_buf(), item)];
+    while let Some((path, item)) = queue.pop() {
+        if let Some(contract) = item.as_contract() {
+            for base in contract.base.iter() {
+                let name = base.name.identifiers.iter().map(|ident| ident.name.as_str());
+                let name = name.collect::<Vec<_>>().join(".");
+                if let Some((base_path, base)) = resolver.declaration(&path, &name, contracts) {
+                    if visited.insert((base_path.clone(), name)) {
+                        queue.push((base_path.clone(), *base));
+                    }
+                }
+            }
+        }
+        inheritance.push((path, item));
+    }
+    inheritance
+}
+
+/// Collect the canonical signatures of the externally visible functions and public state
+/// variable getters of the contract [inheritance]. The signatures with the user defined types
+/// are skipped, since their selectors can't be computed without resolving the types.
+pub(super) fn signatures(inheritance: &[(PathBuf, &ParseItem)]) -> BTreeSet<String> {
+    let mut signatures = BTreeSet::default();
+    for (_, item) in inheritance.iter() {
+        for (func, _, _) in item.functions().unwrap_or_default() {
+            if matches!(func.ty, FunctionTy::Function) && is_externally_visible(func) {
+                signatures.extend(function_signature(func));
+            }
+        }
+        for (var, _, _) in item.variables().unwrap_or_default() {
+            if is_public_variable(var) {
+                signatures.extend(getter_signature(var));
+            }
+        }
+    }
+    signatures.retain(|signature| is_canonical(signature));
+    signatures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    fn documents(sources: &[(&str, &str)]) -> Vec<Document> {
+        sources.iter().flat_map(|(path, src)| test_documents(path, src)).collect()
+    }
+
+    fn matrix(documents: Vec<Document>) -> Option<ConformanceMatrix> {
+        let preprocessor = InterfaceConformance {
+            root: PathBuf::new(),
+            out: PathBuf::from("docs"),
+            remappings: vec![],
+        };
+        match preprocessor.preprocess(documents).unwrap().pop()?.content {
+            DocumentContent::InterfaceConformance(matrix) => Some(matrix),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn conforming_contracts() {
+        let documents = documents(&[(
+            "src/Vault.sol",
+            r#"
+            interface IVault {
+                function deposit(uint amount) external;
+                function total() external view returns (uint256);
+                function sweep(Token token) external;
+            }
+
+            interface IPausable {
+                function pause() external;
+            }
+
+            abstract contract Base {
+                function deposit(uint256 amount) public virtual { }
+            }
+
+            contract Vault is Base, IVault {
+                uint256 public total;
+            }
+
+            contract Pool {
+                function deposit(uint256 amount) external { }
+            }
+        "#,
+        )]);
+
+        // The `uint` alias matches by the selector, and the user defined `sweep(Token)` is skipped
+        let matrix = self::matrix(documents).unwrap();
+        assert_eq!(matrix.interfaces, vec!["IPausable".to_owned(), "IVault".to_owned()]);
+        assert_eq!(
+            matrix.rows,
+            vec![
+                (
+                    "Pool".to_owned(),
+                    vec![
+                        Conformance::Unimplemented,
+                        Conformance::Partial(vec!["total()".to_owned()])
+                    ]
+                ),
+                ("Vault".to_owned(), vec![Conformance::Unimplemented, Conformance::Full]),
+            ]
+        );
+
+        // The matrix needs two interfaces
+        let single = self::documents(&[(
+            "src/Vault.sol",
+            "interface IVault { function total() external; } contract Vault { }",
+        )]);
+        assert_eq!(self::matrix(single), None);
+    }
+
+    #[test]
+    fn same_name_contracts() {
+        let documents = documents(&[
+            ("src/v1/Base.sol", "abstract contract Base { function pause() external { } }"),
+            ("src/v2/Base.sol", "abstract contract Base { function total() external { } }"),
+            (
+                "src/Interfaces.sol",
+                "interface IPausable { function pause() external; } \
+                 interface ITotal { function total() external; }",
+            ),
+            ("src/v1/Vault.sol", r#"import "./Base.sol"; contract Vault is Base { }"#),
+            ("src/v2/Vault.sol", r#"import "./Base.sol"; contract Vault is Base { }"#),
+        ]);
+
+        // The bases are resolved through the imports of each vault
+        let matrix = matrix(documents).unwrap();
+        assert_eq!(
+            matrix.rows,
+            vec![
+                (
+                    "Vault (src/v1/Vault.sol)".to_owned(),
+                    vec![Conformance::Full, Conformance::Unimplemented]
+                ),
+                (
+                    "Vault (src/v2/Vault.sol)".to_owned(),
+                    vec![Conformance::Unimplemented, Conformance::Full]
+                ),
+            ]
+        );
+    }
+}
@@ -4,7 +4,10 @@ use crate::{Comments, Document};
 use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 
 mod contract_inheritance;
-pub use contract_inheritance::{ContractInheritance, CONTRACT_INHERITANCE_ID};
+pub use contract_inheritance::{BaseLink, ContractInheritance, CONTRACT_INHERITANCE_ID};
+
+mod contract_intro;
+pub use contract_intro::{ContractIntro, CONTRACT_INTRO_ID};
 
 mod inheritdoc;
 pub use inheritdoc::{Inheritdoc, INHERITDOC_ID};
@@ -12,8 +15,95 @@ pub use inheritdoc::{Inheritdoc, INHERITDOC_ID};
 mod git_source;
 pub use git_source::{GitSource, GIT_SOURCE_ID};
 
+mod edit_link;
+pub use edit_link::{EditLink, EDIT_LINK_ID};
+
+mod git_since;
+pub use git_since::{GitSince, GIT_SINCE_ID, SINCE_TAG};
+
+mod build_info;
+pub(crate) use build_info::hardfork_index;
+pub use build_info::{BuildInfo, CompilerSettings, EvmRequirements, BUILD_INFO_ID};
+
+mod script_usage;
+pub use script_usage::{ScriptEntrypoint, ScriptInfo, ScriptUsage, SCRIPT_USAGE_ID};
+
+mod test_plan;
+pub use test_plan::{CheatcodeCategory, TestCase, TestKind, TestPlan, TEST_PLAN_ID};
+
+mod struct_links;
+pub use struct_links::{ImportAliases, StructLinks, IMPORT_ALIASES_ID, STRUCT_LINKS_ID};
+
+mod embed_abi;
+pub use embed_abi::{EmbedAbi, EMBED_ABI_ID};
+
+mod gas_estimates;
+pub use gas_estimates::{GasEstimates, GAS_ESTIMATES_ID, GAS_TAG};
+
+mod deployments;
+pub use deployments::{Deployment, Deployments, DEPLOYMENTS_ID};
+
+mod struct_fields;
+pub use struct_fields::{StructField, StructFields, STRUCT_FIELDS_ID};
+
+mod overload_docs;
+pub use overload_docs::{OverloadDocs, OVERLOAD_DOCS_ID, SEE_OVERLOAD_TAG};
+
+mod security;
+pub use security::{
+    Security, SecurityNote, SecurityNotes, SECURITY_CONTACT_TAG, SECURITY_ID, SECURITY_TAG,
+};
+
+mod dependencies;
+pub use dependencies::{
+    Dependencies, DependencyPackage, DependencyType, TypeUsage, DEPENDENCIES_ID,
+};
+
+mod selector_collisions;
+pub use selector_collisions::{
+    CollidingFunction, SelectorCollision, SelectorCollisions, SELECTOR_COLLISIONS_ID,
+};
+
+mod see_also;
+pub use see_also::{SeeAlso, SeeAlsoEntry, SEE_ALSO_ID, SEE_TAG};
+
+mod standard_bases;
+pub use standard_bases::{StandardBase, StandardBases, STANDARD_BASES_ID};
+
+mod interface_conformance;
+pub use interface_conformance::{
+    Conformance, ConformanceMatrix, InterfaceConformance, INTERFACE_CONFORMANCE_ID,
+};
+
+mod event_layout;
+pub(crate) use event_layout::is_reference;
+pub use event_layout::{EventLayout, LogEncoding, LogLocation, LogSlot, EVENT_LAYOUT_ID};
+
+mod file_types;
+pub use file_types::{FileType, FileTypes, FILE_TYPES_ID};
+
+mod preconditions;
+pub(crate) use preconditions::function_key;
+pub use preconditions::{GuardKind, Precondition, Preconditions, PRECONDITIONS_ID};
+
+mod effective_visibility;
+pub use effective_visibility::{EffectiveVisibility, VisibilityChange, EFFECTIVE_VISIBILITY_ID};
+
+mod evm_compatibility;
+pub use evm_compatibility::{CompatibilityNote, EvmCompatibility, EVM_COMPATIBILITY_ID};
+
+mod assembly_index;
+pub use assembly_index::{AssemblyFunction, AssemblyIndex, ASSEMBLY_INDEX_ID};
+
+mod standard_json;
+pub use standard_json::{StandardJson, StandardJsonInput, STANDARD_JSON_ID};
+
+mod section_limits;
+pub(crate) use section_limits::{continuation_path, member_pages};
+pub use section_limits::{SectionLimits, SectionOverflow, TruncatedSection, SECTION_LIMITS_ID};
+
 /// The preprocessor id.
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub struct PreprocessorId(&'static str);
 
 /// Preprocessor output.
@@ -22,14 +112,80 @@ pub struct PreprocessorId(&'static str);
 #[derive(Debug, Clone)]
 pub enum PreprocessorOutput {
     /// The contract inheritance output.
-    /// The map of contract base idents to the path of the base contract.
-    ContractInheritance(HashMap<String, PathBuf>),
+    /// The map of contract base idents to the links of the base contract.
+    ContractInheritance(HashMap<String, BaseLink>),
     /// The inheritdoc output.
     /// The map of inherited item keys to their comments.
     Inheritdoc(HashMap<String, Comments>),
     /// The git source output.
     /// The git url of the item path.
     GitSource(String),
+    /// The edit link output.
+    /// The url of the editor of the page source on the repository.
+    EditLink(String),
+    /// The script usage output.
+    /// The entry points of the script contract.
+    ScriptUsage(ScriptInfo),
+    /// The test plan output.
+    /// The test functions of the test contract, `setUp` first.
+    TestPlan(Vec<TestCase>),
+    /// The struct links output.
+    /// The map of struct names to the links of their documentation.
+    StructLinks(HashMap<String, String>),
+    /// The import aliases output.
+    /// The aliases of the types imported by the document source.
+    ImportAliases(ImportAliases),
+    /// The struct fields output.
+    /// The map of the struct names to their fields, including the expanded nested fields.
+    StructFields(HashMap<String, Vec<StructField>>),
+    /// The security output.
+    /// The security contacts and notes of the contract.
+    Security(SecurityNotes),
+    /// The deployments output.
+    /// The deployed addresses of the contract, sorted by the chain id.
+    Deployments(Vec<Deployment>),
+    /// The overload docs output.
+    /// The map of the overloaded function names to their shared `@notice` and `@dev` comments.
+    OverloadDocs(HashMap<String, Comments>),
+    /// The embed ABI output.
+    /// The pretty-printed ABI JSON of the contract.
+    EmbedAbi(String),
+    /// The gas estimates output.
+    /// The map of the external function signatures to the compiler's gas estimates.
+    GasEstimates(HashMap<String, String>),
+    /// The standard bases output.
+    /// The well-known bases of the contract, e.g. the OpenZeppelin `ERC20`.
+    StandardBases(Vec<StandardBase>),
+    /// The see also output.
+    /// The related contracts referenced by the `@custom:see` tags of the contract.
+    SeeAlso(Vec<SeeAlsoEntry>),
+    /// The selector collisions output.
+    /// The selector collisions involving the functions of the contract.
+    SelectorCollisions(Vec<SelectorCollision>),
+    /// The event layout output.
+    /// The map of the event signatures to the locations of their parameters in the log.
+    EventLayout(HashMap<String, Vec<LogSlot>>),
+    /// The section limits output.
+    /// The sections cut from the contract page, or the section of the continuation page.
+    SectionLimits(SectionOverflow),
+    /// The file types output.
+    /// The types declared at the file scope of the contract source.
+    FileTypes(Vec<FileType>),
+    /// The standard json output.
+    /// The standard json input of the contract's compilation unit.
+    StandardJson(StandardJsonInput),
+    /// The preconditions output.
+    /// The map of the function keys to the preconditions inferred from their guards.
+    Preconditions(HashMap<String, Vec<Precondition>>),
+    /// The effective visibility output.
+    /// The map of the function keys to their visibility differing from the overridden base.
+    EffectiveVisibility(HashMap<String, VisibilityChange>),
+    /// The EVM compatibility output.
+    /// The compatibility considerations of the contract, e.g. the transient storage.
+    EvmCompatibility(Vec<CompatibilityNote>),
+    /// The contract intro output.
+    /// The hand-written introduction of the contract, with the links rewritten.
+    ContractIntro(String),
 }
 
 /// Trait for preprocessing and/or modifying existing documents
@@ -42,3 +198,27 @@ pub trait Preprocessor: Debug {
     /// Preprocess the collection of documents
     fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error>;
 }
+
+/// Parse the source declared at `path` into the documents of its items, with the targets
+/// of the `docs` output, e.g. `docs/src/src/Vault.sol/contract.Vault.md` for `src/Vault.sol`.
+#[cfg(test)]
+pub(crate) fn test_documents(path: &str, src: &str) -> Vec<Document> {
+    use crate::{document::DocumentContent, Parser};
+    use forge_fmt::Visitable;
+
+    let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+    let mut parser = Parser::new(comments, src.to_owned());
+    source.visit(&mut parser).expect("failed to visit source");
+    parser
+        .items()
+        .into_iter()
+        .map(|item| {
+            let ident = item.source.ident();
+            let target_path = PathBuf::from("docs/src").join(path).join(item.filename());
+            let mut document = Document::new(PathBuf::from(path), target_path)
+                .with_content(DocumentContent::Single(item), ident);
+            document.item_content = src.to_owned();
+            document
+        })
+        .collect()
+}
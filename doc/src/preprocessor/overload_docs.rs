@@ -0,0 +1,167 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent, helpers::function_signature, CommentTag, Comments, Document,
+    ParseItem, ParseSource, PreprocessorOutput,
+};
+use std::collections::HashMap;
+
+/// [OverloadDocs] preprocessor id.
+pub const OVERLOAD_DOCS_ID: PreprocessorId = PreprocessorId("overload_docs");
+
+/// The custom tag deferring to the docs of another overload, i.e.
+/// `@custom:see-overload transfer(address,uint256)`.
+pub const SEE_OVERLOAD_TAG: &str = "see-overload";
+
+/// The overload docs preprocessor.
+///
+/// It resolves the `@custom:see-overload` tags like `@inheritdoc`, but within the overloads
+/// of the same function: the tags missing from the overload are copied from the referenced one.
+/// Then the `@notice` and `@dev` comments identical across all overloads of the function are
+/// collected, so they are rendered once at the group heading.
+///
+/// This preprocessor modifies the comments of the overloads and writes to [Document]'s context.
+#[derive(Default, Debug)]
+#[non_exhaustive]
+pub struct OverloadDocs;
+
+impl Preprocessor for OverloadDocs {
+    fn id(&self) -> PreprocessorId {
+        OVERLOAD_DOCS_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        for document in documents.iter_mut() {
+            let mut shared = HashMap::new();
+            for mut overloads in overload_groups(&mut document.content) {
+                resolve_see_overload(&mut overloads);
+                if let Some(comments) = shared_docs(&overloads) {
+                    shared.insert(overloads[0].source.ident(), comments);
+                }
+            }
+            if !shared.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::OverloadDocs(shared));
+            }
+        }
+        Ok(documents)
+    }
+}
+
+/// Returns the overloads of each function with more than one, i.e. the overloaded
+/// contract functions or the overloaded top-level functions of the document.
+fn overload_groups(content: &mut DocumentContent) -> Vec<Vec<&mut ParseItem>> {
+    let items = match content {
+        DocumentContent::Single(item) if item.as_contract().is_some() => &mut item.children,
+        DocumentContent::OverloadedFunctions(items) => items,
+        _ => return vec![],
+    };
+    let mut groups: Vec<(String, Vec<&mut ParseItem>)> = vec![];
+    for item in items.iter_mut().filter(|item| matches!(item.source, ParseSource::Function(_))) {
+        let ident = item.source.ident();
+        match groups.iter_mut().find(|(name, _)| *name == ident) {
+            Some((_, overloads)) => overloads.push(item),
+            None => groups.push((ident, vec![item])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, overloads)| overloads.len() > 1)
+        .map(|(_, overloads)| overloads)
+        .collect()
+}
+
+/// Copy the tags missing from the overloads with the `@custom:see-overload` tag from the
+/// referenced overloads. The references to the unknown signatures are left as they are.
+fn resolve_see_overload(overloads: &mut [&mut ParseItem]) {
+    let tag = CommentTag::Custom(SEE_OVERLOAD_TAG.to_owned());
+    let signatures = overloads
+        .iter()
+        .map(|item| item.as_function().and_then(function_signature))
+        .collect::<Vec<_>>();
+    let resolved = overloads
+        .iter()
+        .map(|item| {
+            let target = item.comments.include_tag(tag.clone()).first()?.value.trim().to_owned();
+            let target = signatures.iter().position(|signature| {
+                signature.as_deref() == Some(target.replace(' ', "").as_str())
+            })?;
+            let mut comments = Comments::default();
+            for comment in overloads[target].comments.iter() {
+                if comment.tag != tag && !item.comments.contains_tag(comment) {
+                    comments.push(comment.clone());
+                }
+            }
+            Some(comments)
+        })
+        .collect::<Vec<_>>();
+
+    for (item, comments) in overloads.iter_mut().zip(resolved) {
+        if let Some(comments) = comments {
+            item.comments.retain(|comment| comment.tag != tag);
+            item.comments.extend(comments.iter().cloned());
+        }
+    }
+}
+
+/// Returns the `@notice` and `@dev` comments if they are byte-identical across the overloads.
+fn shared_docs(overloads: &[&mut ParseItem]) -> Option<Comments> {
+    let docs = |item: &ParseItem| {
+        let mut comments = Comments::default();
+        comments.extend(
+            item.comments
+                .include_tags(&[CommentTag::Notice, CommentTag::Dev])
+                .iter()
+                .copied()
+                .cloned(),
+        );
+        comments
+    };
+    let first = docs(overloads.first()?);
+    let identical = overloads.iter().all(|item| docs(item) == first);
+    (identical && !first.is_empty()).then_some(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn overload_docs() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposit the assets.
+                /// @param assets The deposited assets.
+                function deposit(uint256 assets) external { }
+
+                /// @notice Deposit the assets.
+                /// @param receiver The shares receiver.
+                function deposit(uint256 assets, address receiver) external { }
+
+                /// @notice Withdraw the assets.
+                /// @dev Burns the shares.
+                /// @param assets The withdrawn assets.
+                function withdraw(uint256 assets) external { }
+
+                /// @custom:see-overload withdraw(uint256)
+                /// @param receiver The assets receiver.
+                function withdraw(uint256 assets, address receiver) external { }
+            }
+        "#;
+        let documents = OverloadDocs.preprocess(test_documents("src/Vault.sol", src)).unwrap();
+        let shared = read_context!(documents[0], OVERLOAD_DOCS_ID, OverloadDocs).unwrap();
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared["deposit"].len(), 1);
+        assert_eq!(shared["withdraw"].len(), 2);
+
+        // The referenced docs are copied, keeping the own parameter docs
+        let withdraw = match documents[0].content {
+            DocumentContent::Single(ref contract) => &contract.children[3].comments,
+            _ => unreachable!(),
+        };
+        assert!(withdraw
+            .iter()
+            .all(|comment| comment.tag != CommentTag::Custom(SEE_OVERLOAD_TAG.to_owned())));
+        assert_eq!(withdraw.include_tag(CommentTag::Param).len(), 2);
+        assert_eq!(withdraw.include_tag(CommentTag::Dev)[0].value.trim(), "Burns the shares.");
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{as_contract, function_signature},
+    Document, PreprocessorOutput,
+};
+use itertools::Itertools;
+use solang_parser::pt::{Expression, FunctionDefinition, IdentifierPath, Statement};
+use std::collections::HashMap;
+
+/// [Preconditions] preprocessor id.
+pub const PRECONDITIONS_ID: PreprocessorId = PreprocessorId("preconditions");
+
+/// The preconditions preprocessor.
+/// It infers the preconditions of the contract functions from the guards at the top level
+/// of their bodies, i.e. the `require(...)` calls and the `if (...) revert ...;` statements,
+/// so the failure conditions are listed on the page even if the author didn't document them.
+///
+/// The analysis is best-effort. The guards nested in the other statements, the modifiers
+/// and the called functions aren't followed.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct Preconditions;
+
+/// The kind of the function guard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardKind {
+    /// The `require(condition, ...)` call, reverting unless the condition holds.
+    Require,
+    /// The `if (condition) revert ...;` statement, reverting if the condition holds.
+    RevertIf,
+}
+
+/// The precondition inferred from the function guard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precondition {
+    /// The guard kind.
+    pub kind: GuardKind,
+    /// The guarded condition, e.g. `amount > 0`.
+    pub condition: String,
+    /// The revert reason, i.e. the message or the custom error, if any.
+    pub reason: Option<String>,
+}
+
+impl Preprocessor for Preconditions {
+    fn id(&self) -> PreprocessorId {
+        PRECONDITIONS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        for (document, item, _) in documents.iter().filter_map(as_contract) {
+            let preconditions = item
+                .functions()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(func, _, _)| {
+                    let preconditions = function_preconditions(func);
+                    (!preconditions.is_empty()).then(|| (function_key(func), preconditions))
+                })
+                .collect::<HashMap<_, _>>();
+            if !preconditions.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::Preconditions(preconditions));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the key of the function preconditions, i.e. the function signature,
+/// or the kind of the unnamed functions, e.g. `constructor`.
+pub(crate) fn function_key(func: &FunctionDefinition) -> String {
+    function_signature(func).unwrap_or_else(|| func.ty.to_string())
+}
+
+/// Returns the preconditions of the guards at the top level of the function body.
+fn function_preconditions(func: &FunctionDefinition) -> Vec<Precondition> {
+    let statements = match func.body {
+        Some(Statement::Block { ref statements, .. }) => statements,
+        _ => return vec![],
+    };
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Expression(_, Expression::FunctionCall(_, callee, args))
+                if is_require(callee) =>
+            {
+                Some(Precondition {
+                    kind: GuardKind::Require,
+                    condition: args.first()?.to_string(),
+                    reason: args.get(1).map(expression_text),
+                })
+            }
+            Statement::If(_, condition, then, None) => Some(Precondition {
+                kind: GuardKind::RevertIf,
+                condition: condition.to_string(),
+                reason: revert_reason(then)?,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns true if the callee is the `require` builtin.
+fn is_require(callee: &Expression) -> bool {
+    matches!(callee, Expression::Variable(ident) if ident.name == "require")
+}
+
+/// Returns the reason of the statement only reverting, [None] if it does anything else.
+fn revert_reason(statement: &Statement) -> Option<Option<String>> {
+    match statement {
+        Statement::Block { statements, unchecked: false, .. } if statements.len() == 1 => {
+            revert_reason(&statements[0])
+        }
+        Statement::Revert(_, Some(error), args) => {
+            let args = args.iter().map(expression_text).join(", ");
+            Some(Some(format!("{}({args})", path_name(error))))
+        }
+        Statement::Revert(_, None, args) => Some(args.first().map(expression_text)),
+        Statement::RevertNamedArgs(_, error, _) => Some(error.as_ref().map(path_name)),
+        _ => None,
+    }
+}
+
+/// Returns the source text of the expression, with the string literals quoted.
+fn expression_text(expr: &Expression) -> String {
+    match expr {
+        Expression::StringLiteral(parts) => {
+            format!("\"{}\"", parts.iter().map(|part| part.string.as_str()).join(""))
+        }
+        expr => expr.to_string(),
+    }
+}
+
+/// Returns the dotted name of the identifier path, e.g. `Errors.Paused`.
+fn path_name(path: &IdentifierPath) -> String {
+    path.identifiers.iter().map(|ident| ident.name.as_str()).join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_function(src: &str) -> FunctionDefinition {
+        let (source, _) = solang_parser::parse(src, 0).expect("failed to parse source");
+        source
+            .0
+            .into_iter()
+            .find_map(|part| match part {
+                solang_parser::pt::SourceUnitPart::FunctionDefinition(func) => Some(*func),
+                _ => None,
+            })
+            .expect("no function")
+    }
+
+    #[test]
+    fn top_level_guards() {
+        let func = parse_function(
+            r#"
+            function withdraw(uint256 amount) {
+                require(amount > 0, "zero amount");
+                require(msg.sender != address(0));
+                if (paused) revert Paused();
+                if (amount > limit) {
+                    revert LimitExceeded(amount, limit);
+                }
+                if (amount == 1) {
+                    total -= 1;
+                    revert("dust");
+                }
+                {
+                    require(nested);
+                }
+                total -= amount;
+            }
+            "#,
+        );
+        let preconditions = function_preconditions(&func)
+            .into_iter()
+            .map(|precondition| (precondition.kind, precondition.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            preconditions,
+            vec![
+                (GuardKind::Require, Some("\"zero amount\"".to_owned())),
+                (GuardKind::Require, None),
+                (GuardKind::RevertIf, Some("Paused()".to_owned())),
+                (GuardKind::RevertIf, Some("LimitExceeded(amount, limit)".to_owned())),
+            ]
+        );
+        assert_eq!(function_key(&func), "withdraw(uint256)");
+    }
+}
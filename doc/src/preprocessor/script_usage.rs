@@ -0,0 +1,206 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{as_contract, forge_std_descendants, function_signature, is_externally_visible},
+    resolver::ImportResolver,
+    Document, PreprocessorOutput,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{FunctionDefinition, FunctionTy};
+use std::path::PathBuf;
+
+/// [ScriptUsage] preprocessor id.
+pub const SCRIPT_USAGE_ID: PreprocessorId = PreprocessorId("script_usage");
+
+/// The script usage preprocessor.
+/// It detects the contracts inheriting from the forge-std `Script`, either directly
+/// or through other documented scripts, and collects their `run` entry points.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct ScriptUsage {
+    /// The project root.
+    pub root: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+}
+
+/// The usage of the script contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptInfo {
+    /// Whether the script defines the `setUp` function.
+    pub set_up: bool,
+    /// The script entry points.
+    pub entrypoints: Vec<ScriptEntrypoint>,
+}
+
+/// The `run` entry point of the script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptEntrypoint {
+    /// The entry point signature, e.g. `run(address)`.
+    pub signature: String,
+    /// The example `forge script` invocation.
+    pub command: String,
+    /// Whether the entry point broadcasts transactions.
+    pub broadcasts: bool,
+}
+
+/// The name of the forge-std script base contract.
+const SCRIPT_BASE: &str = "Script";
+
+impl Preprocessor for ScriptUsage {
+    fn id(&self) -> PreprocessorId {
+        SCRIPT_USAGE_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let scripts = forge_std_descendants(&documents, SCRIPT_BASE, &mut resolver);
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            if !scripts.contains(name) {
+                continue
+            }
+
+            let path = document.item_path.strip_prefix(&self.root)?.display().to_string();
+            let functions = item.functions().unwrap_or_default();
+            let set_up = functions.iter().any(|(func, _, _)| is_named(func, "setUp"));
+            let entrypoints = functions
+                .iter()
+                .filter(|(func, _, _)| is_named(func, "run") && is_externally_visible(func))
+                .filter_map(|(func, _, _)| {
+                    let signature = function_signature(func)?;
+                    let source = document.item_content.get(func.loc.start()..func.loc.end());
+                    let broadcasts = source
+                        .map(|source| {
+                            source.contains("vm.startBroadcast") || source.contains("vm.broadcast")
+                        })
+                        .unwrap_or_default();
+
+                    let mut command = format!("forge script {path}:{name}");
+                    let placeholders = func
+                        .params
+                        .iter()
+                        .filter_map(|(_, param)| param.as_ref())
+                        .map(|param| match param.name {
+                            Some(ref name) => format!("<{}>", name.name),
+                            None => format!("<{}>", param.ty),
+                        })
+                        .collect::<Vec<_>>();
+                    if !placeholders.is_empty() {
+                        command.push_str(&format!(
+                            " --sig \"{signature}\" {}",
+                            placeholders.join(" ")
+                        ));
+                    }
+                    command.push_str(" --rpc-url <RPC_URL>");
+                    if broadcasts {
+                        command.push_str(" --broadcast");
+                    }
+
+                    Some(ScriptEntrypoint { signature, command, broadcasts })
+                })
+                .collect();
+
+            document.add_context(
+                self.id(),
+                PreprocessorOutput::ScriptUsage(ScriptInfo { set_up, entrypoints }),
+            );
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns true if the function is a regular function with the given name.
+fn is_named(func: &FunctionDefinition, name: &str) -> bool {
+    matches!(func.ty, FunctionTy::Function) &&
+        func.name.as_ref().map(|ident| ident.name == name).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    fn documents(src: &str) -> Vec<Document> {
+        let mut documents = test_documents("script/Deploy.s.sol", src);
+        for document in documents.iter_mut() {
+            document.item_path = PathBuf::from("/root").join(&document.item_path);
+        }
+        documents
+    }
+
+    #[test]
+    fn script_entrypoints() {
+        let src = r#"
+            import {Script} from "forge-std/Script.sol";
+
+            contract Base is Script {
+                function setUp() public { }
+            }
+
+            contract Deploy is Base {
+                function run() external {
+                    vm.startBroadcast();
+                    vm.stopBroadcast();
+                }
+
+                function run(address owner, uint256) external view { }
+            }
+        "#;
+        let documents = documents(src);
+
+        let documents = ScriptUsage { root: PathBuf::from("/root"), remappings: vec![] }
+            .preprocess(documents)
+            .unwrap();
+        let base = read_context!(documents[0], SCRIPT_USAGE_ID, ScriptUsage).unwrap();
+        assert!(base.set_up);
+        assert!(base.entrypoints.is_empty());
+
+        let deploy = read_context!(documents[1], SCRIPT_USAGE_ID, ScriptUsage).unwrap();
+        assert!(!deploy.set_up);
+        assert_eq!(
+            deploy.entrypoints,
+            vec![
+                ScriptEntrypoint {
+                    signature: "run()".to_owned(),
+                    command: "forge script script/Deploy.s.sol:Deploy --rpc-url <RPC_URL> --broadcast"
+                        .to_owned(),
+                    broadcasts: true,
+                },
+                ScriptEntrypoint {
+                    signature: "run(address,uint256)".to_owned(),
+                    command: "forge script script/Deploy.s.sol:Deploy --sig \"run(address,uint256)\" <owner> <uint256> --rpc-url <RPC_URL>"
+                        .to_owned(),
+                    broadcasts: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn forge_std_script_base() {
+        let src = r#"
+            import {Script as StdScript} from "std/Script.sol";
+            import {Script} from "./Script.sol";
+
+            contract Deploy is StdScript {
+                function run() external { }
+            }
+
+            contract Migrate is Script {
+                function run() external { }
+            }
+        "#;
+        let documents = documents(src);
+
+        let remappings = vec!["std/=lib/forge-std/src/".parse().unwrap()];
+        let documents =
+            ScriptUsage { root: PathBuf::from("/root"), remappings }.preprocess(documents).unwrap();
+        assert!(read_context!(documents[0], SCRIPT_USAGE_ID, ScriptUsage).is_some());
+        // The project `Script` isn't the forge-std one
+        assert!(read_context!(documents[1], SCRIPT_USAGE_ID, ScriptUsage).is_none());
+    }
+}
@@ -0,0 +1,268 @@
+use super::{
+    Preprocessor, PreprocessorId, CONTRACT_INHERITANCE_ID, EDIT_LINK_ID, EFFECTIVE_VISIBILITY_ID,
+    EVENT_LAYOUT_ID, GAS_ESTIMATES_ID, GIT_SOURCE_ID, IMPORT_ALIASES_ID, INHERITDOC_ID,
+    OVERLOAD_DOCS_ID, PRECONDITIONS_ID, SELECTOR_COLLISIONS_ID, STANDARD_BASES_ID,
+    STRUCT_FIELDS_ID, STRUCT_LINKS_ID,
+};
+use crate::{
+    document::DocumentContent, Comments, Document, ParseItem, ParseSource, PreprocessorOutput,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// [SectionLimits] preprocessor id.
+pub const SECTION_LIMITS_ID: PreprocessorId = PreprocessorId("section_limits");
+
+/// The sections of the contract page capped by the [SectionLimits], in the page order.
+const SECTIONS: [&str; 6] =
+    ["State Variables", "Functions", "Events", "Errors", "Structs", "Enums"];
+
+/// The context of the contract page read by the members of its sections.
+/// It's copied to the continuation pages, so the members render the same on either page.
+const MEMBER_CONTEXT: [PreprocessorId; 14] = [
+    GIT_SOURCE_ID,
+    EDIT_LINK_ID,
+    CONTRACT_INHERITANCE_ID,
+    INHERITDOC_ID,
+    OVERLOAD_DOCS_ID,
+    GAS_ESTIMATES_ID,
+    SELECTOR_COLLISIONS_ID,
+    STANDARD_BASES_ID,
+    STRUCT_LINKS_ID,
+    STRUCT_FIELDS_ID,
+    IMPORT_ALIASES_ID,
+    EVENT_LAYOUT_ID,
+    PRECONDITIONS_ID,
+    EFFECTIVE_VISIBILITY_ID,
+];
+
+/// The section of the contract page cut at the section limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruncatedSection {
+    /// The section title, e.g. `Functions`.
+    pub section: String,
+    /// The number of the members written on the contract page.
+    pub shown: usize,
+    /// The number of the members moved to the continuation page.
+    pub remaining: usize,
+    /// The target path of the continuation page.
+    pub page: PathBuf,
+}
+
+/// The section limits output of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionOverflow {
+    /// The sections of the contract page cut at the limit.
+    Truncated(Vec<TruncatedSection>),
+    /// The continuation page of the section, with the target path of the contract page.
+    Continuation { section: String, page: PathBuf },
+}
+
+/// The section limits preprocessor.
+/// It caps the number of the members in each section of the contract page, e.g. the
+/// functions of the generated contracts with hundreds of them, and moves the rest of
+/// the members to the continuation page of the section, linked from the contract page.
+///
+/// The continuation pages are added as the [DocumentContent::Continuation] documents
+/// next to the contract document, so they're listed in the summary.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct SectionLimits {
+    /// The maximum number of the members of each section. `0` disables the limits.
+    pub max_items: usize,
+}
+
+impl Preprocessor for SectionLimits {
+    fn id(&self) -> PreprocessorId {
+        SECTION_LIMITS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        if self.max_items == 0 {
+            return Ok(documents)
+        }
+
+        let mut processed = Vec::with_capacity(documents.len());
+        for document in documents {
+            let continuations = self.continuations(&document);
+            processed.push(document);
+            processed.extend(continuations);
+        }
+
+        Ok(processed)
+    }
+}
+
+impl SectionLimits {
+    /// Returns the continuation pages of the contract sections over the limit,
+    /// noting the truncated sections in the context of the contract document.
+    fn continuations(&self, document: &Document) -> Vec<Document> {
+        let item = match document.content {
+            DocumentContent::Single(ref item) if item.as_contract().is_some() => item,
+            _ => return vec![],
+        };
+
+        let mut truncated = vec![];
+        let mut continuations = vec![];
+        for section in SECTIONS {
+            let overflow = item
+                .children
+                .iter()
+                .filter(|child| section_title(&child.source) == Some(section))
+                .skip(self.max_items)
+                .cloned()
+                .collect::<Vec<_>>();
+            if overflow.is_empty() {
+                continue
+            }
+
+            let target_path = continuation_path(&document.target_path, section);
+            truncated.push(TruncatedSection {
+                section: section.to_owned(),
+                shown: self.max_items,
+                remaining: overflow.len(),
+                page: target_path.clone(),
+            });
+
+            let content = DocumentContent::Continuation(ParseItem {
+                source: item.source.clone(),
+                comments: Comments::default(),
+                children: overflow,
+                code: item.code.clone(),
+            });
+            let mut continuation = Document::new(document.item_path.clone(), target_path)
+                .with_content(content, format!("{section} (continued)"));
+            continuation.item_content = document.item_content.clone();
+            for id in MEMBER_CONTEXT {
+                if let Some(output) = document.get_from_context(id) {
+                    continuation.add_context(id, output);
+                }
+            }
+            continuation.add_context(
+                self.id(),
+                PreprocessorOutput::SectionLimits(SectionOverflow::Continuation {
+                    section: section.to_owned(),
+                    page: document.target_path.clone(),
+                }),
+            );
+            continuations.push(continuation);
+        }
+
+        if !truncated.is_empty() {
+            document.add_context(
+                self.id(),
+                PreprocessorOutput::SectionLimits(SectionOverflow::Truncated(truncated)),
+            );
+        }
+        continuations
+    }
+}
+
+/// Returns the continuation page of the section next to the contract page,
+/// e.g. `contract.Vault.functions.md` next to `contract.Vault.md`.
+pub(crate) fn continuation_path(page: &Path, section: &str) -> PathBuf {
+    let stem = page.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let slug = section.to_lowercase().replace(' ', "-");
+    page.with_file_name(format!("{stem}.{slug}.md"))
+}
+
+/// Returns the title of the contract page section listing the member.
+fn section_title(source: &ParseSource) -> Option<&'static str> {
+    match source {
+        ParseSource::Variable(_) => Some("State Variables"),
+        ParseSource::Function(_) => Some("Functions"),
+        ParseSource::Event(_) => Some("Events"),
+        ParseSource::Error(_) => Some("Errors"),
+        ParseSource::Struct(_) => Some("Structs"),
+        ParseSource::Enum(_) => Some("Enums"),
+        ParseSource::Contract(_) | ParseSource::Type(_) => None,
+    }
+}
+
+/// Returns the continuation page of each member of the contract,
+/// [None] for the members written on the contract page.
+pub(crate) fn member_pages<'a>(
+    item: &ParseItem,
+    truncated: &'a [TruncatedSection],
+) -> Vec<Option<&'a PathBuf>> {
+    let mut counts = HashMap::new();
+    item.children
+        .iter()
+        .map(|child| {
+            let section = section_title(&child.source)?;
+            let count = counts.entry(section).or_insert(0);
+            *count += 1;
+            truncated
+                .iter()
+                .find(|truncated| truncated.section == section && *count > truncated.shown)
+                .map(|truncated| &truncated.page)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+
+    #[test]
+    fn continuation_pages() {
+        let src = r#"
+            contract Vault {
+                uint256 public total;
+                event Deposit(uint256 amount);
+                function a() external {}
+                function b() external {}
+                function c() external {}
+            }
+        "#;
+        let mut documents = test_documents("src/Vault.sol", src);
+        documents[0].book_src = PathBuf::from("docs/src");
+
+        let documents = SectionLimits { max_items: 2 }.preprocess(documents).unwrap();
+        assert_eq!(documents.len(), 2);
+        let page = PathBuf::from("docs/src/src/Vault.sol/contract.Vault.functions.md");
+        assert_eq!(documents[1].target_path, page);
+        assert_eq!(documents[1].identity, "Functions (continued)");
+        match documents[1].content {
+            DocumentContent::Continuation(ref item) => {
+                assert_eq!(item.children.len(), 1);
+                assert_eq!(item.children[0].source.ident(), "c");
+            }
+            _ => panic!("expected the continuation page"),
+        }
+
+        let truncated = vec![TruncatedSection {
+            section: "Functions".to_owned(),
+            shown: 2,
+            remaining: 1,
+            page: page.clone(),
+        }];
+        match documents[0].get_from_context(SECTION_LIMITS_ID) {
+            Some(PreprocessorOutput::SectionLimits(SectionOverflow::Truncated(sections))) => {
+                assert_eq!(sections, truncated)
+            }
+            _ => panic!("expected the truncated sections"),
+        }
+        let item = match documents[0].content {
+            DocumentContent::Single(ref item) => item,
+            _ => panic!("expected the contract page"),
+        };
+        assert_eq!(member_pages(item, &truncated), vec![None, None, None, None, Some(&page)]);
+
+        let main = documents[0].as_doc().unwrap();
+        assert!(main.contains("### b"));
+        assert!(!main.contains("### c"));
+        assert!(main.contains(
+            "> Truncated, 1 more items: \
+             [Functions (continued)](/src/Vault.sol/contract.Vault.functions.md)"
+        ));
+        let continuation = documents[1].as_doc().unwrap();
+        assert!(continuation.starts_with("# Vault (continued)"));
+        assert!(continuation.contains("### c"));
+        assert!(!continuation.contains("### a"));
+    }
+}
@@ -0,0 +1,173 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent, helpers::as_contract, CommentTag, Comments, DocBuilder, Document,
+    PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::path::{Path, PathBuf};
+
+/// [Security] preprocessor id.
+pub const SECURITY_ID: PreprocessorId = PreprocessorId("security");
+
+/// The custom tag of the security considerations, i.e. `@custom:security`.
+pub const SECURITY_TAG: &str = "security";
+
+/// The custom tag of the security contact, i.e. `@custom:security-contact`.
+pub const SECURITY_CONTACT_TAG: &str = "security-contact";
+
+/// The security preprocessor.
+/// It aggregates the `@custom:security-contact` tags of the contracts and the
+/// `@custom:security` notes of the contracts and their members into the per-contract
+/// [SecurityNotes], and appends the project-wide appendix listing them all.
+///
+/// The notes keep the order of appearance. The identical notes of the same member,
+/// e.g. repeated across the overloads, are listed once.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct Security {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+}
+
+/// The security considerations of the contract.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityNotes {
+    /// The contract name.
+    pub contract: String,
+    /// The link to the contract documentation.
+    pub link: String,
+    /// The security contacts.
+    pub contacts: Vec<String>,
+    /// The security notes.
+    pub notes: Vec<SecurityNote>,
+}
+
+/// The `@custom:security` note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityNote {
+    /// The name of the flagged member, or [None] for the notes of the contract itself.
+    pub member: Option<String>,
+    /// The note text.
+    pub text: String,
+}
+
+impl SecurityNotes {
+    /// Returns true if there are neither contacts nor notes.
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty() && self.notes.is_empty()
+    }
+
+    fn add_notes(&mut self, member: Option<&str>, comments: &Comments) {
+        for comment in comments.include_tag(CommentTag::Custom(SECURITY_TAG.to_owned())).iter() {
+            let note = SecurityNote {
+                member: member.map(str::to_owned),
+                text: comment.value.trim().replace('\n', " "),
+            };
+            if !note.text.is_empty() && !self.notes.contains(&note) {
+                self.notes.push(note);
+            }
+        }
+    }
+}
+
+impl Preprocessor for Security {
+    fn id(&self) -> PreprocessorId {
+        SECURITY_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        let mut appendix = vec![];
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let page = document.target_path.strip_prefix(&out_src).unwrap_or(&document.target_path);
+            let mut security = SecurityNotes {
+                contract: contract.name.safe_unwrap().name.clone(),
+                link: Path::new("/").join(page).display().to_string(),
+                ..Default::default()
+            };
+            for comment in item
+                .comments
+                .include_tag(CommentTag::Custom(SECURITY_CONTACT_TAG.to_owned()))
+                .iter()
+            {
+                let contact = comment.value.trim().to_owned();
+                if !contact.is_empty() && !security.contacts.contains(&contact) {
+                    security.contacts.push(contact);
+                }
+            }
+            security.add_notes(None, &item.comments);
+            for child in item.children.iter() {
+                security.add_notes(Some(&child.source.ident()), &child.comments);
+            }
+
+            if !security.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::Security(security.clone()));
+                appendix.push(security);
+            }
+        }
+
+        if !appendix.is_empty() {
+            let target_path = out_src.join("appendix/security.md");
+            documents.push(Document::new(self.root.clone(), target_path).with_content(
+                DocumentContent::Security(appendix),
+                "Security Considerations".to_owned(),
+            ));
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn security_notes() {
+        let src = r#"
+            /// @custom:security-contact security@example.com
+            /// @custom:security Upgradeable through the proxy admin.
+            contract Vault {
+                /// @custom:security Reentrancy guarded.
+                function withdraw(uint256 amount) external { }
+
+                /// @custom:security Reentrancy guarded.
+                function withdraw(uint256 amount, address to) external { }
+
+                /// @custom:security Trusts the oracle price.
+                function liquidate() external { }
+            }
+
+            contract Token { }
+        "#;
+        let documents = Security { root: PathBuf::new(), out: PathBuf::from("docs") }
+            .preprocess(test_documents("src/Vault.sol", src))
+            .unwrap();
+        let note = |member: Option<&str>, text: &str| SecurityNote {
+            member: member.map(str::to_owned),
+            text: text.to_owned(),
+        };
+        let expected = SecurityNotes {
+            contract: "Vault".to_owned(),
+            link: "/src/Vault.sol/contract.Vault.md".to_owned(),
+            contacts: vec!["security@example.com".to_owned()],
+            notes: vec![
+                note(None, "Upgradeable through the proxy admin."),
+                note(Some("withdraw"), "Reentrancy guarded."),
+                note(Some("liquidate"), "Trusts the oracle price."),
+            ],
+        };
+        assert_eq!(read_context!(documents[0], SECURITY_ID, Security), Some(expected.clone()));
+        assert_eq!(read_context!(documents[1], SECURITY_ID, Security), None);
+
+        assert_eq!(documents.len(), 3);
+        assert!(matches!(
+            documents[2].content,
+            DocumentContent::Security(ref appendix) if appendix == &vec![expected]
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::as_contract, CommentTag, Diagnostics, DocDiagnostic, Document, PreprocessorOutput,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+/// [SeeAlso] preprocessor id.
+pub const SEE_ALSO_ID: PreprocessorId = PreprocessorId("see_also");
+
+/// The custom tag cross-referencing the related contract, i.e. `@custom:see Vault`.
+pub const SEE_TAG: &str = "see";
+
+/// The related contract referenced by the `@custom:see` tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeeAlsoEntry {
+    /// The referenced contract name.
+    pub name: String,
+    /// The description following the name, if any.
+    pub description: String,
+    /// The page of the referenced contract, or [None] if it isn't documented.
+    pub path: Option<PathBuf>,
+}
+
+/// The see also preprocessor.
+/// It resolves the contracts referenced by the `@custom:see <ContractName>` tags of each
+/// contract to their pages, so the related contracts outside the inheritance are linked.
+///
+/// The names not matching any documented contract are reported and rendered as plain text.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct SeeAlso {
+    /// The project root.
+    pub root: PathBuf,
+    /// The collector of the unresolved names diagnostics.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+impl Preprocessor for SeeAlso {
+    fn id(&self) -> PreprocessorId {
+        SEE_ALSO_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let pages = documents
+            .iter()
+            .filter_map(as_contract)
+            .map(|(document, _, contract)| {
+                (contract.name.safe_unwrap().name.clone(), document.target_path.clone())
+            })
+            .collect::<HashMap<_, _>>();
+
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let entries = item
+                .comments
+                .iter()
+                .filter(|comment| comment.tag == CommentTag::Custom(SEE_TAG.to_owned()))
+                .filter_map(|comment| {
+                    let value = comment.value.trim();
+                    let (name, description) = value.split_once(' ').unwrap_or((value, ""));
+                    (!name.is_empty()).then(|| SeeAlsoEntry {
+                        name: name.to_owned(),
+                        description: description.trim().to_owned(),
+                        path: pages.get(name).cloned(),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for entry in entries.iter().filter(|entry| entry.path.is_none()) {
+                let source =
+                    document.item_path.strip_prefix(&self.root).unwrap_or(&document.item_path);
+                self.diagnostics.push(
+                    DocDiagnostic::warning(
+                        "see-unresolved",
+                        format!(
+                            "`@custom:see {}` doesn't match any documented contract",
+                            entry.name
+                        ),
+                    )
+                    .with_location(source.to_path_buf(), None)
+                    .with_item(&contract.name.safe_unwrap().name),
+                );
+            }
+            if !entries.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::SeeAlso(entries));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn see_also_links() {
+        let src = r#"
+            /// @custom:see Oracle The price source.
+            /// @custom:see Router
+            contract Vault { }
+
+            contract Oracle { }
+        "#;
+        let diagnostics = Arc::new(Diagnostics::default());
+        let documents = SeeAlso { root: PathBuf::new(), diagnostics: diagnostics.clone() }
+            .preprocess(test_documents("src/Vault.sol", src))
+            .unwrap();
+        assert_eq!(
+            read_context!(documents[0], SEE_ALSO_ID, SeeAlso),
+            Some(vec![
+                SeeAlsoEntry {
+                    name: "Oracle".to_owned(),
+                    description: "The price source.".to_owned(),
+                    path: Some(PathBuf::from("docs/src/src/Vault.sol/contract.Oracle.md")),
+                },
+                SeeAlsoEntry { name: "Router".to_owned(), description: String::new(), path: None },
+            ])
+        );
+
+        // The unresolved name is reported
+        let diagnostics = diagnostics.take();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].item.as_deref(), Some("Vault"));
+    }
+}
@@ -0,0 +1,270 @@
+use super::{
+    interface_conformance::{inheritance, signatures},
+    Preprocessor, PreprocessorId,
+};
+use crate::{
+    document::DocumentContent,
+    helpers::{as_contract, selector},
+    resolver::ImportResolver,
+    Diagnostics, DocBuilder, DocDiagnostic, Document, ParseItem, PreprocessorOutput,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// [SelectorCollisions] preprocessor id.
+pub const SELECTOR_COLLISIONS_ID: PreprocessorId = PreprocessorId("selector_collisions");
+
+/// The distinct functions sharing the 4-byte selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorCollision {
+    /// The shared selector, e.g. `0x42966c68`.
+    pub selector: String,
+    /// The colliding functions, ordered by the contract name and source.
+    pub functions: Vec<CollidingFunction>,
+    /// Whether any of the contracts inherits from another one, i.e. they sit behind
+    /// the same proxy, which is the practically dangerous case.
+    pub inherited: bool,
+}
+
+/// The function of the [SelectorCollision].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollidingFunction {
+    /// The contract name.
+    pub contract: String,
+    /// The contract source.
+    pub source: PathBuf,
+    /// The function signature, e.g. `burn(uint256)`.
+    pub signature: String,
+    /// The link to the contract documentation.
+    pub link: String,
+}
+
+/// The selector collisions preprocessor.
+/// It computes the selectors of the external and public functions and the public getters
+/// of the documented contracts, including the inherited ones, and reports the distinct
+/// signatures sharing a selector. The contracts are keyed by their source and the bases
+/// are resolved through the imports, the same way as in
+/// [InterfaceConformance](super::InterfaceConformance).
+///
+/// The colliding functions are flagged on the pages of their contracts, and the project-wide
+/// appendix lists all collisions. The signatures with the user defined types are skipped,
+/// since their selectors can't be computed without resolving the types.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct SelectorCollisions {
+    /// The project root.
+    pub root: PathBuf,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+    /// The collector of the collision diagnostics.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+impl Preprocessor for SelectorCollisions {
+    fn id(&self) -> PreprocessorId {
+        SELECTOR_COLLISIONS_ID
+    }
+
+    fn preprocess(&self, mut documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        let mut contracts: HashMap<String, Vec<(PathBuf, &ParseItem)>> = HashMap::new();
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            contracts
+                .entry(contract.name.safe_unwrap().name.clone())
+                .or_default()
+                .push((document.item_path.clone(), item));
+        }
+
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let mut by_selector: BTreeMap<String, Vec<CollidingFunction>> = BTreeMap::new();
+        let mut ancestors = HashMap::new();
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            let name = contract.name.safe_unwrap().name.clone();
+            let page = document.target_path.strip_prefix(&out_src).unwrap_or(&document.target_path);
+            let link = Path::new("/").join(page).display().to_string();
+            let inheritance = inheritance(&document.item_path, item, &contracts, &mut resolver);
+            for signature in signatures(&inheritance) {
+                by_selector.entry(selector(&signature)).or_default().push(CollidingFunction {
+                    contract: name.clone(),
+                    source: document.item_path.clone(),
+                    signature,
+                    link: link.clone(),
+                });
+            }
+            let bases = inheritance.iter().skip(1).filter_map(|(path, base)| {
+                base.as_contract().map(|base| (path.clone(), base.name.safe_unwrap().name.clone()))
+            });
+            ancestors.insert((document.item_path.clone(), name), bases.collect::<HashSet<_>>());
+        }
+
+        let key =
+            |function: &CollidingFunction| (function.source.clone(), function.contract.clone());
+        let inherits = |derived: &CollidingFunction, base: &CollidingFunction| {
+            key(derived) == key(base) ||
+                ancestors
+                    .get(&key(derived))
+                    .map(|bases| bases.contains(&key(base)))
+                    .unwrap_or_default()
+        };
+        let collisions = by_selector
+            .into_iter()
+            .filter(|(_, functions)| {
+                functions.iter().map(|function| &function.signature).unique().count() > 1
+            })
+            .map(|(selector, mut functions)| {
+                functions.sort_by(|a, b| (&a.contract, &a.source).cmp(&(&b.contract, &b.source)));
+                let inherited = functions
+                    .iter()
+                    .tuple_combinations()
+                    .any(|(a, b)| a.signature != b.signature && (inherits(a, b) || inherits(b, a)));
+                SelectorCollision { selector, functions, inherited }
+            })
+            .collect::<Vec<_>>();
+
+        for collision in collisions.iter() {
+            let functions = collision
+                .functions
+                .iter()
+                .map(|function| format!("`{}` of `{}`", function.signature, function.contract))
+                .join(", ");
+            let tree = if collision.inherited { " along the same inheritance chain" } else { "" };
+            self.diagnostics.push(DocDiagnostic::warning(
+                "selector-collision",
+                format!("selector {} is shared by {functions}{tree}", collision.selector),
+            ));
+        }
+
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            let flagged = collisions
+                .iter()
+                .filter(|collision| {
+                    collision.functions.iter().any(|function| {
+                        function.contract == *name && function.source == document.item_path
+                    })
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if !flagged.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::SelectorCollisions(flagged));
+            }
+        }
+
+        if !collisions.is_empty() {
+            let target_path = out_src.join("appendix/selector-collisions.md");
+            documents.push(Document::new(self.root.clone(), target_path).with_content(
+                DocumentContent::SelectorCollisions(collisions),
+                "Selector Collisions".to_owned(),
+            ));
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn colliding_selectors() {
+        // `burn(uint256)` and `collate_propagate_storage(bytes16)` share `0x42966c68`,
+        // `transferFrom(address,address,uint256)` and `gasprice_bit_ether(int128)` share
+        // `0x23b872dd`
+        let src = r#"
+            contract Token {
+                function burn(uint256 amount) external { }
+                function totalSupply() external view returns (uint256) { }
+            }
+
+            contract Proxy {
+                function collate_propagate_storage(bytes16 slot) external { }
+            }
+
+            abstract contract Context { }
+            contract Upgradeable is Context {
+                function collate_propagate_storage(bytes16 slot) public { }
+            }
+            contract Burnable is Context {
+                function burn(uint256 amount) public { }
+                function totalSupply() public view returns (uint256) { }
+            }
+
+            contract Ledger {
+                function transferFrom(address from, address to, uint256 amount) external { }
+            }
+            contract Vault is Ledger {
+                function gasprice_bit_ether(int128 price) external { }
+            }
+        "#;
+        let diagnostics = Arc::new(Diagnostics::default());
+        let documents = SelectorCollisions {
+            root: PathBuf::new(),
+            out: PathBuf::from("docs"),
+            remappings: vec![],
+            diagnostics: diagnostics.clone(),
+        }
+        .preprocess(test_documents("src/Vault.sol", src))
+        .unwrap();
+        let functions = |collision: &SelectorCollision| {
+            collision
+                .functions
+                .iter()
+                .map(|function| (function.contract.clone(), function.signature.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        // The shared `totalSupply()` isn't a collision
+        let collision =
+            read_context!(documents[0], SELECTOR_COLLISIONS_ID, SelectorCollisions).unwrap();
+        assert_eq!(collision.len(), 1);
+        assert_eq!(collision[0].selector, "0x42966c68");
+        assert_eq!(
+            functions(&collision[0]),
+            vec![
+                ("Burnable".to_owned(), "burn(uint256)".to_owned()),
+                ("Proxy".to_owned(), "collate_propagate_storage(bytes16)".to_owned()),
+                ("Token".to_owned(), "burn(uint256)".to_owned()),
+                ("Upgradeable".to_owned(), "collate_propagate_storage(bytes16)".to_owned()),
+            ]
+        );
+        // `Burnable` and `Upgradeable` only share the `Context`
+        assert!(!collision[0].inherited);
+        assert_eq!(read_context!(documents[2], SELECTOR_COLLISIONS_ID, SelectorCollisions), None);
+
+        // `Vault` inherits the `transferFrom` of the `Ledger`
+        let collision =
+            read_context!(documents[6], SELECTOR_COLLISIONS_ID, SelectorCollisions).unwrap();
+        assert_eq!(collision.len(), 1);
+        assert_eq!(collision[0].selector, "0x23b872dd");
+        assert_eq!(
+            functions(&collision[0]),
+            vec![
+                ("Ledger".to_owned(), "transferFrom(address,address,uint256)".to_owned()),
+                ("Vault".to_owned(), "gasprice_bit_ether(int128)".to_owned()),
+                ("Vault".to_owned(), "transferFrom(address,address,uint256)".to_owned()),
+            ]
+        );
+        assert!(collision[0].inherited);
+
+        assert!(matches!(
+            documents.last().unwrap().content,
+            DocumentContent::SelectorCollisions(ref collisions) if collisions.len() == 2
+        ));
+        let diagnostics = diagnostics.take();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].to_string().ends_with("along the same inheritance chain"));
+        assert!(!diagnostics[1].to_string().ends_with("along the same inheritance chain"));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent,
+    helpers::{function_signature, walk_expressions},
+    resolver::imports,
+    CommentTag, Document, ParseItem, ParseSource, PreprocessorOutput,
+};
+use solang_parser::pt::{Expression, FunctionAttribute, FunctionDefinition, Import, Statement};
+use std::path::Path;
+
+/// [StandardBases] preprocessor id.
+pub const STANDARD_BASES_ID: PreprocessorId = PreprocessorId("standard_bases");
+
+/// The well-known base of the contract, e.g. the OpenZeppelin `ERC20`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardBase {
+    /// The base name, as inherited by the contract.
+    pub name: String,
+    /// The import path of the base, e.g. `@openzeppelin/contracts/token/ERC20/ERC20.sol`.
+    pub path: String,
+    /// The signatures of the contract functions which only forward to the base,
+    /// with the docs inherited from it.
+    pub forwarded: Vec<String>,
+}
+
+/// The standard bases preprocessor.
+/// It matches the bases of each contract against the configured import paths of
+/// the well-known bases, e.g. the OpenZeppelin or solmate `ERC20`, so the contract page
+/// notes the standard interface once instead of listing the boilerplate functions.
+///
+/// The functions overriding the base which only call `super` and only `@inheritdoc`
+/// the base are collapsed into the note, the other overrides and additions are listed.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct StandardBases {
+    /// The import paths of the well-known bases, matched by suffix,
+    /// e.g. `openzeppelin-contracts/contracts/token/ERC20/ERC20.sol` or `tokens/ERC20.sol`.
+    pub bases: Vec<String>,
+}
+
+impl Preprocessor for StandardBases {
+    fn id(&self) -> PreprocessorId {
+        STANDARD_BASES_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        if self.bases.is_empty() {
+            return Ok(documents)
+        }
+
+        for document in documents.iter() {
+            let item = match document.content {
+                DocumentContent::Single(ref item) if item.as_contract().is_some() => item,
+                _ => continue,
+            };
+            let bases = self.standard_bases(item, &document.item_content);
+            if !bases.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::StandardBases(bases));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+impl StandardBases {
+    /// Returns the standard bases of the contract imported by its source.
+    fn standard_bases(&self, item: &ParseItem, source: &str) -> Vec<StandardBase> {
+        let contract = match item.as_contract() {
+            Some(contract) => contract,
+            None => return vec![],
+        };
+        let source_unit = match solang_parser::parse(source, 0) {
+            Ok((source_unit, _)) => source_unit,
+            Err(_) => return vec![],
+        };
+
+        // The names imported from the standard bases, by their import path
+        let mut imported = vec![];
+        for import in imports(source_unit) {
+            match import {
+                Import::Plain(path, _) if self.is_standard(&path.string) => {
+                    // The plain import is matched by the file name, e.g. `ERC20` for `ERC20.sol`
+                    if let Some(stem) = Path::new(&path.string).file_stem() {
+                        imported.push((stem.to_string_lossy().into_owned(), path.string));
+                    }
+                }
+                Import::Rename(path, symbols, _) if self.is_standard(&path.string) => {
+                    for (symbol, alias) in symbols {
+                        let name = alias.unwrap_or(symbol).name;
+                        imported.push((name, path.string.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        contract
+            .base
+            .iter()
+            .filter_map(|base| {
+                let name = &base.name.identifiers.last()?.name;
+                let (_, path) = imported.iter().find(|(imported, _)| imported == name)?;
+                let forwarded = item
+                    .children
+                    .iter()
+                    .filter_map(|child| match child.source {
+                        ParseSource::Function(ref func) if is_forwarded(child, func, name) => {
+                            function_signature(func)
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                Some(StandardBase { name: name.clone(), path: path.clone(), forwarded })
+            })
+            .collect()
+    }
+
+    /// Returns true if the import path is one of the configured standard bases.
+    fn is_standard(&self, path: &str) -> bool {
+        self.bases.iter().any(|base| path.ends_with(base.as_str()))
+    }
+}
+
+/// Returns true if the function overrides the base without adding anything, i.e.
+/// its body is the only statement calling the same function of `super`, without calling
+/// anything else, and its docs are only `@inheritdoc` the base.
+fn is_forwarded(item: &ParseItem, func: &FunctionDefinition, base: &str) -> bool {
+    let name = match func.name {
+        Some(ref name) => &name.name,
+        None => return false,
+    };
+    let is_override =
+        func.attributes.iter().any(|attr| matches!(attr, FunctionAttribute::Override(..)));
+    let inherits_docs = matches!(
+        item.comments.as_slice(),
+        [comment] if comment.tag == CommentTag::Inheritdoc && comment.value.trim() == base
+    );
+    let statement = match func.body {
+        Some(Statement::Block { ref statements, .. }) => match statements.as_slice() {
+            [statement @ (Statement::Return(_, Some(_)) | Statement::Expression(..))] => statement,
+            _ => return false,
+        },
+        _ => return false,
+    };
+    // The arguments are forwarded as they are, e.g. `super.transfer(to, amount)`
+    let mut calls = vec![];
+    walk_expressions(statement, &mut |expr| {
+        if let Expression::FunctionCall(_, callee, _) = expr {
+            calls.push(callee.as_ref());
+        }
+    });
+    let calls_super = match calls.as_slice() {
+        [Expression::MemberAccess(_, target, member)] => {
+            member.name == *name &&
+                matches!(target.as_ref(), Expression::Variable(ident) if ident.name == "super")
+        }
+        _ => false,
+    };
+    is_override && inherits_docs && calls_super
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn forwarded_overrides() {
+        let src = r#"
+            import {ERC20} from "@openzeppelin/contracts/token/ERC20/ERC20.sol";
+            import "./Pausable.sol";
+
+            contract Token is ERC20, Pausable {
+                /// @inheritdoc ERC20
+                function decimals() public view override returns (uint8) {
+                    return super.decimals();
+                }
+
+                /// @inheritdoc ERC20
+                function transfer(address to, uint256 amount) public override returns (bool) {
+                    require(!paused());
+                    return super.transfer(to, amount);
+                }
+
+                /// @inheritdoc ERC20
+                function approve(address spender, uint256 amount) public override returns (bool) {
+                    return super.approve(_spender(spender), amount);
+                }
+
+                function name() public view override returns (string memory) {
+                    return super.name();
+                }
+
+                /// @inheritdoc ERC20
+                function symbol() public view override returns (string memory) { }
+
+                /// @notice Mint the tokens.
+                function mint(address to, uint256 amount) external { }
+            }
+        "#;
+        let bases = StandardBases { bases: vec!["token/ERC20/ERC20.sol".to_owned()] };
+        let documents = bases.preprocess(test_documents("src/Token.sol", src)).unwrap();
+        assert_eq!(
+            read_context!(documents[0], STANDARD_BASES_ID, StandardBases),
+            Some(vec![StandardBase {
+                name: "ERC20".to_owned(),
+                path: "@openzeppelin/contracts/token/ERC20/ERC20.sol".to_owned(),
+                forwarded: vec!["decimals()".to_owned()],
+            }])
+        );
+    }
+}
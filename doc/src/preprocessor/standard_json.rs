@@ -0,0 +1,178 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::as_contract, Diagnostics, DocBuilder, DocDiagnostic, Document, PreprocessorOutput,
+};
+use ethers_solc::Project;
+use forge_fmt::solang_ext::SafeUnwrap;
+use foundry_common::compile::standard_json_input;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// [StandardJson] preprocessor id.
+pub const STANDARD_JSON_ID: PreprocessorId = PreprocessorId("standard_json");
+
+/// The standard json preprocessor.
+/// It constructs the solc standard json input of the compilation unit of each contract,
+/// as submitted for the verification, so it's written to `verify/<Contract>.input.json`
+/// next to the pages and linked from the contract page.
+///
+/// The paths of the input are relative to the project root. The compilation units with
+/// the sources outside of the root, i.e. the local absolute paths, are skipped and reported.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct StandardJson {
+    /// The documented project.
+    pub project: Project,
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// The collector of the skipped inputs diagnostics.
+    pub diagnostics: Arc<Diagnostics>,
+}
+
+/// The standard json input of the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandardJsonInput {
+    /// The target path of the input file.
+    pub path: PathBuf,
+    /// The link to the input file from the pages.
+    pub link: String,
+    /// The pretty-printed standard json input.
+    pub input: String,
+}
+
+impl StandardJson {
+    /// The directory of the input files within the book source.
+    pub const DIR: &'static str = "verify";
+
+    /// Returns the pretty-printed standard json input of the target's compilation unit.
+    fn input(&self, target: &Path) -> eyre::Result<String> {
+        let input = standard_json_input(&self.project, target)?;
+        if input.sources.iter().any(|(path, _)| path.is_absolute()) {
+            eyre::bail!("the compilation unit includes the sources outside of the project root")
+        }
+        Ok(serde_json::to_string_pretty(&input)?)
+    }
+}
+
+impl Preprocessor for StandardJson {
+    fn id(&self) -> PreprocessorId {
+        STANDARD_JSON_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        // The contracts of the same source share the compilation unit
+        let mut inputs = HashMap::<PathBuf, Option<String>>::new();
+        let mut names = HashMap::<String, usize>::new();
+        for (document, _, contract) in documents.iter().filter_map(as_contract) {
+            let name = &contract.name.safe_unwrap().name;
+            let input = inputs.entry(document.item_path.clone()).or_insert_with(|| {
+                match self.input(&document.item_path) {
+                    Ok(input) => Some(input),
+                    Err(err) => {
+                        let source = document
+                            .item_path
+                            .strip_prefix(self.project.root())
+                            .unwrap_or(&document.item_path);
+                        self.diagnostics.push(
+                            DocDiagnostic::warning(
+                                "standard-json-skipped",
+                                format!("no standard json input of `{name}`: {err}"),
+                            )
+                            .with_location(source.to_path_buf(), None),
+                        );
+                        None
+                    }
+                }
+            });
+            let input = match input {
+                Some(input) => input.clone(),
+                None => continue,
+            };
+
+            // The contracts sharing the name, ignoring the case, are suffixed
+            let count = names.entry(name.to_lowercase()).or_default();
+            let file_name = if *count == 0 {
+                format!("{name}.input.json")
+            } else {
+                format!("{name}-{count}.input.json")
+            };
+            *count += 1;
+            let path = Path::new(Self::DIR).join(file_name);
+            document.add_context(
+                self.id(),
+                PreprocessorOutput::StandardJson(StandardJsonInput {
+                    link: Path::new("/").join(&path).display().to_string(),
+                    path: out_src.join(path),
+                    input,
+                }),
+            );
+        }
+
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, AsDoc};
+    use ethers_solc::ProjectPathsConfig;
+    use std::fs;
+
+    #[test]
+    fn relative_inputs() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        let src = r#"
+            import "./Math.sol";
+            contract Vault { }
+            contract vault { }
+        "#;
+        fs::write(root.join("src/Vault.sol"), src).unwrap();
+        fs::write(root.join("src/Math.sol"), "library Math { }").unwrap();
+        let paths = ProjectPathsConfig::builder().build_with_root(root);
+        let project = Project::builder().paths(paths).ephemeral().no_artifacts().build().unwrap();
+
+        let mut documents = test_documents("src/Vault.sol", src);
+        for document in documents.iter_mut() {
+            document.item_path = root.join(&document.item_path);
+        }
+
+        let diagnostics = Arc::new(Diagnostics::default());
+        let documents =
+            StandardJson { project, out: PathBuf::from("docs"), diagnostics: diagnostics.clone() }
+                .preprocess(documents)
+                .unwrap();
+        assert_eq!(diagnostics.warnings(), 0);
+
+        let inputs = documents
+            .iter()
+            .map(|document| match document.get_from_context(STANDARD_JSON_ID) {
+                Some(PreprocessorOutput::StandardJson(input)) => input,
+                _ => panic!("expected the standard json input"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(inputs[0].path, PathBuf::from("docs/src/verify/Vault.input.json"));
+        assert_eq!(inputs[1].link, "/verify/vault-1.input.json");
+        assert_eq!(inputs[0].input, inputs[1].input);
+
+        let input: serde_json::Value = serde_json::from_str(&inputs[0].input).unwrap();
+        let sources = input["sources"].as_object().unwrap();
+        assert!(sources.contains_key("src/Vault.sol"));
+        assert!(sources.contains_key("src/Math.sol"));
+        assert!(!inputs[0].input.contains(&root.display().to_string()));
+
+        assert!(documents[0].as_doc().unwrap().contains(
+            "## Compilation\n\
+            |Input|File|\n\
+            |-|-|\n\
+            |Standard JSON|[Vault.input.json](/verify/Vault.input.json)|\n"
+        ));
+    }
+}
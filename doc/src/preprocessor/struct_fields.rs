@@ -0,0 +1,334 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    document::DocumentContent, helpers::user_type_name, resolver::ImportResolver, CommentTag,
+    Comments, Document, ParseSource, PreprocessorOutput,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{Expression, StructDefinition};
+use std::{collections::HashMap, path::PathBuf};
+
+/// [StructFields] preprocessor id.
+pub const STRUCT_FIELDS_ID: PreprocessorId = PreprocessorId("struct_fields");
+
+/// The struct fields preprocessor.
+/// It collects the fields of the documented structs with their `@param` descriptions.
+/// The fields of the nested struct types are expanded up to the max depth, e.g.
+/// `position.owner` for the `owner` field of the `Position` struct.
+///
+/// The structs are keyed by their source and contract, so the structs with the same name
+/// in the different contracts or files are told apart. The nested structs are resolved
+/// within the same contract first, then through the imports of the source, e.g. `Vault.Position`
+/// or the imported `Owner`. The arrays of structs are not expanded, and neither are the structs
+/// already being expanded, so the self-referential structs are handled safely.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+pub struct StructFields {
+    /// The maximum depth of the nested struct expansion.
+    /// The nested structs are only linked if zero.
+    pub max_depth: usize,
+    /// The project root.
+    pub root: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+}
+
+/// The field of the documented struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructField {
+    /// The field path, e.g. `position.owner` for the expanded fields.
+    pub name: String,
+    /// The field type.
+    pub ty: String,
+    /// The user defined type name of the field, used to link it to its documentation.
+    pub user_type: Option<String>,
+    /// The `@param` description of the field.
+    pub description: Option<String>,
+}
+
+/// The key of the struct, i.e. its source, the contract it is declared in and its name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct StructKey {
+    source: PathBuf,
+    contract: Option<String>,
+    name: String,
+}
+
+/// The struct definition with its comments.
+struct StructDecl<'a> {
+    def: &'a StructDefinition,
+    comments: &'a Comments,
+}
+
+/// The documented structs, by their key and by their canonical name, e.g. `Vault.Position`.
+struct StructDecls<'a> {
+    decls: HashMap<StructKey, StructDecl<'a>>,
+    by_name: HashMap<String, Vec<(PathBuf, StructKey)>>,
+}
+
+impl Preprocessor for StructFields {
+    fn id(&self) -> PreprocessorId {
+        STRUCT_FIELDS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let mut decls = StructDecls { decls: HashMap::new(), by_name: HashMap::new() };
+        for document in documents.iter() {
+            let item = match document.content {
+                DocumentContent::Single(ref item) => item,
+                _ => continue,
+            };
+            let structs = match item.source {
+                ParseSource::Struct(ref def) => vec![(None, def, &item.comments)],
+                ParseSource::Contract(ref contract) => {
+                    let contract = &contract.name.safe_unwrap().name;
+                    item.structs()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(def, comments, _)| (Some(contract), def, comments))
+                        .collect()
+                }
+                _ => vec![],
+            };
+            for (contract, def, comments) in structs {
+                let name = def.name.safe_unwrap().name.clone();
+                let key = StructKey {
+                    source: document.item_path.clone(),
+                    contract: contract.cloned(),
+                    name: name.clone(),
+                };
+                let canonical = match contract {
+                    Some(contract) => format!("{contract}.{name}"),
+                    None => name,
+                };
+                decls
+                    .by_name
+                    .entry(canonical)
+                    .or_default()
+                    .push((document.item_path.clone(), key.clone()));
+                decls.decls.insert(key, StructDecl { def, comments });
+            }
+        }
+
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let fields = decls
+            .decls
+            .keys()
+            .map(|key| {
+                let mut fields = vec![];
+                self.collect_fields(&decls, &mut resolver, key, "", 0, &mut vec![], &mut fields);
+                (key.clone(), fields)
+            })
+            .collect::<HashMap<_, _>>();
+
+        for document in documents.iter() {
+            // Key the fields by the plain names of the structs in the document
+            let (contract, name) = match document.content {
+                DocumentContent::Single(ref item) => match item.source {
+                    ParseSource::Struct(_) => (None, Some(item.source.ident())),
+                    ParseSource::Contract(ref contract) => {
+                        (Some(contract.name.safe_unwrap().name.clone()), None)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let structs = fields
+                .iter()
+                .filter(|(key, _)| {
+                    key.source == document.item_path &&
+                        key.contract == contract &&
+                        name.as_ref().map_or(true, |name| &key.name == name)
+                })
+                .map(|(key, fields)| (key.name.clone(), fields.clone()))
+                .collect::<HashMap<_, _>>();
+            if !structs.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::StructFields(structs));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+impl StructFields {
+    #[allow(clippy::too_many_arguments)]
+    fn collect_fields(
+        &self,
+        decls: &StructDecls<'_>,
+        resolver: &mut ImportResolver,
+        key: &StructKey,
+        prefix: &str,
+        depth: usize,
+        expanding: &mut Vec<StructKey>,
+        fields: &mut Vec<StructField>,
+    ) {
+        let decl = match decls.decls.get(key) {
+            Some(decl) => decl,
+            None => return,
+        };
+        expanding.push(key.clone());
+        let descriptions = decl.comments.include_tag(CommentTag::Param);
+        for field in decl.def.fields.iter() {
+            let field_name = &field.name.safe_unwrap().name;
+            let name = if prefix.is_empty() {
+                field_name.clone()
+            } else {
+                format!("{prefix}.{field_name}")
+            };
+            let user_type = user_type_name(&field.ty);
+            let description = descriptions
+                .iter()
+                .find_map(|comment| comment.match_first_word(field_name))
+                .map(|description| description.trim().replace('\n', " "));
+            fields.push(StructField {
+                name: name.clone(),
+                ty: field.ty.to_string(),
+                user_type: user_type.clone(),
+                description,
+            });
+
+            if depth >= self.max_depth || matches!(field.ty, Expression::ArraySubscript(..)) {
+                continue
+            }
+            // The structs of the same contract shadow the imported ones
+            let nested = user_type.and_then(|user_type| {
+                let local = StructKey { name: user_type.clone(), ..key.clone() };
+                if key.contract.is_some() && decls.decls.contains_key(&local) {
+                    return Some(local)
+                }
+                resolver
+                    .declaration(&key.source, &user_type, &decls.by_name)
+                    .map(|(_, nested)| nested.clone())
+            });
+            if let Some(nested) = nested {
+                if !expanding.contains(&nested) {
+                    self.collect_fields(
+                        decls,
+                        resolver,
+                        &nested,
+                        &name,
+                        depth + 1,
+                        expanding,
+                        fields,
+                    );
+                }
+            }
+        }
+        expanding.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+    use std::path::PathBuf;
+
+    #[test]
+    fn nested_fields() {
+        let src = r#"
+            /// @param owner The position owner.
+            struct Owner {
+                address owner;
+                Node node;
+            }
+
+            struct Node {
+                Node[] children;
+                Owner owner;
+            }
+
+            contract Vault {
+                /// @param amount The deposited amount.
+                /// @param owner The owner of the deposit.
+                struct Position {
+                    uint256 amount;
+                    Owner owner;
+                }
+            }
+        "#;
+        let names = |depth: usize| {
+            let documents = StructFields { max_depth: depth, ..Default::default() }
+                .preprocess(test_documents("src/Vault.sol", src))
+                .unwrap();
+            let fields = read_context!(documents[2], STRUCT_FIELDS_ID, StructFields).unwrap();
+            fields["Position"].iter().map(|field| field.name.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(0), vec!["amount", "owner"]);
+        assert_eq!(names(1), vec!["amount", "owner", "owner.owner", "owner.node"]);
+        // The struct being expanded is not expanded again
+        assert_eq!(
+            names(5),
+            vec![
+                "amount",
+                "owner",
+                "owner.owner",
+                "owner.node",
+                "owner.node.children",
+                "owner.node.owner",
+            ]
+        );
+
+        let documents = StructFields { max_depth: 1, ..Default::default() }
+            .preprocess(test_documents("src/Vault.sol", src))
+            .unwrap();
+        let fields = read_context!(documents[2], STRUCT_FIELDS_ID, StructFields).unwrap();
+        assert_eq!(
+            fields["Position"][2],
+            StructField {
+                name: "owner.owner".to_owned(),
+                ty: "address".to_owned(),
+                user_type: None,
+                description: Some("The position owner.".to_owned()),
+            }
+        );
+        assert_eq!(fields["Position"][3].user_type.as_deref(), Some("Node"));
+    }
+
+    #[test]
+    fn same_name_structs() {
+        let mut documents = test_documents(
+            "src/Vault.sol",
+            r#"
+            import {Owner} from "./Owner.sol";
+
+            contract Vault {
+                struct Position {
+                    uint256 amount;
+                    Owner owner;
+                }
+            }
+            "#,
+        );
+        documents.extend(test_documents(
+            "src/v2/Vault.sol",
+            r#"
+            import {Owner} from "./Owner.sol";
+
+            contract Vault {
+                struct Position {
+                    Owner owner;
+                }
+            }
+            "#,
+        ));
+        documents.extend(test_documents("src/Owner.sol", "struct Owner { address owner; }"));
+        documents.extend(test_documents("src/v2/Owner.sol", "struct Owner { address account; }"));
+
+        let documents = StructFields { max_depth: 1, root: PathBuf::new(), remappings: vec![] }
+            .preprocess(documents)
+            .unwrap();
+        let names = |idx: usize, name: &str| {
+            let fields = read_context!(documents[idx], STRUCT_FIELDS_ID, StructFields).unwrap();
+            fields[name].iter().map(|field| field.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(0, "Position"), vec!["amount", "owner", "owner.owner"]);
+        assert_eq!(names(1, "Position"), vec!["owner", "owner.account"]);
+        assert_eq!(names(2, "Owner"), vec!["owner"]);
+        assert_eq!(names(3, "Owner"), vec!["account"]);
+    }
+}
@@ -0,0 +1,281 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    anchors::PageAnchors,
+    document::DocumentContent,
+    resolver::{imports, ImportResolver},
+    DocBuilder, Document, ParseSource, PreprocessorOutput,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::Import;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// [StructLinks] preprocessor id.
+pub const STRUCT_LINKS_ID: PreprocessorId = PreprocessorId("struct_links");
+
+/// The id of the import aliases written by the [StructLinks] preprocessor.
+pub const IMPORT_ALIASES_ID: PreprocessorId = PreprocessorId("import_aliases");
+
+/// The struct links preprocessor.
+/// It resolves the user defined type names, i.e. the structs, enums, contracts and
+/// user defined value types, to the links of their documentation, so that the parameters
+/// and return values, as well as the references in their descriptions, can be linked
+/// to their definitions.
+///
+/// The structs and enums declared within contracts are keyed by the qualified name,
+/// e.g. `Vault.Position`, and by the plain name within the declaring contract.
+///
+/// The names are resolved through the imports of the document source and the remappings
+/// to their declarations, so the names declared more than once, e.g. the `Position` structs
+/// of two files, are linked to the declaration in the same file or in the imported file.
+/// The names which can't be told apart aren't linked.
+///
+/// The types imported under an alias, i.e. `import {Foo as Bar} from "./Foo.sol"` or
+/// `import "./Foo.sol" as X`, are resolved to the canonical names, so `Bar` and `X.Foo`
+/// are linked to the documentation of `Foo`.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct StructLinks {
+    /// The documentation output directory.
+    pub out: PathBuf,
+    /// Whether to show the alias next to the resolved type name.
+    pub show_aliases: bool,
+    /// The project root.
+    pub root: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+    /// The maximum number of the members of each section of the contract page, see
+    /// [SectionLimits](crate::SectionLimits). `0` for no limit.
+    pub section_limit: usize,
+}
+
+/// The import aliases of the document source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportAliases {
+    /// The map of the aliases to the canonical names, e.g. `Bar` to `Foo`.
+    /// The aliases of the imported files map to the empty name, e.g. `X`.
+    pub aliases: HashMap<String, String>,
+    /// Whether to show the alias next to the resolved type name.
+    pub show: bool,
+}
+
+impl ImportAliases {
+    /// Resolve the aliased type name, e.g. `Bar[]` to `Foo[]` or `X.Foo` to `Foo`.
+    /// Returns [None] if the type isn't aliased.
+    pub fn resolve(&self, ty: &str) -> Option<String> {
+        let end = ty.find(['.', '[']).unwrap_or(ty.len());
+        let canonical = self.aliases.get(&ty[..end])?;
+        let rest = &ty[end..];
+        if canonical.is_empty() {
+            rest.strip_prefix('.').map(str::to_owned)
+        } else {
+            Some(format!("{canonical}{rest}"))
+        }
+    }
+}
+
+impl Preprocessor for StructLinks {
+    fn id(&self) -> PreprocessorId {
+        STRUCT_LINKS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let out_src = self.out.join(DocBuilder::SRC);
+        let link = |page: &Path| {
+            Path::new("/").join(page.strip_prefix(&out_src).unwrap_or(page)).display().to_string()
+        };
+
+        let mut declarations: HashMap<String, Vec<(PathBuf, String)>> = HashMap::new();
+        let mut local = HashMap::new();
+        for document in documents.iter() {
+            if let DocumentContent::Single(ref item) = document.content {
+                match item.source {
+                    ParseSource::Struct(_) | ParseSource::Enum(_) | ParseSource::Type(_) => {
+                        declarations
+                            .entry(item.source.ident())
+                            .or_default()
+                            .push((document.item_path.clone(), link(&document.target_path)));
+                    }
+                    ParseSource::Contract(ref contract) => {
+                        let contract = &contract.name.safe_unwrap().name;
+                        declarations
+                            .entry(contract.clone())
+                            .or_default()
+                            .push((document.item_path.clone(), link(&document.target_path)));
+                        let anchors = PageAnchors::contract_pages(item, self.section_limit);
+                        let structs = item.structs().unwrap_or_default().into_iter();
+                        let enums = item.enums().unwrap_or_default().into_iter();
+                        let names = structs
+                            .map(|(def, _, _)| ("Structs", &def.name))
+                            .chain(enums.map(|(def, _, _)| ("Enums", &def.name)));
+                        for (section, name) in names {
+                            let name = &name.safe_unwrap().name;
+                            // The members over the section limit are on the continuation page
+                            let target = match anchors.locate(&document.target_path, section, name)
+                            {
+                                Some((page, anchor)) => format!("{}#{anchor}", link(&page)),
+                                None => link(&document.target_path),
+                            };
+                            declarations
+                                .entry(format!("{contract}.{name}"))
+                                .or_default()
+                                .push((document.item_path.clone(), target.clone()));
+                            local
+                                .entry(contract.clone())
+                                .or_insert_with(HashMap::new)
+                                .insert(name.clone(), target);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if declarations.is_empty() {
+            return Ok(documents)
+        }
+
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let mut sources = HashMap::new();
+        for document in documents.iter() {
+            let (aliases, links) = sources.entry(&document.item_path).or_insert_with(|| {
+                let imports = source_imports(&document.item_content);
+                let links = resolve_links(&declarations, &document.item_path, &mut resolver);
+                (import_aliases(&imports), links)
+            });
+            if !aliases.is_empty() {
+                let aliases = ImportAliases { aliases: aliases.clone(), show: self.show_aliases };
+                document.add_context(IMPORT_ALIASES_ID, PreprocessorOutput::ImportAliases(aliases));
+            }
+
+            let mut links = links.clone();
+            links.extend(alias_links(&links, aliases));
+            if let DocumentContent::Single(ref item) = document.content {
+                if let Some(local) = item
+                    .as_contract()
+                    .and_then(|contract| local.get(&contract.name.safe_unwrap().name))
+                {
+                    links.extend(local.clone());
+                }
+            }
+            document.add_context(self.id(), PreprocessorOutput::StructLinks(links));
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the import directives of the source, or none if it can't be parsed.
+fn source_imports(source: &str) -> Vec<Import> {
+    solang_parser::parse(source, 0).map(|(source_unit, _)| imports(source_unit)).unwrap_or_default()
+}
+
+/// Returns the import aliases of the source, see [ImportAliases::aliases].
+fn import_aliases(imports: &[Import]) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    for import in imports {
+        match import {
+            Import::Rename(_, symbols, _) => {
+                for (symbol, alias) in symbols {
+                    if let Some(alias) = alias {
+                        aliases.insert(alias.name.clone(), symbol.name.clone());
+                    }
+                }
+            }
+            Import::GlobalSymbol(_, alias, _) => {
+                aliases.insert(alias.name.clone(), String::new());
+            }
+            _ => {}
+        }
+    }
+    aliases
+}
+
+/// Returns the links of the declared names for the source file. The names are resolved
+/// to their declarations through the imports of the source, and the names which can't be
+/// told apart, e.g. declared in two files neither of which is imported, are skipped.
+fn resolve_links(
+    declarations: &HashMap<String, Vec<(PathBuf, String)>>,
+    source: &Path,
+    resolver: &mut ImportResolver,
+) -> HashMap<String, String> {
+    declarations
+        .keys()
+        .filter_map(|name| {
+            let (_, link) = resolver.declaration(source, name, declarations)?;
+            Some((name.clone(), link.clone()))
+        })
+        .collect()
+}
+
+/// Returns the links of the aliased names, e.g. `Bar` and `Bar.Position` for the `Foo` alias,
+/// or `X.Foo` for the file alias.
+fn alias_links(
+    links: &HashMap<String, String>,
+    aliases: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut aliased = vec![];
+    for (alias, canonical) in aliases.iter() {
+        for (name, link) in links.iter() {
+            let name = if canonical.is_empty() {
+                Some(format!("{alias}.{name}"))
+            } else if name == canonical {
+                Some(alias.clone())
+            } else {
+                name.strip_prefix(canonical.as_str())
+                    .filter(|member| member.starts_with('.'))
+                    .map(|member| format!("{alias}{member}"))
+            };
+            if let Some(name) = name {
+                aliased.push((name, link.clone()));
+            }
+        }
+    }
+    aliased
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{document::read_context, preprocessor::test_documents};
+
+    #[test]
+    fn colliding_declarations() {
+        let document = |path: &str, src: &str| test_documents(path, src).pop().expect("no items");
+        let documents = vec![
+            document("src/a/Types.sol", "struct Position { uint256 shares; }"),
+            document("src/b/Types.sol", "struct Position { uint256 assets; }"),
+            document("src/Vault.sol", r#"import "./b/Types.sol"; contract Vault { }"#),
+            document("src/Pool.sol", "contract Pool { }"),
+            document(
+                "src/Router.sol",
+                r#"import {Position} from "types/Types.sol"; contract Router { }"#,
+            ),
+        ];
+
+        let documents = StructLinks {
+            out: PathBuf::from("docs"),
+            show_aliases: false,
+            root: PathBuf::new(),
+            remappings: vec!["types/=src/a/".parse().unwrap()],
+            section_limit: 0,
+        }
+        .preprocess(documents)
+        .unwrap();
+        let links = |i: usize| read_context!(documents[i], STRUCT_LINKS_ID, StructLinks).unwrap();
+
+        // The declaring file and the importing file link to their own declaration
+        assert_eq!(links(0)["Position"], "/src/a/Types.sol/struct.Position.md");
+        assert_eq!(links(2)["Position"], "/src/b/Types.sol/struct.Position.md");
+        // The ambiguous name isn't linked, the unique ones are
+        assert_eq!(links(3).get("Position"), None);
+        assert_eq!(links(3)["Vault"], "/src/Vault.sol/contract.Vault.md");
+        // The remapped import is resolved to the declaring file
+        assert_eq!(links(4)["Position"], "/src/a/Types.sol/struct.Position.md");
+    }
+}
@@ -0,0 +1,332 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{
+    helpers::{
+        as_contract, canonical_type, forge_std_descendants, walk_expressions, walk_statements,
+    },
+    resolver::ImportResolver,
+    Document, PreprocessorOutput,
+};
+use ethers_solc::remappings::Remapping;
+use solang_parser::pt::{Expression, FunctionDefinition, FunctionTy, Statement};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    path::PathBuf,
+};
+
+/// [TestPlan] preprocessor id.
+pub const TEST_PLAN_ID: PreprocessorId = PreprocessorId("test_plan");
+
+/// The test plan preprocessor.
+/// It detects the contracts inheriting from the forge-std `Test`, either directly
+/// or through other documented tests, and annotates their test functions
+/// with the cheatcodes they use.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+pub struct TestPlan {
+    /// The project root.
+    pub root: PathBuf,
+    /// The import remappings.
+    pub remappings: Vec<Remapping>,
+}
+
+/// The kind of the test function, derived from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TestKind {
+    /// The `setUp` function.
+    SetUp,
+    /// The unit test, i.e. the `test` prefixed function without parameters.
+    Unit,
+    /// The fuzz test, i.e. the `testFuzz` prefixed function or a test with parameters.
+    Fuzz,
+    /// The `invariant` prefixed function.
+    Invariant,
+}
+
+impl TestKind {
+    /// Returns the kind of the function, or [None] if the function is not a test.
+    fn new(func: &FunctionDefinition) -> Option<Self> {
+        if !matches!(func.ty, FunctionTy::Function) {
+            return None
+        }
+        let name = &func.name.as_ref()?.name;
+        if name == "setUp" {
+            Some(Self::SetUp)
+        } else if name.starts_with("invariant") {
+            Some(Self::Invariant)
+        } else if name.starts_with("testFuzz") ||
+            (name.starts_with("test") && !func.params.is_empty())
+        {
+            Some(Self::Fuzz)
+        } else if name.starts_with("test") {
+            Some(Self::Unit)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the group title of the test kind.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::SetUp => "Setup",
+            Self::Unit => "Unit tests",
+            Self::Fuzz => "Fuzz tests",
+            Self::Invariant => "Invariant tests",
+        }
+    }
+}
+
+/// The category of the cheatcodes used by the test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheatcodeCategory {
+    /// `vm.prank` and `vm.startPrank`.
+    Prank,
+    /// `vm.expectRevert`.
+    ExpectRevert,
+    /// `vm.expectEmit`.
+    ExpectEmit,
+    /// `vm.expectCall`.
+    ExpectCall,
+    /// `vm.createFork`, `vm.selectFork` and related.
+    Fork,
+    /// `vm.warp` and `vm.roll`.
+    Time,
+    /// `vm.deal`.
+    Deal,
+    /// `vm.mockCall`.
+    Mock,
+}
+
+impl CheatcodeCategory {
+    /// Returns the category of the cheatcode.
+    fn new(cheatcode: &str) -> Option<Self> {
+        let category = match cheatcode {
+            "prank" | "startPrank" | "stopPrank" => Self::Prank,
+            "expectRevert" => Self::ExpectRevert,
+            "expectEmit" => Self::ExpectEmit,
+            "expectCall" => Self::ExpectCall,
+            "createFork" | "createSelectFork" | "selectFork" | "rollFork" | "makePersistent" => {
+                Self::Fork
+            }
+            "warp" | "roll" => Self::Time,
+            "deal" => Self::Deal,
+            "mockCall" | "mockCallRevert" | "clearMockedCalls" => Self::Mock,
+            _ => return None,
+        };
+        Some(category)
+    }
+}
+
+impl fmt::Display for CheatcodeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let category = match self {
+            Self::Prank => "prank",
+            Self::ExpectRevert => "expect revert",
+            Self::ExpectEmit => "expect emit",
+            Self::ExpectCall => "expect call",
+            Self::Fork => "fork",
+            Self::Time => "time",
+            Self::Deal => "deal",
+            Self::Mock => "mock",
+        };
+        f.write_str(category)
+    }
+}
+
+/// The test function of the test contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    /// The function name.
+    pub name: String,
+    /// The test kind.
+    pub kind: TestKind,
+    /// The categories of the used cheatcodes.
+    pub cheatcodes: BTreeSet<CheatcodeCategory>,
+    /// The fuzzed inputs, e.g. `uint256 amount`.
+    pub fuzz_inputs: Vec<String>,
+}
+
+impl Preprocessor for TestPlan {
+    fn id(&self) -> PreprocessorId {
+        TEST_PLAN_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let mut resolver = ImportResolver::new(self.root.clone(), self.remappings.clone())
+            .with_documents(&documents);
+        let tests = forge_std_descendants(&documents, "Test", &mut resolver);
+        for (document, item, contract) in documents.iter().filter_map(as_contract) {
+            if !contract.name.as_ref().map(|name| tests.contains(&name.name)).unwrap_or_default() {
+                continue
+            }
+
+            // The forge-std `vm` and the `Vm` state variables
+            let vms = item
+                .variables()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(var, _, _)| is_vm(&var.ty))
+                .filter_map(|(var, _, _)| Some(var.name.as_ref()?.name.as_str()))
+                .chain(["vm"])
+                .collect::<HashSet<_>>();
+            let mut cases = item
+                .functions()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(func, _, _)| {
+                    let kind = TestKind::new(func)?;
+                    let fuzz_inputs = func
+                        .params
+                        .iter()
+                        .filter_map(|(_, param)| param.as_ref())
+                        .map(|param| {
+                            let ty = canonical_type(&param.ty);
+                            match param.name {
+                                Some(ref name) => format!("{ty} {}", name.name),
+                                None => ty,
+                            }
+                        })
+                        .collect();
+                    Some(TestCase {
+                        name: func.name.as_ref()?.name.clone(),
+                        kind,
+                        cheatcodes: cheatcodes(func, vms.clone())
+                            .into_iter()
+                            .filter_map(CheatcodeCategory::new)
+                            .collect(),
+                        fuzz_inputs,
+                    })
+                })
+                .collect::<Vec<_>>();
+            // The stable sort keeps the declaration order within the groups.
+            cases.sort_by_key(|case| case.kind);
+
+            if !cases.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::TestPlan(cases));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the names of the cheatcodes called within the function on the `Vm` typed variables,
+/// i.e. the given state variables and the `Vm` parameters and locals of the function,
+/// or on the `Vm` casts, e.g. `Vm(HEVM_ADDRESS).warp(1)`.
+fn cheatcodes<'a>(func: &'a FunctionDefinition, mut vms: HashSet<&'a str>) -> Vec<&'a str> {
+    let body = match func.body {
+        Some(ref body) => body,
+        None => return vec![],
+    };
+
+    // The parameters and the locals shadow the state variables
+    let mut declare = |ty: &Expression, name: &'a str| {
+        if is_vm(ty) {
+            vms.insert(name);
+        } else {
+            vms.remove(name);
+        }
+    };
+    for param in func.params.iter().filter_map(|(_, param)| param.as_ref()) {
+        if let Some(ref name) = param.name {
+            declare(&param.ty, &name.name);
+        }
+    }
+    walk_statements(body, &mut |statement| {
+        if let Statement::VariableDefinition(_, decl, _) = statement {
+            if let Some(ref name) = decl.name {
+                declare(&decl.ty, &name.name);
+            }
+        }
+    });
+
+    let mut cheatcodes = vec![];
+    walk_expressions(body, &mut |expr| {
+        if let Expression::MemberAccess(_, base, member) = expr {
+            let on_vm = match base.as_ref() {
+                Expression::Variable(ident) => vms.contains(ident.name.as_str()),
+                Expression::FunctionCall(_, callee, _) => is_vm(callee),
+                _ => false,
+            };
+            if on_vm {
+                cheatcodes.push(member.name.as_str());
+            }
+        }
+    });
+    cheatcodes
+}
+
+/// Returns true if the type is the forge-std `Vm` interface.
+fn is_vm(ty: &Expression) -> bool {
+    matches!(ty, Expression::Variable(ident) if ident.name == "Vm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_function(src: &str) -> FunctionDefinition {
+        let (source, _) = solang_parser::parse(src, 0).expect("failed to parse source");
+        source
+            .0
+            .into_iter()
+            .find_map(|part| match part {
+                solang_parser::pt::SourceUnitPart::FunctionDefinition(func) => Some(*func),
+                _ => None,
+            })
+            .expect("no function")
+    }
+
+    #[test]
+    fn cheatcode_categories() {
+        let func = parse_function(
+            r#"
+            function testWithdraw(Vault vault) {
+                vm.startPrank(alice);
+                vm.expectRevert(Vault.Paused.selector);
+                vault.withdraw(1);
+                revm.deal(alice, 1);
+                // vm.roll(1);
+                string memory reason = "vm.deal";
+                vm.stopPrank();
+                if (true) { vm.warp(block.timestamp + 1); }
+            }
+            "#,
+        );
+        let vms = HashSet::from(["vm"]);
+        assert_eq!(
+            cheatcodes(&func, vms.clone()),
+            vec!["startPrank", "expectRevert", "stopPrank", "warp"]
+        );
+        assert_eq!(
+            cheatcodes(&func, vms)
+                .into_iter()
+                .filter_map(CheatcodeCategory::new)
+                .collect::<BTreeSet<_>>(),
+            BTreeSet::from([
+                CheatcodeCategory::Prank,
+                CheatcodeCategory::ExpectRevert,
+                CheatcodeCategory::Time
+            ])
+        );
+    }
+
+    #[test]
+    fn vm_typed_variables() {
+        let func = parse_function(
+            r#"
+            function testMock(uint256 vm) {
+                Vm hevm = Vm(HEVM_ADDRESS);
+                hevm.mockCall(target, data, result);
+                cheats.deal(alice, vm);
+                Vm(HEVM_ADDRESS).roll(1);
+            }
+            "#,
+        );
+        assert_eq!(
+            cheatcodes(&func, HashSet::from(["vm", "cheats"])),
+            vec!["mockCall", "deal", "roll"]
+        );
+    }
+}
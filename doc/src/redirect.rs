@@ -0,0 +1,244 @@
+//! The redirects of the moved and renamed pages.
+
+use crate::{Diagnostics, DocDiagnostic, DocModel, ModelItem};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+/// Resolve the redirects of the pages that no longer exist to the current pages.
+///
+/// The redirects are collected from the previous model, the moves detected since
+/// the previous generation and the configured redirects, in the order of precedence.
+/// The chains of moves are followed up to the current page, e.g. for the page moved twice.
+/// The redirects from the current pages and to the missing pages are dropped,
+/// the dropped configured ones are reported.
+pub(crate) fn resolve_redirects(
+    previous: Option<&DocModel>,
+    current: &DocModel,
+    configured: &BTreeMap<PathBuf, PathBuf>,
+    diagnostics: &Diagnostics,
+) -> BTreeMap<PathBuf, PathBuf> {
+    let pages = current.items.iter().map(|item| item.page.as_path()).collect::<HashSet<_>>();
+    let mut redirects = BTreeMap::new();
+    if let Some(previous) = previous {
+        redirects.extend(previous.redirects.clone());
+        redirects.extend(detect_moves(previous, current));
+    }
+    redirects.extend(configured.clone());
+
+    let resolved = redirects
+        .iter()
+        .filter(|(from, _)| !pages.contains(from.as_path()))
+        .filter_map(|(from, to)| {
+            let mut target = to;
+            for _ in 0..redirects.len() {
+                if pages.contains(target.as_path()) {
+                    return Some((from.clone(), target.clone()))
+                }
+                target = redirects.get(target)?;
+            }
+            None
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (from, to) in configured.iter().filter(|(from, _)| !resolved.contains_key(*from)) {
+        diagnostics.push(DocDiagnostic::warning(
+            "redirect-ignored",
+            format!(
+                "ignoring the redirect from `{}` to `{}`: the page is documented \
+                 or the target is missing",
+                from.display(),
+                to.display()
+            ),
+        ));
+    }
+    resolved
+}
+
+/// Detect the pages moved since the previous generation.
+/// The items are matched by the kind, the name and the signatures of their members,
+/// so the items matching more than one current page are not redirected.
+fn detect_moves(previous: &DocModel, current: &DocModel) -> BTreeMap<PathBuf, PathBuf> {
+    let key = |item: &ModelItem| {
+        let members =
+            item.all_members().map(|member| member.signature.clone()).sorted().collect_vec();
+        (item.kind.clone(), item.name.clone(), item.signature.clone(), members)
+    };
+    let mut moved_to: HashMap<_, Vec<&Path>> = HashMap::new();
+    for item in current.items.iter() {
+        let pages = moved_to.entry(key(item)).or_default();
+        if !pages.contains(&item.page.as_path()) {
+            pages.push(&item.page);
+        }
+    }
+    let pages = current.items.iter().map(|item| item.page.as_path()).collect::<HashSet<_>>();
+
+    previous
+        .items
+        .iter()
+        .filter(|item| !pages.contains(item.page.as_path()))
+        .filter_map(|item| match moved_to.get(&key(item)).map(Vec::as_slice) {
+            Some([page]) => Some((item.page.clone(), page.to_path_buf())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the absolute url of the rendered page, e.g. `/src/Vault.sol/contract.Vault.html`.
+pub(crate) fn page_url(page: &Path) -> String {
+    Path::new("/").join(page.with_extension("html")).display().to_string()
+}
+
+/// Returns the url of the rendered page relative to the directory of the redirected page,
+/// e.g. `../core/Vault.sol/contract.Vault.html`.
+pub(crate) fn relative_page_url(from: &Path, to: &Path) -> String {
+    let from_dir = from.parent().unwrap_or(Path::new("")).components().collect_vec();
+    let to = to.with_extension("html");
+    let to = to.components().collect_vec();
+    let common = from_dir.iter().zip(to.iter()).take_while(|(lhs, rhs)| lhs == rhs).count();
+    std::iter::repeat("..")
+        .take(from_dir.len() - common)
+        .chain(to[common..].iter().filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        }))
+        .join("/")
+}
+
+/// Returns the markdown page written at the path of the moved page.
+pub(crate) fn redirect_stub(to: &Path) -> String {
+    let link = Path::new("/").join(to).display().to_string();
+    let name = to.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    format!(
+        "<meta http-equiv=\"refresh\" content=\"0; url={}\">\n\n# Moved\n\n\
+         This page has moved to [{name}]({link}).\n",
+        page_url(to)
+    )
+}
+
+/// Returns the `_redirects` file with the permanent redirects of the rendered pages.
+pub(crate) fn redirects_file(redirects: &BTreeMap<PathBuf, PathBuf>) -> String {
+    redirects
+        .iter()
+        .map(|(from, to)| format!("{} {} 301\n", page_url(from), page_url(to)))
+        .collect()
+}
+
+/// Returns the `netlify.toml` fragment with the permanent redirects of the rendered pages.
+pub(crate) fn netlify_redirects(redirects: &BTreeMap<PathBuf, PathBuf>) -> String {
+    redirects
+        .iter()
+        .map(|(from, to)| {
+            format!(
+                "[[redirects]]\nfrom = \"{}\"\nto = \"{}\"\nstatus = 301\n",
+                page_url(from),
+                page_url(to)
+            )
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModelMember, SpecialFunctions};
+
+    fn model(items: &[(&str, &str, &[&str])]) -> DocModel {
+        DocModel {
+            version: DocModel::VERSION,
+            items: items
+                .iter()
+                .map(|(name, page, members)| ModelItem {
+                    kind: "contract".to_owned(),
+                    name: name.to_string(),
+                    signature: name.to_string(),
+                    source: PathBuf::from("src/Vault.sol"),
+                    page: PathBuf::from(page),
+                    code: format!("contract {name}"),
+                    comments: vec![],
+                    security_contact: None,
+                    special: SpecialFunctions::default(),
+                    members: members
+                        .iter()
+                        .map(|signature| ModelMember {
+                            kind: "function".to_owned(),
+                            name: signature.split('(').next().unwrap().to_owned(),
+                            signature: signature.to_string(),
+                            code: String::new(),
+                            comments: vec![],
+                            topic: None,
+                            indexed: vec![],
+                            visibility: None,
+                            page: None,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            build_info: None,
+            redirects: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn moved_pages() {
+        let mut previous = model(&[
+            ("Vault", "src/Vault.sol/contract.Vault.md", &["deposit(uint256)"]),
+            ("Token", "src/Token.sol/contract.Token.md", &["transfer(address,uint256)"]),
+            ("Pool", "src/Pool.sol/contract.Pool.md", &["swap(uint256)"]),
+        ]);
+        previous.redirects = BTreeMap::from([(
+            PathBuf::from("src/Old.sol/contract.Vault.md"),
+            PathBuf::from("src/Vault.sol/contract.Vault.md"),
+        )]);
+        let current = model(&[
+            ("Vault", "src/core/Vault.sol/contract.Vault.md", &["deposit(uint256)"]),
+            ("Token", "src/tokens/Token.sol/contract.Token.md", &["mint(uint256)"]),
+            ("Pool", "src/Pool.sol/contract.Pool.md", &["swap(uint256)"]),
+        ]);
+        let configured = BTreeMap::from([
+            (
+                PathBuf::from("src/Token.sol/contract.Token.md"),
+                PathBuf::from("src/tokens/Token.sol/contract.Token.md"),
+            ),
+            (PathBuf::from("src/Pool.sol/contract.Pool.md"), PathBuf::from("src/Missing.md")),
+        ]);
+
+        let diagnostics = Diagnostics::default();
+        let redirects = resolve_redirects(Some(&previous), &current, &configured, &diagnostics);
+        assert_eq!(
+            redirects,
+            BTreeMap::from([
+                (
+                    PathBuf::from("src/Old.sol/contract.Vault.md"),
+                    PathBuf::from("src/core/Vault.sol/contract.Vault.md")
+                ),
+                (
+                    PathBuf::from("src/Token.sol/contract.Token.md"),
+                    PathBuf::from("src/tokens/Token.sol/contract.Token.md")
+                ),
+                (
+                    PathBuf::from("src/Vault.sol/contract.Vault.md"),
+                    PathBuf::from("src/core/Vault.sol/contract.Vault.md")
+                ),
+            ])
+        );
+
+        // The redirect from the documented page is reported
+        let diagnostics = diagnostics.take();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "redirect-ignored");
+
+        assert_eq!(
+            relative_page_url(
+                Path::new("src/Vault.sol/contract.Vault.md"),
+                Path::new("src/core/Vault.sol/contract.Vault.md")
+            ),
+            "../core/Vault.sol/contract.Vault.html"
+        );
+        assert_eq!(
+            redirects_file(&redirects).lines().last(),
+            Some("/src/Vault.sol/contract.Vault.html /src/core/Vault.sol/contract.Vault.html 301")
+        );
+    }
+}
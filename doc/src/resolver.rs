@@ -0,0 +1,353 @@
+//! The resolution of the names used in the sources to the files declaring them.
+
+use crate::{
+    document::DocumentContent,
+    helpers::{canonical_type, user_type_name},
+    Document, ParseSource,
+};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{Expression, Import, SourceUnit, SourceUnitPart};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// The top-level declarations and the imports of the source file.
+#[derive(Debug, Default)]
+struct FileSymbols {
+    declared: HashSet<String>,
+    imports: Vec<Import>,
+}
+
+/// Resolves the names used in the source files, e.g. the types and the bases, to the files
+/// declaring them, following the imports of the files through the remappings, so the
+/// declarations with the same name in the different files are told apart.
+///
+/// The documented sources are resolved from their parsed content, the other files,
+/// e.g. the dependencies, are read when imported.
+#[derive(Debug)]
+pub(crate) struct ImportResolver {
+    root: PathBuf,
+    remappings: Vec<Remapping>,
+    files: HashMap<PathBuf, FileSymbols>,
+}
+
+impl ImportResolver {
+    /// Create the resolver of the imports of the project at the root.
+    pub(crate) fn new(root: PathBuf, remappings: Vec<Remapping>) -> Self {
+        Self { root, remappings, files: HashMap::new() }
+    }
+
+    /// Add the sources of the documents, so they're resolved without reading them.
+    pub(crate) fn with_documents(mut self, documents: &[Document]) -> Self {
+        for document in documents.iter() {
+            let path = normalize(&document.item_path);
+            if !self.files.contains_key(&path) {
+                let symbols = file_symbols(&document.item_content);
+                self.files.insert(path, symbols);
+            }
+        }
+        self
+    }
+
+    /// Resolve the name used in the file, e.g. `IERC20`, `Vault.Position` or `Interfaces.IERC20`
+    /// for the aliased file imports, to its canonical name, i.e. without the aliases, and
+    /// the file declaring it. The members are resolved to the file declaring the contract.
+    ///
+    /// Returns [None] if the name is neither declared in the file nor imported.
+    pub(crate) fn resolve(&mut self, file: &Path, name: &str) -> Option<(String, PathBuf)> {
+        let file = normalize(file);
+        let (head, member) = match name.split_once('.') {
+            Some((head, member)) => (head, Some(member)),
+            None => (name, None),
+        };
+        let qualified = |head: &str| match member {
+            Some(member) => format!("{head}.{member}"),
+            None => head.to_owned(),
+        };
+
+        if self.symbols(&file).declared.contains(head) {
+            return Some((qualified(head), file))
+        }
+        for import in self.symbols(&file).imports.clone() {
+            let found = match import {
+                Import::Rename(path, symbols, _) => symbols
+                    .iter()
+                    .find(|(symbol, alias)| alias.as_ref().unwrap_or(symbol).name == head)
+                    .and_then(|(symbol, _)| {
+                        let imported = self.import_path(&file, &path.string);
+                        self.declaring_file(&imported, &symbol.name, &mut HashSet::new())
+                            .map(|source| (qualified(&symbol.name), source))
+                    }),
+                Import::GlobalSymbol(path, alias, _) if alias.name == head => {
+                    member.and_then(|member| {
+                        let imported = self.import_path(&file, &path.string);
+                        self.resolve(&imported, member)
+                    })
+                }
+                Import::Plain(path, _) => {
+                    let imported = self.import_path(&file, &path.string);
+                    self.declaring_file(&imported, head, &mut HashSet::new())
+                        .map(|source| (qualified(head), source))
+                }
+                _ => None,
+            };
+            if found.is_some() {
+                return found
+            }
+        }
+        None
+    }
+
+    /// Returns the canonical name and the remapped path of the import bringing the name into
+    /// the file, e.g. `Script` and `lib/forge-std/src/Script.sol` for
+    /// `import {Script} from "forge-std/Script.sol"`, without reading the imported file.
+    ///
+    /// The names of the plain imports are matched by the file name, e.g. `Test` for
+    /// `import "forge-std/Test.sol"`. Returns [None] if the name isn't imported.
+    pub(crate) fn import_source(&mut self, file: &Path, name: &str) -> Option<(String, PathBuf)> {
+        let file = normalize(file);
+        self.symbols(&file).imports.clone().into_iter().find_map(|import| match import {
+            Import::Rename(path, symbols, _) => symbols
+                .iter()
+                .find(|(symbol, alias)| alias.as_ref().unwrap_or(symbol).name == name)
+                .map(|(symbol, _)| (symbol.name.clone(), self.import_path(&file, &path.string))),
+            Import::Plain(path, _) => {
+                let imported = self.import_path(&file, &path.string);
+                let stem = imported.file_stem()?.to_str()?.split('.').next()?.to_owned();
+                (stem == name).then(|| (stem, imported))
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the declaration of the name used in the file among the declarations keyed by
+    /// the canonical name, with the declaring file of each.
+    ///
+    /// The name is resolved through the imports of the file. The names which can't be
+    /// resolved, e.g. of the unparsable sources, fall back to the only declaration with
+    /// the name. Returns [None] if the declaration can't be told apart or isn't documented.
+    pub(crate) fn declaration<'a, T>(
+        &mut self,
+        file: &Path,
+        name: &str,
+        declarations: &'a HashMap<String, Vec<(PathBuf, T)>>,
+    ) -> Option<&'a (PathBuf, T)> {
+        match self.resolve(file, name) {
+            Some((name, source)) => {
+                declarations.get(&name)?.iter().find(|(path, _)| normalize(path) == source)
+            }
+            None => match declarations.get(name)?.as_slice() {
+                [declaration] => Some(declaration),
+                _ => None,
+            },
+        }
+    }
+
+    /// Resolve the import path, relative to the importing file or through the remappings.
+    pub(crate) fn import_path(&self, file: &Path, import: &str) -> PathBuf {
+        let path = if import.starts_with("./") || import.starts_with("../") {
+            file.parent().unwrap_or(file).join(import)
+        } else {
+            let remapping = self
+                .remappings
+                .iter()
+                .filter(|remapping| import.starts_with(&remapping.name))
+                .max_by_key(|remapping| remapping.name.len());
+            let path = match remapping {
+                Some(remapping) => {
+                    PathBuf::from(format!("{}{}", remapping.path, &import[remapping.name.len()..]))
+                }
+                None => PathBuf::from(import),
+            };
+            if path.is_relative() {
+                self.root.join(path)
+            } else {
+                path
+            }
+        };
+        normalize(&path)
+    }
+
+    /// Returns the file declaring the name, following the imports of the file.
+    fn declaring_file(
+        &mut self,
+        file: &Path,
+        name: &str,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Option<PathBuf> {
+        if !visited.insert(file.to_path_buf()) {
+            return None
+        }
+        if self.symbols(file).declared.contains(name) {
+            return Some(file.to_path_buf())
+        }
+
+        for import in self.symbols(file).imports.clone() {
+            let found = match import {
+                Import::Plain(path, _) => {
+                    let imported = self.import_path(file, &path.string);
+                    self.declaring_file(&imported, name, visited)
+                }
+                Import::Rename(path, renamed, _) => {
+                    match renamed
+                        .iter()
+                        .find(|(symbol, alias)| alias.as_ref().unwrap_or(symbol).name == name)
+                    {
+                        Some((symbol, _)) => {
+                            let imported = self.import_path(file, &path.string);
+                            self.declaring_file(&imported, &symbol.name, visited)
+                        }
+                        None => None,
+                    }
+                }
+                _ => None,
+            };
+            if found.is_some() {
+                return found
+            }
+        }
+        None
+    }
+
+    /// Returns the symbols of the file, reading it if it's not documented.
+    fn symbols(&mut self, file: &Path) -> &FileSymbols {
+        self.files.entry(file.to_path_buf()).or_insert_with(|| {
+            fs::read_to_string(file).map(|source| file_symbols(&source)).unwrap_or_default()
+        })
+    }
+}
+
+/// The ABI types of the documented user defined types, i.e. `address` for the contracts,
+/// `uint8` for the enums and the underlying type for the user defined value types, keyed by
+/// the canonical name, e.g. `Vault.Status`, with the declaring file of each.
+///
+/// The structs aren't included, as their tuple type depends on the types of their fields.
+#[derive(Debug, Default)]
+pub(crate) struct AbiTypes {
+    types: HashMap<String, Vec<(PathBuf, String)>>,
+}
+
+impl AbiTypes {
+    /// Collect the ABI types of the user defined types declared in the documents.
+    pub(crate) fn new(documents: &[Document]) -> Self {
+        let mut types: HashMap<String, Vec<(PathBuf, String)>> = HashMap::new();
+        for document in documents.iter() {
+            let item = match document.content {
+                DocumentContent::Single(ref item) => item,
+                _ => continue,
+            };
+            let mut declare = |name: String, ty: String| {
+                types.entry(name).or_default().push((document.item_path.clone(), ty));
+            };
+            match item.source {
+                ParseSource::Contract(ref contract) => {
+                    let contract = &contract.name.safe_unwrap().name;
+                    declare(contract.clone(), "address".to_owned());
+                    for child in item.children.iter() {
+                        let ty = match child.source {
+                            ParseSource::Enum(_) => "uint8".to_owned(),
+                            ParseSource::Type(ref def) => canonical_type(&def.ty),
+                            _ => continue,
+                        };
+                        declare(format!("{contract}.{}", child.source.ident()), ty);
+                    }
+                }
+                ParseSource::Enum(_) => declare(item.source.ident(), "uint8".to_owned()),
+                ParseSource::Type(ref def) => declare(item.source.ident(), canonical_type(&def.ty)),
+                _ => {}
+            }
+        }
+        Self { types }
+    }
+
+    /// Returns the ABI type of the type used in the contract of the file,
+    /// e.g. `uint8[]` for the `Status[]` enum array.
+    /// The types which aren't documented, e.g. the structs, are returned as declared.
+    pub(crate) fn resolve(
+        &self,
+        resolver: &mut ImportResolver,
+        file: &Path,
+        contract: Option<&str>,
+        ty: &Expression,
+    ) -> String {
+        match ty {
+            Expression::ArraySubscript(_, inner, size) => format!(
+                "{}[{}]",
+                self.resolve(resolver, file, contract, inner),
+                size.as_ref().map(|size| size.to_string()).unwrap_or_default()
+            ),
+            Expression::Variable(_) | Expression::MemberAccess(..) => {
+                let name = match user_type_name(ty) {
+                    Some(name) => name,
+                    None => return canonical_type(ty),
+                };
+                // The types of the same contract shadow the imported ones
+                let local = contract.and_then(|contract| {
+                    self.types
+                        .get(&format!("{contract}.{name}"))?
+                        .iter()
+                        .find(|(path, _)| path == file)
+                });
+                local
+                    .or_else(|| resolver.declaration(file, &name, &self.types))
+                    .map_or_else(|| canonical_type(ty), |(_, abi_type)| abi_type.clone())
+            }
+            _ => canonical_type(ty),
+        }
+    }
+}
+
+/// Returns the symbols of the source, or none if it can't be parsed.
+fn file_symbols(source: &str) -> FileSymbols {
+    solang_parser::parse(source, 0)
+        .map(|(source_unit, _)| FileSymbols {
+            declared: declarations(&source_unit),
+            imports: imports(source_unit),
+        })
+        .unwrap_or_default()
+}
+
+/// Resolve the parent and the current directories of the path, so the paths can be compared.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    path.components().fold(PathBuf::new(), |mut normalized, component| {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            component => normalized.push(component),
+        }
+        normalized
+    })
+}
+
+/// Returns the import directives of the source unit.
+pub(crate) fn imports(source_unit: SourceUnit) -> Vec<Import> {
+    source_unit
+        .0
+        .into_iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ImportDirective(import) => Some(import),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the names of the top-level types declared in the source unit.
+fn declarations(source_unit: &SourceUnit) -> HashSet<String> {
+    source_unit
+        .0
+        .iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ContractDefinition(def) => def.name.as_ref(),
+            SourceUnitPart::StructDefinition(def) => def.name.as_ref(),
+            SourceUnitPart::EnumDefinition(def) => def.name.as_ref(),
+            SourceUnitPart::TypeDefinition(def) => Some(&def.name),
+            _ => None,
+        })
+        .map(|name| name.name.clone())
+        .collect()
+}
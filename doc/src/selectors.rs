@@ -0,0 +1,441 @@
+//! Registering the documented selectors in the public signature databases.
+
+use crate::{DocModel, ModelMember};
+use foundry_config::{DocSelectorsConfig, SelectorDatabase, SignatureDatabase};
+use reqwest::{
+    header::{AUTHORIZATION, RETRY_AFTER},
+    RequestBuilder, Response, StatusCode,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{collections::HashMap, fmt, time::Duration};
+
+/// The signatures of the documented selectors, i.e. the external and public functions,
+/// the errors and the events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorSignatures {
+    /// The function signatures, e.g. `transfer(address,uint256)`.
+    pub functions: Vec<String>,
+    /// The error signatures, e.g. `InsufficientBalance(uint256,uint256)`.
+    pub errors: Vec<String>,
+    /// The event signatures, e.g. `Transfer(address,address,uint256)`.
+    pub events: Vec<String>,
+    /// The signatures with the user defined types, e.g. `open(Position)`, prefixed by the kind.
+    /// They are not submitted, since the types aren't resolved to their ABI form.
+    pub skipped: Vec<String>,
+}
+
+impl SelectorSignatures {
+    /// Collect the signatures from the documentation model.
+    /// The free functions are not included, since they aren't externally callable.
+    pub fn new(model: &DocModel) -> Self {
+        let mut signatures = Self::default();
+        for item in model.items.iter() {
+            if matches!(item.kind.as_str(), "event" | "error") {
+                signatures.add(&item.kind, &item.signature);
+            }
+            for member in item.members.iter() {
+                match member.kind.as_str() {
+                    "function" if is_external(member) => {
+                        signatures.add(&member.kind, &member.signature)
+                    }
+                    "event" | "error" => signatures.add(&member.kind, &member.signature),
+                    _ => {}
+                }
+            }
+        }
+        signatures
+    }
+
+    /// Returns true if there are no signatures to submit.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.errors.is_empty() && self.events.is_empty()
+    }
+
+    fn add(&mut self, kind: &str, signature: &str) {
+        let (signatures, signature) = if !is_canonical(signature) {
+            (&mut self.skipped, format!("{kind} {signature}"))
+        } else {
+            let signatures = match kind {
+                "function" => &mut self.functions,
+                "event" => &mut self.events,
+                _ => &mut self.errors,
+            };
+            (signatures, signature.to_owned())
+        };
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+}
+
+impl fmt::Display for SelectorSignatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, signatures) in
+            [("function", &self.functions), ("error", &self.errors), ("event", &self.events)]
+        {
+            for signature in signatures.iter() {
+                writeln!(f, "{kind} {signature}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if the function member is declared external or public.
+fn is_external(member: &ModelMember) -> bool {
+    matches!(member.visibility.as_deref(), Some("external" | "public"))
+}
+
+/// Returns true if the signature parameters are the elementary ABI types, e.g. `uint256[2][]`.
+pub(crate) fn is_canonical(signature: &str) -> bool {
+    let params = match signature.split_once('(') {
+        Some((_, params)) => params.trim_end_matches(')'),
+        None => return false,
+    };
+    params.split(',').filter(|param| !param.is_empty()).all(|param| {
+        let ty = param.split('[').next().unwrap_or(param);
+        let sized = |prefix: &str, sizes: &[usize]| {
+            ty.strip_prefix(prefix)
+                .and_then(|size| size.parse::<usize>().ok())
+                .map_or(false, |size| sizes.contains(&size))
+        };
+        let bits = (1..=32).map(|bytes| bytes * 8).collect::<Vec<_>>();
+        let bytes = (1..=32).collect::<Vec<_>>();
+        matches!(ty, "address" | "bool" | "string" | "bytes" | "function") ||
+            sized("uint", &bits) ||
+            sized("int", &bits) ||
+            sized("bytes", &bytes)
+    })
+}
+
+/// The outcome of the signatures submission to the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectorUpload {
+    /// The number of the newly registered signatures.
+    pub imported: usize,
+    /// The number of the already known signatures.
+    pub known: usize,
+}
+
+impl fmt::Display for SelectorUpload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} new, {} already known", self.imported, self.known)
+    }
+}
+
+/// The openchain.xyz import response. The errors are imported as the functions.
+#[derive(Debug, Deserialize)]
+struct OpenchainResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    result: OpenchainResult,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpenchainResult {
+    #[serde(default)]
+    function: OpenchainEffect,
+    #[serde(default)]
+    event: OpenchainEffect,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpenchainEffect {
+    #[serde(default)]
+    imported: HashMap<String, String>,
+    #[serde(default)]
+    duplicated: HashMap<String, String>,
+}
+
+/// The client registering the selectors in the configured signature databases.
+///
+/// The rate limited requests and the server errors are retried with the exponential backoff,
+/// honoring the `Retry-After` header if the database sends one.
+#[derive(Debug)]
+pub struct SelectorRegistry {
+    client: reqwest::Client,
+    config: DocSelectorsConfig,
+    backoff: Duration,
+}
+
+impl SelectorRegistry {
+    /// The request timeout.
+    const TIMEOUT: Duration = Duration::from_secs(15);
+
+    /// Create the registry of the configured databases.
+    pub fn new(config: DocSelectorsConfig) -> eyre::Result<Self> {
+        let client = reqwest::Client::builder().timeout(Self::TIMEOUT).build()?;
+        Ok(Self { client, config, backoff: Duration::from_secs(1) })
+    }
+
+    /// Submit the signatures to each of the configured databases.
+    ///
+    /// The results are returned per database, so that the failure of one database
+    /// doesn't prevent the registration in the others.
+    pub async fn register(
+        &self,
+        signatures: &SelectorSignatures,
+    ) -> Vec<(SignatureDatabase, eyre::Result<SelectorUpload>)> {
+        let mut results = vec![];
+        for database in self.config.databases.iter() {
+            let result = match database.kind {
+                SignatureDatabase::Openchain => self.register_openchain(database, signatures).await,
+                SignatureDatabase::Fourbyte => self.register_fourbyte(database, signatures).await,
+            };
+            results.push((database.kind, result));
+        }
+        results
+    }
+
+    /// Import all signatures at once. The import only accepts the functions and the events,
+    /// so the errors, sharing the function selector format, are imported as the functions.
+    async fn register_openchain(
+        &self,
+        database: &SelectorDatabase,
+        signatures: &SelectorSignatures,
+    ) -> eyre::Result<SelectorUpload> {
+        let functions =
+            signatures.functions.iter().chain(signatures.errors.iter()).collect::<Vec<_>>();
+        let body = json!({
+            "type": "raw",
+            "data": {
+                "function": functions,
+                "event": signatures.events,
+            },
+        });
+        let response = self.send(database, || self.client.post(database.url()).json(&body)).await?;
+        let response: OpenchainResponse = response.error_for_status()?.json().await?;
+        if !response.ok {
+            eyre::bail!("{}", response.error.unwrap_or_else(|| "import rejected".to_owned()))
+        }
+        let OpenchainResult { function, event } = response.result;
+        Ok(SelectorUpload {
+            imported: function.imported.len() + event.imported.len(),
+            known: function.duplicated.len() + event.duplicated.len(),
+        })
+    }
+
+    /// Submit the signatures one by one, the errors are registered as the functions.
+    async fn register_fourbyte(
+        &self,
+        database: &SelectorDatabase,
+        signatures: &SelectorSignatures,
+    ) -> eyre::Result<SelectorUpload> {
+        let url = database.url().trim_end_matches('/');
+        let functions = format!("{url}/signatures/");
+        let events = format!("{url}/event-signatures/");
+        let submissions = signatures
+            .functions
+            .iter()
+            .chain(signatures.errors.iter())
+            .map(|signature| (&functions, signature))
+            .chain(signatures.events.iter().map(|signature| (&events, signature)));
+
+        let mut upload = SelectorUpload::default();
+        for (endpoint, signature) in submissions {
+            let body = json!({ "text_signature": signature });
+            let response = self.send(database, || self.client.post(endpoint).json(&body)).await?;
+            match response.status() {
+                status if status.is_success() => upload.imported += 1,
+                // The known signatures are rejected as the duplicates
+                StatusCode::BAD_REQUEST => upload.known += 1,
+                status => eyre::bail!("failed to submit `{signature}`: {status}"),
+            }
+        }
+        Ok(upload)
+    }
+
+    /// Send the request, retrying the rate limited, failed and timed out requests.
+    async fn send(
+        &self,
+        database: &SelectorDatabase,
+        request: impl Fn() -> RequestBuilder,
+    ) -> eyre::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let mut request = request();
+            if let Some(ref key) = database.key {
+                request = request.header(AUTHORIZATION, key);
+            }
+            let result = request.send().await;
+            let retry = match result {
+                Ok(ref response) => {
+                    response.status() == StatusCode::TOO_MANY_REQUESTS ||
+                        response.status().is_server_error()
+                }
+                Err(ref err) => err.is_timeout() || err.is_connect(),
+            };
+            if !retry || attempt >= self.config.retries {
+                return Ok(result?)
+            }
+
+            let delay = result
+                .ok()
+                .and_then(|response| retry_after(&response))
+                .unwrap_or_else(|| self.backoff.saturating_mul(2u32.saturating_pow(attempt)));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Returns the delay requested by the `Retry-After` header in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+    use std::path::Path;
+
+    #[test]
+    fn model_signatures() {
+        let src = r#"
+            error Unauthorized(address caller);
+
+            contract Vault {
+                struct Position { uint256 amount; }
+
+                event Deposit(address indexed owner, uint amount);
+                error Paused();
+
+                function deposit(uint amount) external { }
+                function balances(address[] memory owners) public view returns (uint256[] memory) { }
+                function open(Position memory position) external { }
+                function _deposit(uint256 amount) internal { }
+            }
+
+            function helper(uint256 amount) pure returns (uint256) { }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+        let model = DocModel::new(&documents, Path::new(""), Path::new("docs/src"));
+
+        let signatures = SelectorSignatures::new(&model);
+        assert_eq!(
+            signatures,
+            SelectorSignatures {
+                functions: vec!["deposit(uint256)".to_owned(), "balances(address[])".to_owned()],
+                errors: vec!["Unauthorized(address)".to_owned(), "Paused()".to_owned()],
+                events: vec!["Deposit(address,uint256)".to_owned()],
+                skipped: vec!["function open(Position)".to_owned()],
+            }
+        );
+        assert_eq!(
+            signatures.to_string(),
+            "function deposit(uint256)\nfunction balances(address[])\nerror Unauthorized(address)\n\
+             error Paused()\nevent Deposit(address,uint256)\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn register_mock_databases() {
+        use std::{
+            net::SocketAddr,
+            sync::{Arc, Mutex},
+        };
+        use warp::{http::StatusCode, path::FullPath, Filter, Reply};
+
+        // The requests received by the mock databases, with their authorization header
+        let requests =
+            Arc::new(Mutex::new(Vec::<(String, Option<String>, serde_json::Value)>::new()));
+        let received = requests.clone();
+        let routes = warp::post()
+            .and(warp::path::full())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json())
+            .map(move |path: FullPath, key: Option<String>, body: serde_json::Value| {
+                let mut received = received.lock().unwrap();
+                received.push((path.as_str().to_owned(), key, body.clone()));
+                let attempts = received.iter().filter(|(sent, _, _)| sent == path.as_str()).count();
+                match path.as_str() {
+                    // The first import is rate limited
+                    "/openchain" if attempts == 1 => {
+                        warp::reply::with_header(StatusCode::TOO_MANY_REQUESTS, "retry-after", "0")
+                            .into_response()
+                    }
+                    "/openchain" => warp::reply::json(&json!({
+                        "ok": true,
+                        "result": {
+                            "function": {
+                                "imported": { "0xb6b55f25": "deposit(uint256)" },
+                                "duplicated": { "0x9e87fac8": "Paused()" },
+                            },
+                            "event": {
+                                "imported": { "0x0000": "Deposit(address,uint256)" },
+                            },
+                        },
+                    }))
+                    .into_response(),
+                    _ if body["text_signature"] == "Paused()" => {
+                        StatusCode::BAD_REQUEST.into_response()
+                    }
+                    _ => StatusCode::CREATED.into_response(),
+                }
+            });
+        let (addr, server): (SocketAddr, _) =
+            warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let database = |kind, path: &str, key: Option<&str>| SelectorDatabase {
+            kind,
+            url: Some(format!("http://{addr}{path}")),
+            key: key.map(str::to_owned),
+        };
+        let config = DocSelectorsConfig {
+            databases: vec![
+                database(SignatureDatabase::Openchain, "/openchain", Some("secret")),
+                database(SignatureDatabase::Fourbyte, "/4byte", None),
+            ],
+            retries: 1,
+        };
+        let signatures = SelectorSignatures {
+            functions: vec!["deposit(uint256)".to_owned()],
+            errors: vec!["Paused()".to_owned()],
+            events: vec!["Deposit(address,uint256)".to_owned()],
+            skipped: vec![],
+        };
+        let results = SelectorRegistry::new(config).unwrap().register(&signatures).await;
+
+        let uploads = results
+            .into_iter()
+            .map(|(database, upload)| (database, upload.unwrap()))
+            .collect::<Vec<_>>();
+        let upload = SelectorUpload { imported: 2, known: 1 };
+        assert_eq!(
+            uploads,
+            vec![(SignatureDatabase::Openchain, upload), (SignatureDatabase::Fourbyte, upload)]
+        );
+
+        let requests = requests.lock().unwrap();
+        let paths = requests.iter().map(|(path, _, _)| path.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "/openchain",
+                "/openchain",
+                "/4byte/signatures/",
+                "/4byte/signatures/",
+                "/4byte/event-signatures/",
+            ]
+        );
+        assert_eq!(requests[0].1.as_deref(), Some("secret"));
+        assert_eq!(requests[2].1, None);
+        assert_eq!(
+            requests[1].2,
+            json!({
+                "type": "raw",
+                "data": {
+                    "function": ["deposit(uint256)", "Paused()"],
+                    "event": ["Deposit(address,uint256)"],
+                },
+            })
+        );
+        assert_eq!(requests[3].2, json!({ "text_signature": "Paused()" }));
+    }
+}
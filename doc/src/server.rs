@@ -1,8 +1,10 @@
+use crate::{DocEvent, EventSink, LandingPage};
 use futures_util::{SinkExt, StreamExt};
 use mdbook::{utils::fs::get_404_output_file, MDBook};
 use std::{
     net::{SocketAddr, ToSocketAddrs},
     path::PathBuf,
+    sync::Arc,
 };
 use tokio::sync::broadcast;
 use warp::{ws::Message, Filter};
@@ -16,11 +18,19 @@ pub struct Server {
     path: PathBuf,
     hostname: String,
     port: usize,
+    open: bool,
+    events: Option<Arc<dyn EventSink>>,
 }
 
 impl Default for Server {
     fn default() -> Self {
-        Self { path: PathBuf::default(), hostname: "localhost".to_owned(), port: 3000 }
+        Self {
+            path: PathBuf::default(),
+            hostname: "localhost".to_owned(),
+            port: 3000,
+            open: false,
+            events: None,
+        }
     }
 }
 
@@ -42,6 +52,25 @@ impl Server {
         self
     }
 
+    /// Set whether to open the served url on the [Server].
+    pub fn with_open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Set the sink of the messages on the [Server], e.g. the served address.
+    pub fn with_events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Emit the message to the sink, if any.
+    fn message(&self, message: String) {
+        if let Some(ref events) = self.events {
+            events.emit(&DocEvent::Message { message });
+        }
+    }
+
     /// Serve the mdbook.
     pub fn serve(self) -> eyre::Result<()> {
         let mut book =
@@ -78,7 +107,13 @@ impl Server {
             serve(build_dir, sockaddr, tx, &file_404);
         });
 
-        println!("Serving on: http://{address}");
+        self.message(format!("Serving on: http://{address}"));
+        if self.open {
+            let page = LandingPage::resolve(&self.path, Some(format!("http://{address}")));
+            if !page.open() {
+                self.message(format!("Open {page} to view the documentation"));
+            }
+        }
 
         let _ = thread_handle.join();
 
@@ -0,0 +1,74 @@
+//! The `sitemap.xml` of the built HTML book.
+
+use std::{fmt::Write, path::Path};
+use walkdir::WalkDir;
+
+/// The sitemap file name.
+pub(crate) const SITEMAP: &str = "sitemap.xml";
+
+/// The generated pages which aren't listed, i.e. the not found page,
+/// the printable single-page book and the table of contents fragment.
+const EXCLUDED_PAGES: &[&str] = &["404.html", "print.html", "toc.html"];
+
+/// Render the sitemap of the HTML pages in the book build directory, with the page URLs
+/// relative to the site URL, e.g. `https://docs.example.com/src/Vault.sol/contract.Vault.html`.
+pub(crate) fn sitemap(html_dir: &Path, site_url: &str) -> eyre::Result<String> {
+    let mut pages = WalkDir::new(html_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(html_dir).ok()?;
+            let is_page = path.extension().map_or(false, |ext| ext == "html") &&
+                !EXCLUDED_PAGES.iter().any(|page| path == Path::new(page));
+            is_page.then(|| path.display().to_string().replace('\\', "/"))
+        })
+        .collect::<Vec<_>>();
+    pages.sort();
+
+    let site_url = site_url.trim_end_matches('/');
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for page in pages {
+        // The index pages are served at the directory URL
+        let page = page.strip_suffix("index.html").unwrap_or(&page);
+        writeln!(out, "  <url><loc>{}</loc></url>", escape(&format!("{site_url}/{page}")))?;
+    }
+    out.push_str("</urlset>\n");
+    Ok(out)
+}
+
+/// Escape the XML special characters.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn html_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = dir.path();
+        fs::create_dir_all(html.join("src/Vault.sol")).unwrap();
+        for page in ["index.html", "404.html", "print.html", "src/Vault.sol/contract.Vault.html"] {
+            fs::write(html.join(page), "").unwrap();
+        }
+        fs::write(html.join("book.js"), "").unwrap();
+
+        assert_eq!(
+            sitemap(html, "https://docs.example.com/").unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n  \
+             <url><loc>https://docs.example.com/</loc></url>\n  \
+             <url><loc>https://docs.example.com/src/Vault.sol/contract.Vault.html</loc></url>\n\
+             </urlset>\n"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! The machine-readable interaction spec of the documented contracts.
+//!
+//! Unlike the [DocModel](crate::DocModel), the spec is flattened per externally callable
+//! function, so that the client wrappers can be generated from it without the pages.
+
+use crate::{
+    document::DocumentContent,
+    helpers::{
+        canonical_type, function_signature, is_externally_visible, selector, walk_statements,
+    },
+    CommentTag, Comments, Document,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use solang_parser::pt::{
+    ContractTy, Expression, FunctionAttribute, FunctionDefinition, FunctionTy, Mutability,
+    Parameter, Statement,
+};
+use std::path::{Path, PathBuf};
+
+/// The interaction spec of the contract, written to `spec/<source>/<contract>.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractSpec {
+    /// The spec schema version.
+    pub version: u32,
+    /// The contract name.
+    pub name: String,
+    /// The contract kind, i.e. `contract`, `abstract` or `interface`.
+    pub kind: String,
+    /// The source file path relative to the project root.
+    pub source: PathBuf,
+    /// The externally callable functions.
+    pub functions: Vec<FunctionSpec>,
+}
+
+/// The externally callable function of the [ContractSpec].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionSpec {
+    /// The function name.
+    pub name: String,
+    /// The function signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// The function selector, e.g. `0xa9059cbb`.
+    pub selector: String,
+    /// The state mutability, i.e. `pure`, `view`, `nonpayable` or `payable`.
+    pub mutability: String,
+    /// Whether the function accepts ether.
+    pub payable: bool,
+    /// The `@notice` comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    /// The `@dev` comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev: Option<String>,
+    /// The function inputs.
+    pub inputs: Vec<ParamSpec>,
+    /// The function outputs.
+    pub outputs: Vec<ParamSpec>,
+    /// The names of the events the function body may emit.
+    pub emits: Vec<String>,
+    /// The names of the custom errors the function body may revert with.
+    pub reverts: Vec<String>,
+}
+
+/// The input or output of the [FunctionSpec].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamSpec {
+    /// The parameter name, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The canonical ABI type, e.g. `uint256`. User defined types are kept as declared.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The `@param` or `@return` description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The project index of the contract specs, written to `spec/index.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecIndex {
+    /// The spec schema version.
+    pub version: u32,
+    /// The documented contracts.
+    pub contracts: Vec<SpecIndexEntry>,
+}
+
+/// The entry of the [SpecIndex].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecIndexEntry {
+    /// The contract name.
+    pub name: String,
+    /// The contract kind.
+    pub kind: String,
+    /// The spec file path relative to the spec directory.
+    pub spec: PathBuf,
+}
+
+impl ContractSpec {
+    /// The current spec schema version.
+    pub const VERSION: u32 = 1;
+    /// The name of the spec directory within the doc output directory.
+    pub const DIR: &'static str = "spec";
+    /// The name of the index file.
+    pub const INDEX: &'static str = "index.json";
+
+    /// Collect the specs of the documented contracts and interfaces.
+    /// The libraries are skipped, since they are not called directly.
+    pub(crate) fn collect(documents: &[Document], root: &Path) -> Vec<Self> {
+        documents
+            .iter()
+            .filter_map(|document| {
+                let item = match document.content {
+                    DocumentContent::Single(ref item) => item,
+                    _ => return None,
+                };
+                let contract = item.as_contract()?;
+                let kind = match contract.ty {
+                    ContractTy::Contract(_) => "contract",
+                    ContractTy::Abstract(_) => "abstract",
+                    ContractTy::Interface(_) => "interface",
+                    ContractTy::Library(_) => return None,
+                };
+                let functions = item
+                    .functions()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(func, _, _)| {
+                        matches!(func.ty, FunctionTy::Function) && is_externally_visible(func)
+                    })
+                    .filter_map(|(func, comments, _)| FunctionSpec::new(func, comments))
+                    .collect();
+                Some(Self {
+                    version: Self::VERSION,
+                    name: contract.name.safe_unwrap().name.clone(),
+                    kind: kind.to_owned(),
+                    source: document
+                        .item_path
+                        .strip_prefix(root)
+                        .unwrap_or(&document.item_path)
+                        .to_path_buf(),
+                    functions,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the spec file path relative to the spec directory,
+    /// e.g. `src/Vault.sol/Vault.json`.
+    pub fn file(&self) -> PathBuf {
+        self.source.join(format!("{}.json", self.name))
+    }
+}
+
+impl FunctionSpec {
+    fn new(func: &FunctionDefinition, comments: &Comments) -> Option<Self> {
+        let signature = function_signature(func)?;
+        let mutability = func
+            .attributes
+            .iter()
+            .find_map(|attr| match attr {
+                FunctionAttribute::Mutability(Mutability::Pure(_)) => Some("pure"),
+                FunctionAttribute::Mutability(Mutability::View(_) | Mutability::Constant(_)) => {
+                    Some("view")
+                }
+                FunctionAttribute::Mutability(Mutability::Payable(_)) => Some("payable"),
+                _ => None,
+            })
+            .unwrap_or("nonpayable");
+        let comment = |tag: CommentTag| {
+            let docs = comments.include_tag(tag);
+            let values = docs.iter().map(|doc| doc.value.trim()).collect::<Vec<_>>();
+            (!values.is_empty()).then(|| values.join("\n"))
+        };
+
+        let params = |params: Vec<&Parameter>, tag: CommentTag| {
+            let docs = comments.include_tag(tag.clone());
+            let mut positional = docs.iter().filter(|doc| {
+                !params.iter().any(|param| {
+                    param
+                        .name
+                        .as_ref()
+                        .map(|name| doc.match_first_word(&name.name).is_some())
+                        .unwrap_or_default()
+                })
+            });
+            params
+                .iter()
+                .map(|param| {
+                    let name = param.name.as_ref().map(|name| name.name.clone());
+                    let mut description = name.as_ref().and_then(|name| {
+                        docs.iter().find_map(|doc| doc.match_first_word(name)).map(str::to_owned)
+                    });
+                    // The return descriptions are matched by position if not matched by name.
+                    if description.is_none() && matches!(tag, CommentTag::Return) {
+                        description = positional.next().map(|doc| doc.value.clone());
+                    }
+                    ParamSpec {
+                        name,
+                        ty: canonical_type(&param.ty),
+                        description: description.map(|desc| desc.trim().replace('\n', " ")),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let (emits, reverts) = called_names(func);
+        Some(Self {
+            name: func.name.safe_unwrap().name.clone(),
+            selector: selector(&signature),
+            signature,
+            mutability: mutability.to_owned(),
+            payable: mutability == "payable",
+            notice: comment(CommentTag::Notice),
+            dev: comment(CommentTag::Dev),
+            inputs: params(
+                func.params.iter().filter_map(|(_, param)| param.as_ref()).collect(),
+                CommentTag::Param,
+            ),
+            outputs: params(
+                func.returns.iter().filter_map(|(_, param)| param.as_ref()).collect(),
+                CommentTag::Return,
+            ),
+            emits,
+            reverts,
+        })
+    }
+}
+
+/// Returns the distinct names of the events emitted and the errors reverted with in the function
+/// body, e.g. `Transfer` for `emit Transfer(from, to, amount)` and `Errors.Paused` for
+/// `revert Errors.Paused()`.
+fn called_names(func: &FunctionDefinition) -> (Vec<String>, Vec<String>) {
+    let (mut emits, mut reverts) = (vec![], vec![]);
+    let push = |names: &mut Vec<String>, name: String| {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    };
+    if let Some(ref body) = func.body {
+        walk_statements(body, &mut |statement| match statement {
+            Statement::Emit(_, Expression::FunctionCall(_, event, _)) |
+            Statement::Emit(_, Expression::NamedFunctionCall(_, event, _)) => {
+                push(&mut emits, event.to_string())
+            }
+            Statement::Revert(_, Some(error), _) |
+            Statement::RevertNamedArgs(_, Some(error), _) => {
+                let name = error.identifiers.iter().map(|ident| ident.name.as_str()).join(".");
+                push(&mut reverts, name)
+            }
+            _ => {}
+        });
+    }
+    (emits, reverts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    #[test]
+    fn vault_spec() {
+        let src = r#"
+            contract Vault {
+                event Deposit(address indexed owner, uint256 amount);
+                error ZeroAmount();
+
+                /// @notice Deposits the tokens.
+                /// @param amount The amount to deposit.
+                /// @return shares The minted shares.
+                function deposit(uint amount) external payable returns (uint256 shares) {
+                    if (amount == 0) revert ZeroAmount();
+                    // emit Withdraw(msg.sender, amount);
+                    string memory reason = "revert Paused()";
+                    emit Deposit(msg.sender, amount);
+                    emit Deposit({owner: msg.sender, amount: amount});
+                    revert Errors.Paused();
+                    revert("unreachable");
+                }
+
+                function total() public view returns (uint256) { }
+
+                function _mint() internal { }
+            }
+        "#;
+        let specs = ContractSpec::collect(&test_documents("src/Vault.sol", src), Path::new(""));
+        assert_eq!(specs.len(), 1);
+        let spec = &specs[0];
+        assert_eq!(spec.file(), PathBuf::from("src/Vault.sol/Vault.json"));
+        assert_eq!(
+            spec.functions.iter().map(|func| func.signature.as_str()).collect::<Vec<_>>(),
+            vec!["deposit(uint256)", "total()"]
+        );
+
+        let deposit = &spec.functions[0];
+        assert_eq!(deposit.selector, "0xb6b55f25");
+        assert!(deposit.payable);
+        assert_eq!(deposit.notice.as_deref(), Some("Deposits the tokens."));
+        assert_eq!(
+            deposit.inputs,
+            vec![ParamSpec {
+                name: Some("amount".to_owned()),
+                ty: "uint256".to_owned(),
+                description: Some("The amount to deposit.".to_owned()),
+            }]
+        );
+        assert_eq!(deposit.outputs[0].description.as_deref(), Some("The minted shares."));
+        assert_eq!(deposit.emits, vec!["Deposit"]);
+        assert_eq!(deposit.reverts, vec!["ZeroAmount", "Errors.Paused"]);
+
+        let total = &spec.functions[1];
+        assert_eq!(total.mutability, "view");
+        assert!(total.emits.is_empty());
+    }
+}
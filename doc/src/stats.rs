@@ -0,0 +1,150 @@
+//! The statistics of the documentation generation.
+
+use crate::{number, DocModel, SINCE_TAG};
+use serde::{Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, path::PathBuf, time::Duration};
+
+/// The statistics of the documentation generation, i.e. the time spent in each stage,
+/// the written pages and the documented items.
+///
+/// The statistics are not deterministic, so they are never written to the output directory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocStats {
+    /// The time spent parsing the sources.
+    #[serde(serialize_with = "serialize_millis")]
+    pub parse: Duration,
+    /// The time spent applying the preprocessors and collecting the model.
+    #[serde(serialize_with = "serialize_millis")]
+    pub model: Duration,
+    /// The time spent rendering the pages.
+    #[serde(serialize_with = "serialize_millis")]
+    pub render: Duration,
+    /// The time spent writing the pages and the additional outputs.
+    #[serde(serialize_with = "serialize_millis")]
+    pub write: Duration,
+    /// The number of pages written.
+    pub pages_written: usize,
+    /// The number of pages left untouched, since their content didn't change.
+    pub pages_unchanged: usize,
+    /// The number of pages left over from the previous generations,
+    /// i.e. the pages without a corresponding documented item.
+    pub pages_stale: usize,
+    /// The stale pages, relative to the output directory.
+    #[serde(skip)]
+    pub stale_pages: Vec<PathBuf>,
+    /// The number of documented items by kind, including the contract members.
+    pub items: BTreeMap<String, usize>,
+    /// The percentage of the items with natspec comments.
+    pub coverage: f64,
+}
+
+impl DocStats {
+    /// Collect the number of items and the natspec coverage from the model.
+    pub(crate) fn with_model(mut self, model: &DocModel) -> Self {
+        let mut documented = 0;
+        let entries = model.items.iter().map(|item| (&item.kind, &item.comments)).chain(
+            model
+                .items
+                .iter()
+                .flat_map(|item| item.all_members().map(|member| (&member.kind, &member.comments))),
+        );
+        for (kind, comments) in entries {
+            *self.items.entry(kind.clone()).or_default() += 1;
+            // The `@custom:since` tag alone doesn't document the item
+            if comments.iter().any(|comment| comment.tag.strip_prefix("custom:") != Some(SINCE_TAG))
+            {
+                documented += 1;
+            }
+        }
+
+        let total = self.items.values().sum::<usize>();
+        self.coverage = if total == 0 { 100. } else { documented as f64 * 100. / total as f64 };
+        self
+    }
+}
+
+impl fmt::Display for DocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16}{:>10}", "Stage", "Time")?;
+        for (stage, duration) in [
+            ("parse", self.parse),
+            ("model", self.model),
+            ("render", self.render),
+            ("write", self.write),
+        ] {
+            writeln!(f, "{stage:<16}{:>8}ms", duration.as_millis())?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "{:<16}{:>10}", "Pages", "Count")?;
+        for (pages, count) in [
+            ("written", self.pages_written),
+            ("unchanged", self.pages_unchanged),
+            ("stale", self.pages_stale),
+        ] {
+            writeln!(f, "{pages:<16}{count:>10}")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "{:<16}{:>10}", "Items", "Count")?;
+        for (kind, count) in self.items.iter() {
+            writeln!(f, "{kind:<16}{count:>10}")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "Coverage: {}", number::percentage(self.coverage))
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModelComment, ModelItem, ModelMember, SpecialFunctions};
+    use std::path::PathBuf;
+
+    #[test]
+    fn items_coverage() {
+        let member = |name: &str, comments: Vec<ModelComment>| ModelMember {
+            kind: "function".to_owned(),
+            name: name.to_owned(),
+            signature: format!("{name}()"),
+            code: format!("function {name}() external;"),
+            comments,
+            topic: None,
+            indexed: vec![],
+            visibility: None,
+            page: None,
+        };
+        let model = DocModel {
+            version: DocModel::VERSION,
+            items: vec![ModelItem {
+                kind: "contract".to_owned(),
+                name: "Vault".to_owned(),
+                signature: "Vault".to_owned(),
+                source: PathBuf::from("src/Vault.sol"),
+                page: PathBuf::from("src/Vault.sol/contract.Vault.md"),
+                code: "contract Vault".to_owned(),
+                comments: vec![ModelComment {
+                    tag: "notice".to_owned(),
+                    value: "Vault".to_owned(),
+                }],
+                security_contact: None,
+                special: SpecialFunctions::default(),
+                members: vec![member("deposit", vec![]), member("withdraw", vec![])],
+            }],
+            build_info: None,
+            redirects: BTreeMap::new(),
+        };
+
+        let stats = DocStats::default().with_model(&model);
+        assert_eq!(
+            stats.items,
+            BTreeMap::from([("contract".to_owned(), 1), ("function".to_owned(), 2)])
+        );
+        assert!((stats.coverage - 100. / 3.).abs() < f64::EPSILON * 100.);
+    }
+}
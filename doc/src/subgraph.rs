@@ -0,0 +1,370 @@
+//! The subgraph helpers of the documented events, i.e. the boilerplate of The Graph
+//! integrations: the entity suggestions, the `subgraph.yaml` event handlers and the
+//! AssemblyScript handler stubs.
+
+use crate::{
+    document::DocumentContent,
+    helpers::event_signature,
+    preprocessor::is_reference,
+    resolver::{AbiTypes, ImportResolver},
+    Document,
+};
+use ethers_core::abi::{HumanReadableParser, ParamType};
+use ethers_solc::remappings::Remapping;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{EventDefinition, Expression};
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The event parameter of the [SubgraphEvent].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SubgraphParam {
+    /// The parameter name, as generated by `graph codegen` for the unnamed ones.
+    name: String,
+    /// The canonical ABI type.
+    ty: String,
+    /// Whether the parameter is stored as the topic.
+    indexed: bool,
+    /// The GraphQL type of the entity field.
+    graphql: String,
+}
+
+/// The event of the [SubgraphHelpers] with its handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SubgraphEvent {
+    /// The event class and entity name, suffixed with the overload index like `graph codegen`.
+    name: String,
+    /// The event signature of the handler, e.g. `Transfer(indexed address,uint256)`.
+    signature: String,
+    /// The event parameters.
+    params: Vec<SubgraphParam>,
+}
+
+/// The subgraph helpers of the contract, written to `subgraph/<source>/<contract>/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SubgraphHelpers {
+    /// The contract name.
+    name: String,
+    /// The source file path relative to the project root.
+    source: PathBuf,
+    /// The events with the handlers.
+    events: Vec<SubgraphEvent>,
+    /// The events without the handlers, with the reason they're skipped.
+    skipped: Vec<(String, &'static str)>,
+}
+
+impl SubgraphHelpers {
+    /// The name of the subgraph helpers directory within the doc output directory.
+    pub(crate) const DIR: &'static str = "subgraph";
+
+    /// Collect the subgraph helpers of the documented contracts with the events.
+    /// The contracts, enums and user-defined value types of the event parameters are resolved
+    /// to their ABI types through the imports.
+    pub(crate) fn collect(
+        documents: &[Document],
+        root: &Path,
+        remappings: &[Remapping],
+    ) -> Vec<Self> {
+        let abi_types = AbiTypes::new(documents);
+        let mut resolver =
+            ImportResolver::new(root.to_path_buf(), remappings.to_vec()).with_documents(documents);
+        documents
+            .iter()
+            .filter_map(|document| {
+                let item = match document.content {
+                    DocumentContent::Single(ref item) => item,
+                    _ => return None,
+                };
+                let contract = item.as_contract()?;
+                let name = &contract.name.safe_unwrap().name;
+                let mut abi_type = |ty: &Expression| {
+                    abi_types.resolve(&mut resolver, &document.item_path, Some(name.as_str()), ty)
+                };
+                let mut overloads = HashMap::new();
+                let mut events = vec![];
+                let mut skipped = vec![];
+                for (event, _, _) in item.events()? {
+                    let signature = match event_signature(event) {
+                        Some(signature) => signature,
+                        None => continue,
+                    };
+                    if event.anonymous {
+                        skipped.push((signature, "the anonymous events have no signature topic"));
+                        continue
+                    }
+                    match SubgraphEvent::new(event, &mut overloads, &mut abi_type) {
+                        Some(event) => events.push(event),
+                        None => skipped
+                            .push((signature, "the struct and undocumented types aren't resolved")),
+                    }
+                }
+                if events.is_empty() && skipped.is_empty() {
+                    return None
+                }
+                Some(Self {
+                    name: name.clone(),
+                    source: document
+                        .item_path
+                        .strip_prefix(root)
+                        .unwrap_or(&document.item_path)
+                        .to_path_buf(),
+                    events,
+                    skipped,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the helpers directory path relative to the subgraph directory,
+    /// e.g. `src/Token.sol/Token`.
+    pub(crate) fn dir(&self) -> PathBuf {
+        self.source.join(&self.name)
+    }
+
+    /// Render the entity suggestions, i.e. the `schema.graphql` fragment.
+    /// The entities are immutable, with the fields of the event parameters and the log metadata.
+    pub(crate) fn schema(&self) -> Result<String, fmt::Error> {
+        let mut out = String::new();
+        for event in self.events.iter() {
+            writeln!(out, "type {} @entity(immutable: true) {{", event.name)?;
+            writeln!(out, "  id: Bytes!")?;
+            for param in event.params.iter() {
+                let field = field_name(&event.name, &param.name);
+                let indexed = if param.indexed { "indexed " } else { "" };
+                writeln!(out, "  {field}: {}! # {indexed}{}", param.graphql, param.ty)?;
+            }
+            writeln!(out, "  blockNumber: BigInt!")?;
+            writeln!(out, "  blockTimestamp: BigInt!")?;
+            writeln!(out, "  transactionHash: Bytes!")?;
+            writeln!(out, "}}\n")?;
+        }
+        Ok(out)
+    }
+
+    /// Render the `eventHandlers` stanza of the contract data source in `subgraph.yaml`.
+    pub(crate) fn manifest(&self) -> Result<String, fmt::Error> {
+        let mut out = format!("# The event handlers of the `{}` data source.\n", self.name);
+        for (signature, reason) in self.skipped.iter() {
+            writeln!(out, "# Skipped `{signature}`: {reason}.")?;
+        }
+        if self.events.is_empty() {
+            return Ok(out)
+        }
+        writeln!(out, "eventHandlers:")?;
+        for event in self.events.iter() {
+            writeln!(out, "  - event: {}", event.signature)?;
+            writeln!(out, "    handler: handle{}", event.name)?;
+        }
+        Ok(out)
+    }
+
+    /// Render the AssemblyScript handler stubs, i.e. the mapping file of the data source,
+    /// saving the entity of each event.
+    pub(crate) fn mappings(&self) -> Result<String, fmt::Error> {
+        let mut out = String::new();
+        if self.events.is_empty() {
+            return Ok(out)
+        }
+        let events = self.events.iter().map(|event| format!("{0} as {0}Event", event.name));
+        writeln!(
+            out,
+            "import {{ {} }} from \"../generated/{1}/{1}\"",
+            events.collect::<Vec<_>>().join(", "),
+            self.name
+        )?;
+        let entities = self.events.iter().map(|event| event.name.as_str());
+        writeln!(
+            out,
+            "import {{ {} }} from \"../generated/schema\"",
+            entities.collect::<Vec<_>>().join(", ")
+        )?;
+        for event in self.events.iter() {
+            writeln!(out)?;
+            writeln!(
+                out,
+                "export function handle{0}(event: {0}Event): void {{\n  \
+                let entity = new {0}(event.transaction.hash.concatI32(event.logIndex.toI32()))",
+                event.name
+            )?;
+            for param in event.params.iter() {
+                let field = field_name(&event.name, &param.name);
+                writeln!(out, "  entity.{field} = event.params.{}", param.name)?;
+            }
+            writeln!(out, "  entity.blockNumber = event.block.number")?;
+            writeln!(out, "  entity.blockTimestamp = event.block.timestamp")?;
+            writeln!(out, "  entity.transactionHash = event.transaction.hash\n")?;
+            writeln!(out, "  entity.save()\n}}")?;
+        }
+        Ok(out)
+    }
+}
+
+impl SubgraphEvent {
+    /// Returns [None] if any parameter type can't be resolved to its ABI type
+    /// with the given function.
+    fn new(
+        event: &EventDefinition,
+        overloads: &mut HashMap<String, usize>,
+        abi_type: &mut impl FnMut(&Expression) -> String,
+    ) -> Option<Self> {
+        let name = &event.name.as_ref()?.name;
+        let params = event
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let ty = abi_type(&param.ty);
+                let param_type = HumanReadableParser::parse_type(&ty).ok()?;
+                let graphql = if param.indexed && is_reference(&param_type) {
+                    // The indexed reference types are stored as the hash of the value
+                    "Bytes".to_owned()
+                } else {
+                    graphql_type(&param_type)?
+                };
+                Some(SubgraphParam {
+                    name: param
+                        .name
+                        .as_ref()
+                        .map_or_else(|| format!("param{index}"), |name| name.name.clone()),
+                    ty,
+                    indexed: param.indexed,
+                    graphql,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        // The overloaded events are suffixed with the index, e.g. `Transfer1`
+        let overload = overloads.entry(name.clone()).or_insert(0);
+        let class = if *overload == 0 { name.clone() } else { format!("{name}{overload}") };
+        *overload += 1;
+
+        let inputs = params
+            .iter()
+            .map(
+                |param| {
+                    if param.indexed {
+                        format!("indexed {}", param.ty)
+                    } else {
+                        param.ty.clone()
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        Some(Self { signature: format!("{name}({})", inputs.join(",")), name: class, params })
+    }
+}
+
+/// Returns the GraphQL type of the ABI type, as mapped by `graph codegen`.
+/// Returns [None] for the tuples, which need the entities of their own.
+fn graphql_type(ty: &ParamType) -> Option<String> {
+    let graphql = match ty {
+        ParamType::Address | ParamType::Bytes | ParamType::FixedBytes(_) => "Bytes".to_owned(),
+        ParamType::Bool => "Boolean".to_owned(),
+        ParamType::String => "String".to_owned(),
+        ParamType::Int(size) if *size <= 32 => "Int".to_owned(),
+        ParamType::Uint(size) if *size <= 24 => "Int".to_owned(),
+        ParamType::Int(_) | ParamType::Uint(_) => "BigInt".to_owned(),
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+            format!("[{}!]", graphql_type(inner)?)
+        }
+        ParamType::Tuple(_) => return None,
+    };
+    Some(graphql)
+}
+
+/// Returns the entity field name of the event parameter.
+/// The parameters named as the entity metadata fields are prefixed with the event name.
+fn field_name(event: &str, param: &str) -> String {
+    match param {
+        "id" | "blockNumber" | "blockTimestamp" | "transactionHash" => {
+            let mut chars = event.chars();
+            let event = chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect::<String>())
+                .unwrap_or_default();
+            format!("{event}_{param}")
+        }
+        _ => param.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::test_documents;
+
+    #[test]
+    fn token_helpers() {
+        let src = r#"
+            type Price is uint128;
+
+            enum Kind { Mint, Burn }
+
+            interface IERC20 {}
+
+            contract Token {
+                event Transfer(address indexed from, address indexed to, uint256 value);
+                event Transfer(address indexed from, uint8 kind, bytes32[] ids);
+                event Memo(string indexed memo, uint256 id);
+                event Moved(Position position);
+                event Raw(uint256) anonymous;
+                event Traded(IERC20 indexed token, Kind kind, Price price);
+            }
+        "#;
+        let documents = test_documents("src/Token.sol", src);
+        let helpers = SubgraphHelpers::collect(&documents, Path::new(""), &[]);
+        assert_eq!(helpers.len(), 1);
+        let helpers = &helpers[0];
+        assert_eq!(helpers.dir(), PathBuf::from("src/Token.sol/Token"));
+
+        assert_eq!(
+            helpers.manifest().unwrap(),
+            "# The event handlers of the `Token` data source.\n\
+            # Skipped `Moved(Position)`: the struct and undocumented types aren't resolved.\n\
+            # Skipped `Raw(uint256)`: the anonymous events have no signature topic.\n\
+            eventHandlers:\n  \
+            - event: Transfer(indexed address,indexed address,uint256)\n    \
+            handler: handleTransfer\n  \
+            - event: Transfer(indexed address,uint8,bytes32[])\n    \
+            handler: handleTransfer1\n  \
+            - event: Memo(indexed string,uint256)\n    \
+            handler: handleMemo\n  \
+            - event: Traded(indexed address,uint8,uint128)\n    \
+            handler: handleTraded\n"
+        );
+
+        // The stanza is valid YAML, with the handler of each event
+        let manifest: serde_yaml::Value = serde_yaml::from_str(&helpers.manifest().unwrap())
+            .expect("failed to parse subgraph.yaml");
+        let handlers = manifest["eventHandlers"].as_sequence().unwrap();
+        let handler = |index: usize, key: &str| handlers[index][key].as_str().unwrap();
+        assert_eq!(handlers.len(), 4);
+        assert_eq!(handler(1, "event"), "Transfer(indexed address,uint8,bytes32[])");
+        assert_eq!(handler(1, "handler"), "handleTransfer1");
+        assert_eq!(handler(3, "event"), "Traded(indexed address,uint8,uint128)");
+
+        let schema = helpers.schema().unwrap();
+        assert!(schema.contains("type Transfer1 @entity(immutable: true) {\n  id: Bytes!\n"));
+        assert!(schema.contains("  kind: Int! # uint8\n  ids: [Bytes!]! # bytes32[]\n"));
+        assert!(schema.contains("  memo: Bytes! # indexed string\n  memo_id: BigInt! # uint256\n"));
+        assert!(schema.contains(
+            "  token: Bytes! # indexed address\n  kind: Int! # uint8\n  price: BigInt! # uint128\n"
+        ));
+
+        let mappings = helpers.mappings().unwrap();
+        assert!(mappings.starts_with(
+            "import { Transfer as TransferEvent, Transfer1 as Transfer1Event, Memo as MemoEvent, \
+             Traded as TradedEvent } from \"../generated/Token/Token\"\n\
+             import { Transfer, Transfer1, Memo, Traded } from \"../generated/schema\"\n"
+        ));
+        assert!(mappings.contains(
+            "export function handleMemo(event: MemoEvent): void {\n  \
+             let entity = new Memo(event.transaction.hash.concatI32(event.logIndex.toI32()))\n  \
+             entity.memo = event.params.memo\n  \
+             entity.memo_id = event.params.id\n"
+        ));
+    }
+}
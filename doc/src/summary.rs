@@ -0,0 +1,119 @@
+//! The navigation tree of the book.
+
+use crate::{AsDoc, AsDocResult, BufWriter};
+use serde::{Deserialize, Serialize};
+
+/// The navigation tree of the book, rendered as `SUMMARY.md` and written as `summary.json`,
+/// so the custom site generators can build their sidebar without parsing the markdown.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryTree {
+    /// The titled parts of the navigation, e.g. `src` or `Appendix`.
+    pub parts: Vec<SummaryPart>,
+}
+
+/// The titled part of the [SummaryTree].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryPart {
+    /// The part title.
+    pub title: String,
+    /// The top-level entries of the part.
+    pub entries: Vec<SummaryEntry>,
+}
+
+/// The page of the [SummaryTree] with its nested pages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryEntry {
+    /// The page name.
+    pub name: String,
+    /// The page path relative to the book source directory.
+    pub path: String,
+    /// The nested pages, e.g. the pages of the section.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SummaryEntry>,
+}
+
+impl SummaryTree {
+    /// The navigation tree file name within the documentation output directory.
+    pub const FILE_NAME: &'static str = "summary.json";
+
+    /// Start the new part with the title.
+    pub fn push_part(&mut self, title: &str) {
+        self.parts.push(SummaryPart { title: title.to_owned(), entries: vec![] });
+    }
+
+    /// Add the page to the last part, nested under the last page of the previous depth.
+    /// The page is added to the deepest existing level if the depth is skipped.
+    pub fn push_entry(&mut self, name: &str, path: &str, depth: usize) {
+        if self.parts.is_empty() {
+            self.push_part("");
+        }
+        let mut entries = &mut self.parts.last_mut().expect("parts are not empty").entries;
+        for _ in 0..depth {
+            if entries.is_empty() {
+                break
+            }
+            entries = &mut entries.last_mut().expect("entries are not empty").children;
+        }
+        entries.push(SummaryEntry {
+            name: name.to_owned(),
+            path: path.to_owned(),
+            children: vec![],
+        });
+    }
+}
+
+impl AsDoc for SummaryTree {
+    fn as_doc(&self) -> AsDocResult {
+        fn write_entries(
+            writer: &mut BufWriter,
+            entries: &[SummaryEntry],
+            depth: usize,
+        ) -> std::fmt::Result {
+            for entry in entries.iter() {
+                writer.write_link_list_item(&entry.name, &entry.path, depth)?;
+                write_entries(writer, &entry.children, depth + 1)?;
+            }
+            Ok(())
+        }
+
+        let mut writer = BufWriter::default();
+        for part in self.parts.iter() {
+            writer.write_title(&part.title)?;
+            write_entries(&mut writer, &part.entries, 0)?;
+        }
+        Ok(writer.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_tree() {
+        let mut summary = SummaryTree::default();
+        summary.push_part("Summary");
+        summary.push_entry("Home", "README.md", 0);
+        summary.push_part("src");
+        summary.push_entry("❱ tokens", "src/tokens/README.md", 0);
+        summary.push_entry("Token", "src/tokens/Token.sol/contract.Token.md", 1);
+        summary.push_entry("Vault", "src/Vault.sol/contract.Vault.md", 0);
+
+        assert_eq!(
+            summary.as_doc().unwrap(),
+            "# Summary\n- [Home](README.md)\n# src\n- [❱ tokens](src/tokens/README.md)\n  \
+            - [Token](src/tokens/Token.sol/contract.Token.md)\n\
+            - [Vault](src/Vault.sol/contract.Vault.md)\n"
+        );
+        assert_eq!(
+            serde_json::to_value(&summary.parts[1].entries[0]).unwrap(),
+            serde_json::json!({
+                "name": "❱ tokens",
+                "path": "src/tokens/README.md",
+                "children": [
+                    {"name": "Token", "path": "src/tokens/Token.sol/contract.Token.md"}
+                ]
+            })
+        );
+    }
+}
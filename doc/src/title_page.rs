@@ -0,0 +1,156 @@
+//! The protocol overview of the title page.
+
+use crate::{
+    document::read_context,
+    helpers::{as_contract, deployment_address},
+    BufWriter, CommentTag, Document, Markdown, PreprocessorOutput, DEPLOYMENTS_ID,
+};
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::ContractTy;
+use std::{collections::HashSet, fmt, path::Path};
+
+/// The custom tag excluding the contract from the protocol overview,
+/// i.e. `@custom:nodoc-overview`.
+pub const NODOC_OVERVIEW_TAG: &str = "nodoc-overview";
+
+/// Render the "Protocol at a glance" section of the title page.
+///
+/// It lists the top-level contracts, i.e. the contracts not inherited by any other documented
+/// contract, and the libraries with their one-line notices and deployments, and links
+/// the appendix pages. The interfaces are implemented by the listed contracts, so they're not.
+pub(crate) fn protocol_overview(
+    documents: &[&Document],
+    out_src: &Path,
+) -> Result<String, fmt::Error> {
+    let contracts = documents.iter().copied().filter_map(as_contract).collect::<Vec<_>>();
+    let inherited = contracts
+        .iter()
+        .flat_map(|(_, _, contract)| contract.base.iter())
+        .filter_map(|base| base.name.identifiers.last().map(|ident| ident.name.as_str()))
+        .collect::<HashSet<_>>();
+    let nodoc = CommentTag::Custom(NODOC_OVERVIEW_TAG.to_owned());
+    let top_level = contracts
+        .into_iter()
+        .filter(|(_, item, contract)| {
+            matches!(
+                contract.ty,
+                ContractTy::Contract(_) | ContractTy::Abstract(_) | ContractTy::Library(_)
+            ) && !inherited.contains(contract.name.safe_unwrap().name.as_str()) &&
+                !item.comments.iter().any(|comment| comment.tag == nodoc)
+        })
+        .collect::<Vec<_>>();
+    let appendix = documents
+        .iter()
+        .copied()
+        .filter(|document| document.content.is_appendix())
+        .collect::<Vec<_>>();
+    if top_level.is_empty() && appendix.is_empty() {
+        return Ok(String::new())
+    }
+
+    let page = |document: &Document| {
+        document
+            .target_path
+            .strip_prefix(out_src)
+            .unwrap_or(&document.target_path)
+            .display()
+            .to_string()
+    };
+    let mut writer = BufWriter::new("\n");
+    writer.write_subtitle("Protocol at a glance")?;
+    for (document, item, contract) in top_level.iter() {
+        let name = &contract.name.safe_unwrap().name;
+        let link = Markdown::Link(name, &page(document)).to_string();
+        let notice = item
+            .comments
+            .include_tag(CommentTag::Notice)
+            .first()
+            .and_then(|notice| notice.value.lines().next())
+            .map(str::trim)
+            .filter(|notice| !notice.is_empty());
+        match notice {
+            Some(notice) => writer.write_list_item(&format!("{link}: {notice}"), 0)?,
+            None => writer.write_list_item(&link, 0)?,
+        }
+    }
+    writer.writeln()?;
+
+    let deployments = top_level
+        .iter()
+        .filter_map(|(document, _, contract)| {
+            read_context!(document, DEPLOYMENTS_ID, Deployments)
+                .map(|deployments| (&contract.name.safe_unwrap().name, deployments))
+        })
+        .collect::<Vec<_>>();
+    if !deployments.is_empty() {
+        writer.write_heading("Deployments")?;
+        writer.write_piped("Contract|Network|Address")?;
+        writer.write_piped("-|-|-")?;
+        for (name, deployments) in deployments.iter() {
+            for deployment in deployments.iter() {
+                writer.write_piped(&format!(
+                    "{name}|{}|{}",
+                    deployment.chain,
+                    deployment_address(deployment)
+                ))?;
+            }
+        }
+        writer.writeln()?;
+    }
+
+    if !appendix.is_empty() {
+        writer.write_heading("Index")?;
+        for document in appendix {
+            writer.write_link_list_item(&document.identity, &page(document), 0)?;
+        }
+        writer.writeln()?;
+    }
+
+    Ok(writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, Deployment};
+    use foundry_config::Chain;
+
+    #[test]
+    fn top_level_contracts() {
+        let src = r#"
+            interface IVault { }
+            abstract contract Base { }
+
+            /// @notice The vault of the protocol.
+            /// Holds the assets.
+            contract Vault is Base, IVault { }
+
+            /// @notice The test helper.
+            /// @custom:nodoc-overview
+            contract Helper { }
+
+            /// @notice The fixed point math.
+            library Math { }
+        "#;
+        let documents = test_documents("src/Vault.sol", src);
+        documents[2].add_context(
+            DEPLOYMENTS_ID,
+            PreprocessorOutput::Deployments(vec![Deployment {
+                chain: Chain::Id(424242),
+                address: "0x01".to_owned(),
+            }]),
+        );
+
+        let overview =
+            protocol_overview(&documents.iter().collect::<Vec<_>>(), Path::new("docs/src"))
+                .unwrap();
+        assert_eq!(
+            overview,
+            "\n## Protocol at a glance\n\
+            - [Vault](src/Vault.sol/contract.Vault.md): The vault of the protocol.\n\
+            - [Math](src/Vault.sol/library.Math.md): The fixed point math.\n\n\
+            ### Deployments\n\
+            |Contract|Network|Address|\n|-|-|-|\n|Vault|424242|`0x01`|\n\n"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! The structural validation of the rendered markdown pages.
+
+use crate::{DocDiagnostic, Document};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+
+/// Validate the rendered page, i.e. report the table rows with the mismatched number of
+/// the columns, the unclosed code fences and the empty headings.
+///
+/// The problems are attributed to the member of the nearest preceding member heading,
+/// e.g. `Vault.deposit`, or to the page item.
+pub(crate) fn validate_markdown(document: &Document, content: &str) -> Vec<DocDiagnostic> {
+    let mut diagnostics = vec![];
+    let mut item = document.identity.clone();
+    let mut heading: Option<(usize, String)> = None;
+    let mut columns = 0;
+
+    for (event, range) in Parser::new_ext(content, Options::ENABLE_TABLES).into_offset_iter() {
+        let mut report = |code: &'static str, message: String| {
+            diagnostics.push(
+                DocDiagnostic::warning(code, message)
+                    .with_location(
+                        document.target_path.clone(),
+                        Some(line_number(content, range.start)),
+                    )
+                    .with_item(&item),
+            );
+        };
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                heading = Some((level as usize, String::new()))
+            }
+            Event::Text(ref text) | Event::Code(ref text) => {
+                if let Some((_, ref mut title)) = heading {
+                    title.push_str(text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, title)) = heading.take() {
+                    if title.trim().is_empty() {
+                        report("markdown-heading", "heading without text".to_owned());
+                    } else if level >= 3 {
+                        // The member headings, e.g. `### deposit`
+                        item = format!("{}.{}", document.identity, title.trim());
+                    } else {
+                        item = document.identity.clone();
+                    }
+                }
+            }
+            Event::Start(Tag::Table(ref alignments)) => columns = alignments.len(),
+            Event::Start(Tag::TableRow) => {
+                let cells = cell_count(&content[range.clone()]);
+                if cells != columns {
+                    report(
+                        "markdown-table",
+                        format!("table row with {cells} column(s), expected {columns}"),
+                    );
+                }
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
+                if !is_closed_fence(&content[range.clone()]) {
+                    report("markdown-fence", "unclosed code fence".to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// Returns the number of the cells of the table row source. Only the escaped pipes don't
+/// separate the cells, including the ones within the code spans.
+fn cell_count(row: &str) -> usize {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = row.strip_suffix('|').filter(|row| !row.ends_with('\\')).unwrap_or(row);
+    let (mut cells, mut escaped) = (1, false);
+    for ch in row.chars() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '|' => cells += 1,
+            _ => {}
+        }
+    }
+    cells
+}
+
+/// Returns true if the fenced code block source ends with the closing fence.
+fn is_closed_fence(block: &str) -> bool {
+    let mut lines = block.trim_end().lines();
+    let opening = lines.next().unwrap_or_default().trim_start();
+    let fence_char = opening.chars().next().unwrap_or('`');
+    let fence = opening.chars().take_while(|ch| *ch == fence_char).count();
+    lines.last().map_or(false, |closing| {
+        let closing = closing.trim();
+        closing.chars().all(|ch| ch == fence_char) && closing.len() >= fence
+    })
+}
+
+/// Returns the 1-based line number of the byte offset.
+fn line_number(content: &str, offset: usize) -> usize {
+    content.get(..offset).unwrap_or(content).matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::DocumentContent;
+    use std::path::PathBuf;
+
+    #[test]
+    fn markdown_problems() {
+        let document = Document::new(
+            PathBuf::from("src/Vault.sol"),
+            PathBuf::from("docs/src/src/Vault.sol/contract.Vault.md"),
+        )
+        .with_content(DocumentContent::Empty, "Vault".to_owned());
+        let content = "# Vault\n\n\
+            ## Functions\n\
+            ### deposit\n\n\
+            |Name|Type|Description|\n\
+            |----|----|-----------|\n\
+            |`assets`|`uint256`|The assets, `a \\| b` or a \\| b.|\n\
+            |`receiver`|`address`|The receiver | owner.|\n\n\
+            ###\n\n\
+            ```solidity\n\
+            function withdraw() external;\n";
+
+        let diagnostics = validate_markdown(&document, content)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.to_string(), diagnostic.item.unwrap_or_default()))
+            .collect::<Vec<_>>();
+        let page = "docs/src/src/Vault.sol/contract.Vault.md";
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    format!("{page}:9: table row with 4 column(s), expected 3"),
+                    "Vault.deposit".to_owned()
+                ),
+                (format!("{page}:11: heading without text"), "Vault.deposit".to_owned()),
+                (format!("{page}:13: unclosed code fence"), "Vault.deposit".to_owned()),
+            ]
+        );
+    }
+}
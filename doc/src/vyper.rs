@@ -0,0 +1,80 @@
+//! The Vyper contracts, documented from the ABI and the natspec of their compiled artifacts.
+
+use crate::{artifact::abi_declaration, helpers::read_artifact};
+use std::path::Path;
+
+/// The Vyper source file extension.
+pub(crate) const VYPER_EXT: &str = "vy";
+
+/// Returns true if the path is the Vyper source file.
+pub(crate) fn is_vyper(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == VYPER_EXT)
+}
+
+/// Returns the Solidity declaration of the Vyper contract built from its compiled artifact,
+/// i.e. `<artifacts>/<file name>/<contract>.json`, so it's parsed and documented like the
+/// Solidity contracts. The contract is named after the file, as in Vyper.
+///
+/// The `devdoc` and `userdoc` are read from the artifact or its metadata, and may be missing.
+/// Returns [None] if the artifact or its ABI is missing.
+pub(crate) fn vyper_declaration(path: &Path, artifacts: &Path) -> Option<String> {
+    let name = path.file_stem()?.to_str()?;
+    let artifact = read_artifact(artifacts, path, name)?;
+    abi_declaration(name, &artifact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn vyper_artifact_declaration() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifacts = dir.path().join("out");
+        fs::create_dir_all(artifacts.join("Vault.vy")).unwrap();
+        fs::write(
+            artifacts.join("Vault.vy/Vault.json"),
+            r#"{
+                "abi": [
+                    {"type": "event", "name": "Deposit", "inputs": [
+                        {"name": "owner", "type": "address", "indexed": true},
+                        {"name": "assets", "type": "uint256", "indexed": false}
+                    ], "anonymous": false},
+                    {"type": "function", "name": "deposit", "stateMutability": "nonpayable",
+                     "inputs": [{"name": "assets", "type": "uint256"}],
+                     "outputs": [{"name": "", "type": "uint256"}]},
+                    {"type": "function", "name": "name", "stateMutability": "view",
+                     "inputs": [], "outputs": [{"name": "", "type": "string"}]}
+                ],
+                "devdoc": {
+                    "title": "The vault",
+                    "methods": {"deposit(uint256)": {
+                        "details": "Mints the shares.",
+                        "params": {"assets": "The deposited assets."},
+                        "returns": {"_0": "The minted shares."}
+                    }}
+                },
+                "userdoc": {"methods": {"deposit(uint256)": "Deposit the assets."}}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vyper_declaration(Path::new("src/Vault.vy"), &artifacts).unwrap(),
+            "/// @title The vault\n\
+             contract Vault {\n    \
+             event Deposit(address indexed owner, uint256 assets);\n    \
+             /// @notice Deposit the assets.\n    \
+             /// @dev Mints the shares.\n    \
+             /// @param assets The deposited assets.\n    \
+             /// @return The minted shares.\n    \
+             function deposit(uint256 assets) external returns (uint256) {}\n    \
+             function name() external view returns (string memory) {}\n\
+             }\n"
+        );
+
+        // The contracts without the artifact aren't documented
+        assert_eq!(vyper_declaration(Path::new("src/Token.vy"), &artifacts), None);
+    }
+}
@@ -1,15 +1,35 @@
-use std::path::Path;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use crate::{
+    artifact::is_artifact,
     document::{read_context, DocumentContent},
+    helpers::{
+        base_constructor_calls, deployment_address, event_params, event_signature, event_topic,
+        function_modifiers, function_signature, immutable_assignments, inline_assembly,
+        is_initializer, is_unimplemented, since_version, variable_kind, VariableKind,
+    },
+    number,
     parser::ParseSource,
-    writer::BufWriter,
-    CommentTag, Comments, CommentsRef, Document, Markdown, PreprocessorOutput,
-    CONTRACT_INHERITANCE_ID, GIT_SOURCE_ID, INHERITDOC_ID,
+    preprocessor::{function_key, hardfork_index},
+    vyper::is_vyper,
+    writer::{heading_anchor, BufWriter},
+    BaseLink, CommentTag, Comments, CommentsRef, CompatibilityNote, Conformance, Document,
+    GuardKind, LogEncoding, LogLocation, LogSlot, Markdown, Precondition, PreprocessorOutput,
+    SectionOverflow, SecurityNotes, SelectorCollision, TruncatedSection, VisibilityChange,
+    CONTRACT_INHERITANCE_ID, CONTRACT_INTRO_ID, DEPLOYMENTS_ID, DOCS_TAG, EDIT_LINK_ID,
+    EFFECTIVE_VISIBILITY_ID, EMBED_ABI_ID, EVENT_LAYOUT_ID, EVM_COMPATIBILITY_ID, FILE_TYPES_ID,
+    GAS_ESTIMATES_ID, GAS_TAG, GIT_SOURCE_ID, IMPORT_ALIASES_ID, INHERITDOC_ID, OVERLOAD_DOCS_ID,
+    PRECONDITIONS_ID, SCRIPT_USAGE_ID, SECTION_LIMITS_ID, SECURITY_ID, SEE_ALSO_ID,
+    SELECTOR_COLLISIONS_ID, SINCE_TAG, STANDARD_BASES_ID, STANDARD_JSON_ID, STRUCT_FIELDS_ID,
+    STRUCT_LINKS_ID, TEST_PLAN_ID,
 };
 use forge_fmt::solang_ext::SafeUnwrap;
 use itertools::Itertools;
-use solang_parser::pt::Base;
+use solang_parser::pt::{Base, FunctionAttribute, FunctionDefinition, FunctionTy};
 
 /// The result of [Asdoc::as_doc] method.
 pub type AsDocResult = Result<String, std::fmt::Error>;
@@ -38,6 +58,13 @@ impl<'a> AsDoc for CommentsRef<'a> {
     fn as_doc(&self) -> AsDocResult {
         let mut writer = BufWriter::default();
 
+        // Write the version the item was introduced in
+        let since = self.include_tag(CommentTag::Custom(SINCE_TAG.to_owned()));
+        if let Some(since) = since.first() {
+            writer.write_italic(&format!("Since: {}", since_version(since.value.trim())))?;
+            writer.writeln()?;
+        }
+
         // Write author tag(s)
         let authors = self.include_tag(CommentTag::Author);
         if !authors.is_empty() {
@@ -53,10 +80,21 @@ impl<'a> AsDoc for CommentsRef<'a> {
             writer.writeln()?;
         }
 
-        // Write dev tags
+        // Write dev tags, the block markdown as is since it can't be wrapped into italic
         let devs = self.include_tag(CommentTag::Dev);
         for dev in devs.iter() {
-            writer.write_italic(&dev.value)?;
+            if is_block_markdown(&dev.value) {
+                writer.writeln_raw(markdown_block(&dev.value))?;
+            } else {
+                writer.write_italic(&dev.value)?;
+            }
+            writer.writeln()?;
+        }
+
+        // Write the free-form docs last, so they have the same place on every page
+        let docs = self.include_tag(CommentTag::Custom(DOCS_TAG.to_owned()));
+        for docs in docs.iter() {
+            writer.writeln_raw(markdown_block(docs.value.trim()))?;
             writer.writeln()?;
         }
 
@@ -64,24 +102,403 @@ impl<'a> AsDoc for CommentsRef<'a> {
     }
 }
 
+/// Returns true if the line starts the markdown block, i.e. a heading, a table row,
+/// a list item or a quote.
+fn is_block_start(line: &str) -> bool {
+    let heading = line.trim_start_matches('#');
+    (heading.len() < line.len() && heading.starts_with(' ')) ||
+        line.starts_with('|') ||
+        line.starts_with("- ") ||
+        line.starts_with("* ") ||
+        line.starts_with('>')
+}
+
+/// Returns true if the comment contains the block markdown or a code fence.
+fn is_block_markdown(text: &str) -> bool {
+    text.lines().map(str::trim_start).any(|line| is_block_start(line) || line.starts_with("```"))
+}
+
+/// Returns the comment as the markdown block.
+/// The blocks following the text are separated by an empty line, since the comment lines
+/// are joined without them, and the unterminated code fence is closed so it doesn't swallow
+/// the rest of the page. The lines within the code fences are kept as is.
+fn markdown_block(text: &str) -> String {
+    let mut lines = vec![];
+    let mut fenced = false;
+    let mut follows_text = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            if !fenced && follows_text {
+                lines.push("");
+            }
+            fenced = !fenced;
+            follows_text = false;
+        } else if !fenced {
+            if follows_text && is_block_start(trimmed) {
+                lines.push("");
+            }
+            follows_text = !trimmed.is_empty() && !is_block_start(trimmed);
+        }
+        lines.push(line);
+    }
+    if fenced {
+        lines.push("```");
+    }
+    lines.join("\n")
+}
+
 impl AsDoc for Base {
     fn as_doc(&self) -> AsDocResult {
         Ok(self.name.identifiers.iter().map(|ident| ident.name.to_owned()).join("."))
     }
 }
 
+/// Write the security contacts and the notes of the contract.
+/// The notes of the members are prefixed with the member name.
+fn write_security_notes(writer: &mut BufWriter, security: &SecurityNotes) -> fmt::Result {
+    if !security.contacts.is_empty() {
+        writer.writeln_raw(format!(
+            "{}: {}",
+            Markdown::Bold("Security contact"),
+            security.contacts.join(", ")
+        ))?;
+        writer.writeln()?;
+    }
+    for note in security.notes.iter() {
+        match note.member {
+            Some(ref member) => {
+                writer.write_list_item(&format!("{}: {}", Markdown::Code(member), note.text), 0)?
+            }
+            None => writer.write_list_item(&note.text, 0)?,
+        }
+    }
+    if !security.notes.is_empty() {
+        writer.writeln()?;
+    }
+    Ok(())
+}
+
+/// Write the gas the author expects the function to use, i.e. the `@custom:gas` tag,
+/// along with the compiler's estimate if there is one, e.g.
+/// `**Gas**: ~25k (author); 24512 (compiler estimate)`.
+/// Doesn't write anything if there is neither.
+fn write_gas(
+    writer: &mut BufWriter,
+    func: &FunctionDefinition,
+    comments: &Comments,
+    estimates: Option<&HashMap<String, String>>,
+) -> fmt::Result {
+    let signature = match func.ty {
+        FunctionTy::Constructor => Some("constructor".to_owned()),
+        _ => function_signature(func),
+    };
+    let estimate = signature
+        .zip(estimates)
+        .and_then(|(signature, estimates)| estimates.get(&signature).cloned());
+    let notes = comments
+        .include_tag(CommentTag::Custom(GAS_TAG.to_owned()))
+        .iter()
+        .map(|gas| format!("{} (author)", gas.value.trim()))
+        .chain(estimate.map(|estimate| format!("{estimate} (compiler estimate)")))
+        .collect::<Vec<_>>();
+    if notes.is_empty() {
+        return Ok(())
+    }
+    writer.writeln_raw(format!("{}: {}", Markdown::Bold("Gas"), notes.join("; ")))?;
+    writer.writeln()
+}
+
+/// Write the warning of the selector collisions of the contract function, e.g.
+/// `> **Warning**: the selector `0x42966c68` collides with ...`.
+fn write_selector_collisions(
+    writer: &mut BufWriter,
+    func: &FunctionDefinition,
+    contract: &str,
+    collisions: &[SelectorCollision],
+) -> fmt::Result {
+    let signature = match function_signature(func) {
+        Some(signature) => signature,
+        None => return Ok(()),
+    };
+    for collision in collisions.iter() {
+        let is_colliding = collision
+            .functions
+            .iter()
+            .any(|function| function.contract == contract && function.signature == signature);
+        if !is_colliding {
+            continue
+        }
+        let others = collision
+            .functions
+            .iter()
+            .filter(|function| function.signature != signature)
+            .map(|function| {
+                format!(
+                    "{} of {}",
+                    Markdown::Code(&function.signature),
+                    Markdown::Link(&function.contract, &function.link)
+                )
+            })
+            .join(", ");
+        writer.writeln_raw(format!(
+            "> {}: the selector {} collides with {others}.",
+            Markdown::Bold("Warning"),
+            Markdown::Code(&collision.selector)
+        ))?;
+        writer.writeln()?;
+    }
+    Ok(())
+}
+
+/// Write the modifiers applied to the function along with its state mutability,
+/// so that the full behavior is visible in one place, e.g.
+/// `**Mutability:** `view`; **Modifiers:** `whenNotPaused``.
+/// The base constructor calls are not modifiers and are skipped.
+/// Doesn't write anything if the function has no modifiers.
+fn write_modifiers(
+    writer: &mut BufWriter,
+    func: &FunctionDefinition,
+    bases: &[Base],
+) -> fmt::Result {
+    let modifiers = function_modifiers(func)
+        .filter(|modifier| {
+            !bases.iter().any(|base| {
+                base.name.identifiers.last().map_or(false, |ident| ident.name == *modifier)
+            })
+        })
+        .map(|modifier| Markdown::Code(modifier).to_string())
+        .collect::<Vec<_>>();
+    if modifiers.is_empty() {
+        return Ok(())
+    }
+
+    let mutability = func
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            FunctionAttribute::Mutability(mutability) => Some(mutability.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "nonpayable".to_owned());
+    writer.writeln_raw(format!(
+        "{} {}; {} {}",
+        Markdown::Bold("Mutability:"),
+        Markdown::Code(&mutability),
+        Markdown::Bold("Modifiers:"),
+        modifiers.join(", ")
+    ))?;
+    writer.writeln()
+}
+
+/// Write the visibility of the overriding function differing from the overridden base, e.g.
+/// `**Visibility:** `public` (declared `external` in `IVault`)`.
+fn write_visibility_change(writer: &mut BufWriter, change: &VisibilityChange) -> fmt::Result {
+    writer.writeln_raw(format!(
+        "{} {} (declared {} in {})",
+        Markdown::Bold("Visibility:"),
+        Markdown::Code(&change.effective),
+        Markdown::Code(&change.declared),
+        Markdown::Code(&change.base)
+    ))?;
+    writer.writeln()
+}
+
+/// Write the EVM compatibility note of the contract, e.g.
+/// `> **PUSH0:** compiled for `shanghai`, ...`.
+fn write_compatibility_note(writer: &mut BufWriter, note: &CompatibilityNote) -> fmt::Result {
+    let note = match note {
+        CompatibilityNote::TransientStorage(features) => format!(
+            "{} uses {} (EIP-1153), so the contract can only be deployed on the chains \
+             with the Cancun upgrade.",
+            Markdown::Bold("Transient storage:"),
+            features.iter().map(|feature| Markdown::Code(feature).to_string()).join(", ")
+        ),
+        CompatibilityNote::Push0(evm_version) => format!(
+            "{} compiled for {}, so the bytecode may contain the {} opcode (EIP-3855), \
+             unsupported on the chains without the Shanghai upgrade, e.g. some L2s.",
+            Markdown::Bold("PUSH0:"),
+            Markdown::Code(evm_version),
+            Markdown::Code("PUSH0")
+        ),
+    };
+    writer.writeln_raw(format!("> {note}"))?;
+    writer.writeln()
+}
+
+/// Write the inline assembly note of the function, e.g.
+/// `**Inline assembly:** 1 block, declared `memory-safe``.
+fn write_inline_assembly(
+    writer: &mut BufWriter,
+    func: &FunctionDefinition,
+    source: &str,
+) -> fmt::Result {
+    let assembly = inline_assembly(func, source);
+    let blocks = match assembly.blocks {
+        0 => return Ok(()),
+        1 => "1 block".to_owned(),
+        blocks => format!("{blocks} blocks"),
+    };
+    let memory_safe = match assembly.memory_safe {
+        0 => String::new(),
+        safe if safe == assembly.blocks => ", declared `memory-safe`".to_owned(),
+        safe => format!(", {safe} declared `memory-safe`"),
+    };
+    writer.writeln_raw(format!("{} {blocks}{memory_safe}", Markdown::Bold("Inline assembly:")))?;
+    writer.writeln()
+}
+
+/// Write the preconditions inferred from the function guards, marked as inferred.
+fn write_preconditions(writer: &mut BufWriter, preconditions: &[Precondition]) -> fmt::Result {
+    writer.writeln_raw(format!(
+        "{} (inferred from the guards, may be incomplete):",
+        Markdown::Bold("Preconditions")
+    ))?;
+    writer.writeln()?;
+    for precondition in preconditions.iter() {
+        let condition = Markdown::Code(&precondition.condition);
+        let mut entry = match precondition.kind {
+            GuardKind::Require => format!("Requires {condition}"),
+            GuardKind::RevertIf => format!("Reverts if {condition}"),
+        };
+        if let Some(ref reason) = precondition.reason {
+            entry.push_str(&format!(": {}", Markdown::Code(reason)));
+        }
+        writer.write_list_item(&entry, 0)?;
+    }
+    writer.writeln()
+}
+
+/// Write the locations of the event parameters in the log, i.e. the topics of the indexed ones
+/// and the byte ranges of the others within the data.
+fn write_log_layout(writer: &mut BufWriter, layout: &[LogSlot]) -> fmt::Result {
+    writer.write_bold("Log layout:")?;
+    writer.writeln()?;
+    writer.write_piped("Location|Parameter|Type|Encoding")?;
+    writer.write_piped("-|-|-|-")?;
+    for slot in layout.iter() {
+        let location = match slot.location {
+            LogLocation::Topic(index) => format!("topic {index}"),
+            LogLocation::Data(Some(ref range)) => {
+                format!(
+                    "data {}",
+                    Markdown::Code(&format!("[{:#x}:{:#x}]", range.start, range.end))
+                )
+            }
+            LogLocation::Data(None) => "data, after the user-defined type".to_owned(),
+        };
+        let encoding = match slot.encoding {
+            LogEncoding::Value => "value",
+            LogEncoding::Hash => "keccak256 hash of the value",
+            LogEncoding::TailOffset => "offset of the value within the data",
+            LogEncoding::Unknown => "depends on the type definition",
+        };
+        writer.write_piped(&format!(
+            "{location}|{}|{}|{encoding}",
+            Markdown::Code(&slot.name),
+            Markdown::Code(&slot.ty)
+        ))?;
+    }
+    writer.writeln()
+}
+
+/// Returns the name of the base contract linked to its documentation, or to its constructor
+/// if `constructor` is set and the base declares one, or the plain name if the base contract
+/// is not documented.
+fn base_link(
+    base: &Base,
+    linked: Option<&HashMap<String, BaseLink>>,
+    book_src: &Path,
+    constructor: bool,
+) -> AsDocResult {
+    let base_doc = base.as_doc()?;
+    let base_ident = &base.name.identifiers.last().unwrap().name;
+    let link = match linked.and_then(|linked| linked.get(base_ident)) {
+        Some(linked) => match linked.constructor {
+            Some((ref page, ref anchor)) if constructor => {
+                format!("{}#{anchor}", page_link(book_src, page))
+            }
+            _ => page_link(book_src, &linked.path),
+        },
+        None => return Ok(base_doc),
+    };
+    Markdown::Link(&base_doc, &link).as_doc()
+}
+
+/// Write the links to the source of the page on the repository, if any.
+fn write_source_links(writer: &mut BufWriter, document: &Document) -> fmt::Result {
+    if let Some(git_source) = read_context!(document, GIT_SOURCE_ID, GitSource) {
+        writer.write_link("Git Source", &git_source)?;
+        writer.writeln()?;
+    }
+    if let Some(edit_link) = read_context!(document, EDIT_LINK_ID, EditLink) {
+        writer.write_link("Edit this page", &edit_link)?;
+        writer.writeln()?;
+    }
+    Ok(())
+}
+
+/// Write the link to the continuation page of the section cut by the section limits.
+fn write_truncated(
+    writer: &mut BufWriter,
+    truncated: &[TruncatedSection],
+    book_src: &Path,
+    section: &str,
+) -> fmt::Result {
+    if let Some(truncated) = truncated.iter().find(|truncated| truncated.section == section) {
+        writer.writeln_raw(format!(
+            "> Truncated, {} more items: {}",
+            truncated.remaining,
+            Markdown::Link(
+                &format!("{section} (continued)"),
+                &page_link(book_src, &truncated.page)
+            )
+        ))?;
+        writer.writeln()?;
+    }
+    Ok(())
+}
+
+/// Returns the constant value as code, cut at the maximum number of the characters noting
+/// the omitted characters, and whether the value was cut. `0` doesn't cut the value.
+fn constant_value(value: &str, max_chars: usize) -> (String, bool) {
+    let chars = value.chars().count();
+    if max_chars == 0 || chars <= max_chars {
+        return (Markdown::Code(value).to_string(), false)
+    }
+    let shown = value.chars().take(max_chars).collect::<String>();
+    (format!("{}… ({} more characters)", Markdown::Code(&shown), chars - max_chars), true)
+}
+
+/// Returns the absolute link of the page within the book, e.g. `/src/Vault.sol/contract.Vault.md`,
+/// where the book source is the `src` of the output directory.
+fn page_link(book_src: &Path, path: &Path) -> String {
+    Path::new("/").join(path.strip_prefix(book_src).unwrap_or(path)).display().to_string()
+}
+
 impl AsDoc for Document {
     fn as_doc(&self) -> AsDocResult {
-        let mut writer = BufWriter::default();
+        let aliases = read_context!(self, IMPORT_ALIASES_ID, ImportAliases).unwrap_or_default();
+        let mut writer = BufWriter::default().with_import_aliases(aliases);
+        let struct_links = read_context!(self, STRUCT_LINKS_ID, StructLinks).unwrap_or_default();
 
         match &self.content {
             DocumentContent::OverloadedFunctions(items) => {
                 writer
                     .write_title(&format!("function {}", items.first().unwrap().source.ident()))?;
-                if let Some(git_source) = read_context!(self, GIT_SOURCE_ID, GitSource) {
-                    writer.write_link("Git Source", &git_source)?;
-                    writer.writeln()?;
+                write_source_links(&mut writer, self)?;
+
+                // Write the docs shared by the overloads at the group heading
+                let shared = read_context!(self, OVERLOAD_DOCS_ID, OverloadDocs)
+                    .and_then(|mut shared| shared.remove(&items.first().unwrap().source.ident()));
+                if let Some(ref shared) = shared {
+                    writer.writeln_doc(shared)?;
                 }
+                let excluded = if shared.is_some() {
+                    vec![CommentTag::Notice, CommentTag::Dev]
+                } else {
+                    vec![]
+                };
 
                 for item in items.iter() {
                     let func = item.as_function().unwrap();
@@ -96,15 +513,16 @@ impl AsDoc for Document {
                         ));
                     }
                     writer.write_heading(&heading)?;
-                    writer.write_section(&item.comments, &item.code)?;
+                    writer.writeln_raw(item.comments.exclude_tags(&excluded).as_doc()?)?;
+                    writer.write_code(&item.code)?;
+                    write_modifiers(&mut writer, func, &[])?;
+                    write_inline_assembly(&mut writer, func, &self.item_content)?;
+                    writer.writeln()?;
                 }
             }
             DocumentContent::Constants(items) => {
                 writer.write_title("Constants")?;
-                if let Some(git_source) = read_context!(self, GIT_SOURCE_ID, GitSource) {
-                    writer.write_link("Git Source", &git_source)?;
-                    writer.writeln()?;
-                }
+                write_source_links(&mut writer, self)?;
 
                 for item in items.iter() {
                     let var = item.as_variable().unwrap();
@@ -112,54 +530,272 @@ impl AsDoc for Document {
                     writer.write_section(&item.comments, &item.code)?;
                 }
             }
-            DocumentContent::Single(item) => {
-                writer.write_title(&item.source.ident())?;
-                if let Some(git_source) = read_context!(self, GIT_SOURCE_ID, GitSource) {
-                    writer.write_link("Git Source", &git_source)?;
+            DocumentContent::Single(item) | DocumentContent::Continuation(item) => {
+                let overflow = read_context!(self, SECTION_LIMITS_ID, SectionLimits);
+                let (truncated, continued) = match overflow {
+                    Some(SectionOverflow::Truncated(truncated)) => (truncated, None),
+                    Some(SectionOverflow::Continuation { section, page }) => {
+                        (vec![], Some((section, page)))
+                    }
+                    None => (vec![], None),
+                };
+                let shown = |section: &str| {
+                    truncated
+                        .iter()
+                        .find(|truncated| truncated.section == section)
+                        .map_or(usize::MAX, |truncated| truncated.shown)
+                };
+
+                match continued {
+                    Some(_) => {
+                        writer.write_title(&format!("{} (continued)", item.source.ident()))?
+                    }
+                    None => writer.write_title(&item.source.ident())?,
+                }
+                write_source_links(&mut writer, self)?;
+                if let Some((ref section, ref page)) = continued {
+                    writer.writeln_raw(format!(
+                        "The {} of {} over the section limit.",
+                        section.to_lowercase(),
+                        Markdown::Link(&item.source.ident(), &page_link(&self.book_src, page))
+                    ))?;
                     writer.writeln()?;
                 }
 
                 match &item.source {
                     ParseSource::Contract(contract) => {
-                        if !contract.base.is_empty() {
+                        if is_vyper(&self.item_path) {
+                            writer.write_bold("Language:")?;
+                            writer.writeln_raw("Vyper")?;
+                            writer.writeln()?;
+                        }
+                        if is_artifact(&self.item_path) {
+                            writer.writeln_raw(
+                                "> Generated from the compiled artifact without the source, \
+                                 so only the ABI and the natspec are documented.",
+                            )?;
+                            writer.writeln()?;
+                        }
+
+                        if !contract.base.is_empty() && continued.is_none() {
                             writer.write_bold("Inherits:")?;
 
                             let mut bases = vec![];
                             let linked =
                                 read_context!(self, CONTRACT_INHERITANCE_ID, ContractInheritance);
                             for base in contract.base.iter() {
-                                let base_doc = base.as_doc()?;
-                                let base_ident = &base.name.identifiers.last().unwrap().name;
-                                bases.push(
-                                    linked
-                                        .as_ref()
-                                        .and_then(|l| {
-                                            l.get(base_ident).map(|path| {
-                                                let path = Path::new("/").join(
-                                                    path.strip_prefix("docs/src")
-                                                        .ok()
-                                                        .unwrap_or(path),
-                                                );
-                                                Markdown::Link(
-                                                    &base_doc,
-                                                    &path.display().to_string(),
-                                                )
-                                                .as_doc()
-                                            })
-                                        })
-                                        .transpose()?
-                                        .unwrap_or(base_doc),
-                                )
+                                bases.push(base_link(
+                                    base,
+                                    linked.as_ref(),
+                                    &self.book_src,
+                                    false,
+                                )?);
                             }
 
                             writer.writeln_raw(bases.join(", "))?;
                             writer.writeln()?;
                         }
 
+                        // Note the well-known bases once instead of their boilerplate functions
+                        let standard_bases = read_context!(self, STANDARD_BASES_ID, StandardBases)
+                            .unwrap_or_default();
+                        for standard in standard_bases.iter().filter(|_| continued.is_none()) {
+                            let linked =
+                                read_context!(self, CONTRACT_INHERITANCE_ID, ContractInheritance);
+                            let name = match contract.base.iter().find(|base| {
+                                base.name
+                                    .identifiers
+                                    .last()
+                                    .map_or(false, |ident| ident.name == standard.name)
+                            }) {
+                                Some(base) => {
+                                    base_link(base, linked.as_ref(), &self.book_src, false)?
+                                }
+                                None => standard.name.clone(),
+                            };
+                            writer.writeln_raw(format!(
+                                "> Standard {name} interface (inherited from {}), only the \
+                                functions the contract overrides or adds are listed.",
+                                Markdown::Code(&standard.path)
+                            ))?;
+                            if !standard.forwarded.is_empty() {
+                                writer.writeln_raw(">")?;
+                                writer.writeln_raw(format!(
+                                    "> Forwarded to {name}: {}",
+                                    standard
+                                        .forwarded
+                                        .iter()
+                                        .map(|signature| Markdown::Code(signature).to_string())
+                                        .join(", ")
+                                ))?;
+                            }
+                            writer.writeln()?;
+                        }
+
+                        if let Some(intro) = read_context!(self, CONTRACT_INTRO_ID, ContractIntro)
+                            .filter(|_| continued.is_none())
+                        {
+                            writer.writeln_raw(intro.trim())?;
+                            writer.writeln()?;
+                        }
+
                         writer.writeln_doc(&item.comments)?;
 
-                        if let Some(state_vars) = item.variables() {
+                        if let Some(see_also) = read_context!(self, SEE_ALSO_ID, SeeAlso) {
+                            writer.write_subtitle("See also")?;
+                            for entry in see_also.iter() {
+                                let name = match entry.path {
+                                    Some(ref path) => Markdown::Link(
+                                        &entry.name,
+                                        &page_link(&self.book_src, path),
+                                    )
+                                    .as_doc()?,
+                                    None => entry.name.clone(),
+                                };
+                                match entry.description.as_str() {
+                                    "" => writer.write_list_item(&name, 0)?,
+                                    description => writer
+                                        .write_list_item(&format!("{name}: {description}"), 0)?,
+                                }
+                            }
+                            writer.writeln()?;
+                        }
+
+                        if let Some(security) = read_context!(self, SECURITY_ID, Security) {
+                            writer.write_subtitle("Security Considerations")?;
+                            write_security_notes(&mut writer, &security)?;
+                        }
+
+                        if let Some(deployments) = read_context!(self, DEPLOYMENTS_ID, Deployments)
+                        {
+                            writer.write_subtitle("Deployments")?;
+                            writer.write_piped("Network|Address")?;
+                            writer.write_piped("-|-")?;
+                            for deployment in deployments.iter() {
+                                writer.write_piped(&format!(
+                                    "{}|{}",
+                                    deployment.chain,
+                                    deployment_address(deployment)
+                                ))?;
+                            }
+                            writer.writeln()?;
+                        }
+
+                        if let Some(script) = read_context!(self, SCRIPT_USAGE_ID, ScriptUsage) {
+                            writer.write_subtitle("Usage")?;
+                            if script.set_up {
+                                writer.writeln_raw(
+                                    "The `setUp` function is executed before the entry point.",
+                                )?;
+                                writer.writeln()?;
+                            }
+                            for entrypoint in script.entrypoints.iter() {
+                                writer.write_bold(&entrypoint.signature)?;
+                                writer.writeln()?;
+                                if entrypoint.broadcasts {
+                                    writer.writeln_raw(
+                                        "Broadcasts the transactions with `vm.startBroadcast`.",
+                                    )?;
+                                    writer.writeln()?;
+                                }
+                                writer
+                                    .writeln_raw(Markdown::CodeBlock("sh", &entrypoint.command))?;
+                                writer.writeln()?;
+                            }
+                        }
+
+                        if let Some(cases) = read_context!(self, TEST_PLAN_ID, TestPlan) {
+                            writer.write_subtitle("Tests")?;
+                            for (kind, cases) in &cases.iter().group_by(|case| case.kind) {
+                                writer.write_bold(kind.title())?;
+                                writer.writeln()?;
+                                writer.write_piped("Test|Cheatcodes|Fuzz inputs")?;
+                                writer.write_piped("-|-|-")?;
+                                for case in cases {
+                                    writer.write_piped(&format!(
+                                        "{}|{}|{}",
+                                        Markdown::Code(&case.name),
+                                        case.cheatcodes.iter().join(", "),
+                                        case.fuzz_inputs
+                                            .iter()
+                                            .map(|input| Markdown::Code(input).to_string())
+                                            .join(", ")
+                                    ))?;
+                                }
+                                writer.writeln()?;
+                            }
+                        }
+
+                        // Write the initializers of the upgradeable contracts,
+                        // or the immutables set at deployment.
+                        let initializers = item
+                            .functions()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter(|_| continued.is_none())
+                            .filter(|(func, _, _)| {
+                                is_initializer(func) ||
+                                    func.name.as_ref().map_or(false, |n| n.name == "initialize")
+                            })
+                            .collect::<Vec<_>>();
+                        let immutables = if initializers.is_empty() && continued.is_none() {
+                            immutable_assignments(item)
+                        } else {
+                            vec![]
+                        };
+                        if !initializers.is_empty() || !immutables.is_empty() {
+                            writer.write_subtitle("Initialization")?;
+                        }
+                        for (func, comments, code) in initializers {
+                            writer.write_heading(
+                                &func.name.as_ref().map_or(func.ty.to_string(), |n| n.name.clone()),
+                            )?;
+                            if !is_initializer(func) {
+                                writer.writeln_raw(format!(
+                                    "> {}: the function is not protected by the `initializer` \
+                                    or `reinitializer` modifier.",
+                                    Markdown::Bold("Warning")
+                                ))?;
+                                writer.writeln()?;
+                            }
+                            writer.writeln_doc(
+                                comments.exclude_tags(&[CommentTag::Param, CommentTag::Return]),
+                            )?;
+                            writer.write_code(code)?;
+                            let params =
+                                func.params.iter().filter_map(|p| p.1.as_ref()).collect::<Vec<_>>();
+                            writer.try_write_param_table(
+                                CommentTag::Param,
+                                &params,
+                                comments,
+                                &struct_links,
+                            )?;
+                            writer.writeln()?;
+                        }
+                        if !immutables.is_empty() {
+                            writer
+                                .writeln_raw("The immutables are assigned once at deployment.")?;
+                            writer.writeln()?;
+                            writer.write_piped("Immutable|Assigned from")?;
+                            writer.write_piped("-|-")?;
+                            for assignment in immutables.iter() {
+                                let value = Markdown::Code(&assignment.value);
+                                let value = if assignment.from_param {
+                                    format!("constructor parameter {value}")
+                                } else {
+                                    value.to_string()
+                                };
+                                writer.write_piped(&format!(
+                                    "{}|{value}",
+                                    Markdown::Code(&assignment.name)
+                                ))?;
+                            }
+                            writer.writeln()?;
+                        }
+
+                        if let Some(mut state_vars) = item.variables() {
                             writer.write_subtitle("State Variables")?;
+                            state_vars.truncate(shown("State Variables"));
                             state_vars.into_iter().try_for_each(|(item, comments, code)| {
                                 let comments = comments.merge_inheritdoc(
                                     &item.name.safe_unwrap().name,
@@ -167,13 +803,82 @@ impl AsDoc for Document {
                                 );
 
                                 writer.write_heading(&item.name.safe_unwrap().name)?;
-                                writer.write_section(&comments, code)?;
+
+                                // Distinguish compile-time constants from the values
+                                // assigned at deployment
+                                let mut code = code.to_owned();
+                                match variable_kind(item) {
+                                    VariableKind::Constant => {
+                                        let value = item
+                                            .initializer
+                                            .as_ref()
+                                            .map(|value| value.to_string())
+                                            .unwrap_or_default();
+                                        let (value, cut) = constant_value(
+                                            &number::integer(&value),
+                                            self.max_value_chars,
+                                        );
+                                        writer.writeln_raw(format!(
+                                            "{}: compile-time value {value}",
+                                            Markdown::Bold("Constant"),
+                                        ))?;
+                                        writer.writeln()?;
+
+                                        // Leave the long values, e.g. the lookup tables,
+                                        // out of the declaration too
+                                        if let Some(index) = code.find(" = ").filter(|_| cut) {
+                                            code = format!("{} = ...;", &code[..index]);
+                                        }
+                                    }
+                                    VariableKind::Immutable => {
+                                        writer.writeln_raw(format!(
+                                            "{}: the value is assigned once at deployment",
+                                            Markdown::Bold("Immutable")
+                                        ))?;
+                                        writer.writeln()?;
+                                    }
+                                    VariableKind::Mutable => {}
+                                }
+
+                                writer.write_section(&comments, &code)?;
                                 writer.writeln()
                             })?;
+                            write_truncated(
+                                &mut writer,
+                                &truncated,
+                                &self.book_src,
+                                "State Variables",
+                            )?;
                         }
 
-                        if let Some(funcs) = item.functions() {
+                        let funcs = item.functions().map(|funcs| {
+                            funcs
+                                .into_iter()
+                                .take(shown("Functions"))
+                                .filter(|(func, _, _)| {
+                                    let signature = function_signature(func);
+                                    !standard_bases.iter().any(|standard| {
+                                        signature.as_ref().map_or(false, |signature| {
+                                            standard.forwarded.contains(signature)
+                                        })
+                                    })
+                                })
+                                .collect::<Vec<_>>()
+                        });
+                        if let Some(funcs) = funcs.filter(|funcs| !funcs.is_empty()) {
                             writer.write_subtitle("Functions")?;
+                            let overload_docs = read_context!(self, OVERLOAD_DOCS_ID, OverloadDocs)
+                                .unwrap_or_default();
+                            let mut overloads_written = HashSet::new();
+                            let gas_estimates = read_context!(self, GAS_ESTIMATES_ID, GasEstimates);
+                            let preconditions =
+                                read_context!(self, PRECONDITIONS_ID, Preconditions)
+                                    .unwrap_or_default();
+                            let visibility_changes =
+                                read_context!(self, EFFECTIVE_VISIBILITY_ID, EffectiveVisibility)
+                                    .unwrap_or_default();
+                            let selector_collisions =
+                                read_context!(self, SELECTOR_COLLISIONS_ID, SelectorCollisions);
                             funcs.into_iter().try_for_each(|(func, comments, code)| {
                                 let func_name = func
                                     .name
@@ -188,13 +893,44 @@ impl AsDoc for Document {
                                 writer.write_heading(&func_name)?;
                                 writer.writeln()?;
 
+                                // Write the docs shared by the overloads once, at the first one
+                                let mut excluded = vec![CommentTag::Param, CommentTag::Return];
+                                if let Some(shared) = overload_docs.get(&func_name) {
+                                    if overloads_written.insert(func_name.clone()) {
+                                        writer.writeln_doc(shared)?;
+                                    }
+                                    excluded.extend([CommentTag::Notice, CommentTag::Dev]);
+                                }
+
                                 // Write function docs
-                                writer.writeln_doc(
-                                    comments.exclude_tags(&[CommentTag::Param, CommentTag::Return]),
+                                writer.writeln_doc(comments.exclude_tags(&excluded))?;
+                                write_selector_collisions(
+                                    &mut writer,
+                                    func,
+                                    &contract.name.safe_unwrap().name,
+                                    selector_collisions.as_deref().unwrap_or_default(),
                                 )?;
 
                                 // Write function header
                                 writer.write_code(code)?;
+                                if is_unimplemented(contract, func) {
+                                    writer.writeln_raw(format!(
+                                        "{} not implemented, the derived contracts must \
+                                        implement it.",
+                                        Markdown::Bold("Abstract:")
+                                    ))?;
+                                    writer.writeln()?;
+                                }
+                                if let Some(change) = visibility_changes.get(&function_key(func)) {
+                                    write_visibility_change(&mut writer, change)?;
+                                }
+                                write_modifiers(&mut writer, func, &contract.base)?;
+                                write_inline_assembly(&mut writer, func, &self.item_content)?;
+                                write_gas(&mut writer, func, &comments, gas_estimates.as_ref())?;
+                                if let Some(preconditions) = preconditions.get(&function_key(func))
+                                {
+                                    write_preconditions(&mut writer, preconditions)?;
+                                }
 
                                 // Write function parameter comments in a table
                                 let params = func
@@ -206,8 +942,45 @@ impl AsDoc for Document {
                                     CommentTag::Param,
                                     &params,
                                     &comments,
+                                    &struct_links,
                                 )?;
 
+                                // Write the arguments passed to the base constructors
+                                if matches!(func.ty, FunctionTy::Constructor) {
+                                    let calls = base_constructor_calls(contract, func);
+                                    if !calls.is_empty() {
+                                        let linked = read_context!(
+                                            self,
+                                            CONTRACT_INHERITANCE_ID,
+                                            ContractInheritance
+                                        );
+                                        writer.write_bold("Base constructors:")?;
+                                        writer.writeln()?;
+                                        writer.write_piped("Base|Arguments")?;
+                                        writer.write_piped("-|-")?;
+                                        for base in calls {
+                                            let args = base
+                                                .args
+                                                .iter()
+                                                .flatten()
+                                                .map(|arg| {
+                                                    Markdown::Code(&arg.to_string()).to_string()
+                                                })
+                                                .join(", ");
+                                            writer.write_piped(&format!(
+                                                "{}|{args}",
+                                                base_link(
+                                                    base,
+                                                    linked.as_ref(),
+                                                    &self.book_src,
+                                                    true
+                                                )?
+                                            ))?;
+                                        }
+                                        writer.writeln()?;
+                                    }
+                                }
+
                                 // Write function parameter comments in a table
                                 let returns = func
                                     .returns
@@ -218,6 +991,7 @@ impl AsDoc for Document {
                                     CommentTag::Return,
                                     &returns,
                                     &comments,
+                                    &struct_links,
                                 )?;
 
                                 writer.writeln()?;
@@ -225,37 +999,168 @@ impl AsDoc for Document {
                                 Ok::<(), std::fmt::Error>(())
                             })?;
                         }
+                        write_truncated(&mut writer, &truncated, &self.book_src, "Functions")?;
 
-                        if let Some(events) = item.events() {
+                        if let Some(mut events) = item.events() {
                             writer.write_subtitle("Events")?;
+                            let layouts = read_context!(self, EVENT_LAYOUT_ID, EventLayout)
+                                .unwrap_or_default();
+                            events.truncate(shown("Events"));
                             events.into_iter().try_for_each(|(item, comments, code)| {
                                 writer.write_heading(&item.name.safe_unwrap().name)?;
+
+                                // Write the metadata needed to decode the logs
+                                if let Some(signature) = event_signature(item) {
+                                    writer.write_list_item(
+                                        &format!(
+                                            "{}: {}",
+                                            Markdown::Bold("Signature"),
+                                            Markdown::Code(&signature)
+                                        ),
+                                        0,
+                                    )?;
+                                }
+                                let topic = match event_topic(item) {
+                                    Some(topic) => Markdown::Code(&topic).to_string(),
+                                    None => "none, the event is anonymous".to_owned(),
+                                };
+                                writer.write_list_item(
+                                    &format!("{}: {topic}", Markdown::Bold("Topic 0")),
+                                    0,
+                                )?;
+                                // The indexed parameters with their topic, for the log filters
+                                let (indexed, data) = event_params(item);
+                                let indexed = indexed
+                                    .iter()
+                                    .map(|(name, topic)| {
+                                        format!("{} (topic {topic})", Markdown::Code(name))
+                                    })
+                                    .collect::<Vec<_>>();
+                                let data = data
+                                    .iter()
+                                    .map(|name| Markdown::Code(name).to_string())
+                                    .collect::<Vec<_>>();
+                                let names = |names: Vec<String>| {
+                                    if names.is_empty() {
+                                        "none".to_owned()
+                                    } else {
+                                        names.join(", ")
+                                    }
+                                };
+                                writer.write_list_item(
+                                    &format!(
+                                        "{}: {}",
+                                        Markdown::Bold("Indexed (topics)"),
+                                        names(indexed)
+                                    ),
+                                    0,
+                                )?;
+                                writer.write_list_item(
+                                    &format!("{}: {}", Markdown::Bold("Data"), names(data)),
+                                    0,
+                                )?;
+                                writer.writeln()?;
+                                if let Some(layout) = event_signature(item)
+                                    .and_then(|signature| layouts.get(&signature))
+                                {
+                                    write_log_layout(&mut writer, layout)?;
+                                }
+
                                 writer.write_section(comments, code)
                             })?;
+                            write_truncated(&mut writer, &truncated, &self.book_src, "Events")?;
                         }
 
-                        if let Some(errors) = item.errors() {
+                        if let Some(mut errors) = item.errors() {
                             writer.write_subtitle("Errors")?;
+                            errors.truncate(shown("Errors"));
                             errors.into_iter().try_for_each(|(item, comments, code)| {
                                 writer.write_heading(&item.name.safe_unwrap().name)?;
                                 writer.write_section(comments, code)
                             })?;
+                            write_truncated(&mut writer, &truncated, &self.book_src, "Errors")?;
                         }
 
-                        if let Some(structs) = item.structs() {
+                        if let Some(mut structs) = item.structs() {
                             writer.write_subtitle("Structs")?;
+                            let fields = read_context!(self, STRUCT_FIELDS_ID, StructFields)
+                                .unwrap_or_default();
+                            structs.truncate(shown("Structs"));
                             structs.into_iter().try_for_each(|(item, comments, code)| {
-                                writer.write_heading(&item.name.safe_unwrap().name)?;
-                                writer.write_section(comments, code)
+                                let name = &item.name.safe_unwrap().name;
+                                writer.write_heading(name)?;
+                                writer.write_section(comments, code)?;
+                                match fields.get(name) {
+                                    Some(fields) => {
+                                        writer.try_write_struct_fields(fields, &struct_links)
+                                    }
+                                    None => Ok(()),
+                                }
                             })?;
+                            write_truncated(&mut writer, &truncated, &self.book_src, "Structs")?;
                         }
 
-                        if let Some(enums) = item.enums() {
+                        if let Some(mut enums) = item.enums() {
                             writer.write_subtitle("Enums")?;
+                            enums.truncate(shown("Enums"));
                             enums.into_iter().try_for_each(|(item, comments, code)| {
                                 writer.write_heading(&item.name.safe_unwrap().name)?;
                                 writer.write_section(comments, code)
                             })?;
+                            write_truncated(&mut writer, &truncated, &self.book_src, "Enums")?;
+                        }
+
+                        if let Some(file_types) = read_context!(self, FILE_TYPES_ID, FileTypes) {
+                            writer.write_subtitle("Types")?;
+                            for file_type in file_types.iter() {
+                                let name = Markdown::Link(
+                                    &file_type.name,
+                                    &page_link(&self.book_src, &file_type.page),
+                                )
+                                .as_doc()?;
+                                let entry = format!("{name} ({})", file_type.kind);
+                                match file_type.notice {
+                                    Some(ref notice) => {
+                                        writer.write_list_item(&format!("{entry}: {notice}"), 0)?
+                                    }
+                                    None => writer.write_list_item(&entry, 0)?,
+                                }
+                            }
+                            writer.writeln()?;
+                        }
+
+                        let input = read_context!(self, STANDARD_JSON_ID, StandardJson);
+                        let compatibility =
+                            read_context!(self, EVM_COMPATIBILITY_ID, EvmCompatibility)
+                                .unwrap_or_default();
+                        if input.is_some() || !compatibility.is_empty() {
+                            writer.write_subtitle("Compilation")?;
+                        }
+                        for note in compatibility.iter() {
+                            write_compatibility_note(&mut writer, note)?;
+                        }
+                        if let Some(input) = input {
+                            writer.write_piped("Input|File")?;
+                            writer.write_piped("-|-")?;
+                            let file_name = input
+                                .path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            writer.write_piped(&format!(
+                                "Standard JSON|{}",
+                                Markdown::Link(&file_name, &input.link)
+                            ))?;
+                            writer.writeln()?;
+                        }
+
+                        // Write the ABI collapsed, so it doesn't get in the way of the docs
+                        if let Some(abi) = read_context!(self, EMBED_ABI_ID, EmbedAbi) {
+                            writer.write_subtitle("ABI")?;
+                            writer.writeln_raw("<details>\n<summary>ABI JSON</summary>\n")?;
+                            writer.writeln_raw(Markdown::CodeBlock("json", &abi))?;
+                            writer.writeln_raw("</details>")?;
+                            writer.writeln()?;
                         }
                     }
 
@@ -268,11 +1173,19 @@ impl AsDoc for Document {
 
                         // Write function header
                         writer.write_code(&item.code)?;
+                        write_modifiers(&mut writer, func, &[])?;
+                        write_inline_assembly(&mut writer, func, &self.item_content)?;
+                        write_gas(&mut writer, func, &item.comments, None)?;
 
                         // Write function parameter comments in a table
                         let params =
                             func.params.iter().filter_map(|p| p.1.as_ref()).collect::<Vec<_>>();
-                        writer.try_write_param_table(CommentTag::Param, &params, &item.comments)?;
+                        writer.try_write_param_table(
+                            CommentTag::Param,
+                            &params,
+                            &item.comments,
+                            &struct_links,
+                        )?;
 
                         // Write function parameter comments in a table
                         let returns =
@@ -281,24 +1194,722 @@ impl AsDoc for Document {
                             CommentTag::Return,
                             &returns,
                             &item.comments,
+                            &struct_links,
                         )?;
 
                         writer.writeln()?;
                     }
 
+                    ParseSource::Struct(_) => {
+                        writer.write_section(&item.comments, &item.code)?;
+                        let fields = read_context!(self, STRUCT_FIELDS_ID, StructFields);
+                        if let Some(fields) =
+                            fields.and_then(|mut fields| fields.remove(&item.source.ident()))
+                        {
+                            writer.try_write_struct_fields(&fields, &struct_links)?;
+                        }
+                    }
+
                     ParseSource::Variable(_) |
                     ParseSource::Event(_) |
                     ParseSource::Error(_) |
-                    ParseSource::Struct(_) |
                     ParseSource::Enum(_) |
                     ParseSource::Type(_) => {
                         writer.write_section(&item.comments, &item.code)?;
                     }
                 }
             }
+            DocumentContent::InterfaceConformance(matrix) => {
+                writer.write_title(&self.identity)?;
+                writer.writeln_raw(
+                    "Conformance of the project contracts to the project interfaces. \
+                    ✓ - all interface functions are implemented, \
+                    ✗ - none of the interface functions are implemented.",
+                )?;
+                writer.writeln()?;
+
+                let header = std::iter::once("Contract")
+                    .chain(matrix.interfaces.iter().map(String::as_str))
+                    .join("|");
+                writer.write_piped(&header)?;
+                writer.write_piped(&vec!["-"; matrix.interfaces.len() + 1].join("|"))?;
+                for (contract, conformance) in matrix.rows.iter() {
+                    let cells = conformance
+                        .iter()
+                        .zip(matrix.interfaces.iter())
+                        .map(|(conformance, interface)| match conformance {
+                            Conformance::Full => Ok("✓".to_owned()),
+                            Conformance::Unimplemented => Ok("✗".to_owned()),
+                            Conformance::Partial(_) => {
+                                let anchor = heading_anchor(&format!("{contract}: {interface}"));
+                                Markdown::Link("partial", &format!("#{anchor}")).as_doc()
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    writer
+                        .write_piped(&std::iter::once(contract.clone()).chain(cells).join("|"))?;
+                }
+                writer.writeln()?;
+
+                for (contract, conformance) in matrix.rows.iter() {
+                    for (conformance, interface) in conformance.iter().zip(matrix.interfaces.iter())
+                    {
+                        if let Conformance::Partial(missing) = conformance {
+                            writer.write_heading(&format!("{contract}: {interface}"))?;
+                            writer.writeln_raw("Missing functions:")?;
+                            writer.writeln()?;
+                            for signature in missing {
+                                writer.write_list_item(&Markdown::Code(signature).as_doc()?, 0)?;
+                            }
+                            writer.writeln()?;
+                        }
+                    }
+                }
+            }
+            DocumentContent::BuildInfo(settings, requirements) => {
+                writer.write_title(&self.identity)?;
+                writer
+                    .writeln_raw("The compiler settings used to build the documented contracts.")?;
+                writer.writeln()?;
+
+                writer.write_piped("Setting|Value")?;
+                writer.write_piped("-|-")?;
+                let solc = settings.solc.as_deref().unwrap_or("auto-detected");
+                writer.write_piped(&format!("Solc|{}", Markdown::Code(solc)))?;
+                writer.write_piped(&format!(
+                    "EVM version|{}",
+                    Markdown::Code(&settings.evm_version)
+                ))?;
+                let optimizer = if settings.optimizer {
+                    format!("enabled, {} runs", settings.optimizer_runs)
+                } else {
+                    "disabled".to_owned()
+                };
+                writer.write_piped(&format!("Optimizer|{optimizer}"))?;
+                writer.write_piped(&format!(
+                    "Via IR|{}",
+                    if settings.via_ir { "enabled" } else { "disabled" }
+                ))?;
+                writer.writeln()?;
+
+                // The natspec is read from the sources, only the compiler outputs may differ
+                if settings.via_ir {
+                    writer.writeln_raw(
+                        "The contracts are compiled through the IR pipeline. The documentation \
+                        is read from the sources, so it's the same with either pipeline, but \
+                        the compiler's gas estimates may be missing.",
+                    )?;
+                    writer.writeln()?;
+                }
+
+                // The bytecode for Shanghai and later contains `PUSH0`
+                let target = hardfork_index(&settings.evm_version);
+                if target >= hardfork_index("shanghai") {
+                    writer.writeln_raw(format!(
+                        "The contracts compiled for {} may contain the `PUSH0` opcode, \
+                        so they can't be deployed to the chains preceding Shanghai.",
+                        Markdown::Code(&settings.evm_version)
+                    ))?;
+                    writer.writeln()?;
+                }
+
+                if !requirements.is_empty() {
+                    writer.write_subtitle("EVM Requirements")?;
+                    writer.writeln_raw(
+                        "The contracts using the opcodes or the features introduced by \
+                        the hardforks, detected from the sources on the best-effort basis.",
+                    )?;
+                    writer.writeln()?;
+                    writer.write_piped("Contract|Features|Requires")?;
+                    writer.write_piped("-|-|-")?;
+                    for contract in requirements.iter() {
+                        let features = contract
+                            .features
+                            .iter()
+                            .map(|(feature, hardfork)| {
+                                format!("{} ({hardfork})", Markdown::Code(feature))
+                            })
+                            .join(", ");
+                        let hardfork = contract.hardfork().unwrap_or_default();
+                        let mut requires = Markdown::Code(hardfork).to_string();
+                        if target.is_some() && hardfork_index(hardfork) > target {
+                            requires.push_str(&format!(
+                                " ({} {})",
+                                Markdown::Bold("newer than the target"),
+                                Markdown::Code(&settings.evm_version)
+                            ));
+                        }
+                        writer.write_piped(&format!(
+                            "{}|{features}|{requires}",
+                            Markdown::Link(&contract.contract, &contract.link)
+                        ))?;
+                    }
+                    writer.writeln()?;
+                }
+            }
+            DocumentContent::Security(appendix) => {
+                writer.write_title(&self.identity)?;
+                writer.writeln_raw(
+                    "The security contacts and the `@custom:security` notes of the documented \
+                    contracts.",
+                )?;
+                writer.writeln()?;
+
+                for security in appendix.iter() {
+                    writer.write_subtitle(
+                        &Markdown::Link(&security.contract, &security.link).as_doc()?,
+                    )?;
+                    write_security_notes(&mut writer, security)?;
+                }
+            }
+            DocumentContent::Dependencies(packages) => {
+                writer.write_title(&self.identity)?;
+                writer.writeln_raw(
+                    "The types of the dependencies taken or returned by the external and public \
+                    functions of the documented contracts.",
+                )?;
+                writer.writeln()?;
+
+                for package in packages.iter() {
+                    let title = match package.version {
+                        Some(ref version) => {
+                            format!("{} {}", package.name, Markdown::Code(version))
+                        }
+                        None => package.name.clone(),
+                    };
+                    writer.write_subtitle(&title)?;
+                    writer.writeln_raw(Markdown::Code(&package.path.display().to_string()))?;
+                    writer.writeln()?;
+
+                    for ty in package.types.iter() {
+                        writer.write_heading(&ty.name)?;
+                        writer.writeln_raw(format!(
+                            "Declared in {}.",
+                            Markdown::Code(&ty.source.display().to_string())
+                        ))?;
+                        writer.writeln()?;
+                        for usage in ty.usages.iter() {
+                            writer.write_link_list_item(&usage.function, &usage.link, 0)?;
+                        }
+                        writer.writeln()?;
+                    }
+                }
+            }
+            DocumentContent::SelectorCollisions(collisions) => {
+                writer.write_title(&self.identity)?;
+                writer.writeln_raw(
+                    "The distinct functions of the documented contracts sharing the 4-byte \
+                    selector. The collisions along the same inheritance chain are the dangerous \
+                    ones, since the contracts sit behind the same proxy.",
+                )?;
+                writer.writeln()?;
+
+                writer.write_piped("Selector|Function|Contract|Inheritance chain")?;
+                writer.write_piped("-|-|-|-")?;
+                for collision in collisions.iter() {
+                    let tree = if collision.inherited { "shared" } else { "" };
+                    for function in collision.functions.iter() {
+                        writer.write_piped(&format!(
+                            "{}|{}|{}|{tree}",
+                            Markdown::Code(&collision.selector),
+                            Markdown::Code(&function.signature),
+                            Markdown::Link(&function.contract, &function.link)
+                        ))?;
+                    }
+                }
+                writer.writeln()?;
+            }
+            DocumentContent::AssemblyIndex(functions) => {
+                writer.write_title(&self.identity)?;
+                writer.writeln_raw(
+                    "The functions of the documented contracts with inline assembly, with the \
+                    number of the blocks declared `memory-safe`. The blocks without the \
+                    declaration limit the optimizations of the IR pipeline.",
+                )?;
+                writer.writeln()?;
+
+                writer.write_piped("Function|Contract|Blocks|Memory-safe")?;
+                writer.write_piped("-|-|-|-")?;
+                for function in functions.iter() {
+                    let name = Markdown::Code(&function.function).as_doc()?;
+                    writer.write_piped(&format!(
+                        "{}|{}|{}|{}",
+                        Markdown::Link(&name, &function.link),
+                        function.contract,
+                        function.blocks,
+                        function.memory_safe
+                    ))?;
+                }
+                writer.writeln()?;
+            }
             DocumentContent::Empty => (),
         };
 
         Ok(writer.finish())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{preprocessor::test_documents, Comment, Preconditions, Preprocessor, StructLinks};
+    use std::path::PathBuf;
+
+    fn render(src: &str) -> String {
+        let document = test_documents("src/Vault.sol", src).pop().expect("no items");
+        let documents = StructLinks {
+            out: PathBuf::from("docs"),
+            show_aliases: false,
+            root: PathBuf::new(),
+            remappings: vec![],
+            section_limit: 0,
+        }
+        .preprocess(vec![document])
+        .expect("failed to preprocess documents");
+        documents[0].as_doc().expect("failed to render document")
+    }
+
+    #[test]
+    fn constant_and_immutable_variables() {
+        let doc = render(
+            r#"
+            contract Vault {
+                uint256 public constant FEE = 100;
+                address public immutable owner;
+                uint256 public total;
+
+                constructor() { owner = msg.sender; }
+            }
+        "#,
+        );
+
+        assert!(doc.contains("### FEE\n**Constant**: compile-time value `100`\n"));
+        assert!(
+            doc.contains("### owner\n**Immutable**: the value is assigned once at deployment\n")
+        );
+        assert!(!doc.contains("### total\n**"));
+    }
+
+    #[test]
+    fn cut_constant_values() {
+        assert_eq!(constant_value("0x0123456789", 0), ("`0x0123456789`".to_owned(), false));
+        assert_eq!(constant_value("0x0123456789", 12), ("`0x0123456789`".to_owned(), false));
+        assert_eq!(
+            constant_value("0x0123456789", 4),
+            ("`0x01`… (8 more characters)".to_owned(), true)
+        );
+    }
+
+    #[test]
+    fn struct_return_links() {
+        let doc = render(
+            r#"
+            contract Vault {
+                struct Position {
+                    uint256 amount;
+                }
+
+                function positionOf(address owner) external view returns (Position memory) { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains("|`<none>`|[`Position`](/src/Vault.sol/contract.Vault.md#position)||"));
+    }
+
+    #[test]
+    fn import_alias_links() {
+        let parse =
+            |src: &str, ident: &str| test_documents(&format!("src/{ident}.sol"), src).remove(0);
+        let documents = || {
+            vec![
+                parse("contract Foo { }", "Foo"),
+                parse(
+                    r#"
+                import {Foo as Bar} from "./Foo.sol";
+
+                contract Vault {
+                    /// @param foo The `Bar` contract.
+                    function deposit(Bar foo) external { }
+                }
+            "#,
+                    "Vault",
+                ),
+            ]
+        };
+
+        let render = |show_aliases: bool| {
+            let documents = StructLinks {
+                out: PathBuf::from("docs"),
+                show_aliases,
+                root: PathBuf::new(),
+                remappings: vec![],
+                section_limit: 0,
+            }
+            .preprocess(documents())
+            .unwrap();
+            documents[1].as_doc().unwrap()
+        };
+
+        let link = "[`Foo`](/src/Foo.sol/contract.Foo.md)";
+        assert!(render(false).contains(&format!(
+            "|`foo`|{link}|The [`Bar`](/src/Foo.sol/contract.Foo.md) contract.|"
+        )));
+        assert!(render(true).contains(&format!("|`foo`|{link} (as `Bar`)|")));
+    }
+
+    #[test]
+    fn event_metadata() {
+        let doc = render(
+            r#"
+            contract Token {
+                event Transfer(address indexed from, address indexed to, uint256 amount);
+                event Log(bytes) anonymous;
+                event Indexed(uint256 indexed id, bytes data) anonymous;
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "### Transfer\n\
+            - **Signature**: `Transfer(address,address,uint256)`\n\
+            - **Topic 0**: `0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef`\n\
+            - **Indexed (topics)**: `from` (topic 1), `to` (topic 2)\n\
+            - **Data**: `amount`\n"
+        ));
+        assert!(doc.contains(
+            "### Log\n\
+            - **Signature**: `Log(bytes)`\n\
+            - **Topic 0**: none, the event is anonymous\n\
+            - **Indexed (topics)**: none\n\
+            - **Data**: `bytes`\n"
+        ));
+        // The anonymous events have no signature topic
+        assert!(doc.contains(
+            "- **Indexed (topics)**: `id` (topic 0)\n\
+            - **Data**: `data`\n"
+        ));
+    }
+
+    #[test]
+    fn abstract_functions() {
+        let doc = render(
+            r#"
+            abstract contract Oracle {
+                /// @notice Returns the price of the asset.
+                function price(address asset) public view virtual returns (uint256);
+
+                function fee() public pure returns (uint256) { return 1; }
+            }
+        "#,
+        );
+
+        assert_eq!(doc.matches("**Abstract:** not implemented").count(), 1);
+        let marker = doc.find("**Abstract:**").unwrap();
+        assert!(doc[..marker].contains("### price\n\nReturns the price of the asset."));
+        assert!(!doc[..marker].contains("### fee"));
+    }
+
+    #[test]
+    fn initialization_section() {
+        let doc = render(
+            r#"
+            contract Vault {
+                address public immutable owner;
+                uint256 public immutable fee;
+                uint256 public immutable start = 1;
+
+                constructor(address _owner, uint256 _fee) {
+                    owner = _owner;
+                    fee = _fee * 2;
+                }
+            }
+        "#,
+        );
+        assert!(doc.contains(
+            "## Initialization\n\
+            The immutables are assigned once at deployment.\n\n\
+            |Immutable|Assigned from|\n\
+            |-|-|\n\
+            |`owner`|constructor parameter `_owner`|\n\
+            |`fee`|`_fee * 2`|\n\
+            |`start`|`1`|\n"
+        ));
+
+        let doc = render(
+            r#"
+            contract Proxied {
+                function initialize(address owner) external initializer { }
+                function initializeV2() external reinitializer(2) { }
+            }
+        "#,
+        );
+        assert!(doc.contains("## Initialization\n### initialize\n\n```solidity"));
+        assert!(!doc.contains("**Warning**"));
+
+        let doc = render(
+            r#"
+            contract Unprotected {
+                function initialize(address owner) external { }
+            }
+        "#,
+        );
+        assert!(doc.contains(
+            "### initialize\n> **Warning**: the function is not protected by the `initializer` \
+            or `reinitializer` modifier.\n"
+        ));
+    }
+
+    #[test]
+    fn inferred_preconditions() {
+        let src = r#"
+            contract Vault {
+                error Paused();
+
+                function deposit(uint256 amount) external {
+                    require(amount > 0, "zero amount");
+                    if (paused) revert Paused();
+                    total += amount;
+                }
+
+                function withdraw(uint256 amount) external {
+                    total -= amount;
+                }
+            }
+        "#;
+        let documents = Preconditions::default()
+            .preprocess(test_documents("src/Vault.sol", src))
+            .expect("failed to preprocess documents");
+        let doc = documents[0].as_doc().expect("failed to render document");
+
+        assert!(doc.contains(
+            "**Preconditions** (inferred from the guards, may be incomplete):\n\n\
+            - Requires `amount > 0`: `\"zero amount\"`\n\
+            - Reverts if `paused`: `Paused()`\n"
+        ));
+        // The functions without the guards have no preconditions
+        assert_eq!(doc.matches("**Preconditions**").count(), 1);
+    }
+
+    #[test]
+    fn base_constructor_arguments() {
+        let doc = render(
+            r#"
+            contract Vault is Token(18), Ownable, Pausable {
+                constructor(address owner) Ownable(owner) whenNotPaused { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "**Base constructors:**\n\n\
+            |Base|Arguments|\n\
+            |-|-|\n\
+            |Token|`18`|\n\
+            |Ownable|`owner`|\n"
+        ));
+        assert!(!doc.contains("|whenNotPaused|"));
+    }
+
+    #[test]
+    fn mixed_named_returns() {
+        let doc = render(
+            r#"
+            contract Vault {
+                /// @return The deposited amount.
+                /// @return shares The minted shares.
+                /// @return The fee.
+                function deposit() external returns (uint256, uint256 shares, uint256) { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "|`<none>`|`uint256`|The deposited amount.|\n\
+            |`shares`|`uint256`|The minted shares.|\n\
+            |`<none>`|`uint256`|The fee.|\n"
+        ));
+    }
+
+    #[test]
+    fn param_description_links() {
+        let doc = render(
+            r#"
+            contract Vault {
+                enum Status { Open, Closed }
+
+                /// @param status The `Status` to set, see `Vault.Unknown`.
+                function setStatus(Status status) external { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "|`status`|[`Status`](/src/Vault.sol/contract.Vault.md#status)|The \
+            [`Status`](/src/Vault.sol/contract.Vault.md#status) to set, see `Vault.Unknown`.|"
+        ));
+    }
+
+    #[test]
+    fn param_constraints() {
+        let doc = render(
+            r#"
+            contract Vault {
+                /// @param amount The amount to deposit.
+                /// @custom:param:amount Must be at most `maxDeposit`.
+                /// @custom:constraint amount Non-zero.
+                function deposit(uint256 amount, address to) external { }
+            }
+        "#,
+        );
+
+        assert!(doc
+            .contains("|Name|Type|Description|Constraints|\n|----|----|-----------|-----------|"));
+        assert!(doc.contains(
+            "|`amount`|`uint256`|The amount to deposit.|Must be at most `maxDeposit`.; Non-zero.|"
+        ));
+        assert!(doc.contains("|`to`|`address`|||"));
+    }
+
+    #[test]
+    fn since_versions() {
+        let doc = render(
+            r#"
+            /// @notice The vault.
+            /// @custom:since 1.2.0
+            contract Vault {
+                /// @custom:since v1.3.0-rc.1
+                function deposit() external { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "*Since: v1.2.0*
+
+The vault.
+"
+        ));
+        assert!(doc.contains(
+            "### deposit
+
+*Since: v1.3.0-rc.1*
+"
+        ));
+    }
+
+    #[test]
+    fn function_modifiers_with_mutability() {
+        let doc = render(
+            r#"
+            contract Vault is Ownable {
+                constructor(address owner) Ownable(owner) initializer { }
+
+                function total() external view whenNotPaused returns (uint256) { }
+
+                function deposit() external payable onlyOwner nonReentrant { }
+
+                function pause() external { }
+            }
+        "#,
+        );
+
+        assert!(doc.contains(
+            "```
+**Mutability:** `nonpayable`; **Modifiers:** `initializer`
+
+"
+        ));
+        assert!(doc.contains(
+            "```
+**Mutability:** `view`; **Modifiers:** `whenNotPaused`
+
+"
+        ));
+        assert!(doc.contains(
+            "```
+**Mutability:** `payable`; **Modifiers:** `onlyOwner`, `nonReentrant`
+
+"
+        ));
+        assert_eq!(doc.matches("**Mutability:**").count(), 3);
+    }
+
+    #[test]
+    fn gas_notes() {
+        let src = r#"
+            contract Vault {
+                /// @notice Deposit the assets.
+                /// @custom:gas ~25k
+                function deposit(uint256 assets) external { }
+                /// @custom:gas 3k
+                function total() external view returns (uint256) { }
+                function name() external view returns (string memory) { }
+            }
+        "#;
+        let document = test_documents("src/Vault.sol", src).pop().expect("no items");
+        document.add_context(
+            GAS_ESTIMATES_ID,
+            PreprocessorOutput::GasEstimates(HashMap::from([
+                ("deposit(uint256)".to_owned(), "24512".to_owned()),
+                ("name()".to_owned(), "infinite".to_owned()),
+            ])),
+        );
+        let doc = document.as_doc().expect("failed to render document");
+
+        assert!(doc.contains("**Gas**: ~25k (author); 24512 (compiler estimate)\n"));
+        assert!(doc.contains("**Gas**: 3k (author)\n"));
+        assert!(doc.contains("**Gas**: infinite (compiler estimate)\n"));
+    }
+
+    #[test]
+    fn markdown_sections() {
+        let mut comments = Comments::default();
+        comments.push(Comment::new(CommentTag::Dev, "Only the owner.".to_owned()));
+        comments.push(Comment::new(
+            CommentTag::Dev,
+            "The fees:\n| Tier | Fee |\n|-|-|\n| 1 | 0.3% |".to_owned(),
+        ));
+        comments.push(Comment::new(
+            CommentTag::Custom(DOCS_TAG.to_owned()),
+            "\n## Usage\nDeposit first:\n```solidity\nvault.deposit(1e18);".to_owned(),
+        ));
+        comments.push(Comment::new(CommentTag::Notice, "Deposit the assets.".to_owned()));
+        let doc = comments.as_doc().unwrap();
+
+        assert_eq!(
+            doc,
+            "Deposit the assets.\n\n*Only the owner.*\n\nThe fees:\n\n| Tier | Fee |\n|-|-|\n\
+            | 1 | 0.3% |\n\n## Usage\nDeposit first:\n\n```solidity\nvault.deposit(1e18);\n```\n\n"
+        );
+    }
+
+    #[test]
+    fn memory_safe_assembly() {
+        let doc = render(
+            r#"
+            contract Vault {
+                function load() external view returns (uint256 value) {
+                    assembly ("memory-safe") { value := sload(0) }
+                }
+
+                function copy() external {
+                    if (true) {
+                        /// @solidity memory-safe-assembly
+                        assembly { mstore(0, 1) }
+                    }
+                    assembly { mstore(0x40, 0) }
+                }
+
+                function store() external {
+                    assembly { sstore(0, 1) }
+                }
+            }
+        "#,
+        );
+
+        assert!(doc.contains("**Inline assembly:** 1 block, declared `memory-safe`\n"));
+        assert!(doc.contains("**Inline assembly:** 2 blocks, 1 declared `memory-safe`\n"));
+        assert!(doc.contains("**Inline assembly:** 1 block\n"));
+    }
+}
@@ -1,9 +1,15 @@
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use solang_parser::pt::Parameter;
-use std::fmt::{self, Display, Write};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Write},
+};
 
-use crate::{AsDoc, CommentTag, Comments, Markdown};
+use crate::{
+    helpers::user_type_name, AsDoc, CommentTag, Comments, ImportAliases, Markdown, StructField,
+    INFERRED_PARAM_TAG,
+};
 
 /// Solidity language name.
 const SOLIDITY: &str = "solidity";
@@ -12,18 +18,27 @@ const SOLIDITY: &str = "solidity";
 const PARAM_TABLE_HEADERS: &[&str] = &["Name", "Type", "Description"];
 static PARAM_TABLE_SEPARATOR: Lazy<String> =
     Lazy::new(|| PARAM_TABLE_HEADERS.iter().map(|h| "-".repeat(h.len())).join("|"));
+/// The header of the parameter table column of the `@custom:param:<name>` constraints.
+const CONSTRAINTS_HEADER: &str = "Constraints";
 
 /// The buffered writer.
 /// Writes various display items into the internal buffer.
 #[derive(Default, Debug)]
 pub struct BufWriter {
     buf: String,
+    aliases: ImportAliases,
 }
 
 impl BufWriter {
     /// Create new instance of [BufWriter] from [ToString].
     pub fn new(content: impl ToString) -> Self {
-        Self { buf: content.to_string() }
+        Self { buf: content.to_string(), ..Default::default() }
+    }
+
+    /// Set the import aliases the user defined types are resolved with.
+    pub fn with_import_aliases(mut self, aliases: ImportAliases) -> Self {
+        self.aliases = aliases;
+        self
     }
 
     /// Returns true if the buffer is empty.
@@ -111,17 +126,52 @@ impl BufWriter {
     }
 
     /// Tries to write the parameters table to the buffer.
-    /// Doesn't write anything if either params or comments are empty.
+    /// The user defined types and the code references to them in the descriptions are linked to
+    /// their documentation using the provided links. Doesn't write anything if params are
+    /// empty, or if there are neither comments nor linked types.
     pub fn try_write_param_table(
         &mut self,
         tag: CommentTag,
         params: &[&Parameter],
         comments: &Comments,
+        links: &HashMap<String, String>,
     ) -> fmt::Result {
+        // The constraints of the parameters, written in the extra column if there are any
+        let constraints = params
+            .iter()
+            .map(|param| match (&tag, &param.name) {
+                (CommentTag::Param, Some(name)) => comments
+                    .iter()
+                    .filter_map(|comment| comment.match_constraint(&name.name))
+                    .join("; "),
+                _ => String::new(),
+            })
+            .collect::<Vec<_>>();
+        let constrained = constraints.iter().any(|constraint| !constraint.is_empty());
+
+        // The descriptions inferred from the trailing comments of the parameters
+        let inferred_tag = CommentTag::Custom(INFERRED_PARAM_TAG.to_owned());
+        let inferred = params
+            .iter()
+            .map(|param| match (&tag, &param.name) {
+                (CommentTag::Param, Some(name)) => comments
+                    .iter()
+                    .filter(|comment| comment.tag == inferred_tag)
+                    .find_map(|comment| comment.match_first_word(&name.name)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
         let comments = comments.include_tag(tag.clone());
+        let link = |param: &Parameter| user_type_name(&param.ty).and_then(|name| links.get(&name));
 
         // There is nothing to write.
-        if params.is_empty() || comments.is_empty() {
+        if params.is_empty() ||
+            (comments.is_empty() &&
+                !constrained &&
+                inferred.iter().all(Option::is_none) &&
+                !params.iter().any(|p| link(p).is_some()))
+        {
             return Ok(())
         }
 
@@ -134,10 +184,33 @@ impl BufWriter {
         self.write_bold(heading)?;
         self.writeln()?;
 
-        self.write_piped(&PARAM_TABLE_HEADERS.join("|"))?;
-        self.write_piped(&PARAM_TABLE_SEPARATOR)?;
+        if constrained {
+            self.write_piped(&format!("{}|{CONSTRAINTS_HEADER}", PARAM_TABLE_HEADERS.join("|")))?;
+            self.write_piped(&format!(
+                "{}|{}",
+                *PARAM_TABLE_SEPARATOR,
+                "-".repeat(CONSTRAINTS_HEADER.len())
+            ))?;
+        } else {
+            self.write_piped(&PARAM_TABLE_HEADERS.join("|"))?;
+            self.write_piped(&PARAM_TABLE_SEPARATOR)?;
+        }
 
-        for (index, param) in params.iter().enumerate() {
+        // The return comments which don't start with any of the return names
+        // are matched by position.
+        let mut positional = comments.iter().filter(|comment| {
+            !params.iter().any(|param| {
+                param
+                    .name
+                    .as_ref()
+                    .map(|name| comment.match_first_word(&name.name).is_some())
+                    .unwrap_or_default()
+            })
+        });
+
+        for ((param, constraint), inferred) in
+            params.iter().zip(constraints.iter()).zip(inferred.iter())
+        {
             let param_name = param.name.as_ref().map(|n| n.name.to_owned());
 
             let mut comment = param_name.as_ref().and_then(|name| {
@@ -145,15 +218,76 @@ impl BufWriter {
             });
 
             // If it's a return tag and couldn't match by first word,
-            // lookup the doc by index.
+            // lookup the next doc that is not matched by name.
             if comment.is_none() && matches!(tag, CommentTag::Return) {
-                comment = comments.get(index).map(|c| &*c.value);
+                comment = positional.next().map(|c| &*c.value);
             }
 
-            let row = [
+            // The pipes of the informal comments would split the table cell
+            let inferred = inferred.map(|inferred| inferred.replace('|', "\\|"));
+            if comment.is_none() {
+                comment = inferred.as_deref();
+            }
+
+            // The aliased types are written with the canonical names
+            let ty = param.ty.to_string();
+            let canonical = user_type_name(&param.ty).and_then(|_| self.aliases.resolve(&ty));
+            let code = Markdown::Code(canonical.as_deref().unwrap_or(&ty)).as_doc()?;
+            let mut ty_cell = match link(param) {
+                Some(link) => Markdown::Link(&code, link).as_doc()?,
+                None => code,
+            };
+            if canonical.is_some() && self.aliases.show {
+                ty_cell.push_str(&format!(" (as {})", Markdown::Code(&ty).as_doc()?));
+            }
+
+            let mut row = vec![
                 Markdown::Code(&param_name.unwrap_or_else(|| "<none>".to_owned())).as_doc()?,
-                Markdown::Code(&param.ty.to_string()).as_doc()?,
-                comment.unwrap_or_default().replace('\n', " "),
+                ty_cell,
+                link_references(&comment.unwrap_or_default().replace('\n', " "), links),
+            ];
+            if constrained {
+                row.push(constraint.replace('\n', " "));
+            }
+            self.write_piped(&row.join("|"))?;
+        }
+
+        self.writeln()?;
+
+        Ok(())
+    }
+
+    /// Tries to write the struct fields table to the buffer.
+    /// The user defined types are linked to their documentation using the provided links.
+    /// Doesn't write anything if there are neither descriptions, linked types
+    /// nor expanded nested fields.
+    pub fn try_write_struct_fields(
+        &mut self,
+        fields: &[StructField],
+        links: &HashMap<String, String>,
+    ) -> fmt::Result {
+        let link = |field: &StructField| field.user_type.as_ref().and_then(|ty| links.get(ty));
+        if !fields.iter().any(|field| {
+            field.description.is_some() || link(field).is_some() || field.name.contains('.')
+        }) {
+            return Ok(())
+        }
+
+        self.write_bold("Fields")?;
+        self.writeln()?;
+
+        self.write_piped(&PARAM_TABLE_HEADERS.join("|"))?;
+        self.write_piped(&PARAM_TABLE_SEPARATOR)?;
+
+        for field in fields.iter() {
+            let ty = Markdown::Code(&field.ty).as_doc()?;
+            let row = [
+                Markdown::Code(&field.name).as_doc()?,
+                match link(field) {
+                    Some(link) => Markdown::Link(&ty, link).as_doc()?,
+                    None => ty,
+                },
+                link_references(field.description.as_deref().unwrap_or_default(), links),
             ];
             self.write_piped(&row.join("|"))?;
         }
@@ -175,3 +309,21 @@ impl BufWriter {
         self.buf
     }
 }
+
+/// Link the code spans of the text referencing the documented types, e.g. `` `Position` ``.
+/// The unresolved references are kept as is.
+fn link_references(text: &str, links: &HashMap<String, String>) -> String {
+    // Skip the text with the unbalanced backticks.
+    if text.matches('`').count() % 2 == 1 {
+        return text.to_owned()
+    }
+    text.split('`')
+        .enumerate()
+        .map(|(idx, segment)| match links.get(segment) {
+            // The odd segments are within the backticks.
+            Some(link) if idx % 2 == 1 => format!("[`{segment}`]({link})"),
+            _ if idx % 2 == 1 => format!("`{segment}`"),
+            _ => segment.to_owned(),
+        })
+        .collect()
+}
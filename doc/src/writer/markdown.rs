@@ -42,3 +42,19 @@ impl<'a> std::fmt::Display for Markdown<'a> {
         f.write_fmt(format_args!("{}", self.as_doc()?))
     }
 }
+
+/// Returns the anchor id that mdbook generates for the heading.
+pub(crate) fn heading_anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter_map(|ch| {
+            if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                Some(ch.to_ascii_lowercase())
+            } else if ch.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
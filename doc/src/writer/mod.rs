@@ -6,4 +6,5 @@ mod markdown;
 
 pub use as_doc::{AsDoc, AsDocResult};
 pub use buf_writer::BufWriter;
+pub(crate) use markdown::heading_anchor;
 pub use markdown::Markdown;